@@ -0,0 +1,147 @@
+//! Generates WinRT bindings from a build script rather than with the `import!` macro
+//!
+//! The bindings are generated once per build and written to `OUT_DIR`, one source file per
+//! namespace, where they can be read and indexed by IDEs:
+//!
+//! ```no_run
+//! // In the `main` function of build.rs
+//! winrt_build::generate(&["Windows.Storage"], std::env::var("OUT_DIR").unwrap());
+//! ```
+//!
+//! The crate then includes them wherever the `import!` macro would have been invoked:
+//!
+//! ```ignore
+//! include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+//! ```
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use winmd::{load_winmd, TypeLimits, TypeOptions, TypeReader, TypeStage};
+
+/// Generates the modules, and the types they depend on, from the operating system's metadata
+///
+/// Modules are matched the same way as those named by `import!`, so both `Windows.Storage`
+/// and `windows.storage` work. Use [`Builder`] for other dependencies or for options.
+///
+/// # Panics
+///
+/// Panics if a module isn't found or the bindings can't be written, which fails the build.
+pub fn generate<P: AsRef<Path>>(modules: &[&str], out_dir: P) {
+    modules
+        .iter()
+        .fold(Builder::new(), |builder, module| builder.module(module))
+        .write(out_dir)
+}
+
+/// Configures the dependencies, modules, types, aliases, and options to generate, as the sections of `import!` do
+#[derive(Default)]
+pub struct Builder {
+    dependencies: BTreeSet<PathBuf>,
+    modules: Vec<String>,
+    types: Vec<String>,
+    options: TypeOptions,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds metadata to generate from, which takes the same values as the `dependencies` of
+    /// `import!`, such as a `.winmd` file or `sdk:10.0.19041.0`
+    ///
+    /// The operating system's metadata is used if no dependencies are added.
+    pub fn dependency(mut self, dependency: &str) -> Self {
+        self.dependencies
+            .append(&mut load_winmd::dependency_files(dependency));
+        self
+    }
+
+    /// Adds a namespace to generate
+    pub fn module(mut self, module: &str) -> Self {
+        self.modules.push(module.to_string());
+        self
+    }
+
+    /// Adds a type to generate, such as `Windows.Foundation.Uri`, without the rest of its
+    /// namespace
+    ///
+    /// Only the types that it transitively refers to are generated along with it, as with the
+    /// `types` of `import!`.
+    pub fn type_name(mut self, name: &str) -> Self {
+        self.types.push(name.to_string());
+        self
+    }
+
+    /// Re-exports a namespace module under a shorter name, written as the `aliases` of
+    /// `import!` are, such as `windows.ui.xaml.controls as ctrl`
+    pub fn alias(mut self, alias: &str) -> Self {
+        self.options.insert_alias(alias);
+        self
+    }
+
+    /// Enables a generation option by name, such as `contract_features`
+    ///
+    /// Relative paths given to options, such as `overrides:winrt.toml`, are relative to the
+    /// crate being built.
+    pub fn option(mut self, option: &str) -> Self {
+        self.options.insert(option);
+        self
+    }
+
+    /// Generates the bindings and writes them to a directory, usually `OUT_DIR`
+    ///
+    /// Cargo is told to run the build script again if any of the metadata changes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a module isn't found or the bindings can't be written.
+    pub fn write<P: AsRef<Path>>(self, out_dir: P) {
+        let dependencies = if self.dependencies.is_empty() {
+            load_winmd::dependency_files("os")
+        } else {
+            self.dependencies
+        };
+
+        for path in &dependencies {
+            println!("cargo:rerun-if-changed={}", path.display());
+        }
+
+        let reader = &TypeReader::new(load_winmd::from_files(dependencies));
+        let mut limits = TypeLimits::default();
+
+        for module in &self.modules {
+            limits.insert(reader, module);
+        }
+
+        for name in &self.types {
+            limits.insert_type(reader, name);
+        }
+
+        let mut stage = TypeStage::from_limits(reader, &limits);
+        self.options.overrides.apply(&mut stage);
+
+        if self.options.skip_deprecated {
+            stage.remove_deprecated(reader);
+        }
+
+        if let Some(build) = self.options.max_build {
+            for contract in stage.limit_to_build(build) {
+                println!(
+                    "cargo:warning=max_build:{} kept types from {}, which doesn't map to a Windows build",
+                    build, contract
+                );
+            }
+        }
+
+        let tree = stage.into_tree(reader);
+
+        for (old, new) in reader.take_moved() {
+            println!("cargo:warning=`{}` has moved to `{}`", old, new);
+        }
+
+        let out_dir = out_dir.as_ref();
+        tree.write(out_dir, &self.options)
+            .unwrap_or_else(|e| panic!("Could not write bindings to {:?}: {}", out_dir, e));
+    }
+}
@@ -0,0 +1,43 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, LitStr, Result};
+
+/// Parses a GUID string literal into a constant expression
+///
+/// The string is parsed the same way as by `Guid::from_str`, in the dashed form and
+/// optionally in braces, so that invalid GUIDs fail to compile rather than panicking.
+pub fn guid(literal: LitStr) -> Result<TokenStream> {
+    let value = literal.value();
+
+    match parse(&value) {
+        Some(value) => Ok(quote! { ::winrt::Guid::from_u128(#value) }),
+        None => Err(Error::new(
+            literal.span(),
+            "expected a GUID such as \"96369F54-8EB6-48F0-ABCE-C1B211E627C3\"",
+        )),
+    }
+}
+
+fn parse(value: &str) -> Option<u128> {
+    let value = value
+        .strip_prefix('{')
+        .and_then(|value| value.strip_suffix('}'))
+        .unwrap_or(value);
+
+    const DASHES: [usize; 4] = [8, 13, 18, 23];
+    let bytes = value.as_bytes();
+
+    if bytes.len() != 36 || DASHES.iter().any(|dash| bytes[*dash] != b'-') {
+        return None;
+    }
+
+    bytes
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !DASHES.contains(index))
+        .try_fold(0, |guid, (_, digit)| {
+            (*digit as char)
+                .to_digit(16)
+                .map(|digit| guid << 4 | digit as u128)
+        })
+}
@@ -1,15 +1,104 @@
+mod guid;
+mod value_set;
+
 use proc_macro::{TokenStream, TokenTree};
-use winmd::{TypeLimits, TypeReader, TypeStage};
+use winmd::{
+    load_winmd, TypeLimits, TypeManifest, TypeOptions, TypeReader, TypeStage, TypeSurface,
+};
 
 use std::collections::BTreeSet;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
 /// A macro for generating WinRT modules into the current module
+///
+/// Modules may use `*` to match any part of a namespace name, so `"windows.devices.*"` imports
+/// every namespace nested within `Windows.Devices`, as found in the winmd files.
+///
+/// A `prelude` module is generated alongside the namespace modules, re-exporting the traits of
+/// every interface and the runtime's traits, so that `use prelude::*;` brings all of their
+/// methods into scope.
+///
+/// An optional `types` section imports individual types, such as `"windows.foundation.uri"`,
+/// along with only the types they transitively refer to through their members and required
+/// interfaces, rather than their whole namespaces.
+///
+/// An optional `aliases` section re-exports namespace modules under shorter names next to the
+/// root `windows` module, e.g. `"windows.ui.xaml.controls as ctrl"` lets the importing crate
+/// write `ctrl::Button` rather than `windows::ui::xaml::controls::Button`. The namespace must
+/// be one of those imported.
+///
+/// An optional `options` section enables generation options by name:
+///
+/// * `"contract_features"` gates types and members introduced after the first release of
+///   Windows 10 behind cargo features named after the Windows build that introduced them,
+///   e.g. `#[cfg(feature = "14393")]`. The importing crate should declare these features,
+///   with each one enabling the builds before it. Metadata from a newer SDK than this crate
+///   knows the builds of fails to import rather than leaving its newest APIs ungated.
+/// * `"namespace_features"` gates each namespace module behind a cargo feature named after
+///   the namespace, e.g. `#[cfg(feature = "Windows_Devices_Bluetooth")]`. Setting the
+///   `WINRT_FEATURES` environment variable to a file path writes the `[features]` entries the
+///   importing crate should declare, each enabling the namespaces it depends on.
+/// * `"target:<build>"`, e.g. `"target:17763"`, makes calls to members introduced after that
+///   Windows build check with `ApiInformation` that the method, property, or event is present
+///   first and fail with `ErrorCode::OLD_WIN_VERSION` on older versions of Windows.
+/// * `"max_build:<build>"`, e.g. `"max_build:19041"`, leaves out the types, and the interfaces
+///   of classes, introduced after that Windows build, so that the bindings can't call APIs
+///   missing from the oldest version of Windows an app supports.
+/// * `"compact_vtables:<methods>"`, e.g. `"compact_vtables:100"`, generates the vtables of
+///   interfaces with at least that many methods as tables of method slots rather than a field
+///   per method, which speeds up compiling namespaces with huge interfaces such as
+///   `Windows.UI.Xaml`.
+/// * `"safe_api"` has the generated methods and conversions call the runtime's audited ABI
+///   helpers rather than casting pointers themselves, and marks them, the unsafe trait
+///   implementations, and the ABI callbacks with `#[allow(unsafe_code)]`, so that the bindings
+///   build in a crate that denies `unsafe_code`.
+/// * `"visibility:<visibility>"`, e.g. `"visibility:pub(crate)"`, generates modules, types,
+///   and their members with that visibility rather than `pub`, so that a crate using the
+///   bindings internally doesn't export them.
+/// * `"abi_only"` generates only the ABI: a vtable struct and IID for each interface and
+///   delegate, named like `abi_IStringable`, and `repr(C)` structs and integer enums, with
+///   objects and strings passed as `RawPtr`. Classes and generic definitions are left out.
+/// * `"skip_deprecated"` leaves out the types and members marked as deprecated in metadata,
+///   other than deprecated types that are still used by the rest of the bindings.
+/// * `"serde"` derives `Serialize` and `Deserialize` for enums and for structs without string
+///   or object fields, such as `Point` and `Color`. The runtime's `serde` feature must be
+///   enabled as well.
+/// * `"futures_io"` generates `AsyncInputStream` and `AsyncOutputStream` in
+///   `Windows.Storage.Streams`, which wrap `IInputStream` and `IOutputStream` and implement
+///   `AsyncRead` and `AsyncWrite` from `futures_io`. The runtime's `futures-io` feature must be
+///   enabled as well.
+/// * `"futures_core"` implements `Stream` from `futures_core` for the `{Class}StatusChanges`
+///   subscriptions of static classes whose properties raise change events, such as
+///   `PowerManagerStatusChanges`. The runtime's `futures-core` feature must be enabled as well.
+/// * `"chrono"` converts `DateTime` to and from `chrono::DateTime<Utc>` and `TimeSpan` to and
+///   from `chrono::Duration`. The runtime's `chrono` feature must be enabled as well.
+/// * `"sorted_members"` generates the methods of each interface in name order rather than
+///   vtable order, so that adding a member to an interface only adds lines to the generated
+///   code. Overloads are named the same either way.
+/// * `"overrides:<path>"`, e.g. `"overrides:winrt.toml"`, reads adjustments for members with
+///   known-bad metadata from a file relative to the crate, marking them as nullable, renaming
+///   them, or leaving them out. See `winmd::TypeOverrides` for the file format.
 #[proc_macro]
 pub fn import(stream: TokenStream) -> TokenStream {
-    let (_dependencies, namespaces) = parse_import_stream(stream);
+    let (dependencies, sources, namespaces, types, options) = parse_import_stream(stream);
+
+    let dependencies = if dependencies.is_empty() {
+        load_winmd::dependency_files("os")
+    } else {
+        dependencies
+    };
+
+    // Imports of the same namespace from different metadata are told apart in the manifest by
+    // the dependencies they were imported from.
+    let sources = if sources.is_empty() {
+        "os".to_string()
+    } else {
+        sources.join(" ")
+    };
 
-    let reader = &TypeReader::from_os();
+    // Every `import!` in the crate is expanded by the same process so the metadata is only
+    // parsed once for each set of dependencies.
+    let reader = &*TypeReader::cached(dependencies);
 
     let mut limits = TypeLimits::default();
 
@@ -17,26 +106,168 @@ pub fn import(stream: TokenStream) -> TokenStream {
         limits.insert(reader, &namespace);
     }
 
-    let stage = TypeStage::from_limits(reader, &limits);
-    let tree = stage.into_tree();
-    let stream = tree.to_tokens();
+    for name in types {
+        limits.insert_type(reader, &name);
+    }
+
+    let mut stage = TypeStage::from_limits(reader, &limits);
+    options.overrides.apply(&mut stage);
+
+    if options.skip_deprecated {
+        stage.remove_deprecated(reader);
+    }
+
+    if let Some(build) = options.max_build {
+        // Proc macros can't emit warnings on stable, so this is printed along with the
+        // compiler's output.
+        for contract in stage.limit_to_build(build) {
+            eprintln!(
+                "warning: max_build:{} kept types from {}, which doesn't map to a Windows build",
+                build, contract
+            );
+        }
+    }
+
+    if let Some(path) = std::env::var_os("WINRT_FEATURES") {
+        append_to_file(&path, &stage.features().to_string());
+    }
+
+    let tree = stage.into_tree(reader);
+    let stream = tree.to_tokens(&options);
+
+    for (old, new) in reader.take_moved() {
+        eprintln!("warning: `{}` has moved to `{}`", old, new);
+    }
+
+    if let Some(path) = std::env::var_os("WINRT_SYMBOL_MAP") {
+        append_to_file(&path, &tree.symbol_map().to_string());
+    }
+
+    if let Some(path) = std::env::var_os("WINRT_MANIFEST") {
+        append_to_file(&path, &tree.manifest(&options, &sources).to_string());
+    }
+
+    if let Some(path) = std::env::var_os("WINRT_VERIFY_MANIFEST") {
+        let expected = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Could not read manifest at {:?}: {}", path, e));
+
+        if let Err(e) = tree
+            .manifest(&options, &sources)
+            .verify(&TypeManifest::parse(&expected))
+        {
+            panic!("Generated code does not match manifest {:?}:\n{}", path, e);
+        }
+    }
+
+    if let Some(path) = std::env::var_os("WINRT_SURFACE") {
+        append_to_file(&path, &tree.surface(&options).to_string());
+    }
+
+    if let Some(path) = std::env::var_os("WINRT_VERIFY_SURFACE") {
+        let previous = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Could not read surface at {:?}: {}", path, e));
+
+        let changes = tree
+            .surface(&options)
+            .compare(&TypeSurface::parse(&previous));
+
+        if changes.is_breaking() {
+            panic!(
+                "Generated code breaks the surface recorded at {:?}:\n{}",
+                path, changes
+            );
+        }
+    }
 
     stream.into()
 }
 
+/// Derives `ToValueSet`, storing each field of a struct in a `PropertyBag`
+///
+/// Values are boxed with `PropertyValue` and keyed by the field name, which may be changed
+/// with `#[value_set(rename = "Key")]`. Fields of type `Option<T>` are removed from the bag
+/// when `None`. The traits are generated along with the Windows.Foundation module, which is
+/// expected at `crate::windows::foundation` unless the struct has a
+/// `#[value_set(path = "...")]` attribute naming another path.
+#[proc_macro_derive(ToValueSet, attributes(value_set))]
+pub fn to_value_set(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+
+    value_set::to_value_set(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// Derives `FromValueSet`, reading each field of a struct from a `PropertyBag`
+///
+/// Fields are read with the same keys as `ToValueSet`. Reading fails if a value is missing,
+/// unless the field is an `Option<T>`, or if it can't be unboxed as the field's type.
+#[proc_macro_derive(FromValueSet, attributes(value_set))]
+pub fn from_value_set(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+
+    value_set::from_value_set(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// A macro for writing a `winrt::Guid` that's parsed when compiling
+///
+/// The GUID is written as a string in its usual form, optionally in braces, e.g.
+/// `winrt::guid!("96369F54-8EB6-48F0-ABCE-C1B211E627C3")`. The macro expands to a constant
+/// expression so it may be used to define the IIDs of interop interfaces in a `const`. Invalid
+/// strings fail to compile.
+#[proc_macro]
+pub fn guid(input: TokenStream) -> TokenStream {
+    let literal = syn::parse_macro_input!(input as syn::LitStr);
+
+    guid::guid(literal)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// Appends a symbol map, manifest, surface, or features to the file at `path`
+///
+/// Several `import!` invocations may share the same file so it is appended to rather
+/// than overwritten.
+fn append_to_file(path: &std::ffi::OsStr, text: &str) {
+    use std::io::Write;
+
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(text.as_bytes()))
+        .unwrap_or_else(|e| panic!("Could not write to {:?}: {}", path, e));
+}
+
 #[derive(PartialEq)]
 enum ImportCategory {
     None,
     Dependency,
     Namespace,
+    Type,
+    Alias,
+    Option,
 }
 
-/// Parse `import!` macro and return a set of paths to dependencies and
-/// a set to all the namespaces referenced
-fn parse_import_stream(stream: TokenStream) -> (BTreeSet<PathBuf>, BTreeSet<String>) {
+/// Parse `import!` macro and return a set of paths to dependencies,
+/// a set to all the namespaces and types referenced and the generation options
+fn parse_import_stream(
+    stream: TokenStream,
+) -> (
+    BTreeSet<PathBuf>,
+    Vec<String>,
+    BTreeSet<String>,
+    BTreeSet<String>,
+    TypeOptions,
+) {
     let mut category = ImportCategory::None;
     let mut dependencies = BTreeSet::<PathBuf>::new();
+    let mut sources = Vec::<String>::new();
     let mut modules = BTreeSet::<String>::new();
+    let mut types = BTreeSet::<String>::new();
+    let mut options = TypeOptions::default();
     let mut stream = stream.into_iter().peekable();
 
     while let Some(token) = stream.next() {
@@ -45,7 +276,10 @@ fn parse_import_stream(stream: TokenStream) -> (BTreeSet<PathBuf>, BTreeSet<Stri
                 match value.to_string().as_ref() {
                     "dependencies" => category = ImportCategory::Dependency,
                     "modules" => category = ImportCategory::Namespace,
-                    value => panic!("winrt::import macro expects either `dependencies` or `modules` but found `{}`", value),
+                    "types" => category = ImportCategory::Type,
+                    "aliases" => category = ImportCategory::Alias,
+                    "options" => category = ImportCategory::Option,
+                    value => panic!("winrt::import macro expects either `dependencies`, `modules`, `types`, `aliases` or `options` but found `{}`", value),
                 }
                 if let Some(TokenTree::Punct(p)) = stream.peek() {
                     if p.as_char() == ':' {
@@ -55,15 +289,26 @@ fn parse_import_stream(stream: TokenStream) -> (BTreeSet<PathBuf>, BTreeSet<Stri
             }
             TokenTree::Literal(value) => match category {
                 ImportCategory::None => panic!(
-                    "winrt::import macro expects either `dependencies` or `modules` but found `{}`",
+                    "winrt::import macro expects either `dependencies`, `modules`, `types`, `aliases` or `options` but found `{}`",
                     value
                 ),
                 ImportCategory::Dependency => {
-                    dependencies.append(&mut to_dependencies(value.to_string().trim_matches('"')));
+                    let source = value.to_string().trim_matches('"').to_string();
+                    dependencies.append(&mut load_winmd::dependency_files(&source));
+                    sources.push(source);
                 }
                 ImportCategory::Namespace => {
                     modules.insert(namespace_literal_to_rough_namespace(&value.to_string()));
                 }
+                ImportCategory::Type => {
+                    types.insert(namespace_literal_to_rough_namespace(&value.to_string()));
+                }
+                ImportCategory::Alias => {
+                    options.insert_alias(value.to_string().trim_matches('"'));
+                }
+                ImportCategory::Option => {
+                    options.insert(value.to_string().trim_matches('"'));
+                }
             },
             _ => panic!(
                 "winrt::import macro encountered an unrecognized token: {}",
@@ -72,44 +317,7 @@ fn parse_import_stream(stream: TokenStream) -> (BTreeSet<PathBuf>, BTreeSet<Stri
         }
     }
 
-    (dependencies, modules)
-}
-
-/// Returns the paths to resolved dependencies
-fn to_dependencies<P: AsRef<Path>>(dependency: P) -> BTreeSet<PathBuf> {
-    let path = dependency.as_ref();
-    let mut result = BTreeSet::new();
-
-    if path.is_dir() {
-        let paths = std::fs::read_dir(path).unwrap_or_else(|e| {
-            panic!(
-                "Could not read dependecy directory at path {:?}: {}",
-                path, e
-            )
-        });
-        for path in paths {
-            if let Ok(path) = path {
-                let path = path.path();
-                if path.is_file() {
-                    result.insert(path);
-                }
-            }
-        }
-    } else if path.is_file() {
-        result.insert(path.to_path_buf());
-    } else if path.to_str().map(|p| p == "os").unwrap_or(false) {
-        let mut path = PathBuf::new();
-        let wind_dir_env = std::env::var("windir")
-            .unwrap_or_else(|_| panic!("No `windir` environment variable found"));
-        path.push(wind_dir_env);
-        path.push(SYSTEM32);
-        path.push("winmetadata");
-        result.append(&mut to_dependencies(path));
-    } else {
-        panic!("Dependency {:?} is not a file or directory", path);
-    }
-
-    result
+    (dependencies, sources, modules, types, options)
 }
 
 // Snake <-> camel casing is lossy so we go for character but not case conversion
@@ -123,9 +331,3 @@ fn namespace_literal_to_rough_namespace(namespace: &str) -> String {
     }
     result
 }
-
-#[cfg(target_pointer_width = "64")]
-const SYSTEM32: &str = "System32";
-
-#[cfg(target_pointer_width = "32")]
-const SYSTEM32: &str = "SysNative";
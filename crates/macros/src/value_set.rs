@@ -0,0 +1,199 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Error, Fields, Lit, Meta, NestedMeta, Result, Type};
+
+// The module generated for Windows.Foundation when `import!` is invoked at the crate root.
+const DEFAULT_PATH: &str = "crate::windows::foundation";
+
+struct Field {
+    ident: syn::Ident,
+    key: String,
+    ty: Type,
+    optional: Option<Type>,
+}
+
+/// Implements `ToValueSet` by boxing each field into the bag
+pub fn to_value_set(input: DeriveInput) -> Result<TokenStream> {
+    let path = path(&input)?;
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = fields(&input)?.into_iter().map(|field| {
+        let ident = &field.ident;
+        let key = &field.key;
+
+        if field.optional.is_some() {
+            quote! {
+                match &self.#ident {
+                    Some(value) => {
+                        bag.map().insert(#key, ::winrt::Boxable::box_value(value)?)?;
+                    }
+                    None => bag.remove(#key)?,
+                }
+            }
+        } else {
+            quote! {
+                bag.map().insert(#key, ::winrt::Boxable::box_value(&self.#ident)?)?;
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics #path::ToValueSet for #name #type_generics #where_clause {
+            fn to_value_set(&self, bag: &#path::PropertyBag) -> ::winrt::Result<()> {
+                #(#fields)*
+                Ok(())
+            }
+        }
+    })
+}
+
+/// Implements `FromValueSet` by unboxing each field from the bag
+pub fn from_value_set(input: DeriveInput) -> Result<TokenStream> {
+    let path = path(&input)?;
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields = fields(&input)?.into_iter().map(|field| {
+        let ident = &field.ident;
+        let key = &field.key;
+
+        match &field.optional {
+            Some(ty) => quote! {
+                #ident: bag.get::<#ty>(#key)?,
+            },
+            None => {
+                let ty = &field.ty;
+                quote! {
+                    #ident: bag.get::<#ty>(#key)?.ok_or_else(|| {
+                        ::winrt::Error::from(::winrt::ErrorCode::INVALID_ARGUMENT)
+                    })?,
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics #path::FromValueSet for #name #type_generics #where_clause {
+            fn from_value_set(bag: &#path::PropertyBag) -> ::winrt::Result<Self> {
+                Ok(Self {
+                    #(#fields)*
+                })
+            }
+        }
+    })
+}
+
+/// The path to the generated Windows.Foundation module, set with `#[value_set(path = "...")]`
+fn path(input: &DeriveInput) -> Result<syn::Path> {
+    let mut path = None;
+
+    for (name, value) in attributes(&input.attrs)? {
+        match name.as_str() {
+            "path" => path = Some(syn::parse_str(&value.value())?),
+            _ => {
+                return Err(Error::new(
+                    value.span(),
+                    format!("unrecognized value_set attribute `{}`", name),
+                ))
+            }
+        }
+    }
+
+    Ok(path.unwrap_or_else(|| syn::parse_str(DEFAULT_PATH).unwrap()))
+}
+
+fn fields(input: &DeriveInput) -> Result<Vec<Field>> {
+    let named = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "value sets can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "value sets can only be derived for structs",
+            ))
+        }
+    };
+
+    named
+        .iter()
+        .map(|field| {
+            let ident = field.ident.clone().unwrap();
+            let mut key = ident.to_string().trim_start_matches("r#").to_string();
+
+            for (name, value) in attributes(&field.attrs)? {
+                match name.as_str() {
+                    "rename" => key = value.value(),
+                    _ => {
+                        return Err(Error::new(
+                            value.span(),
+                            format!("unrecognized value_set attribute `{}`", name),
+                        ))
+                    }
+                }
+            }
+
+            Ok(Field {
+                ident,
+                key,
+                ty: field.ty.clone(),
+                optional: option_type(&field.ty),
+            })
+        })
+        .collect()
+}
+
+// Collects the `name = "value"` pairs of any `#[value_set(...)]` attributes.
+fn attributes(attrs: &[syn::Attribute]) -> Result<Vec<(String, syn::LitStr)>> {
+    let mut pairs = Vec::new();
+
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("value_set")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(Error::new_spanned(meta, "expected `value_set(...)`")),
+        };
+
+        for nested in list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(pair)) => {
+                    match (pair.path.get_ident(), pair.lit) {
+                        (Some(name), Lit::Str(value)) => pairs.push((name.to_string(), value)),
+                        (_, lit) => return Err(Error::new_spanned(lit, "expected a string")),
+                    }
+                }
+                nested => return Err(Error::new_spanned(nested, "expected `name = \"value\"`")),
+            }
+        }
+    }
+
+    Ok(pairs)
+}
+
+// Fields of type `Option<T>` are left out of the bag when `None`.
+fn option_type(ty: &Type) -> Option<Type> {
+    let segment = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+        _ => return None,
+    };
+
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(arguments) if arguments.args.len() == 1 => {
+            match &arguments.args[0] {
+                syn::GenericArgument::Type(ty) => Some(ty.clone()),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
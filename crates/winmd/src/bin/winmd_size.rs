@@ -0,0 +1,40 @@
+//! Attributes the size of a binary's symbols to the namespaces generated by `import!`
+//!
+//! Build with the `WINRT_SYMBOL_MAP` environment variable naming a file to collect the
+//! symbol map, then pipe a demangled symbol listing with sizes into this tool:
+//!
+//! ```text
+//! WINRT_SYMBOL_MAP=symbols.map cargo build --release
+//! llvm-nm -S -C target/release/app.exe | winmd_size symbols.map
+//! ```
+
+use std::io::Read;
+use winmd::SymbolMap;
+
+fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| panic!("Usage: winmd_size <symbol map> < <symbol listing>"));
+
+    let map = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Could not read symbol map at {:?}: {}", path, e));
+    let map = SymbolMap::parse(&map);
+
+    let mut listing = String::new();
+    std::io::stdin()
+        .read_to_string(&mut listing)
+        .unwrap_or_else(|e| panic!("Could not read symbol listing: {}", e));
+
+    let mut sizes: Vec<(String, u64)> = map.account(&listing).into_iter().collect();
+    sizes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    for (namespace, size) in sizes {
+        let namespace = if namespace.is_empty() {
+            "(other)"
+        } else {
+            &namespace
+        };
+
+        println!("{:>10}  {}", size, namespace);
+    }
+}
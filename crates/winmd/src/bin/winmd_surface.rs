@@ -0,0 +1,37 @@
+//! Compares the public items generated by `import!` between two builds
+//!
+//! Build with the `WINRT_SURFACE` environment variable naming a file to record the surface,
+//! then compare the surface of a release against that of the next one:
+//!
+//! ```text
+//! WINRT_SURFACE=new.surface cargo build
+//! winmd_surface old.surface new.surface
+//! ```
+//!
+//! The differences are printed and the tool exits with a non-zero status if any of them
+//! would break code written against the older surface.
+
+use winmd::TypeSurface;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+
+    let (old, new) = match (args.next(), args.next()) {
+        (Some(old), Some(new)) => (read(&old), read(&new)),
+        _ => panic!("Usage: winmd_surface <old surface> <new surface>"),
+    };
+
+    let changes = new.compare(&old);
+    print!("{}", changes);
+
+    if changes.is_breaking() {
+        std::process::exit(1);
+    }
+}
+
+fn read(path: &str) -> TypeSurface {
+    let text = std::fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("Could not read surface at {:?}: {}", path, e));
+
+    TypeSurface::parse(&text)
+}
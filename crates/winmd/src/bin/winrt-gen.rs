@@ -0,0 +1,126 @@
+//! Generates bindings to source files rather than with the `import!` macro
+//!
+//! Takes the same dependencies, modules, and options as `import!` and writes one source file
+//! per namespace to a directory, as `TypeTree::write` does, so that the generated code can be
+//! read, diffed, and shipped as part of a crate:
+//!
+//! ```text
+//! winrt-gen --out src/bindings --option contract_features windows.foundation windows.ui
+//! ```
+//!
+//! Dependencies are given with `--dependency`, which takes the same values as the
+//! `dependencies` of `import!`, and default to `os`. The crate then pulls in the bindings
+//! with `include!("bindings/bindings.rs");`.
+//!
+//! `--option format` runs the files through rustfmt so that they're readable rather than a
+//! single line of tokens per namespace.
+//!
+//! `--type <name>` generates a type, such as `Windows.Foundation.Uri`, along with only the
+//! types it refers to rather than its whole namespace, as the `types` of `import!` do.
+//!
+//! `--alias "windows.ui.xaml.controls as ctrl"` re-exports a namespace module from the root of
+//! the bindings under a shorter name, as the `aliases` of `import!` do.
+//!
+//! `--all` generates every namespace in the metadata rather than the modules given. Along
+//! with the `namespace_features` option, `--features <Cargo.toml>` writes the feature of each
+//! namespace to the `[features]` section of the crate's manifest, so that a crate of bindings
+//! for the whole API only compiles the namespaces its users enable.
+
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use winmd::{load_winmd, TypeLimits, TypeOptions, TypeReader, TypeStage};
+
+const USAGE: &str = "Usage: winrt-gen [--dependency <path>]... [--option <option>]... [--type <name>]... [--alias \"<module> as <alias>\"]... [--features <manifest>] --out <directory> (--all | <module>...)";
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let mut files = BTreeSet::new();
+    let mut options = TypeOptions::default();
+    let mut out = None;
+    let mut features = None;
+    let mut all = false;
+    let mut modules = Vec::new();
+    let mut types = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dependency" => files.append(&mut load_winmd::dependency_files(value(&mut args))),
+            "--option" => options.insert(&value(&mut args)),
+            "--alias" => options.insert_alias(&value(&mut args)),
+            "--out" => out = Some(PathBuf::from(value(&mut args))),
+            "--type" => types.push(value(&mut args)),
+            "--features" => features = Some(PathBuf::from(value(&mut args))),
+            "--all" => all = true,
+            "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            _ => modules.push(arg),
+        }
+    }
+
+    let out = out.unwrap_or_else(|| panic!("{}", USAGE));
+
+    if modules.is_empty() && types.is_empty() && !all {
+        panic!("{}", USAGE);
+    }
+
+    let reader = &if files.is_empty() {
+        TypeReader::from_os()
+    } else {
+        TypeReader::new(load_winmd::from_files(files))
+    };
+
+    let mut limits = TypeLimits::default();
+
+    if all {
+        modules.extend(reader.types.keys().cloned());
+    }
+
+    for module in &modules {
+        limits.insert(reader, module);
+    }
+
+    for name in &types {
+        limits.insert_type(reader, name);
+    }
+
+    let mut stage = TypeStage::from_limits(reader, &limits);
+    options.overrides.apply(&mut stage);
+
+    if options.skip_deprecated {
+        stage.remove_deprecated(reader);
+    }
+
+    if let Some(build) = options.max_build {
+        for contract in stage.limit_to_build(build) {
+            eprintln!(
+                "warning: max_build:{} kept types from {}, which doesn't map to a Windows build",
+                build, contract
+            );
+        }
+    }
+
+    if let Some(manifest) = features {
+        let contents = std::fs::read_to_string(&manifest)
+            .unwrap_or_else(|e| panic!("Could not read {:?}: {}", manifest, e));
+
+        std::fs::write(&manifest, stage.features().to_manifest(&contents))
+            .unwrap_or_else(|e| panic!("Could not write {:?}: {}", manifest, e));
+    }
+
+    let tree = stage.into_tree(reader);
+
+    for (old, new) in reader.take_moved() {
+        eprintln!("warning: `{}` has moved to `{}`", old, new);
+    }
+
+    tree.write(&out, &options)
+        .unwrap_or_else(|e| panic!("Could not write bindings to {:?}: {}", out, e));
+
+    println!("Wrote bindings to {:?}", out);
+}
+
+fn value<I: Iterator<Item = String>>(args: &mut I) -> String {
+    args.next().unwrap_or_else(|| panic!("{}", USAGE))
+}
@@ -7,14 +7,16 @@ pub struct Blob<'a> {
     pub reader: &'a TypeReader,
     pub file_index: u16,
     offset: usize,
+    size: usize,
 }
 
 impl<'a> Blob<'a> {
-    pub fn new(reader: &'a TypeReader, file_index: u16, offset: usize) -> Self {
+    pub fn new(reader: &'a TypeReader, file_index: u16, offset: usize, size: usize) -> Self {
         Blob {
             reader,
             file_index,
             offset,
+            size,
         }
     }
 
@@ -76,6 +78,17 @@ impl<'a> Blob<'a> {
         std::str::from_utf8(&self.file().bytes[self.offset - len..self.offset]).unwrap()
     }
 
+    /// Reads the whole blob as a UTF-16 string, which is how string constants are stored
+    pub fn read_utf16(&mut self) -> String {
+        let wide: Vec<u16> = self.bytes()[..self.size]
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+
+        self.offset += self.size;
+        String::from_utf16_lossy(&wide)
+    }
+
     pub fn read_i8(&mut self) -> i8 {
         let value = i8::from_le_bytes(self.bytes()[..1].try_into().unwrap());
         self.offset += 1;
@@ -49,6 +49,13 @@ pub enum HasConstant {
     Param,
 }
 
+#[type_code(2)]
+pub enum Implementation {
+    File,
+    AssemblyRef,
+    ExportedType,
+}
+
 #[type_code(3)]
 pub enum AttributeType {
     MethodDef = 2,
@@ -12,15 +12,19 @@ pub struct WinmdFile {
     pub strings: u32,
     pub blobs: u32,
     pub guids: u32,
-    pub tables: [TableData; 11],
+    pub tables: [TableData; 15],
 }
 
 #[repr(u16)]
 #[derive(Copy, Clone, Eq, PartialEq, Debug, PartialOrd, Ord)]
 pub enum TableIndex {
-    Constant = 0,
+    Assembly = 0,
+    AssemblyRef,
+    Constant,
     CustomAttribute,
+    ExportedType,
     Field,
+    File,
     GenericParam,
     InterfaceImpl,
     MemberRef,
@@ -158,21 +162,17 @@ impl WinmdFile {
 
         // These tables are unused by WinRT, but needed temporarily to calculate sizes and offsets for subsequent tables.
         let unused_empty = TableData::default();
-        let mut unused_assembly = TableData::default();
         let mut unused_assembly_os = TableData::default();
         let mut unused_assembly_processor = TableData::default();
-        let mut unused_assembly_ref = TableData::default();
         let mut unused_assembly_ref_os = TableData::default();
         let mut unused_assembly_ref_processor = TableData::default();
         let mut unused_class_layout = TableData::default();
         let mut unused_decl_security = TableData::default();
         let mut unused_event = TableData::default();
         let mut unused_event_map = TableData::default();
-        let mut unused_exported_type = TableData::default();
         let mut unused_field_layout = TableData::default();
         let mut unused_field_marshal = TableData::default();
         let mut unused_field_rva = TableData::default();
-        let mut unused_file = TableData::default();
         let mut unused_generic_param_constraint = TableData::default();
         let mut unused_impl_map = TableData::default();
         let mut unused_manifest_resource = TableData::default();
@@ -220,14 +220,14 @@ impl WinmdFile {
                 0x1b => file.tables[TableIndex::TypeSpec as usize].row_count = row_count,
                 0x1c => unused_impl_map.row_count = row_count,
                 0x1d => unused_field_rva.row_count = row_count,
-                0x20 => unused_assembly.row_count = row_count,
+                0x20 => file.tables[TableIndex::Assembly as usize].row_count = row_count,
                 0x21 => unused_assembly_processor.row_count = row_count,
                 0x22 => unused_assembly_os.row_count = row_count,
-                0x23 => unused_assembly_ref.row_count = row_count,
+                0x23 => file.tables[TableIndex::AssemblyRef as usize].row_count = row_count,
                 0x24 => unused_assembly_ref_processor.row_count = row_count,
                 0x25 => unused_assembly_ref_os.row_count = row_count,
-                0x26 => unused_file.row_count = row_count,
-                0x27 => unused_exported_type.row_count = row_count,
+                0x26 => file.tables[TableIndex::File as usize].row_count = row_count,
+                0x27 => file.tables[TableIndex::ExportedType as usize].row_count = row_count,
                 0x28 => unused_manifest_resource.row_count = row_count,
                 0x29 => unused_nested_class.row_count = row_count,
                 0x2a => file.tables[TableIndex::GenericParam as usize].row_count = row_count,
@@ -263,10 +263,10 @@ impl WinmdFile {
             &unused_standalone_sig,
             &unused_module_ref,
             &file.tables[TableIndex::TypeSpec as usize],
-            &unused_assembly,
-            &unused_assembly_ref,
-            &unused_file,
-            &unused_exported_type,
+            &file.tables[TableIndex::Assembly as usize],
+            &file.tables[TableIndex::AssemblyRef as usize],
+            &file.tables[TableIndex::File as usize],
+            &file.tables[TableIndex::ExportedType as usize],
             &unused_manifest_resource,
             &file.tables[TableIndex::GenericParam as usize],
             &unused_generic_param_constraint,
@@ -281,7 +281,7 @@ impl WinmdFile {
         let has_decl_security = composite_index_size(&[
             &file.tables[TableIndex::TypeDef as usize],
             &file.tables[TableIndex::MethodDef as usize],
-            &unused_assembly,
+            &file.tables[TableIndex::Assembly as usize],
         ]);
 
         let member_ref_parent = composite_index_size(&[
@@ -304,8 +304,11 @@ impl WinmdFile {
             &file.tables[TableIndex::MethodDef as usize],
         ]);
 
-        let implementation =
-            composite_index_size(&[&unused_file, &unused_assembly_ref, &unused_exported_type]);
+        let implementation = composite_index_size(&[
+            &file.tables[TableIndex::File as usize],
+            &file.tables[TableIndex::AssemblyRef as usize],
+            &file.tables[TableIndex::ExportedType as usize],
+        ]);
 
         let custom_attribute_type = composite_index_size(&[
             &file.tables[TableIndex::MethodDef as usize],
@@ -318,7 +321,7 @@ impl WinmdFile {
         let resolution_scope = composite_index_size(&[
             &unused_module,
             &unused_module_ref,
-            &unused_assembly_ref,
+            &file.tables[TableIndex::AssemblyRef as usize],
             &file.tables[TableIndex::TypeRef as usize],
         ]);
 
@@ -327,7 +330,7 @@ impl WinmdFile {
             &file.tables[TableIndex::MethodDef as usize],
         ]);
 
-        unused_assembly.set_columns(
+        file.tables[TableIndex::Assembly as usize].set_columns(
             4,
             8,
             4,
@@ -337,7 +340,7 @@ impl WinmdFile {
         );
         unused_assembly_os.set_columns(4, 4, 4, 0, 0, 0);
         unused_assembly_processor.set_columns(4, 0, 0, 0, 0, 0);
-        unused_assembly_ref.set_columns(
+        file.tables[TableIndex::AssemblyRef as usize].set_columns(
             8,
             4,
             blob_index_size,
@@ -345,8 +348,22 @@ impl WinmdFile {
             string_index_size,
             blob_index_size,
         );
-        unused_assembly_ref_os.set_columns(4, 4, 4, unused_assembly_ref.index_size(), 0, 0);
-        unused_assembly_ref_processor.set_columns(4, unused_assembly_ref.index_size(), 0, 0, 0, 0);
+        unused_assembly_ref_os.set_columns(
+            4,
+            4,
+            4,
+            file.tables[TableIndex::AssemblyRef as usize].index_size(),
+            0,
+            0,
+        );
+        unused_assembly_ref_processor.set_columns(
+            4,
+            file.tables[TableIndex::AssemblyRef as usize].index_size(),
+            0,
+            0,
+            0,
+            0,
+        );
         unused_class_layout.set_columns(
             2,
             4,
@@ -381,7 +398,7 @@ impl WinmdFile {
             0,
         );
         unused_event.set_columns(2, string_index_size, type_def_or_ref, 0, 0, 0);
-        unused_exported_type.set_columns(
+        file.tables[TableIndex::ExportedType as usize].set_columns(
             4,
             4,
             string_index_size,
@@ -414,7 +431,14 @@ impl WinmdFile {
             0,
             0,
         );
-        unused_file.set_columns(4, string_index_size, blob_index_size, 0, 0, 0);
+        file.tables[TableIndex::File as usize].set_columns(
+            4,
+            string_index_size,
+            blob_index_size,
+            0,
+            0,
+            0,
+        );
         file.tables[TableIndex::GenericParam as usize].set_columns(
             2,
             2,
@@ -552,14 +576,14 @@ impl WinmdFile {
         file.tables[TableIndex::TypeSpec as usize].set_data(&mut view);
         unused_impl_map.set_data(&mut view);
         unused_field_rva.set_data(&mut view);
-        unused_assembly.set_data(&mut view);
+        file.tables[TableIndex::Assembly as usize].set_data(&mut view);
         unused_assembly_processor.set_data(&mut view);
         unused_assembly_os.set_data(&mut view);
-        unused_assembly_ref.set_data(&mut view);
+        file.tables[TableIndex::AssemblyRef as usize].set_data(&mut view);
         unused_assembly_ref_processor.set_data(&mut view);
         unused_assembly_ref_os.set_data(&mut view);
-        unused_file.set_data(&mut view);
-        unused_exported_type.set_data(&mut view);
+        file.tables[TableIndex::File as usize].set_data(&mut view);
+        file.tables[TableIndex::ExportedType as usize].set_data(&mut view);
         unused_manifest_resource.set_data(&mut view);
         unused_nested_class.set_data(&mut view);
         file.tables[TableIndex::GenericParam as usize].set_data(&mut view);
@@ -1,5 +1,6 @@
 pub struct MethodFlags(pub u32);
 pub struct TypeFlags(pub u32);
+pub struct FieldFlags(pub u32);
 
 #[derive(Default)]
 pub struct ParamFlags(pub u32);
@@ -17,6 +18,15 @@ impl TypeFlags {
     pub fn interface(&self) -> bool {
         self.0 & 0b10_0000 != 0
     }
+    pub fn forwarder(&self) -> bool {
+        self.0 & 0b10_0000_0000_0000_0000_0000 != 0
+    }
+}
+
+impl FieldFlags {
+    pub fn literal(&self) -> bool {
+        self.0 & 0b100_0000 != 0
+    }
 }
 
 impl ParamFlags {
@@ -34,6 +44,19 @@ pub enum TypeCategory {
     Delegate,
 }
 
+impl TypeCategory {
+    /// The kind of type in lowercase, as used in messages
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Interface => "interface",
+            Self::Class => "class",
+            Self::Enum => "enum",
+            Self::Struct => "struct",
+            Self::Delegate => "delegate",
+        }
+    }
+}
+
 #[allow(dead_code)]
 pub enum ParamCategory {
     Array,
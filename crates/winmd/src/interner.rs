@@ -0,0 +1,68 @@
+use crate::case::to_snake;
+use crate::types::MethodKind;
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::rc::Rc;
+
+type SnakeKey = (MethodKind, Rc<str>);
+
+/// Shares the strings read from metadata, and the identifiers derived from them, so that each
+/// is only allocated once
+///
+/// The same namespace and type names are read for every reference to a type, and the same
+/// method and parameter names are converted to snake case for every class that requires an
+/// interface, which adds up to millions of small allocations for large imports.
+#[derive(Default)]
+pub struct Interner {
+    strings: RefCell<BTreeSet<Rc<str>>>,
+    snake: RefCell<BTreeMap<SnakeKey, Rc<str>>>,
+}
+
+impl Interner {
+    /// The shared copy of a string
+    pub fn intern(&self, value: &str) -> Rc<str> {
+        if let Some(value) = self.strings.borrow().get(value) {
+            return value.clone();
+        }
+
+        let value: Rc<str> = value.into();
+        self.strings.borrow_mut().insert(value.clone());
+        value
+    }
+
+    /// The shared snake case conversion of a name, as by `case::to_snake`
+    pub fn to_snake(&self, camel: &str, kind: MethodKind) -> Rc<str> {
+        let camel = self.intern(camel);
+        let key = (kind, camel);
+
+        if let Some(snake) = self.snake.borrow().get(&key) {
+            return snake.clone();
+        }
+
+        let snake = self.intern(&to_snake(&key.1, kind));
+        self.snake.borrow_mut().insert(key, snake.clone());
+        snake
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern() {
+        let interner = Interner::default();
+        let first = interner.intern("Windows.Foundation");
+        let second = interner.intern("Windows.Foundation");
+        assert!(Rc::ptr_eq(&first, &second));
+
+        let snake = interner.to_snake("GetResults", MethodKind::Normal);
+        assert_eq!(&*snake, "get_results");
+        assert!(Rc::ptr_eq(
+            &snake,
+            &interner.to_snake("GetResults", MethodKind::Normal)
+        ));
+        assert_eq!(&*interner.to_snake("Size", MethodKind::Set), "set_size");
+    }
+}
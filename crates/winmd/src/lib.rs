@@ -3,19 +3,33 @@ mod case;
 mod codes;
 mod file;
 mod flags;
+mod interner;
+mod literal_pool;
 mod row;
+mod symbol_map;
 mod tables;
+mod type_features;
 mod type_limits;
+mod type_manifest;
 mod type_namespaces;
+mod type_options;
+mod type_overrides;
 mod type_reader;
 mod type_stage;
+mod type_surface;
 mod type_tree;
 mod types;
 
 pub mod load_winmd;
+pub use symbol_map::SymbolMap;
+pub use type_features::TypeFeatures;
 pub use type_limits::TypeLimits;
+pub use type_manifest::TypeManifest;
+pub use type_options::TypeOptions;
+pub use type_overrides::{MemberOverride, TypeOverrides};
 pub use type_reader::TypeReader;
 pub use type_stage::TypeStage;
+pub use type_surface::{SurfaceChanges, TypeSurface};
 
 fn format_ident(name: &str) -> proc_macro2::Ident {
     if name == "Self" {
@@ -0,0 +1,297 @@
+use proc_macro2::{Delimiter, Group, Ident, TokenStream, TokenTree};
+use quote::{format_ident, quote};
+
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
+
+/// Pools literals that are repeated across the generated code into shared statics
+///
+/// GUIDs are repeated for every class and interface that requires the same interface, and the
+/// type and method names passed to `MethodCheck::new` are repeated for every method of an
+/// interface, as are the type names that `RuntimeName` gives classes and interfaces. Literals
+/// that appear more than once are moved into statics in a hidden `__pool` module at the root of
+/// the generated code and referenced from there, so that each is written once in both the source
+/// and the binary. Namespace names only appear within type names, which are pooled whole.
+#[derive(Default)]
+pub struct LiteralPool {
+    guids: BTreeMap<String, (usize, Group)>,
+    strings: BTreeMap<String, usize>,
+}
+
+impl LiteralPool {
+    /// Rewrites the generated code so that repeated literals refer to the pool
+    pub fn apply(tokens: TokenStream) -> TokenStream {
+        let mut pool = Self::default();
+        pool.count(tokens.clone());
+
+        let guids: BTreeMap<String, Ident> = pool
+            .guids
+            .iter()
+            .filter(|(_, (count, _))| *count > 1)
+            .enumerate()
+            .map(|(index, (key, _))| (key.clone(), format_ident!("GUID_{}", index)))
+            .collect();
+
+        let strings: BTreeMap<String, Ident> = pool
+            .strings
+            .iter()
+            .filter(|(_, count)| **count > 1)
+            .enumerate()
+            .map(|(index, (key, _))| (key.clone(), format_ident!("STRING_{}", index)))
+            .collect();
+
+        if guids.is_empty() && strings.is_empty() {
+            return tokens;
+        }
+
+        let names = Names { guids, strings };
+        let tokens = names.rewrite(tokens, 0);
+
+        let guids = TokenStream::from_iter(names.guids.iter().map(|(key, name)| {
+            let values = &pool.guids[key].1;
+            quote! { pub static #name: ::winrt::Guid = ::winrt::Guid::from_values #values; }
+        }));
+
+        let strings = TokenStream::from_iter(names.strings.iter().map(|(value, name)| {
+            let value: TokenStream = value.parse().unwrap();
+            quote! { pub static #name: &str = #value; }
+        }));
+
+        quote! {
+            #[doc(hidden)]
+            pub mod __pool {
+                #guids
+                #strings
+            }
+            #tokens
+        }
+    }
+
+    fn count(&mut self, tokens: TokenStream) {
+        let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+
+        for (index, token) in tokens.iter().enumerate() {
+            if is_runtime_name(&tokens, index) {
+                *self.strings.entry(token.to_string()).or_default() += 1;
+            }
+
+            if let TokenTree::Group(group) = token {
+                match call_name(&tokens, index).as_deref() {
+                    Some("from_values") => {
+                        self.guids
+                            .entry(group.stream().to_string())
+                            .or_insert_with(|| (0, group.clone()))
+                            .0 += 1;
+                    }
+                    Some("MethodCheck") => {
+                        for token in group.stream() {
+                            if let TokenTree::Literal(literal) = token {
+                                *self.strings.entry(literal.to_string()).or_default() += 1;
+                            }
+                        }
+                    }
+                    _ => self.count(group.stream()),
+                }
+            }
+        }
+    }
+}
+
+struct Names {
+    guids: BTreeMap<String, Ident>,
+    strings: BTreeMap<String, Ident>,
+}
+
+impl Names {
+    fn rewrite(&self, tokens: TokenStream, depth: usize) -> TokenStream {
+        let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+        let mut result: Vec<TokenTree> = Vec::with_capacity(tokens.len());
+
+        for (index, token) in tokens.iter().enumerate() {
+            let group = match token {
+                TokenTree::Group(group) => group,
+                _ => {
+                    match self.strings.get(&token.to_string()) {
+                        Some(name) if is_runtime_name(&tokens, index) => {
+                            result.extend(pool_path(depth, name))
+                        }
+                        _ => result.push(token.clone()),
+                    }
+                    continue;
+                }
+            };
+
+            match call_name(&tokens, index).as_deref() {
+                Some("from_values") => {
+                    if let Some(name) = self.guids.get(&group.stream().to_string()) {
+                        // Replace the whole `::winrt::Guid::from_values(...)` call. The path
+                        // holds no groups so it was copied to the result as is.
+                        result.truncate(result.len() - guid_call().into_iter().count());
+                        result.extend(pool_path(depth, name));
+                        continue;
+                    }
+                }
+                Some("MethodCheck") => {
+                    let args = group.stream().into_iter().flat_map(|token| match &token {
+                        TokenTree::Literal(literal) => {
+                            match self.strings.get(&literal.to_string()) {
+                                Some(name) => pool_path(depth, name),
+                                None => TokenStream::from(token),
+                            }
+                        }
+                        _ => TokenStream::from(token),
+                    });
+
+                    result.push(with_stream(group, TokenStream::from_iter(args)));
+                    continue;
+                }
+                _ => {}
+            }
+
+            // Generated code only uses `mod` for namespaces so that's enough to track the depth.
+            let depth = if group.delimiter() == Delimiter::Brace && is_mod(&tokens, index) {
+                depth + 1
+            } else {
+                depth
+            };
+
+            result.push(with_stream(group, self.rewrite(group.stream(), depth)));
+        }
+
+        TokenStream::from_iter(result)
+    }
+}
+
+/// The name of the function called with the group at `index`, if any
+///
+/// For `::winrt::MethodCheck::new(...)` this returns `MethodCheck` so that only those calls are
+/// matched.
+fn call_name(tokens: &[TokenTree], index: usize) -> Option<String> {
+    match tokens.get(index) {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {}
+        _ => return None,
+    }
+
+    if ends_with(&tokens[..index], quote! { ::winrt::MethodCheck::new }) {
+        Some("MethodCheck".to_string())
+    } else if ends_with(&tokens[..index], guid_call()) {
+        Some("from_values".to_string())
+    } else {
+        None
+    }
+}
+
+// The path that generated code calls to create GUIDs, which is replaced along with its arguments.
+fn guid_call() -> TokenStream {
+    quote! { ::winrt::Guid::from_values }
+}
+
+fn ends_with(tokens: &[TokenTree], path: TokenStream) -> bool {
+    let path: Vec<String> = path.into_iter().map(|token| token.to_string()).collect();
+
+    tokens.len() >= path.len()
+        && tokens[tokens.len() - path.len()..]
+            .iter()
+            .zip(&path)
+            .all(|(token, expected)| token.to_string() == *expected)
+}
+
+/// Whether the token at `index` is the string given to `RuntimeName::NAME`, which generated
+/// code writes as `const NAME: &'static str = "...";`
+fn is_runtime_name(tokens: &[TokenTree], index: usize) -> bool {
+    if !matches!(tokens.get(index), Some(TokenTree::Literal(_))) || index < 7 {
+        return false;
+    }
+
+    let before: Vec<String> = tokens[index - 7..index]
+        .iter()
+        .map(|token| token.to_string())
+        .collect();
+
+    before == ["NAME", ":", "&", "'", "static", "str", "="]
+}
+
+fn is_mod(tokens: &[TokenTree], index: usize) -> bool {
+    match index.checked_sub(2).map(|index| &tokens[index]) {
+        Some(TokenTree::Ident(ident)) => ident == "mod",
+        _ => false,
+    }
+}
+
+fn pool_path(depth: usize, name: &Ident) -> TokenStream {
+    let supers = TokenStream::from_iter((0..depth).map(|_| quote! { super:: }));
+    quote! { #supers __pool::#name }
+}
+
+fn with_stream(group: &Group, stream: TokenStream) -> TokenTree {
+    let mut result = Group::new(group.delimiter(), stream);
+    result.set_span(group.span());
+    TokenTree::Group(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool() {
+        let tokens = quote! {
+            pub mod windows {
+                pub mod foundation {
+                    const A: ::winrt::Guid = ::winrt::Guid::from_values(1u32, 2u16, 3u16, [4u8]);
+                    const B: ::winrt::Guid = ::winrt::Guid::from_values(1u32, 2u16, 3u16, [4u8]);
+                    const C: ::winrt::Guid = ::winrt::Guid::from_values(5u32, 6u16, 7u16, [8u8]);
+                    fn f() {
+                        static CHECK: ::winrt::MethodCheck = ::winrt::MethodCheck::new("IFoo", "Bar");
+                        panic!("IFoo");
+                    }
+                    fn g() {
+                        static CHECK: ::winrt::MethodCheck = ::winrt::MethodCheck::new("IFoo", "Baz");
+                    }
+                    impl ::winrt::RuntimeName for IFoo {
+                        const NAME: &'static str = "IFoo";
+                    }
+                }
+            }
+        };
+
+        let expected = quote! {
+            #[doc(hidden)]
+            pub mod __pool {
+                pub static GUID_0: ::winrt::Guid = ::winrt::Guid::from_values(1u32, 2u16, 3u16, [4u8]);
+                pub static STRING_0: &str = "IFoo";
+            }
+            pub mod windows {
+                pub mod foundation {
+                    const A: ::winrt::Guid = super::super::__pool::GUID_0;
+                    const B: ::winrt::Guid = super::super::__pool::GUID_0;
+                    const C: ::winrt::Guid = ::winrt::Guid::from_values(5u32, 6u16, 7u16, [8u8]);
+                    fn f() {
+                        static CHECK: ::winrt::MethodCheck = ::winrt::MethodCheck::new(super::super::__pool::STRING_0, "Bar");
+                        panic!("IFoo");
+                    }
+                    fn g() {
+                        static CHECK: ::winrt::MethodCheck = ::winrt::MethodCheck::new(super::super::__pool::STRING_0, "Baz");
+                    }
+                    impl ::winrt::RuntimeName for IFoo {
+                        const NAME: &'static str = super::super::__pool::STRING_0;
+                    }
+                }
+            }
+        };
+
+        assert_eq!(LiteralPool::apply(tokens).to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_nothing_to_pool() {
+        let tokens = quote! {
+            const A: ::winrt::Guid = ::winrt::Guid::from_values(1u32, 2u16, 3u16, [4u8]);
+        };
+
+        assert_eq!(
+            LiteralPool::apply(tokens.clone()).to_string(),
+            tokens.to_string()
+        );
+    }
+}
@@ -1,30 +1,361 @@
 use crate::file::WinmdFile;
 
+use std::collections::BTreeSet;
 use std::path::{Path, PathBuf};
 
+#[cfg(windows)]
+mod resolve;
+
+#[cfg(not(windows))]
+mod resolve {
+    pub fn resolve_namespace(_: &str) -> Option<Vec<std::path::PathBuf>> {
+        None
+    }
+}
+
 /// Get [`WinmdFile`]s from the operating system
 ///
 /// This searches well known paths for Windows metadata related to
 /// operating system APIs.
 pub fn from_os() -> Vec<WinmdFile> {
+    from_dir(os_dir())
+}
+
+/// Get [`WinmdFile`]s from the Windows App SDK
+///
+/// This includes the Microsoft.UI.* metadata for WinUI 3 along with the operating
+/// system metadata that it depends on.
+pub fn from_windows_app_sdk() -> Vec<WinmdFile> {
+    from_files(
+        windows_app_sdk_files()
+            .into_iter()
+            .chain(winmd_files(os_dir())),
+    )
+}
+
+/// Get [`WinmdFile`]s from a specific version of the Windows SDK
+///
+/// The version is the SDK's full version string, such as "10.0.19041.0".
+pub fn from_sdk(version: &str) -> Vec<WinmdFile> {
+    from_dir(sdk_dir(version))
+}
+
+/// Get [`WinmdFile`]s that define a namespace
+///
+/// See [`namespace_files`] for how the files are found.
+pub fn from_namespace(namespace: &str) -> Vec<WinmdFile> {
+    from_files(namespace_files(namespace))
+}
+
+/// Get [`WinmdFile`]s from a directory
+pub fn from_dir<P: AsRef<Path>>(directory: P) -> Vec<WinmdFile> {
+    from_files(winmd_files(directory))
+}
+
+/// Get [`WinmdFile`]s from an iterator of file paths
+pub fn from_files<P: IntoIterator<Item = PathBuf>>(filenames: P) -> Vec<WinmdFile> {
+    filenames.into_iter().map(WinmdFile::new).collect()
+}
+
+/// The paths of the metadata files named by a dependency
+///
+/// A dependency is a `.winmd` file, a directory of them, or one of these well-known sets:
+///
+/// * `os`: the operating system's metadata
+/// * `sdk:<version>`: the union metadata of a specific version of the Windows SDK
+/// * `namespace:<namespace>`: the files that define a namespace, see [`namespace_files`]
+/// * `windows_app_sdk`: the Windows App SDK metadata along with the operating system's
+///
+/// # Panics
+///
+/// Panics if the dependency is none of these.
+pub fn dependency_files<P: AsRef<Path>>(dependency: P) -> BTreeSet<PathBuf> {
+    let path = dependency.as_ref();
+    let mut result = BTreeSet::new();
+
+    if path.is_dir() {
+        let paths = std::fs::read_dir(path).unwrap_or_else(|e| {
+            panic!(
+                "Could not read dependecy directory at path {:?}: {}",
+                path, e
+            )
+        });
+        for path in paths.flatten() {
+            let path = path.path();
+            if path.is_file()
+                && path
+                    .extension()
+                    .is_some_and(|e| e.eq_ignore_ascii_case("winmd"))
+            {
+                result.insert(path);
+            }
+        }
+    } else if path.is_file() {
+        result.insert(path.to_path_buf());
+    } else if path.to_str() == Some("os") {
+        result.append(&mut dependency_files(os_dir()));
+    } else if let Some(version) = path.to_str().and_then(|p| p.strip_prefix("sdk:")) {
+        result.append(&mut dependency_files(sdk_dir(version)));
+    } else if let Some(namespace) = path.to_str().and_then(|p| p.strip_prefix("namespace:")) {
+        result.extend(namespace_files(namespace));
+    } else if path.to_str() == Some("windows_app_sdk") {
+        // The Windows App SDK profile includes the Microsoft.UI.* metadata for WinUI 3.
+        // Those types depend on the OS metadata so that is always included as well.
+        result.extend(windows_app_sdk_files());
+        result.append(&mut dependency_files(os_dir()));
+    } else {
+        panic!("Dependency {:?} is not a file or directory", path);
+    }
+
+    result
+}
+
+/// The directory containing the operating system's Windows metadata
+pub fn os_dir() -> PathBuf {
     let windir = std::env::var("windir").expect("No `windir` environent variable set");
     let mut path = PathBuf::from(windir);
     path.push(super::SYSTEM32);
     path.push("winmetadata");
-    from_dir(path)
+    path
 }
 
-/// Get [`WinmdFile`]s from a directory
-pub fn from_dir<P: AsRef<Path>>(directory: P) -> Vec<WinmdFile> {
-    let files = std::fs::read_dir(directory)
+/// The paths of the metadata files that define a namespace
+///
+/// On Windows the files are resolved at runtime with `RoResolveNamespace`, which doesn't
+/// depend on the Windows SDK being installed and also finds metadata registered by packaged
+/// apps. If that isn't available or fails, the files in the operating system's metadata
+/// directory named after the namespace or one of its parents are returned instead.
+pub fn namespace_files(namespace: &str) -> Vec<PathBuf> {
+    if let Some(files) = resolve::resolve_namespace(namespace) {
+        if !files.is_empty() {
+            return files;
+        }
+    }
+
+    files_for_namespace(winmd_files(os_dir()), namespace)
+}
+
+/// Picks the files whose name is the namespace or one of its parents, as metadata files are
+/// named after the namespace they define
+fn files_for_namespace<I: IntoIterator<Item = PathBuf>>(files: I, namespace: &str) -> Vec<PathBuf> {
+    let namespace = namespace.to_lowercase();
+
+    files
+        .into_iter()
+        .filter(|path| {
+            let stem = match path.file_stem().and_then(|stem| stem.to_str()) {
+                Some(stem) => stem.to_lowercase(),
+                None => return false,
+            };
+
+            namespace == stem || namespace.starts_with(&format!("{}.", stem))
+        })
+        .collect()
+}
+
+/// The directory containing the union metadata of a specific version of the Windows SDK
+///
+/// The SDK is located using the `WindowsSdkDir` environment variable, which is set by the
+/// Visual Studio developer command prompt, and otherwise its default install location.
+pub fn sdk_dir(version: &str) -> PathBuf {
+    let mut path = match std::env::var_os("WindowsSdkDir") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(r"C:\Program Files (x86)\Windows Kits\10"),
+    };
+
+    path.push("UnionMetadata");
+    path.push(version);
+
+    if !path.is_dir() {
+        panic!("Windows SDK version `{}` not found at {:?}", version, path);
+    }
+
+    path
+}
+
+/// The paths of the Windows App SDK metadata files
+///
+/// The `WINDOWS_APP_SDK_DIR` environment variable may point to an unpacked
+/// Microsoft.WindowsAppSDK package. Otherwise the latest version found in the
+/// NuGet package cache is used.
+pub fn windows_app_sdk_files() -> Vec<PathBuf> {
+    let package = match std::env::var_os("WINDOWS_APP_SDK_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            let mut packages = match std::env::var_os("NUGET_PACKAGES") {
+                Some(dir) => PathBuf::from(dir),
+                None => {
+                    let profile = std::env::var("USERPROFILE")
+                        .expect("No `USERPROFILE` environment variable set");
+                    let mut path = PathBuf::from(profile);
+                    path.push(".nuget");
+                    path.push("packages");
+                    path
+                }
+            };
+            packages.push("microsoft.windowsappsdk");
+
+            let versions = std::fs::read_dir(&packages)
+                .unwrap_or_else(|e| {
+                    panic!(
+                        "Could not find the Windows App SDK package at {:?}: {}",
+                        packages, e
+                    )
+                })
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| entry.file_name().into_string().ok());
+
+            let version = latest_version(versions)
+                .unwrap_or_else(|| panic!("No Windows App SDK versions found at {:?}", packages));
+
+            packages.push(version);
+            packages
+        }
+    };
+
+    windows_app_sdk_package_files(&package)
+}
+
+fn windows_app_sdk_package_files(package: &Path) -> Vec<PathBuf> {
+    // The package places its metadata under `lib/uap10.0*` folders so we walk the
+    // `lib` folder and pick up every winmd file.
+    let mut files = Vec::new();
+    let mut pending = vec![package.join("lib")];
+
+    while let Some(dir) = pending.pop() {
+        if let Ok(entries) = std::fs::read_dir(&dir) {
+            for entry in entries.filter_map(|entry| entry.ok()) {
+                let path = entry.path();
+
+                if path.is_dir() {
+                    pending.push(path);
+                } else if is_winmd(&path) {
+                    files.push(path);
+                }
+            }
+        }
+    }
+
+    files.sort();
+    files
+}
+
+fn winmd_files<P: AsRef<Path>>(directory: P) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(directory)
         .unwrap()
         .filter_map(|value| value.ok())
-        .map(|value| value.path());
-    // TODO: filter out directories and non-metadata files
-    from_files(files)
+        .map(|value| value.path())
+        .filter(|path| is_winmd(path))
+        .collect();
+
+    files.sort();
+    files
 }
 
-/// Get [`WinmdFile`]s from an iterator of file paths
-pub fn from_files<P: IntoIterator<Item = PathBuf>>(filenames: P) -> Vec<WinmdFile> {
-    filenames.into_iter().map(WinmdFile::new).collect()
+fn is_winmd(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .map(|extension| extension.eq_ignore_ascii_case("winmd"))
+            .unwrap_or(false)
+}
+
+/// Picks the highest dotted version, ignoring any pre-release suffix when comparing
+fn latest_version<I: IntoIterator<Item = String>>(versions: I) -> Option<String> {
+    fn parse(version: &str) -> Vec<u32> {
+        version
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect()
+    }
+
+    versions
+        .into_iter()
+        .max_by(|a, b| parse(a).cmp(&parse(b)).then_with(|| b.len().cmp(&a.len())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_files_for_namespace() {
+        let files = vec![
+            PathBuf::from("Windows.Foundation.winmd"),
+            PathBuf::from("Windows.Foundation.FoundationContract.winmd"),
+            PathBuf::from("Windows.UI.winmd"),
+            PathBuf::from("Windows.UI.Xaml.winmd"),
+        ];
+
+        assert!(
+            files_for_namespace(files.clone(), "Windows.Foundation.Collections")
+                == [PathBuf::from("Windows.Foundation.winmd")]
+        );
+        assert!(
+            files_for_namespace(files.clone(), "windows.ui.xaml.controls")
+                == [
+                    PathBuf::from("Windows.UI.winmd"),
+                    PathBuf::from("Windows.UI.Xaml.winmd")
+                ]
+        );
+        assert!(files_for_namespace(files, "Windows.Storage").is_empty());
+    }
+
+    #[test]
+    fn test_sdk_versions() {
+        let older = crate::TypeReader::cached(dependency_files("sdk:10.0.17763.0"));
+        let newer = crate::TypeReader::cached(dependency_files("sdk:10.0.19041.0"));
+
+        // GuidHelper was added in 10.0.19041.0, so only the newer SDK's metadata defines it.
+        assert!(older
+            .find_type_def(("Windows.Foundation", "GuidHelper"))
+            .is_none());
+        assert!(newer
+            .find_type_def(("Windows.Foundation", "GuidHelper"))
+            .is_some());
+    }
+
+    #[test]
+    fn test_latest_version() {
+        let versions = vec![
+            "1.0.0".to_owned(),
+            "1.2.221109.1".to_owned(),
+            "1.10.0".to_owned(),
+            "1.2.0".to_owned(),
+        ];
+        assert!(latest_version(versions).unwrap() == "1.10.0");
+
+        let versions = vec!["1.0.0-preview1".to_owned(), "1.0.0".to_owned()];
+        assert!(latest_version(versions).unwrap() == "1.0.0");
+
+        let versions = vec!["1.1.0+build".to_owned(), "1.1.1".to_owned()];
+        assert!(latest_version(versions).unwrap() == "1.1.1");
+
+        assert!(latest_version(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn test_windows_app_sdk_files() {
+        let package = std::env::temp_dir().join(format!("winmd-app-sdk-{}", std::process::id()));
+        let metadata = package.join("lib").join("uap10.0.18362");
+        std::fs::create_dir_all(&metadata).unwrap();
+        std::fs::write(metadata.join("Microsoft.UI.winmd"), b"").unwrap();
+        std::fs::write(metadata.join("Microsoft.UI.Xaml.winmd"), b"").unwrap();
+        std::fs::write(metadata.join("Microsoft.UI.xml"), b"").unwrap();
+
+        // The metadata is found in the package's nested `lib` folders, skipping other files.
+        let files = windows_app_sdk_package_files(&package);
+        std::fs::remove_dir_all(&package).unwrap();
+
+        assert!(
+            files
+                == vec![
+                    metadata.join("Microsoft.UI.Xaml.winmd"),
+                    metadata.join("Microsoft.UI.winmd"),
+                ]
+        );
+    }
 }
@@ -0,0 +1,115 @@
+use std::ffi::c_void;
+use std::os::windows::ffi::OsStrExt;
+use std::path::PathBuf;
+
+type HString = *mut c_void;
+
+type WindowsCreateString = extern "system" fn(*const u16, u32, *mut HString) -> i32;
+type WindowsDeleteString = extern "system" fn(HString) -> i32;
+type WindowsGetStringRawBuffer = extern "system" fn(HString, *mut u32) -> *const u16;
+type RoInitialize = extern "system" fn(i32) -> i32;
+type CoTaskMemFree = extern "system" fn(*mut c_void);
+
+type RoResolveNamespace = extern "system" fn(
+    HString,
+    HString,
+    u32,
+    *const HString,
+    *mut u32,
+    *mut *mut HString,
+    *mut u32,
+    *mut *mut HString,
+) -> i32;
+
+#[link(name = "kernel32")]
+extern "system" {
+    fn LoadLibraryW(name: *const u16) -> *mut c_void;
+    fn GetProcAddress(library: *mut c_void, name: *const u8) -> *mut c_void;
+}
+
+/// Resolves the metadata files for a namespace with `RoResolveNamespace`
+///
+/// The functions are loaded dynamically so that this degrades gracefully, by returning
+/// `None`, on versions of Windows that don't provide them.
+pub fn resolve_namespace(namespace: &str) -> Option<Vec<PathBuf>> {
+    unsafe {
+        let combase = library("combase.dll")?;
+        let wintypes = library("wintypes.dll")?;
+
+        let create: WindowsCreateString = function(combase, b"WindowsCreateString\0")?;
+        let delete: WindowsDeleteString = function(combase, b"WindowsDeleteString\0")?;
+        let buffer: WindowsGetStringRawBuffer = function(combase, b"WindowsGetStringRawBuffer\0")?;
+        let initialize: RoInitialize = function(combase, b"RoInitialize\0")?;
+        let free: CoTaskMemFree = function(combase, b"CoTaskMemFree\0")?;
+        let resolve: RoResolveNamespace = function(wintypes, b"RoResolveNamespace\0")?;
+
+        // The thread may already be initialized, possibly for another apartment, which is fine.
+        initialize(1);
+
+        let wide: Vec<u16> = namespace.encode_utf16().collect();
+        let mut name = std::ptr::null_mut();
+
+        if create(wide.as_ptr(), wide.len() as u32, &mut name) < 0 {
+            return None;
+        }
+
+        let mut count = 0;
+        let mut paths = std::ptr::null_mut();
+
+        let result = resolve(
+            name,
+            std::ptr::null_mut(),
+            0,
+            std::ptr::null(),
+            &mut count,
+            &mut paths,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        );
+
+        delete(name);
+
+        if result < 0 {
+            return None;
+        }
+
+        let mut files = Vec::with_capacity(count as usize);
+
+        for index in 0..count as usize {
+            let path = *paths.add(index);
+            let mut len = 0;
+            let data = buffer(path, &mut len);
+            let path_wide = std::slice::from_raw_parts(data, len as usize);
+            files.push(PathBuf::from(String::from_utf16_lossy(path_wide)));
+            delete(path);
+        }
+
+        free(paths as *mut c_void);
+        Some(files)
+    }
+}
+
+unsafe fn library(name: &str) -> Option<*mut c_void> {
+    let name: Vec<u16> = std::ffi::OsStr::new(name)
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+
+    let library = LoadLibraryW(name.as_ptr());
+
+    if library.is_null() {
+        None
+    } else {
+        Some(library)
+    }
+}
+
+unsafe fn function<F>(library: *mut c_void, name: &[u8]) -> Option<F> {
+    let function = GetProcAddress(library, name.as_ptr());
+
+    if function.is_null() {
+        None
+    } else {
+        Some(std::mem::transmute_copy(&function))
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// A map from generated module paths to the namespaces they were generated from
+///
+/// The map is written by the `import!` macro when the `WINRT_SYMBOL_MAP` environment
+/// variable names a file, one `module<TAB>namespace` pair per line. It can then be used to
+/// attribute the symbols of a finished binary back to the namespaces that produced them.
+#[derive(Default, Debug, PartialEq)]
+pub struct SymbolMap(pub BTreeMap<String, String>);
+
+impl SymbolMap {
+    /// Insert a generated module path and the namespace it was generated from
+    pub fn insert(&mut self, module: String, namespace: String) {
+        self.0.insert(module, namespace);
+    }
+
+    /// Parse a map previously written with its `Display` implementation
+    ///
+    /// Blank or malformed lines are ignored so that maps appended by several
+    /// `import!` invocations can be read back as one.
+    pub fn parse(text: &str) -> Self {
+        let mut map = Self::default();
+
+        for line in text.lines() {
+            let mut parts = line.splitn(2, '\t');
+
+            if let (Some(module), Some(namespace)) = (parts.next(), parts.next()) {
+                map.insert(module.trim().to_string(), namespace.trim().to_string());
+            }
+        }
+
+        map
+    }
+
+    /// Returns the namespace a demangled symbol was generated from, if any
+    ///
+    /// The longest matching module path wins so that symbols in nested namespaces
+    /// are not attributed to their parents.
+    pub fn namespace(&self, symbol: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .filter(|(module, _)| contains_module(symbol, module))
+            .max_by_key(|(module, _)| module.len())
+            .map(|(_, namespace)| namespace.as_str())
+    }
+
+    /// Sums symbol sizes per namespace from a demangled symbol listing
+    ///
+    /// The listing is expected in the format produced by `nm -S -C` (or `llvm-nm -S -C`),
+    /// where each line holds an address, a hexadecimal size, a symbol type and a name.
+    /// Lines without a size are ignored and symbols that don't belong to any generated
+    /// namespace are summed under an empty namespace.
+    pub fn account(&self, listing: &str) -> BTreeMap<String, u64> {
+        let mut sizes = BTreeMap::new();
+
+        for line in listing.lines() {
+            let parts: Vec<&str> = line.trim().splitn(4, ' ').collect();
+
+            if parts.len() != 4 || parts[2].len() != 1 {
+                continue;
+            }
+
+            let size = match u64::from_str_radix(parts[1], 16) {
+                Ok(size) => size,
+                Err(_) => continue,
+            };
+
+            let namespace = self.namespace(parts[3]).unwrap_or_default();
+            *sizes.entry(namespace.to_string()).or_default() += size;
+        }
+
+        sizes
+    }
+}
+
+impl fmt::Display for SymbolMap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (module, namespace) in &self.0 {
+            writeln!(f, "{}\t{}", module, namespace)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns true if the symbol contains the module path as whole path segments
+fn contains_module(symbol: &str, module: &str) -> bool {
+    symbol.match_indices(module).any(|(pos, _)| {
+        let before = &symbol[..pos];
+        let after = &symbol[pos + module.len()..];
+        (before.is_empty() || before.ends_with("::") || before.ends_with('<'))
+            && after.starts_with("::")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map() -> SymbolMap {
+        SymbolMap::parse(
+            "windows::foundation\tWindows.Foundation\n\
+             windows::foundation::collections\tWindows.Foundation.Collections\n\
+             \n\
+             windows::foundation\tWindows.Foundation\n",
+        )
+    }
+
+    #[test]
+    fn test_parse() {
+        let map = map();
+        assert_eq!(map.0.len(), 2);
+        assert_eq!(SymbolMap::parse(&map.to_string()), map);
+    }
+
+    #[test]
+    fn test_namespace() {
+        let map = map();
+
+        assert_eq!(
+            map.namespace("app::windows::foundation::Uri::create_uri"),
+            Some("Windows.Foundation")
+        );
+        assert_eq!(
+            map.namespace(
+                "<app::windows::foundation::collections::StringMap as core::ops::drop::Drop>::drop"
+            ),
+            Some("Windows.Foundation.Collections")
+        );
+        assert_eq!(map.namespace("app::windows::foundation_extra::f"), None);
+        assert_eq!(map.namespace("app::main"), None);
+    }
+
+    #[test]
+    fn test_account() {
+        let sizes = map().account(
+            "0000000000001000 0000000000000010 T app::windows::foundation::Uri::new\n\
+             0000000000001010 0000000000000020 t app::windows::foundation::collections::IIterable::first\n\
+             0000000000001030 0000000000000004 T app::main\n\
+             0000000000001040 0000000000000008 T <app::windows::foundation::Uri as core::fmt::Debug>::fmt\n\
+                              U GetProcAddress\n",
+        );
+
+        assert_eq!(sizes["Windows.Foundation"], 0x18);
+        assert_eq!(sizes["Windows.Foundation.Collections"], 0x20);
+        assert_eq!(sizes[""], 0x4);
+        assert_eq!(sizes.len(), 3);
+    }
+}
@@ -0,0 +1,11 @@
+use crate::row::Row;
+use crate::TypeReader;
+
+#[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Debug)]
+pub struct Assembly(pub Row);
+
+impl Assembly {
+    pub fn name(self, reader: &TypeReader) -> &str {
+        reader.str(self.0, 4)
+    }
+}
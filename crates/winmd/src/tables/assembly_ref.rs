@@ -0,0 +1,11 @@
+use crate::row::Row;
+use crate::TypeReader;
+
+#[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Debug)]
+pub struct AssemblyRef(pub Row);
+
+impl AssemblyRef {
+    pub fn name(self, reader: &TypeReader) -> &str {
+        reader.str(self.0, 3)
+    }
+}
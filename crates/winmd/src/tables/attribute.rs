@@ -1,5 +1,5 @@
 use super::TypeDef;
-use crate::codes::{AttributeType, HasAttribute, MemberRefParent};
+use crate::codes::{AttributeType, Decode, HasAttribute, MemberRefParent, TypeDefOrRef};
 use crate::row::Row;
 use crate::TypeReader;
 
@@ -52,12 +52,14 @@ impl Attribute {
                 0x0B => AttributeArg::U64(values.read_u64()),
                 0x0E => AttributeArg::String(values.read_str().to_string()),
                 0x11 | 0x12 => {
-                    sig.read_unsigned();
-                    let name = values.read_str();
-                    let index = name.rfind('.').unwrap();
-                    AttributeArg::TypeDef(
-                        reader.resolve_type_def((&name[0..index], &name[index + 1..])),
-                    )
+                    let code = TypeDefOrRef::decode(sig.read_unsigned(), sig.file_index);
+
+                    if code.name(reader) == ("System", "Type") {
+                        AttributeArg::from_type_name(reader, values.read_str())
+                    } else {
+                        // Enum arguments are stored as their underlying 32-bit value.
+                        AttributeArg::I32(values.read_i32())
+                    }
                 }
                 _ => panic!(),
             };
@@ -74,13 +76,7 @@ impl Attribute {
                 0x02 => AttributeArg::Bool(values.read_u8() != 0),
                 0x08 => AttributeArg::I32(values.read_i32()),
                 0x0E => AttributeArg::String(values.read_str().to_string()),
-                0x50 => {
-                    let name = values.read_str();
-                    let index = name.rfind('.').unwrap();
-                    AttributeArg::TypeDef(
-                        reader.resolve_type_def((&name[0..index], &name[index + 1..])),
-                    )
-                }
+                0x50 => AttributeArg::from_type_name(reader, values.read_str()),
                 // 0x55 => {
                 //     let name = values.read_str();
                 //     let index = name.rfind('.').unwrap();
@@ -113,3 +109,16 @@ pub enum AttributeArg {
     String(String),
     TypeDef(TypeDef),
 }
+
+impl AttributeArg {
+    // Types that the reader ignores, such as API contracts, can't be resolved
+    // so their names are returned as strings instead.
+    fn from_type_name(reader: &TypeReader, name: &str) -> Self {
+        let index = name.rfind('.').unwrap();
+
+        match reader.find_type_def((&name[0..index], &name[index + 1..])) {
+            Some(def) => AttributeArg::TypeDef(def),
+            None => AttributeArg::String(name.to_string()),
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use crate::codes::Implementation;
+use crate::flags::TypeFlags;
+use crate::row::Row;
+use crate::TypeReader;
+
+#[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Debug)]
+pub struct ExportedType(pub Row);
+
+impl ExportedType {
+    pub fn flags(self, reader: &TypeReader) -> TypeFlags {
+        TypeFlags(reader.u32(self.0, 0))
+    }
+
+    pub fn name(self, reader: &TypeReader) -> (&str, &str) {
+        (reader.str(self.0, 3), reader.str(self.0, 2))
+    }
+
+    pub fn implementation(self, reader: &TypeReader) -> Implementation {
+        reader.decode(self.0, 4)
+    }
+}
@@ -1,6 +1,7 @@
 use crate::blob::Blob;
 use crate::codes::HasConstant;
 use crate::file::TableIndex;
+use crate::flags::FieldFlags;
 use crate::row::Row;
 use crate::tables::Constant;
 use crate::TypeReader;
@@ -9,6 +10,10 @@ use crate::TypeReader;
 pub struct Field(pub Row);
 
 impl Field {
+    pub fn flags(self, reader: &TypeReader) -> FieldFlags {
+        FieldFlags(reader.u32(self.0, 0))
+    }
+
     pub fn name(self, reader: &TypeReader) -> &str {
         reader.str(self.0, 1)
     }
@@ -0,0 +1,11 @@
+use crate::row::Row;
+use crate::TypeReader;
+
+#[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Debug)]
+pub struct File(pub Row);
+
+impl File {
+    pub fn name(self, reader: &TypeReader) -> &str {
+        reader.str(self.0, 1)
+    }
+}
@@ -1,6 +1,10 @@
+mod assembly;
+mod assembly_ref;
 mod attribute;
 mod constant;
+mod exported_type;
 mod field;
+mod file;
 mod generic_param;
 mod interface_impl;
 mod member_ref;
@@ -10,9 +14,13 @@ mod type_def;
 mod type_ref;
 mod type_spec;
 
+pub use assembly::*;
+pub use assembly_ref::*;
 pub use attribute::*;
 pub use constant::*;
+pub use exported_type::*;
 pub use field::*;
+pub use file::*;
 pub use generic_param::*;
 pub use interface_impl::*;
 pub use member_ref::*;
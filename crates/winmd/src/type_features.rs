@@ -0,0 +1,129 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// The cargo feature gating each generated namespace and the features it depends on
+///
+/// With the `namespace_features` option each namespace module is gated behind a feature named
+/// after the namespace, e.g. `#[cfg(feature = "Windows_Devices_Bluetooth")]`, so that a crate
+/// publishing pre-generated bindings lets its users compile only the namespaces they need. The
+/// features are written by the `import!` macro when the `WINRT_FEATURES` environment variable
+/// names a file, one `feature = ["dependency", ...]` line per namespace, ready to be pasted into
+/// the `[features]` section of the crate's Cargo.toml. Each feature enables the feature of its
+/// parent namespace, whose module contains it, and of every namespace its types refer to.
+#[derive(Default, Debug, PartialEq)]
+pub struct TypeFeatures(pub BTreeMap<String, BTreeSet<String>>);
+
+impl TypeFeatures {
+    /// Insert a namespace, and its parent namespaces, along with a namespace it depends on
+    pub fn insert(&mut self, namespace: &str, dependency: &str) {
+        self.insert_namespace(namespace);
+        self.insert_namespace(dependency);
+
+        if namespace != dependency {
+            self.0
+                .get_mut(&to_feature(namespace))
+                .unwrap()
+                .insert(to_feature(dependency));
+        }
+    }
+
+    /// Insert a namespace, and its parent namespaces, without any other dependencies
+    pub fn insert_namespace(&mut self, namespace: &str) {
+        if self.0.contains_key(&to_feature(namespace)) {
+            return;
+        }
+
+        let mut dependencies = BTreeSet::new();
+
+        if let Some((parent, _)) = namespace.rsplit_once('.') {
+            self.insert_namespace(parent);
+            dependencies.insert(to_feature(parent));
+        }
+
+        self.0.insert(to_feature(namespace), dependencies);
+    }
+
+    /// Replaces the `[features]` section of a Cargo.toml manifest with these features
+    ///
+    /// The section must come last in the manifest since everything after its header is
+    /// replaced. The section is added to the end if the manifest doesn't have one yet.
+    pub fn to_manifest(&self, manifest: &str) -> String {
+        let manifest = match manifest.find("[features]\n") {
+            Some(pos) => &manifest[..pos],
+            None => manifest,
+        };
+
+        let mut result = manifest.to_string();
+
+        if !result.is_empty() && !result.ends_with("\n\n") {
+            result.push('\n');
+        }
+
+        result.push_str("[features]\n");
+        result.push_str(&self.to_string());
+        result
+    }
+}
+
+impl fmt::Display for TypeFeatures {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (feature, dependencies) in &self.0 {
+            let dependencies: Vec<String> = dependencies
+                .iter()
+                .map(|dependency| format!("{:?}", dependency))
+                .collect();
+
+            writeln!(f, "{} = [{}]", feature, dependencies.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The name of the feature gating a namespace, e.g. `Windows_Devices_Bluetooth`
+pub fn to_feature(namespace: &str) -> String {
+    namespace.replace('.', "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert() {
+        let mut features = TypeFeatures::default();
+        features.insert("Windows.Devices.Bluetooth", "Windows.Foundation");
+        features.insert("Windows.Foundation", "Windows.Foundation");
+
+        assert_eq!(
+            features.to_string(),
+            "Windows = []\n\
+             Windows_Devices = [\"Windows\"]\n\
+             Windows_Devices_Bluetooth = [\"Windows_Devices\", \"Windows_Foundation\"]\n\
+             Windows_Foundation = [\"Windows\"]\n"
+        );
+    }
+
+    #[test]
+    fn test_manifest() {
+        let mut features = TypeFeatures::default();
+        features.insert_namespace("Windows.Foundation");
+
+        let expected = "[package]\n\
+                        name = \"bindings\"\n\
+                        \n\
+                        [features]\n\
+                        Windows = []\n\
+                        Windows_Foundation = [\"Windows\"]\n";
+
+        assert_eq!(
+            features.to_manifest("[package]\nname = \"bindings\"\n"),
+            expected
+        );
+
+        assert_eq!(
+            features.to_manifest("[package]\nname = \"bindings\"\n\n[features]\nOld = []\n"),
+            expected
+        );
+    }
+}
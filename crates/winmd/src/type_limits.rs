@@ -1,37 +1,174 @@
+use crate::tables::TypeDef;
 use crate::TypeReader;
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
-/// The set of relevant namespaces
+/// The set of relevant namespaces and types
 #[derive(Default, Debug)]
-pub struct TypeLimits(pub BTreeSet<String>);
+pub struct TypeLimits {
+    pub namespaces: BTreeSet<String>,
+    /// Types generated without the rest of their namespaces
+    pub types: BTreeSet<TypeDef>,
+}
 
 impl TypeLimits {
     /// Insert a namespace into the set of relevant namespaces
+    ///
+    /// Namespaces are matched ignoring case and underscores so that both `Windows.UI` and
+    /// the module name `windows.ui` are found. A `*` matches any part of a namespace name, so
+    /// `Windows.Devices.*` inserts every namespace nested within `Windows.Devices`.
     pub fn insert(&mut self, reader: &TypeReader, namespace: &str) {
+        let namespace = to_rough_name(namespace);
+
+        if namespace.contains('*') {
+            let found: Vec<&String> = reader
+                .types
+                .keys()
+                .filter(|name| matches_pattern(&namespace, &name.to_lowercase()))
+                .collect();
+
+            if found.is_empty() {
+                panic!(
+                    "No namespaces matching `{}` found in winmd files",
+                    namespace
+                );
+            }
+
+            for name in found {
+                self.insert(reader, name);
+            }
+
+            return;
+        }
+
         let found = reader
             .types
             .keys()
-            .find(|name| name.to_lowercase() == namespace)
-            .unwrap_or_else(|| panic!("Namespace `{}` not found in winmd files", namespace));
+            .find(|name| name.to_lowercase() == namespace);
+
+        let found = match found {
+            Some(found) => found,
+            None => {
+                // A namespace whose types have all moved imports the namespaces they moved to.
+                let (old, types) = reader
+                    .forwarded
+                    .iter()
+                    .find(|(name, _)| name.to_lowercase() == namespace)
+                    .unwrap_or_else(|| {
+                        panic!("Namespace `{}` not found in winmd files", namespace)
+                    });
+
+                for name in types.keys() {
+                    let def = reader.find_forwarded_type_def((old, name)).unwrap();
+                    self.insert(reader, def.name(reader).0);
+                }
+
+                return;
+            }
+        };
 
         let mut namespace = found.as_str();
-        self.0.insert(namespace.to_owned());
+        self.namespaces.insert(namespace.to_owned());
 
         while let Some(pos) = namespace.rfind('.') {
             namespace = &namespace[..pos];
 
             if reader.types.contains_key(namespace) {
-                self.0.insert(namespace.to_owned());
+                self.namespaces.insert(namespace.to_owned());
             }
         }
     }
+
+    /// Insert a type, such as `Windows.Foundation.Uri`, without the rest of its namespace
+    ///
+    /// Types are matched the same way as namespaces, and generic types by their name without
+    /// the number of type parameters, so `windows.foundation.collections.ivector` finds
+    /// ``IVector`1``. Only the types that it transitively refers to, through its members and
+    /// required interfaces, are generated along with it.
+    pub fn insert_type(&mut self, reader: &TypeReader, name: &str) {
+        let name = to_rough_name(name);
+
+        let (namespace, type_name) = name
+            .rsplit_once('.')
+            .unwrap_or_else(|| panic!("Type `{}` must be qualified by its namespace", name));
+
+        let def = match find_type(&reader.types, namespace, type_name) {
+            Some(found) => reader.find_type_def(found).unwrap(),
+            None => find_type(&reader.forwarded, namespace, type_name)
+                .and_then(|found| reader.find_forwarded_type_def(found))
+                .unwrap_or_else(|| panic!("Type `{}` not found in winmd files", name)),
+        };
+
+        self.types.insert(def);
+    }
+}
+
+// Finds a type by its rough namespace and name in a map of namespaces to types.
+fn find_type<'a>(
+    types: &'a BTreeMap<String, BTreeMap<String, TypeDef>>,
+    namespace: &str,
+    type_name: &str,
+) -> Option<(&'a str, &'a str)> {
+    types
+        .iter()
+        .filter(|(found, _)| found.to_lowercase() == namespace)
+        .flat_map(|(found, types)| {
+            types
+                .keys()
+                .map(move |name| (found.as_str(), name.as_str()))
+        })
+        .find(|(_, found)| {
+            let found = found.to_lowercase();
+            found == type_name || found.split('`').next() == Some(type_name)
+        })
+}
+
+// Lowercases a name without underscores, since snake and camel case conversion is lossy.
+pub(crate) fn to_rough_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+// Matches a name against a pattern in which each `*` matches any run of characters.
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    match pattern.find('*') {
+        None => pattern == name,
+        Some(pos) => {
+            let (prefix, rest) = (&pattern[..pos], &pattern[pos + 1..]);
+
+            name.starts_with(prefix)
+                && (prefix.len()..=name.len()).any(|start| matches_pattern(rest, &name[start..]))
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern(
+            "windows.devices.*",
+            "windows.devices.bluetooth"
+        ));
+        assert!(matches_pattern(
+            "windows.devices.*",
+            "windows.devices.bluetooth.advertisement"
+        ));
+        assert!(!matches_pattern("windows.devices.*", "windows.devices"));
+        assert!(!matches_pattern("windows.devices.*", "windows.foundation"));
+        assert!(matches_pattern("windows.*.core", "windows.ui.core"));
+        assert!(matches_pattern("windows.*.core", "windows.media.core"));
+        assert!(!matches_pattern(
+            "windows.*.core",
+            "windows.ui.core.preview"
+        ));
+        assert!(matches_pattern("windows.foundation", "windows.foundation"));
+    }
+
     #[test]
     fn test_parent_inclusion() {
         let reader = &TypeReader::from_os();
@@ -40,29 +177,43 @@ mod tests {
             // Windows.Foundation's parent is empty so that's not included
             let mut limits = TypeLimits::default();
             limits.insert(reader, "windows.foundation");
-            assert!(limits.0.len() == 1);
-            assert!(limits.0.contains("Windows.Foundation"));
+            assert!(limits.namespaces.len() == 1);
+            assert!(limits.namespaces.contains("Windows.Foundation"));
         }
 
         {
             // Windows.Foundation.Collections's parent is not empty so it gets included
             let mut limits = TypeLimits::default();
             limits.insert(reader, "windows.foundation.collections");
-            assert!(limits.0.len() == 2);
-            assert!(limits.0.contains("Windows.Foundation"));
-            assert!(limits.0.contains("Windows.Foundation.Collections"));
+            assert!(limits.namespaces.len() == 2);
+            assert!(limits.namespaces.contains("Windows.Foundation"));
+            assert!(limits.namespaces.contains("Windows.Foundation.Collections"));
         }
 
         {
             let mut limits = TypeLimits::default();
             limits.insert(reader, "windows.foundation.collections");
             limits.insert(reader, "windows.ui.xaml.controls");
-            assert!(limits.0.len() == 5);
-            assert!(limits.0.contains("Windows.Foundation"));
-            assert!(limits.0.contains("Windows.Foundation.Collections"));
-            assert!(limits.0.contains("Windows.UI"));
-            assert!(limits.0.contains("Windows.UI.Xaml"));
-            assert!(limits.0.contains("Windows.UI.Xaml.Controls"));
+            assert!(limits.namespaces.len() == 5);
+            assert!(limits.namespaces.contains("Windows.Foundation"));
+            assert!(limits.namespaces.contains("Windows.Foundation.Collections"));
+            assert!(limits.namespaces.contains("Windows.UI"));
+            assert!(limits.namespaces.contains("Windows.UI.Xaml"));
+            assert!(limits.namespaces.contains("Windows.UI.Xaml.Controls"));
         }
     }
+
+    #[test]
+    fn test_type_inclusion() {
+        let reader = &TypeReader::from_os();
+
+        let mut limits = TypeLimits::default();
+        limits.insert_type(reader, "windows.foundation.uri");
+        limits.insert_type(reader, "windows.foundation.collections.ivector");
+        assert!(limits.namespaces.is_empty());
+
+        let names: Vec<(&str, &str)> = limits.types.iter().map(|def| def.name(reader)).collect();
+        assert!(names.contains(&("Windows.Foundation", "Uri")));
+        assert!(names.contains(&("Windows.Foundation.Collections", "IVector`1")));
+    }
 }
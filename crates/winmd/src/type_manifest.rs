@@ -0,0 +1,143 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// A content hash of the code generated for each namespace
+///
+/// The manifest is written by the `import!` macro when the `WINRT_MANIFEST` environment
+/// variable names a file, one `import<TAB>namespace<TAB>hash` line per namespace, where the
+/// import is named after the dependencies it was imported from. When `WINRT_VERIFY_MANIFEST`
+/// names a previously written manifest, the macro instead regenerates each namespace and fails
+/// the build if its hash doesn't match, which lets hermetic build systems detect stale or
+/// tampered outputs.
+#[derive(Default, Debug, PartialEq)]
+pub struct TypeManifest(pub BTreeMap<(String, String), String>);
+
+impl TypeManifest {
+    /// Insert the code generated for a namespace by an import, storing its SHA-1 hash
+    pub fn insert(&mut self, import: &str, namespace: &str, code: &str) {
+        let mut hash = sha1::Sha1::new();
+        hash.update(code.as_bytes());
+        self.0.insert(
+            (import.to_string(), namespace.to_string()),
+            hash.digest().to_string(),
+        );
+    }
+
+    /// Parse a manifest previously written with its `Display` implementation
+    pub fn parse(text: &str) -> Self {
+        let mut manifest = Self::default();
+
+        for line in text.lines() {
+            let mut parts = line.splitn(3, '\t');
+
+            if let (Some(import), Some(namespace), Some(hash)) =
+                (parts.next(), parts.next(), parts.next())
+            {
+                manifest.0.insert(
+                    (import.trim().to_string(), namespace.trim().to_string()),
+                    hash.trim().to_string(),
+                );
+            }
+        }
+
+        manifest
+    }
+
+    /// Verify that every namespace in this manifest has the hash recorded in `expected`
+    ///
+    /// Namespaces in `expected` that are missing from this manifest are ignored since a
+    /// single manifest may be shared by several `import!` invocations.
+    pub fn verify(&self, expected: &TypeManifest) -> Result<(), String> {
+        let mut errors = Vec::new();
+
+        for (key, hash) in &self.0 {
+            let (import, namespace) = key;
+
+            match expected.0.get(key) {
+                Some(expected) if expected == hash => {}
+                Some(expected) => errors.push(format!(
+                    "`{}` imported from `{}` generated hash {} but the manifest expects {}",
+                    namespace, import, hash, expected
+                )),
+                None => errors.push(format!(
+                    "`{}` imported from `{}` is missing from the manifest",
+                    namespace, import
+                )),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("\n"))
+        }
+    }
+}
+
+impl fmt::Display for TypeManifest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for ((import, namespace), hash) in &self.0 {
+            writeln!(f, "{}\t{}\t{}", import, namespace, hash)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut manifest = TypeManifest::default();
+        manifest.insert("os", "Windows.Foundation", "pub struct Uri;");
+        manifest.insert("os", "Windows.UI", "pub struct Colors;");
+
+        assert_eq!(TypeManifest::parse(&manifest.to_string()), manifest);
+        assert_eq!(
+            manifest.0[&("os".to_string(), "Windows.UI".to_string())].len(),
+            40
+        );
+    }
+
+    #[test]
+    fn test_verify() {
+        let mut expected = TypeManifest::default();
+        expected.insert("os", "Windows.Foundation", "pub struct Uri;");
+        expected.insert("os", "Windows.UI", "pub struct Colors;");
+
+        let mut manifest = TypeManifest::default();
+        manifest.insert("os", "Windows.Foundation", "pub struct Uri;");
+        assert!(manifest.verify(&expected).is_ok());
+
+        manifest.insert("os", "Windows.Foundation", "pub struct Uri(u32);");
+        let error = manifest.verify(&expected).unwrap_err();
+        assert!(error.contains("`Windows.Foundation` imported from `os` generated hash"));
+
+        let mut manifest = TypeManifest::default();
+        manifest.insert("os", "Windows.Data", "");
+        let error = manifest.verify(&expected).unwrap_err();
+        assert!(error.contains("`Windows.Data` imported from `os` is missing"));
+    }
+
+    #[test]
+    fn test_side_by_side() {
+        // The same namespace imported from different metadata keeps a hash for each import.
+        let mut manifest = TypeManifest::default();
+        manifest.insert("sdk:10.0.17763.0", "Windows.Foundation", "pub struct Uri;");
+        manifest.insert(
+            "sdk:10.0.19041.0",
+            "Windows.Foundation",
+            "pub struct GuidHelper;",
+        );
+        assert!(manifest.0.len() == 2);
+
+        let parsed = TypeManifest::parse(&manifest.to_string());
+        assert_eq!(parsed, manifest);
+
+        let mut older = TypeManifest::default();
+        older.insert("sdk:10.0.17763.0", "Windows.Foundation", "pub struct Uri;");
+        assert!(older.verify(&parsed).is_ok());
+    }
+}
@@ -1,7 +1,9 @@
 use crate::case;
 use crate::format_ident;
+use crate::type_features::to_feature;
 use crate::type_tree::TypeTree;
 use crate::types::MethodKind;
+use crate::TypeOptions;
 
 use proc_macro2::TokenStream;
 use quote::quote;
@@ -13,21 +15,45 @@ use std::iter::FromIterator;
 pub struct TypeNamespaces(pub BTreeMap<String, TypeTree>);
 
 impl TypeNamespaces {
-    pub fn to_tokens(&self) -> TokenStream {
-        let mut tokens = Vec::new();
-
-        for (name, tree) in self.0.iter() {
-            let name = case::to_snake(name, MethodKind::Normal);
-            let name = format_ident(&name);
-            let tree = tree.to_tokens();
-
-            tokens.push(quote! {
-                pub mod #name {
-                    #tree
-                }
-            });
+    pub fn to_tokens(&self, options: &TypeOptions) -> TokenStream {
+        TokenStream::from_iter(self.0.iter().map(|(name, tree)| {
+            to_module_tokens(name, tree, options, tree.to_module_tokens(options))
+        }))
+    }
+
+    /// The namespace modules, each including the file generated for it by `TypeTree::to_files`
+    pub fn to_include_tokens(&self, options: &TypeOptions) -> TokenStream {
+        TokenStream::from_iter(self.0.iter().map(|(name, tree)| {
+            let file = format!("{}.rs", tree.namespace);
+            to_module_tokens(name, tree, options, quote! { include!(#file); })
+        }))
+    }
+}
+
+fn to_module_tokens(
+    name: &str,
+    tree: &TypeTree,
+    options: &TypeOptions,
+    contents: TokenStream,
+) -> TokenStream {
+    let name = case::to_snake(name, MethodKind::Normal);
+    let name = format_ident(&name);
+    let gate = to_namespace_gate(&tree.namespace, options);
+
+    quote! {
+        #gate
+        pub mod #name {
+            #contents
         }
+    }
+}
 
-        TokenStream::from_iter(tokens)
+/// The `cfg` attribute gating a namespace module, if the `namespace_features` option is enabled
+pub fn to_namespace_gate(namespace: &str, options: &TypeOptions) -> TokenStream {
+    if options.namespace_features {
+        let feature = to_feature(namespace);
+        quote! { #[cfg(feature = #feature)] }
+    } else {
+        TokenStream::new()
     }
 }
@@ -0,0 +1,232 @@
+use crate::TypeOverrides;
+
+use proc_macro2::TokenStream;
+use std::collections::BTreeMap;
+
+/// Options controlling how types are generated
+#[derive(Default, Debug)]
+pub struct TypeOptions {
+    /// Gate types, and class members, introduced after the first release of Windows 10 behind
+    /// cargo features named after the Windows build that introduced them, e.g. `#[cfg(feature = "14393")]`
+    pub contract_features: bool,
+    /// Gate each namespace module behind a cargo feature named after the namespace,
+    /// e.g. `#[cfg(feature = "Windows_Devices_Bluetooth")]`
+    pub namespace_features: bool,
+    /// The oldest Windows build the generated code should run on. Calls to members introduced after
+    /// this build first check that they are present and fail with `ErrorCode::OLD_WIN_VERSION` if not
+    pub target_build: Option<u32>,
+    /// Generate the vtables of interfaces with at least this many methods as tables of method
+    /// slots rather than a field per method, which is much less code for the compiler to check
+    pub compact_vtables: Option<usize>,
+    /// Call the runtime's audited ABI helpers rather than casting pointers in the generated
+    /// functions, and allow `unsafe` on the functions and trait implementations that need it
+    /// so that the bindings build in crates that deny `unsafe_code`
+    pub safe_api: bool,
+    /// The visibility of generated items in place of `pub`, such as `pub(crate)`, so that
+    /// bindings used internally don't become part of a crate's public API
+    pub visibility: Option<TokenStream>,
+    /// Adjustments to members with known-bad metadata, read from a file
+    pub overrides: TypeOverrides,
+    /// Generate the methods of each type in order of their names rather than in vtable order,
+    /// so that bindings generated from metadata that reorders members still compare equal
+    pub sorted_members: bool,
+    /// Format the source files written by `TypeTree::to_files` and `TypeTree::write` with
+    /// rustfmt so that they can be read and diffed, leaving them unformatted if rustfmt fails
+    pub format: bool,
+    /// Derive `Serialize` and `Deserialize` for enums and blittable structs, which requires the
+    /// runtime's `serde` feature
+    pub serde: bool,
+    /// Generate `AsyncInputStream` and `AsyncOutputStream` adapters implementing the
+    /// `futures_io` traits over WinRT streams, which requires the runtime's `futures-io` feature
+    pub futures_io: bool,
+    /// Implement the futures `Stream` trait for the `{Class}StatusChanges` subscriptions of
+    /// static classes, which requires the runtime's `futures-core` feature
+    pub futures_core: bool,
+    /// Convert `DateTime` and `TimeSpan` to and from `chrono::DateTime<Utc>` and
+    /// `chrono::Duration`, which requires the runtime's `chrono` feature
+    pub chrono: bool,
+    /// Generate only the ABI, the vtables and IIDs of interfaces and delegates and the layouts
+    /// of structs and enums, for crates that build their own wrappers around it
+    pub abi_only: bool,
+    /// Leave out types and members marked as deprecated, see `TypeStage::remove_deprecated`
+    pub skip_deprecated: bool,
+    /// The newest Windows build the generated code may use. Types and class interfaces
+    /// introduced after it are left out, see `TypeStage::limit_to_build`
+    pub max_build: Option<u32>,
+    /// Shorter names for namespace modules, re-exported from the root of the bindings, keyed
+    /// by the alias and naming the namespace as `import!` does, e.g. `ctrl` for
+    /// `windows.ui.xaml.controls`
+    pub aliases: BTreeMap<String, String>,
+}
+
+impl TypeOptions {
+    /// Enable an option by name
+    ///
+    /// # Panics
+    ///
+    /// Panics if the option is not recognized
+    pub fn insert(&mut self, option: &str) {
+        match option {
+            "contract_features" => self.contract_features = true,
+            "namespace_features" => self.namespace_features = true,
+            "safe_api" => self.safe_api = true,
+            "sorted_members" => self.sorted_members = true,
+            "format" => self.format = true,
+            "serde" => self.serde = true,
+            "futures_io" => self.futures_io = true,
+            "futures_core" => self.futures_core = true,
+            "chrono" => self.chrono = true,
+            "abi_only" => self.abi_only = true,
+            "skip_deprecated" => self.skip_deprecated = true,
+            _ => {
+                if let Some(build) = option.strip_prefix("target:") {
+                    let build = build
+                        .parse()
+                        .unwrap_or_else(|_| panic!("Invalid target build `{}`", build));
+                    self.target_build = Some(build);
+                } else if let Some(build) = option.strip_prefix("max_build:") {
+                    let build = build
+                        .parse()
+                        .unwrap_or_else(|_| panic!("Invalid maximum build `{}`", build));
+                    self.max_build = Some(build);
+                } else if let Some(methods) = option.strip_prefix("compact_vtables:") {
+                    let methods = methods
+                        .parse()
+                        .unwrap_or_else(|_| panic!("Invalid method count `{}`", methods));
+                    self.compact_vtables = Some(methods);
+                } else if let Some(visibility) = option.strip_prefix("visibility:") {
+                    let visibility = visibility
+                        .parse()
+                        .unwrap_or_else(|_| panic!("Invalid visibility `{}`", visibility));
+                    self.visibility = Some(visibility);
+                } else if let Some(path) = option.strip_prefix("overrides:") {
+                    // Relative paths are relative to the crate being built.
+                    let path = match std::env::var_os("CARGO_MANIFEST_DIR") {
+                        Some(dir) => std::path::Path::new(&dir).join(path),
+                        None => std::path::PathBuf::from(path),
+                    };
+                    self.overrides =
+                        TypeOverrides::from_file(path).unwrap_or_else(|e| panic!("{}", e));
+                } else {
+                    panic!("Unrecognized option `{}`", option)
+                }
+            }
+        }
+    }
+
+    /// Adds an alias for a namespace module written as `<namespace> as <alias>`, e.g.
+    /// `windows.ui.xaml.controls as ctrl`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the alias is not written that way or is already in use
+    pub fn insert_alias(&mut self, alias: &str) {
+        let mut parts = alias.split_whitespace();
+
+        let (namespace, alias) = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(namespace), Some("as"), Some(alias), None) => (namespace, alias),
+            _ => panic!(
+                "Invalid alias `{}`, expected `<namespace> as <alias>`",
+                alias
+            ),
+        };
+
+        let mut chars = alias.chars();
+        let valid = matches!(chars.next(), Some(c) if c.is_alphabetic() || c == '_')
+            && chars.all(|c| c.is_alphanumeric() || c == '_')
+            && alias != "_";
+
+        if !valid {
+            panic!("Invalid alias name `{}`", alias);
+        }
+
+        if let Some(previous) = self
+            .aliases
+            .insert(alias.to_string(), namespace.to_string())
+        {
+            panic!(
+                "Alias `{}` is used for both `{}` and `{}`",
+                alias, previous, namespace
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert() {
+        let mut options = TypeOptions::default();
+        assert!(!options.contract_features);
+        assert!(!options.namespace_features);
+        assert!(options.target_build.is_none());
+
+        options.insert("contract_features");
+        options.insert("namespace_features");
+        options.insert("target:17763");
+        options.insert("compact_vtables:100");
+        options.insert("safe_api");
+        options.insert("visibility:pub(crate)");
+        options.insert("sorted_members");
+        options.insert("format");
+        options.insert("serde");
+        options.insert("abi_only");
+        options.insert("skip_deprecated");
+        options.insert("max_build:19041");
+        options.insert("futures_io");
+        options.insert("futures_core");
+        options.insert("chrono");
+        assert!(options.contract_features);
+        assert!(options.format);
+        assert!(options.serde);
+        assert!(options.futures_io);
+        assert!(options.futures_core);
+        assert!(options.chrono);
+        assert!(options.abi_only);
+        assert!(options.skip_deprecated);
+        assert_eq!(options.max_build, Some(19041));
+        assert!(options.safe_api);
+        assert!(options.sorted_members);
+        assert_eq!(
+            options.visibility.as_ref().map(ToString::to_string),
+            Some("pub (crate)".to_string())
+        );
+        assert_eq!(options.compact_vtables, Some(100));
+        assert!(options.namespace_features);
+        assert_eq!(options.target_build, Some(17763));
+    }
+
+    #[test]
+    fn test_insert_alias() {
+        let mut options = TypeOptions::default();
+        options.insert_alias("windows.ui.xaml.controls as ctrl");
+        options.insert_alias("Windows.Foundation.Collections  as  collections");
+        assert_eq!(options.aliases["ctrl"], "windows.ui.xaml.controls");
+        assert_eq!(
+            options.aliases["collections"],
+            "Windows.Foundation.Collections"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_alias() {
+        TypeOptions::default().insert_alias("windows.ui.xaml.controls => ctrl");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_duplicate_alias() {
+        let mut options = TypeOptions::default();
+        options.insert_alias("windows.ui.xaml.controls as ctrl");
+        options.insert_alias("windows.ui.composition as ctrl");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_target() {
+        TypeOptions::default().insert("target:rs5");
+    }
+}
@@ -0,0 +1,283 @@
+use crate::type_stage::TypeStage;
+use crate::types::{Method, Type};
+
+use std::collections::BTreeMap;
+
+/// Adjustments to members whose metadata is known to be wrong or awkward
+///
+/// Overrides are read from a file in a small subset of TOML, with a table per member named
+/// after the interface that declares it and the member's metadata name:
+///
+/// ```toml
+/// # Uri.Domain may return null
+/// ["Windows.Foundation.IUriRuntimeClass.get_Domain"]
+/// nullable = true
+/// rename = "host"
+///
+/// ["Windows.Foundation.IUriRuntimeClass.get_Port"]
+/// skip = true
+/// ```
+///
+/// A `nullable` member returns `Option` rather than a null object, `rename` changes the name
+/// of the generated method, and `skip` leaves the member out of the projection. These apply
+/// wherever the interface is projected, including on the classes that implement it.
+#[derive(Default, Debug, PartialEq)]
+pub struct TypeOverrides(pub BTreeMap<String, MemberOverride>);
+
+/// The overrides for a single member
+#[derive(Default, Debug, PartialEq)]
+pub struct MemberOverride {
+    pub nullable: bool,
+    pub rename: Option<String>,
+    pub skip: bool,
+}
+
+impl TypeOverrides {
+    /// Read and parse an override file
+    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Could not read overrides at {:?}: {}", path, e))?;
+
+        Self::parse(&text).map_err(|e| format!("Invalid overrides at {:?}: {}", path, e))
+    }
+
+    /// Parse the text of an override file
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut overrides = Self::default();
+        let mut member = None;
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            let number = number + 1;
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with('[') {
+                let name = line
+                    .strip_prefix('[')
+                    .and_then(|line| line.strip_suffix(']'))
+                    .map(|name| name.trim().trim_matches('"'))
+                    .filter(|name| !name.is_empty())
+                    .ok_or_else(|| format!("line {}: invalid table `{}`", number, line))?;
+
+                overrides.0.entry(name.to_string()).or_default();
+                member = Some(name.to_string());
+                continue;
+            }
+
+            let member = member
+                .as_ref()
+                .and_then(|member| overrides.0.get_mut(member))
+                .ok_or_else(|| format!("line {}: expected a member table first", number))?;
+
+            let mut parts = line.splitn(2, '=');
+            let key = parts.next().unwrap_or_default().trim();
+            let value = parts
+                .next()
+                .map(|value| value.trim())
+                .ok_or_else(|| format!("line {}: expected `key = value`", number))?;
+
+            match key {
+                "nullable" => member.nullable = parse_bool(value, number)?,
+                "skip" => member.skip = parse_bool(value, number)?,
+                "rename" => member.rename = Some(parse_string(value, number)?),
+                _ => return Err(format!("line {}: unrecognized key `{}`", number, key)),
+            }
+        }
+
+        Ok(overrides)
+    }
+
+    /// Apply the overrides to the members of the staged types
+    ///
+    /// # Panics
+    ///
+    /// Panics if a member marked as nullable doesn't return an object, since only objects
+    /// can be null.
+    pub fn apply(&self, stage: &mut TypeStage) {
+        if self.0.is_empty() {
+            return;
+        }
+
+        for t in stage.0.values_mut() {
+            let interfaces = match t {
+                Type::Class(t) => &mut t.interfaces,
+                Type::Interface(t) => &mut t.interfaces,
+                _ => continue,
+            };
+
+            for interface in interfaces {
+                let prefix = format!("{}.{}.", interface.name.namespace, interface.name.name);
+
+                for method in &mut interface.methods {
+                    let key = format!("{}{}", prefix, method.runtime_name);
+
+                    if let Some(member) = self.0.get(&key) {
+                        member.apply(&key, method);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl MemberOverride {
+    fn apply(&self, key: &str, method: &mut Method) {
+        if self.nullable {
+            match &method.return_type {
+                Some(param) if !param.array && param.kind.object() => {}
+                _ => panic!("`{}` can't be nullable as it doesn't return an object", key),
+            }
+
+            method.nullable = true;
+        }
+
+        if let Some(name) = &self.rename {
+            method.name = name.as_str().into();
+        }
+
+        // Methods may already be skipped, such as those that can't be projected.
+        method.skip |= self.skip;
+    }
+}
+
+fn parse_bool(value: &str, number: usize) -> Result<bool, String> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(format!("line {}: expected `true` or `false`", number)),
+    }
+}
+
+fn parse_string(value: &str, number: usize) -> Result<String, String> {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .filter(|value| !value.is_empty())
+        .map(|value| value.to_string())
+        .ok_or_else(|| format!("line {}: expected a quoted name", number))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TypeLimits, TypeOptions, TypeReader};
+
+    fn uri(overrides: &str, options: &TypeOptions) -> String {
+        let reader = &TypeReader::from_os();
+        let mut limits = TypeLimits::default();
+        limits.insert_type(reader, "windows.foundation.uri");
+        let mut stage = TypeStage::from_limits(reader, &limits);
+
+        TypeOverrides::parse(overrides).unwrap().apply(&mut stage);
+
+        let t = stage.0.values().find(|t| &*t.name().name == "Uri").unwrap();
+
+        match t {
+            Type::Class(t) => t.to_tokens(options).to_string(),
+            _ => panic!("Type not a class"),
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let overrides = TypeOverrides::parse(
+            r#"
+            # Comments and blank lines are ignored
+
+            ["Windows.Foundation.IUriRuntimeClass.get_Domain"]
+            nullable = true
+            rename = "host"
+
+            [Windows.Foundation.IUriRuntimeClass.get_Port]
+            skip = true
+            "#,
+        )
+        .unwrap();
+
+        assert!(overrides.0.len() == 2);
+
+        let domain = &overrides.0["Windows.Foundation.IUriRuntimeClass.get_Domain"];
+        assert!(domain.nullable);
+        assert!(domain.rename.as_deref() == Some("host"));
+        assert!(!domain.skip);
+
+        let port = &overrides.0["Windows.Foundation.IUriRuntimeClass.get_Port"];
+        assert!(
+            *port
+                == MemberOverride {
+                    skip: true,
+                    ..Default::default()
+                }
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = TypeOverrides::parse("skip = true").unwrap_err();
+        assert!(error == "line 1: expected a member table first");
+
+        let error = TypeOverrides::parse("[A.B.C]\nskip = yes").unwrap_err();
+        assert!(error == "line 2: expected `true` or `false`");
+
+        let error = TypeOverrides::parse("[A.B.C]\nrename = host").unwrap_err();
+        assert!(error == "line 2: expected a quoted name");
+
+        let error = TypeOverrides::parse("[A.B.C]\nhidden = true").unwrap_err();
+        assert!(error == "line 2: unrecognized key `hidden`");
+
+        let error = TypeOverrides::parse("[]").unwrap_err();
+        assert!(error == "line 1: invalid table `[]`");
+    }
+
+    #[test]
+    fn test_apply() {
+        let tokens = uri("", &Default::default());
+        assert!(tokens.contains("pub fn domain"));
+        assert!(tokens.contains("pub fn port"));
+        assert!(!tokens.contains("is_null (& __ok)"));
+
+        let overrides = r#"
+            ["Windows.Foundation.IUriRuntimeClass.get_Domain"]
+            rename = "host"
+
+            ["Windows.Foundation.IUriRuntimeClass.CombineUri"]
+            nullable = true
+
+            ["Windows.Foundation.IUriRuntimeClass.get_Port"]
+            skip = true
+            "#;
+
+        let tokens = uri(overrides, &Default::default());
+        assert!(tokens.contains("pub fn host"));
+        assert!(!tokens.contains("pub fn domain"));
+
+        // The skipped method remains in the vtable but isn't projected.
+        assert!(!tokens.contains("pub fn port"));
+
+        // A null object is returned as `None` rather than as an object wrapping null.
+        assert!(tokens.contains(":: std :: option :: Option < Uri >"));
+        assert!(tokens.contains("if :: winrt :: ComInterface :: is_null (& __ok) { Ok (None) }"));
+
+        let mut options = TypeOptions::default();
+        options.insert("safe_api");
+        let tokens = uri(overrides, &options);
+        assert!(tokens.contains("if :: winrt :: ComInterface :: is_null (& __ok) { None }"));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "`Windows.Foundation.IUriRuntimeClass.get_Port` can't be nullable as it doesn't return an object"
+    )]
+    fn test_apply_nullable_value() {
+        let overrides = r#"
+            ["Windows.Foundation.IUriRuntimeClass.get_Port"]
+            nullable = true
+            "#;
+
+        uri(overrides, &Default::default());
+    }
+}
@@ -1,12 +1,58 @@
 use crate::blob::Blob;
-use crate::codes::Decode;
+use crate::codes::{Decode, Implementation};
 use crate::file::{TableIndex, View, WinmdFile};
+use crate::interner::Interner;
 use crate::row::Row;
-use crate::tables::TypeDef;
+use crate::tables::{Assembly, ExportedType, Field, MethodDef, Param, TypeDef};
 use crate::types::Type;
 
+use std::cell::RefCell;
 use std::cmp::Ordering;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::SystemTime;
+
+thread_local! {
+    static CACHE: RefCell<ReaderCache<TypeReader>> = RefCell::default();
+}
+
+// Readers keyed on the identity of the metadata files they were created from, along with when
+// each file was last modified. A reader is replaced, rather than added to, when any of its files
+// has been modified since, so the cache only grows with the sets of files asked for.
+struct ReaderCache<T>(BTreeMap<BTreeSet<PathBuf>, CacheEntry<T>>);
+
+type CacheEntry<T> = (Vec<Option<SystemTime>>, Rc<T>);
+
+impl<T> Default for ReaderCache<T> {
+    fn default() -> Self {
+        Self(BTreeMap::new())
+    }
+}
+
+impl<T> ReaderCache<T> {
+    fn get(&mut self, files: &BTreeSet<PathBuf>, create: impl FnOnce() -> T) -> Rc<T> {
+        // The same file may be named by different paths, such as through a symbolic link.
+        let identity: BTreeSet<PathBuf> = files
+            .iter()
+            .map(|path| std::fs::canonicalize(path).unwrap_or_else(|_| path.clone()))
+            .collect();
+
+        let modified: Vec<Option<SystemTime>> = identity
+            .iter()
+            .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+            .collect();
+
+        match self.0.get(&identity) {
+            Some((cached, value)) if *cached == modified => value.clone(),
+            _ => {
+                let value = Rc::new(create());
+                self.0.insert(identity, (modified, value.clone()));
+                value
+            }
+        }
+    }
+}
 
 /// A reader of type information from Windows Metadata
 pub struct TypeReader {
@@ -17,6 +63,38 @@ pub struct TypeReader {
     /// This is a mapping between namespace names and the types inside
     /// that namespace
     pub types: BTreeMap<String, BTreeMap<String, TypeDef>>,
+    /// Types that have moved to another namespace, by the namespace and name they were
+    /// forwarded from
+    ///
+    /// Newer metadata declares a type forwarder in place of a type that has moved so that
+    /// imports written against the old name keep resolving, to the type's new definition.
+    pub forwarded: BTreeMap<String, BTreeMap<String, TypeDef>>,
+    moved: RefCell<BTreeSet<(String, String)>>,
+    context: RefCell<Vec<ContextItem>>,
+    /// The strings shared by the types read
+    pub(crate) strings: Interner,
+}
+
+/// Something being read that may need a type, which is only described if the type can't be
+/// found so that reading doesn't format a description of everything along the way
+#[derive(Copy, Clone, PartialEq)]
+pub(crate) enum ContextItem {
+    Type(TypeDef),
+    Method(MethodDef),
+    Param(Param),
+    Field(Field),
+    ReturnType,
+}
+
+/// Describes what is being read while it's in scope, for reporting types that can't be found
+pub(crate) struct ReaderContext<'a> {
+    reader: &'a TypeReader,
+}
+
+impl<'a> Drop for ReaderContext<'a> {
+    fn drop(&mut self) {
+        self.reader.context.borrow_mut().pop();
+    }
 }
 
 impl TypeReader {
@@ -24,11 +102,41 @@ impl TypeReader {
         Self::new(crate::load_winmd::from_os())
     }
 
+    /// Create a new [`TypeReader`] from the metadata files that define a namespace, which
+    /// are resolved at runtime so that the Windows SDK isn't needed
+    pub fn from_namespace(namespace: &str) -> Self {
+        Self::new(crate::load_winmd::from_namespace(namespace))
+    }
+
+    /// Create a new [`TypeReader`] for the Windows App SDK (WinUI 3) and the
+    /// operating system metadata it depends on
+    pub fn from_windows_app_sdk() -> Self {
+        Self::new(crate::load_winmd::from_windows_app_sdk())
+    }
+
+    /// Returns a [`TypeReader`] for a set of metadata files, reusing the one created by an
+    /// earlier call for the same files on this thread
+    ///
+    /// A crate with several `import!` invocations has them all expanded by the same process,
+    /// so the files are only read and indexed once. A reader is created again if any of the
+    /// files has been modified since.
+    pub fn cached(files: BTreeSet<PathBuf>) -> Rc<Self> {
+        CACHE.with(|cache| {
+            cache.borrow_mut().get(&files, || {
+                Self::new(crate::load_winmd::from_files(files.clone()))
+            })
+        })
+    }
+
     /// Create a new [`TypeReader`] from a [`WinmdFile`]s
     pub fn new(files: Vec<WinmdFile>) -> Self {
         let mut reader = Self {
             files: Vec::default(),
             types: BTreeMap::default(),
+            forwarded: BTreeMap::default(),
+            moved: RefCell::default(),
+            context: RefCell::default(),
+            strings: Interner::default(),
         };
         for (file_index, file) in files.into_iter().enumerate() {
             let row_count = file.type_def_table().row_count;
@@ -53,9 +161,93 @@ impl TypeReader {
                     .or_insert(def);
             }
         }
+
+        reader.forwarded = reader.forwarders();
         reader
     }
 
+    // A forwarder names the type it stands in for and, as its implementation, the assembly that
+    // now defines it, where the type may have moved to another namespace. Forwarders that can't
+    // be resolved are left out, and `missing_type_message` explains why if they're used.
+    fn forwarders(&self) -> BTreeMap<String, BTreeMap<String, TypeDef>> {
+        let mut forwarded: BTreeMap<String, BTreeMap<String, TypeDef>> = BTreeMap::new();
+
+        for exported in self.exported_types() {
+            let (namespace, name) = exported.name(self);
+
+            if self.find_type_def((namespace, name)).is_some() {
+                continue;
+            }
+
+            if let Ok(def) = self.forwarded_type(exported) {
+                forwarded
+                    .entry(namespace.to_string())
+                    .or_default()
+                    .insert(name.to_string(), def);
+            }
+        }
+
+        forwarded
+    }
+
+    fn exported_types(&self) -> impl Iterator<Item = ExportedType> + '_ {
+        self.files
+            .iter()
+            .enumerate()
+            .flat_map(|(file_index, file)| {
+                let row_count = file.tables[TableIndex::ExportedType as usize].row_count;
+
+                (0..row_count).map(move |row| {
+                    ExportedType(Row::new(row, TableIndex::ExportedType, file_index as u16))
+                })
+            })
+            .filter(move |exported| exported.flags(self).forwarder())
+    }
+
+    fn forwarded_type(&self, exported: ExportedType) -> Result<TypeDef, String> {
+        let assembly = match exported.implementation(self) {
+            Implementation::AssemblyRef(assembly) => assembly.name(self),
+            // Windows metadata has neither multi-module assemblies nor nested types.
+            Implementation::File(file) => {
+                return Err(format!(
+                    "it's forwarded to the module `{}` rather than to an assembly",
+                    file.name(self)
+                ))
+            }
+            Implementation::ExportedType(_) => {
+                return Err("it's forwarded as a nested type".to_string())
+            }
+        };
+
+        if !(0..self.files.len()).any(|file| self.assembly_name(file as u16) == Some(assembly)) {
+            return Err(format!(
+                "it's forwarded to the assembly `{}`, which isn't defined by any of the winmd files",
+                assembly
+            ));
+        }
+
+        let (_, name) = exported.name(self);
+
+        let candidates: Vec<(&str, TypeDef)> = self
+            .types
+            .iter()
+            .filter_map(|(namespace, types)| Some((namespace.as_str(), *types.get(name)?)))
+            .filter(|(_, def)| self.assembly_name(def.0.file_index) == Some(assembly))
+            .collect();
+
+        select_forwarded_type(assembly, name, &candidates)
+    }
+
+    fn assembly_name(&self, file_index: u16) -> Option<&str> {
+        let file = &self.files[file_index as usize];
+
+        if file.tables[TableIndex::Assembly as usize].row_count == 0 {
+            return None;
+        }
+
+        Some(Assembly(Row::new(0, TableIndex::Assembly, file_index)).name(self))
+    }
+
     /// Get all the namespace names that the [`TypeReader`] knows about
     pub fn namespaces(&self) -> impl Iterator<Item = &String> {
         self.types.keys()
@@ -75,14 +267,104 @@ impl TypeReader {
     /// # Panics
     ///
     /// Panics if no type definition for the given namespace and type name can be found
+    ///
+    /// The panic message names the chain of types and members that led to the type, and the
+    /// metadata that's missing when the type's whole namespace is unknown.
     pub fn resolve_type_def(&self, (namespace, type_name): (&str, &str)) -> TypeDef {
-        if let Some(types) = self.types.get(namespace) {
-            if let Some(def) = types.get(type_name) {
-                return *def;
+        self.find_type_def((namespace, type_name))
+            .or_else(|| self.find_forwarded_type_def((namespace, type_name)))
+            .unwrap_or_else(|| panic!("{}", self.missing_type_message((namespace, type_name))))
+    }
+
+    fn missing_type_message(&self, (namespace, type_name): (&str, &str)) -> String {
+        let mut message = format!("Could not find type `{}.{}`", namespace, type_name);
+        let context: Vec<String> = self
+            .context
+            .borrow()
+            .iter()
+            .map(|item| self.describe(*item))
+            .collect();
+
+        if !context.is_empty() {
+            message += &format!(
+                ", which is needed by {} -> type `{}.{}`",
+                context.join(" -> "),
+                namespace,
+                type_name
+            );
+        }
+
+        if !self.types.contains_key(namespace) {
+            message += &format!(
+                ". The namespace `{}` isn't defined by any of the winmd files, so add the \
+                 dependency that defines it to `import!`",
+                namespace
+            );
+
+            if namespace.starts_with("Windows.") {
+                message += ", such as `\"os\"`";
+            }
+        }
+
+        let forwarder = self
+            .exported_types()
+            .find(|exported| exported.name(self) == (namespace, type_name));
+
+        if let Some(Err(reason)) = forwarder.map(|exported| self.forwarded_type(exported)) {
+            message += &format!(". The type has a forwarder, but {}", reason);
+        }
+
+        message
+    }
+
+    /// Records what is being read until the returned value is dropped so that a type that
+    /// can't be found can be traced back to what needed it
+    pub(crate) fn context(&self, item: ContextItem) -> ReaderContext<'_> {
+        self.context.borrow_mut().push(item);
+        ReaderContext { reader: self }
+    }
+
+    fn describe(&self, item: ContextItem) -> String {
+        match item {
+            ContextItem::Type(def) => {
+                let (namespace, name) = def.name(self);
+                let category = def.category(self).description();
+                format!("{} `{}.{}`", category, namespace, name)
             }
+            ContextItem::Method(method) => format!("method `{}`", method.name(self)),
+            ContextItem::Param(param) => format!("parameter `{}`", param.name(self)),
+            ContextItem::Field(field) => format!("field `{}`", field.name(self)),
+            ContextItem::ReturnType => "return type".to_string(),
         }
+    }
 
-        panic!("Could not find type `{}.{}`", namespace, type_name);
+    /// Find a type definition given its namespace and type name, if the [`TypeReader`] knows about it
+    pub fn find_type_def(&self, (namespace, type_name): (&str, &str)) -> Option<TypeDef> {
+        self.types
+            .get(namespace)
+            .and_then(|types| types.get(type_name))
+            .copied()
+    }
+
+    /// Find the new definition of a type that has moved to another namespace, recording that
+    /// the old name is still in use, see [`TypeReader::take_moved`]
+    pub fn find_forwarded_type_def(&self, (namespace, type_name): (&str, &str)) -> Option<TypeDef> {
+        let def = *self.forwarded.get(namespace)?.get(type_name)?;
+        let (new_namespace, _) = def.name(self);
+
+        self.moved.borrow_mut().insert((
+            format!("{}.{}", namespace, type_name),
+            format!("{}.{}", new_namespace, type_name),
+        ));
+
+        Some(def)
+    }
+
+    /// Takes the old and new names of the types that have been found by a name that's been
+    /// forwarded since the last call, so that the caller can warn that the old names are still
+    /// in use
+    pub fn take_moved(&self) -> BTreeSet<(String, String)> {
+        self.moved.take()
     }
 
     pub fn resolve_type(&self, (namespace, type_name): (&str, &str)) -> Type {
@@ -141,15 +423,15 @@ impl TypeReader {
         let offset = (file.blobs + self.u32(row, column)) as usize;
         let initial_byte = file.bytes[offset];
         let (mut blob_size, blob_size_bytes) = match initial_byte >> 5 {
-            0..=3 => (initial_byte & 0x7f, 1),
-            4..=5 => (initial_byte & 0x3f, 2),
-            6 => (initial_byte & 0x1f, 4),
+            0..=3 => ((initial_byte & 0x7f) as usize, 1),
+            4..=5 => ((initial_byte & 0x3f) as usize, 2),
+            6 => ((initial_byte & 0x1f) as usize, 4),
             _ => panic!(),
         };
         for byte in &file.bytes[offset + 1..offset + blob_size_bytes] {
-            blob_size = blob_size.checked_shl(8).unwrap_or(0) + byte;
+            blob_size = (blob_size << 8) + *byte as usize;
         }
-        Blob::new(self, row.file_index, offset + blob_size_bytes)
+        Blob::new(self, row.file_index, offset + blob_size_bytes, blob_size)
     }
 
     pub fn equal_range(
@@ -270,3 +552,159 @@ impl TypeReader {
         (first, last)
     }
 }
+
+// Picks the type a forwarder stands in for from the types with its name that the assembly it's
+// forwarded to defines, which must be the only one as the new namespace isn't recorded.
+fn select_forwarded_type(
+    assembly: &str,
+    name: &str,
+    candidates: &[(&str, TypeDef)],
+) -> Result<TypeDef, String> {
+    match candidates {
+        [] => Err(format!(
+            "the assembly `{}` it's forwarded to doesn't define a type named `{}`",
+            assembly, name
+        )),
+        [(_, def)] => Ok(*def),
+        _ => Err(format!(
+            "the assembly `{}` it's forwarded to defines a type named `{}` in each of {}",
+            assembly,
+            name,
+            candidates
+                .iter()
+                .map(|(namespace, _)| format!("`{}`", namespace))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached() {
+        let first = TypeReader::cached(BTreeSet::new());
+        let second = TypeReader::cached(BTreeSet::new());
+        assert!(Rc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn test_cache_invalidation() {
+        let dir = std::env::temp_dir().join(format!("winmd-cache-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("Contoso.winmd");
+        std::fs::write(&path, b"").unwrap();
+
+        let mut cache = ReaderCache::default();
+        let mut created = 0;
+        let files: BTreeSet<PathBuf> = std::iter::once(path.clone()).collect();
+
+        let first = cache.get(&files, || created += 1);
+        let second = cache.get(&files, || created += 1);
+        assert!(Rc::ptr_eq(&first, &second));
+
+        // Another path to the same file finds the same entry.
+        let other: BTreeSet<PathBuf> =
+            std::iter::once(dir.join(".").join("Contoso.winmd")).collect();
+        let third = cache.get(&other, || created += 1);
+        assert!(Rc::ptr_eq(&first, &third));
+        assert!(created == 1);
+
+        // Modifying the file replaces the entry rather than adding another.
+        let modified = SystemTime::now() + std::time::Duration::from_secs(60);
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(modified).unwrap();
+
+        let fourth = cache.get(&files, || created += 1);
+        assert!(!Rc::ptr_eq(&first, &fourth));
+        assert!(created == 2);
+        assert!(cache.0.len() == 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_type_message() {
+        let reader = TypeReader::new(Vec::new());
+
+        assert_eq!(
+            reader.missing_type_message(("Contoso", "Widget")),
+            "Could not find type `Contoso.Widget`. The namespace `Contoso` isn't defined by any \
+             of the winmd files, so add the dependency that defines it to `import!`"
+        );
+
+        let _return_type = reader.context(ContextItem::ReturnType);
+
+        assert_eq!(
+            reader.missing_type_message(("Contoso", "Widget")),
+            "Could not find type `Contoso.Widget`, which is needed by return type -> type \
+             `Contoso.Widget`. The namespace `Contoso` isn't defined by any of the winmd files, \
+             so add the dependency that defines it to `import!`"
+        );
+    }
+
+    #[test]
+    fn test_context() {
+        let reader = &TypeReader::from_os();
+        let def = reader.resolve_type_def(("Windows.Foundation", "Uri"));
+
+        let method = def
+            .methods(reader)
+            .find(|method| method.name(reader) == "CombineUri")
+            .unwrap();
+
+        let param = method
+            .params(reader)
+            .find(|param| param.name(reader) == "relativeUri")
+            .unwrap();
+
+        let _class = reader.context(ContextItem::Type(def));
+
+        {
+            let _method = reader.context(ContextItem::Method(method));
+            let _param = reader.context(ContextItem::Param(param));
+
+            assert_eq!(
+                reader.missing_type_message(("Windows.UI.Xaml", "RoutedEventHandler")),
+                "Could not find type `Windows.UI.Xaml.RoutedEventHandler`, which is needed by \
+                 class `Windows.Foundation.Uri` -> method `CombineUri` -> parameter \
+                 `relativeUri` -> type `Windows.UI.Xaml.RoutedEventHandler`"
+            );
+        }
+
+        assert!(*reader.context.borrow() == vec![ContextItem::Type(def)]);
+    }
+
+    #[test]
+    fn test_select_forwarded_type() {
+        let def = |row| TypeDef(Row::new(row, TableIndex::TypeDef, 0));
+
+        assert_eq!(
+            select_forwarded_type("Contoso", "Widget", &[]),
+            Err(
+                "the assembly `Contoso` it's forwarded to doesn't define a type named `Widget`"
+                    .to_string()
+            )
+        );
+
+        assert_eq!(
+            select_forwarded_type("Contoso", "Widget", &[("Contoso.Controls", def(1))]),
+            Ok(def(1))
+        );
+
+        assert_eq!(
+            select_forwarded_type(
+                "Contoso",
+                "Widget",
+                &[("Contoso.Controls", def(1)), ("Contoso.Legacy", def(2))]
+            ),
+            Err(
+                "the assembly `Contoso` it's forwarded to defines a type named `Widget` in each \
+                 of `Contoso.Controls`, `Contoso.Legacy`"
+                    .to_string()
+            )
+        );
+    }
+}
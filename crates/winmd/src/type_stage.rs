@@ -1,8 +1,8 @@
 use crate::tables::TypeDef;
 use crate::type_limits::TypeLimits;
 use crate::type_tree::TypeTree;
-use crate::types::Type;
-use crate::TypeReader;
+use crate::types::{Contract, InterfaceKind, Method, Specialization, Type, TypeName};
+use crate::{TypeFeatures, TypeReader};
 
 use std::collections::*;
 
@@ -15,12 +15,16 @@ impl TypeStage {
     pub fn from_limits(reader: &TypeReader, limits: &TypeLimits) -> Self {
         let mut stage = Self::default();
 
-        for namespace in &limits.0 {
+        for namespace in &limits.namespaces {
             for def in reader.namespace_types(&namespace) {
                 stage.insert(reader, *def);
             }
         }
 
+        for def in &limits.types {
+            stage.insert(reader, *def);
+        }
+
         stage
     }
 
@@ -35,16 +39,219 @@ impl TypeStage {
         }
     }
 
+    /// Returns the feature for each namespace and the namespaces it depends on
+    pub fn features(&self) -> TypeFeatures {
+        let mut features = TypeFeatures::default();
+
+        for t in self.0.values() {
+            let namespace = &t.name().namespace;
+            features.insert_namespace(namespace);
+
+            for def in t.dependencies() {
+                if let Some(dependency) = self.0.get(&def) {
+                    features.insert(namespace, &dependency.name().namespace);
+                }
+            }
+        }
+
+        features
+    }
+
+    /// Leaves out the types and members marked as deprecated, for the `skip_deprecated` option
+    ///
+    /// Deprecated members are skipped as overrides skip them, remaining in their vtables, so the
+    /// types they refer to are still needed. A deprecated type is only removed once no type that
+    /// remains refers to it.
+    pub fn remove_deprecated(&mut self, reader: &TypeReader) {
+        for t in self.0.values_mut() {
+            let interfaces = match t {
+                Type::Class(t) => &mut t.interfaces,
+                Type::Interface(t) => &mut t.interfaces,
+                _ => continue,
+            };
+
+            for method in interfaces.iter_mut().flat_map(|i| i.methods.iter_mut()) {
+                if method.deprecated {
+                    method.skip = true;
+                }
+            }
+        }
+
+        let removed = self
+            .0
+            .keys()
+            .copied()
+            .filter(|def| {
+                def.has_attribute(
+                    reader,
+                    ("Windows.Foundation.Metadata", "DeprecatedAttribute"),
+                )
+            })
+            .collect();
+
+        self.remove_types(removed);
+    }
+
+    /// Leaves out the types introduced after a Windows build, and the interfaces that classes
+    /// gained after it, for the `max_build` option
+    ///
+    /// Only types versioned by the UniversalApiContract can be mapped to Windows builds. Types
+    /// and interfaces from other contracts are kept, and the contracts are returned so that the
+    /// caller can warn that the build may not have them. So are versions of the
+    /// UniversalApiContract newer than the builds the generator knows of, which are only left
+    /// out for builds older than the latest one it knows.
+    pub fn limit_to_build(&mut self, build: u32) -> BTreeSet<String> {
+        let mut unmapped = BTreeSet::new();
+
+        let mut later = |contract: Option<&Contract>| match contract {
+            Some(contract) => match contract.windows_build() {
+                Some(introduced) => {
+                    if contract.newer_than_known() && introduced <= build {
+                        unmapped.insert(contract.to_string());
+                    }
+
+                    introduced > build
+                }
+                None => {
+                    unmapped.insert(contract.to_string());
+                    false
+                }
+            },
+            None => false,
+        };
+
+        for t in self.0.values_mut() {
+            if let Type::Class(class) = t {
+                class
+                    .interfaces
+                    .retain(|i| i.kind == InterfaceKind::Default || !later(i.contract.as_ref()));
+
+                let interfaces = &class.interfaces;
+                class
+                    .factories
+                    .retain(|factory| interfaces.iter().any(|i| i.name == *factory));
+            }
+        }
+
+        let removed = self
+            .0
+            .iter()
+            .filter(|(_, t)| later(t.contract()))
+            .map(|(def, _)| *def)
+            .collect();
+
+        self.remove_types(removed);
+        unmapped
+    }
+
+    // Removes types, other than those that a type that remains still refers to.
+    fn remove_types(&mut self, mut removed: BTreeSet<TypeDef>) {
+        loop {
+            let needed: BTreeSet<TypeDef> = self
+                .0
+                .iter()
+                .filter(|(def, _)| !removed.contains(def))
+                .flat_map(|(_, t)| t.dependencies())
+                .filter(|def| removed.contains(def))
+                .collect();
+
+            if needed.is_empty() {
+                break;
+            }
+
+            removed = removed.difference(&needed).copied().collect();
+        }
+
+        self.0.retain(|def, _| !removed.contains(def));
+    }
+
     /// Resolve the types into a type tree for code generation
-    pub fn into_tree(self) -> TypeTree {
+    ///
+    /// Types are generated in order of their names, rather than of their rows in the metadata,
+    /// so that the same types generate the same code whichever winmd files they were read from.
+    ///
+    /// The IID of each specialization of a generic interface that a type requires, or that
+    /// appears in the signature of one of its methods, is generated along with the first type to
+    /// use it.
+    pub fn into_tree(self, reader: &TypeReader) -> TypeTree {
+        let defs: BTreeSet<TypeDef> = self.0.keys().copied().collect();
+        let mut types: Vec<Type> = self.0.into_values().collect();
+        types.sort_by(|a, b| a.name().cmp(b.name()));
+
         let mut tree = TypeTree::default();
-        self.0
-            .into_iter()
-            .for_each(|(_, t)| tree.insert(t.name().namespace.clone(), t));
+        let mut specializations = BTreeSet::new();
+
+        for t in types {
+            let namespace = t.name().namespace.to_string();
+
+            // Generic types only know the GUIDs of their required interfaces' definitions.
+            if t.name().generics.is_empty() {
+                for interface in t.interfaces() {
+                    if !interface.name.generics.is_empty()
+                        && interface.name.is_concrete()
+                        && specializations.insert(interface.name.clone())
+                    {
+                        tree.insert_specialization(
+                            namespace.clone(),
+                            Specialization {
+                                name: interface.name.clone(),
+                                guid: interface.guid.clone(),
+                                contract: t.contract().cloned(),
+                            },
+                        );
+                    }
+                }
+
+                for (name, contract) in signature_specializations(&t) {
+                    // Classes don't depend on the types in their signatures, so these may have
+                    // been left out of the bindings.
+                    if name.dependencies().iter().all(|def| defs.contains(def))
+                        && specializations.insert(name.clone())
+                    {
+                        tree.insert_specialization(
+                            namespace.clone(),
+                            Specialization {
+                                name: name.clone(),
+                                guid: name.guid(reader, false),
+                                contract: contract.or_else(|| t.contract()).cloned(),
+                            },
+                        );
+                    }
+                }
+            }
+
+            tree.insert(namespace, t);
+        }
+
         tree
     }
 }
 
+// The specializations of generic interfaces in the parameters and return types of a type's
+// methods, along with the contract of the interface that declares each method.
+fn signature_specializations(t: &Type) -> Vec<(&TypeName, Option<&Contract>)> {
+    let methods: Vec<(&Method, Option<&Contract>)> = match t {
+        Type::Delegate(t) => vec![(&t.method, None)],
+        _ => t
+            .interfaces()
+            .iter()
+            .flat_map(|i| i.methods.iter().map(move |m| (m, i.contract.as_ref())))
+            .collect(),
+    };
+
+    let mut result = Vec::new();
+
+    for (method, contract) in methods {
+        for param in method.return_type.iter().chain(method.params.iter()) {
+            for name in param.kind.specializations() {
+                result.push((name, contract));
+            }
+        }
+    }
+
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -62,10 +269,109 @@ mod tests {
 
         // Windows.Foundation.WwwFormUrlDecoder depends on Windows.Foundation.Collections.IVectorView`1
         // so that's included.
-        assert!(stage.0.values().any(|t| t.name().name == "IVectorView`1"));
+        assert!(stage.0.values().any(|t| &*t.name().name == "IVectorView`1"));
 
         // Windows.Foundation does not however depend on Windows.Foundation.Collections.PropertySet
         // so that's not included.
-        assert!(stage.0.values().any(|t| t.name().name == "PropertySet") == false);
+        assert!(stage.0.values().any(|t| &*t.name().name == "PropertySet") == false);
+    }
+
+    #[test]
+    fn test_type_reachability() {
+        let reader = &TypeReader::from_os();
+
+        // Only the types that Windows.Foundation.Uri refers to are included, not the rest of
+        // Windows.Foundation.
+        let mut limits = TypeLimits::default();
+        limits.insert_type(reader, "windows.foundation.uri");
+        let stage = TypeStage::from_limits(reader, &limits);
+
+        assert!(stage
+            .0
+            .values()
+            .any(|t| &*t.name().name == "IUriRuntimeClass"));
+        assert!(stage
+            .0
+            .values()
+            .any(|t| &*t.name().name == "WwwFormUrlDecoder"));
+        assert!(stage.0.values().any(|t| &*t.name().name == "PropertyValue") == false);
+    }
+
+    #[test]
+    fn test_remove_deprecated() {
+        let reader = &TypeReader::from_os();
+        let mut limits = TypeLimits::default();
+        limits.insert(reader, "windows.ui.view_management");
+        let mut stage = TypeStage::from_limits(reader, &limits);
+
+        // Windows.UI.ViewManagement has members that were deprecated along with snapped views.
+        let methods = |stage: &TypeStage| -> Vec<(bool, bool)> {
+            stage
+                .0
+                .values()
+                .flat_map(|t| t.interfaces())
+                .flat_map(|i| i.methods.iter().map(|m| (m.deprecated, m.skip)))
+                .collect()
+        };
+
+        assert!(methods(&stage).iter().any(|(deprecated, _)| *deprecated));
+        stage.remove_deprecated(reader);
+
+        // Deprecated members remain in their vtables but are skipped.
+        assert!(methods(&stage)
+            .iter()
+            .all(|(deprecated, skip)| !deprecated || *skip));
+
+        // Deprecated types are only kept if they're still used by other types.
+        for (def, _) in stage.0.iter().filter(|(def, _)| {
+            def.has_attribute(
+                reader,
+                ("Windows.Foundation.Metadata", "DeprecatedAttribute"),
+            )
+        }) {
+            assert!(stage
+                .0
+                .values()
+                .any(|t| t.name().def != *def && t.dependencies().contains(def)));
+        }
+    }
+
+    #[test]
+    fn test_limit_to_build() {
+        let reader = &TypeReader::from_os();
+        let mut limits = TypeLimits::default();
+        limits.insert(reader, "windows.ui.composition");
+        let mut stage = TypeStage::from_limits(reader, &limits);
+
+        let later = |contract: Option<&Contract>| matches!(contract.and_then(Contract::windows_build), Some(build) if build > 17134);
+
+        // Windows.UI.Composition has grown with almost every release of Windows 10.
+        assert!(stage.0.values().any(|t| later(t.contract())));
+        let unmapped = stage.limit_to_build(17134);
+
+        // Composition types all belong to the UniversalApiContract, but the Windows.Foundation
+        // types they depend on don't.
+        assert!(!unmapped
+            .iter()
+            .any(|c| c.starts_with("Windows.Foundation.UniversalApiContract ")));
+        assert!(unmapped
+            .iter()
+            .any(|c| c.starts_with("Windows.Foundation.FoundationContract ")));
+
+        for (def, t) in stage.0.iter() {
+            if later(t.contract()) {
+                assert!(stage
+                    .0
+                    .values()
+                    .any(|t| t.name().def != *def && t.dependencies().contains(def)));
+            }
+
+            if let Type::Class(class) = t {
+                assert!(class
+                    .interfaces
+                    .iter()
+                    .all(|i| i.kind == InterfaceKind::Default || !later(i.contract.as_ref())));
+            }
+        }
     }
 }
@@ -0,0 +1,468 @@
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The version of the surface format, written as a header so that surfaces recorded by
+/// different generator versions are never compared item by item by mistake
+pub const SURFACE_VERSION: u32 = 1;
+
+/// The public items generated for each namespace along with their signatures
+///
+/// The surface is written by the `import!` macro when the `WINRT_SURFACE` environment variable
+/// names a file, one `path<TAB>signature` pair per line. Crates that publish pre-generated
+/// bindings record it with each release. When `WINRT_VERIFY_SURFACE` names a previously
+/// recorded surface, the macro fails the build if the generated code would break users of it,
+/// such as by removing a method or changing its signature, so that the generator can be
+/// updated without an accidental semver-breaking change.
+#[derive(Default, Debug, PartialEq)]
+pub struct TypeSurface(pub BTreeMap<String, String>);
+
+/// The differences between two surfaces
+#[derive(Default, Debug, PartialEq)]
+pub struct SurfaceChanges {
+    /// Items that were removed or changed, and members added to structs and enums, which
+    /// break code that constructs or exhaustively matches them
+    pub breaking: Vec<String>,
+    /// Items that were added without affecting existing code
+    pub additions: Vec<String>,
+}
+
+impl TypeSurface {
+    /// Insert the public items of the code generated for a type in a namespace
+    pub fn insert(&mut self, namespace: &str, tokens: TokenStream) {
+        let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+        let mut index = 0;
+
+        while index < tokens.len() {
+            if is_ident(&tokens[index], "pub") {
+                index = self.insert_item(namespace, &tokens, index + 1);
+            } else if is_ident(&tokens[index], "impl") {
+                index = self.insert_impl(namespace, &tokens, index + 1);
+            } else {
+                index += 1;
+            }
+        }
+    }
+
+    /// Parse a surface previously written with its `Display` implementation
+    ///
+    /// # Panics
+    ///
+    /// Panics if the surface was written with a different version of the format.
+    pub fn parse(text: &str) -> Self {
+        let mut surface = Self::default();
+
+        for line in text.lines() {
+            if let Some(version) = line.strip_prefix("# winrt surface v") {
+                assert!(
+                    version.trim() == SURFACE_VERSION.to_string(),
+                    "Surface version {} is not supported, expected {}",
+                    version.trim(),
+                    SURFACE_VERSION
+                );
+                continue;
+            }
+
+            let mut parts = line.splitn(2, '\t');
+
+            if let (Some(path), Some(signature)) = (parts.next(), parts.next()) {
+                surface
+                    .0
+                    .insert(path.trim().to_string(), signature.trim().to_string());
+            }
+        }
+
+        surface
+    }
+
+    /// Compare this surface against a previously recorded one
+    ///
+    /// Namespaces that aren't part of this surface are ignored since a recorded surface may
+    /// be shared by several `import!` invocations.
+    pub fn compare(&self, previous: &TypeSurface) -> SurfaceChanges {
+        let mut changes = SurfaceChanges::default();
+
+        for (path, signature) in &previous.0 {
+            if !self.has_namespace(namespace(path)) {
+                continue;
+            }
+
+            match self.0.get(path) {
+                Some(current) if current == signature => {}
+                Some(current) => changes.breaking.push(format!(
+                    "`{}` changed from `{}` to `{}`",
+                    path, signature, current
+                )),
+                None => changes.breaking.push(format!("`{}` was removed", path)),
+            }
+        }
+
+        for (path, signature) in &self.0 {
+            if previous.0.contains_key(path) {
+                continue;
+            }
+
+            let parent = path.rsplit_once("::").map_or("", |(parent, _)| parent);
+
+            match previous.0.get(parent).map(|parent| parent.as_str()) {
+                Some(kind) if is_exhaustive(kind) => changes
+                    .breaking
+                    .push(format!("`{}` was added to `{}`", path, parent)),
+                _ => changes.additions.push(format!("`{}` {}", path, signature)),
+            }
+        }
+
+        changes
+    }
+
+    fn has_namespace(&self, namespace: &str) -> bool {
+        self.0.keys().any(|path| self::namespace(path) == namespace)
+    }
+
+    fn insert_item(&mut self, namespace: &str, tokens: &[TokenTree], index: usize) -> usize {
+        let kind = match tokens.get(index) {
+            Some(TokenTree::Ident(kind)) => kind.to_string(),
+            _ => return index,
+        };
+
+        let name = match tokens.get(index + 1) {
+            Some(TokenTree::Ident(name)) => name.to_string(),
+            _ => return index,
+        };
+
+        let (signature, body, end) = signature(tokens, index + 2);
+        let path = format!("{}::{}", namespace, name);
+
+        match (kind.as_str(), body) {
+            ("struct", Some(body)) => {
+                for field in split_members(body) {
+                    if let Some((name, signature)) = public_member(&field, ":") {
+                        self.0.insert(format!("{}::{}", path, name), signature);
+                    }
+                }
+            }
+            ("enum", Some(body)) => {
+                for variant in split_members(body) {
+                    if let Some(TokenTree::Ident(name)) = variant.first() {
+                        self.0
+                            .insert(format!("{}::{}", path, name), "variant".into());
+                    }
+                }
+            }
+            ("trait", Some(body)) => {
+                let members: Vec<TokenTree> = body.into_iter().collect();
+                let mut member = 0;
+
+                while member < members.len() {
+                    if is_ident(&members[member], "fn") {
+                        if let Some(TokenTree::Ident(name)) = members.get(member + 1) {
+                            let (signature, _, end) = self::signature(&members, member + 2);
+                            self.0
+                                .insert(format!("{}::{}", path, name), format!("fn {}", signature));
+                            member = end;
+                            continue;
+                        }
+                    }
+                    member += 1;
+                }
+            }
+            _ => {}
+        }
+
+        self.0
+            .insert(path, format!("{} {}", kind, signature).trim().into());
+        end
+    }
+
+    fn insert_impl(&mut self, namespace: &str, tokens: &[TokenTree], index: usize) -> usize {
+        let (header, body, end) = signature(tokens, index);
+        let header: Vec<TokenTree> = header.into_iter().collect();
+        let header = skip_generics(&header);
+
+        // Trait implementations are part of the surface as a whole.
+        if let Some(position) = header.iter().position(|token| is_ident(token, "for")) {
+            let self_type = type_name(&header[position + 1..]);
+            let tokens: TokenStream = header.iter().cloned().collect();
+            self.0.insert(
+                format!("{}::{}::impl {}", namespace, self_type, tokens),
+                "impl".into(),
+            );
+            return end;
+        }
+
+        let path = format!("{}::{}", namespace, type_name(header));
+        let members: Vec<TokenTree> = match body {
+            Some(body) => body.into_iter().collect(),
+            None => return end,
+        };
+
+        let mut member = 0;
+
+        while member < members.len() {
+            if is_ident(&members[member], "pub") {
+                match (members.get(member + 1), members.get(member + 2)) {
+                    (Some(kind), Some(TokenTree::Ident(name)))
+                        if is_ident(kind, "fn") || is_ident(kind, "const") =>
+                    {
+                        let (signature, _, end) = self::signature(&members, member + 3);
+                        self.0.insert(
+                            format!("{}::{}", path, name),
+                            format!("{} {}", kind, signature),
+                        );
+                        member = end;
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+            member += 1;
+        }
+
+        end
+    }
+}
+
+impl SurfaceChanges {
+    pub fn is_breaking(&self) -> bool {
+        !self.breaking.is_empty()
+    }
+}
+
+impl fmt::Display for TypeSurface {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "# winrt surface v{}", SURFACE_VERSION)?;
+
+        for (path, signature) in &self.0 {
+            writeln!(f, "{}\t{}", path, signature)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for SurfaceChanges {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for change in &self.breaking {
+            writeln!(f, "breaking: {}", change)?;
+        }
+
+        for change in &self.additions {
+            writeln!(f, "added: {}", change)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn namespace(path: &str) -> &str {
+    path.split("::").next().unwrap_or_default()
+}
+
+// Adding members to structs and enums breaks code that constructs or matches them exhaustively.
+fn is_exhaustive(kind: &str) -> bool {
+    kind.starts_with("struct") || kind.starts_with("enum")
+}
+
+fn is_ident(token: &TokenTree, name: &str) -> bool {
+    matches!(token, TokenTree::Ident(ident) if ident == name)
+}
+
+fn is_punct(token: &TokenTree, ch: char) -> bool {
+    matches!(token, TokenTree::Punct(punct) if punct.as_char() == ch)
+}
+
+/// Collects the tokens up to an item's body or terminating semicolon
+///
+/// Returns the signature, the body if there is one, and the index following the item.
+fn signature(tokens: &[TokenTree], mut index: usize) -> (TokenStream, Option<TokenStream>, usize) {
+    let mut signature = Vec::new();
+
+    while let Some(token) = tokens.get(index) {
+        index += 1;
+
+        match token {
+            TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
+                return (signature.into_iter().collect(), Some(group.stream()), index);
+            }
+            token if is_punct(token, ';') => break,
+            token => signature.push(token.clone()),
+        }
+    }
+
+    // Constants keep their type but not their value, which may be rewritten by the literal pool.
+    if let Some(position) = signature.iter().position(|token| is_punct(token, '=')) {
+        signature.truncate(position);
+    }
+
+    (signature.into_iter().collect(), None, index)
+}
+
+fn skip_generics(tokens: &[TokenTree]) -> &[TokenTree] {
+    if !tokens.first().is_some_and(|token| is_punct(token, '<')) {
+        return tokens;
+    }
+
+    let mut depth = 0;
+
+    for (index, token) in tokens.iter().enumerate() {
+        if is_punct(token, '<') {
+            depth += 1;
+        } else if is_punct(token, '>') {
+            depth -= 1;
+
+            if depth == 0 {
+                return &tokens[index + 1..];
+            }
+        }
+    }
+
+    &[]
+}
+
+fn type_name(tokens: &[TokenTree]) -> String {
+    tokens
+        .iter()
+        .take_while(|token| !is_punct(token, '<') && !is_ident(token, "where"))
+        .filter_map(|token| match token {
+            TokenTree::Ident(ident) => Some(ident.to_string()),
+            _ => None,
+        })
+        .last()
+        .unwrap_or_default()
+}
+
+fn split_members(body: TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut members = vec![Vec::new()];
+    let mut depth = 0;
+
+    for token in body {
+        if is_punct(&token, '<') {
+            depth += 1;
+        } else if is_punct(&token, '>') && depth > 0 {
+            depth -= 1;
+        } else if is_punct(&token, ',') && depth == 0 {
+            members.push(Vec::new());
+            continue;
+        }
+
+        members.last_mut().unwrap().push(token);
+    }
+
+    members.retain(|member| !member.is_empty());
+    members
+}
+
+fn public_member(member: &[TokenTree], separator: &str) -> Option<(String, String)> {
+    // Skip attributes, such as doc comments, ahead of the member.
+    let mut index = 0;
+
+    while member.get(index).is_some_and(|token| is_punct(token, '#')) {
+        index += 2;
+    }
+
+    match (
+        member.get(index),
+        member.get(index + 1),
+        member.get(index + 2),
+    ) {
+        (Some(visibility), Some(TokenTree::Ident(name)), Some(punct))
+            if is_ident(visibility, "pub") && punct.to_string() == separator =>
+        {
+            let kind: TokenStream = member[index + 3..].iter().cloned().collect();
+            Some((name.to_string(), kind.to_string()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    fn surface(tokens: TokenStream) -> TypeSurface {
+        let mut surface = TypeSurface::default();
+        surface.insert("Windows.Foundation", tokens);
+        surface
+    }
+
+    #[test]
+    fn test_items() {
+        let surface = surface(quote! {
+            #[repr(C)]
+            pub struct Point { pub x: f32, pub y: f32 }
+            pub enum AsyncStatus { Started = 0, Completed = 1 }
+            pub struct Uri { ptr: ::winrt::ComPtr<Uri> }
+            impl Uri {
+                pub const MAX: i32 = 10;
+                pub fn domain(&self) -> ::winrt::Result<::winrt::HString> {
+                    fn helper() {}
+                    helper()
+                }
+                fn private(&self) {}
+            }
+            impl<T: ::winrt::RuntimeType + 'static> ::std::fmt::Debug for IVector<T> {}
+        });
+
+        let expected = TypeSurface::parse(
+            "# winrt surface v1\n\
+             Windows.Foundation::AsyncStatus\tenum\n\
+             Windows.Foundation::AsyncStatus::Completed\tvariant\n\
+             Windows.Foundation::AsyncStatus::Started\tvariant\n\
+             Windows.Foundation::IVector::impl :: std :: fmt :: Debug for IVector < T >\timpl\n\
+             Windows.Foundation::Point\tstruct\n\
+             Windows.Foundation::Point::x\tf32\n\
+             Windows.Foundation::Point::y\tf32\n\
+             Windows.Foundation::Uri\tstruct\n\
+             Windows.Foundation::Uri::MAX\tconst : i32\n\
+             Windows.Foundation::Uri::domain\tfn (& self) -> :: winrt :: Result < :: winrt :: HString >\n",
+        );
+
+        assert!(surface == expected);
+        assert!(TypeSurface::parse(&surface.to_string()) == surface);
+    }
+
+    #[test]
+    fn test_compare() {
+        let previous = surface(quote! {
+            pub struct Point { pub x: f32 }
+            impl Uri {
+                pub fn domain(&self) -> ::winrt::Result<::winrt::HString> {}
+                pub fn port(&self) -> ::winrt::Result<i32> {}
+            }
+        });
+
+        let current = surface(quote! {
+            pub struct Point { pub x: f32, pub y: f32 }
+            impl Uri {
+                pub fn domain(&self) -> ::winrt::Result<::winrt::Object> {}
+                pub fn host(&self) -> ::winrt::Result<::winrt::HString> {}
+            }
+        });
+
+        let changes = current.compare(&previous);
+        assert!(changes.is_breaking());
+        assert_eq!(changes.breaking.len(), 3);
+        assert!(changes.breaking[0].starts_with("`Windows.Foundation::Uri::domain` changed"));
+        assert!(changes.breaking[1] == "`Windows.Foundation::Uri::port` was removed");
+        assert!(
+            changes.breaking[2]
+                == "`Windows.Foundation::Point::y` was added to `Windows.Foundation::Point`"
+        );
+        assert!(changes.additions.len() == 1);
+        assert!(changes.additions[0].starts_with("`Windows.Foundation::Uri::host`"));
+
+        assert!(!current.compare(&current).is_breaking());
+
+        // Namespaces that aren't generated are left alone.
+        let mut other = TypeSurface::default();
+        other.insert("Windows.UI", quote! { pub struct Color { pub a: u8 } });
+        assert!(!current.compare(&other).is_breaking());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_version() {
+        TypeSurface::parse("# winrt surface v0\n");
+    }
+}
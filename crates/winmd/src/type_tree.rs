@@ -1,12 +1,25 @@
-use crate::type_namespaces::TypeNamespaces;
-use crate::types::Type;
+use crate::case;
+use crate::format_ident;
+use crate::literal_pool::LiteralPool;
+use crate::type_limits::to_rough_name;
+use crate::type_namespaces::{to_namespace_gate, TypeNamespaces};
+use crate::types::{
+    interface_trait_tokens, to_gated_tokens, to_visibility_tokens, MethodKind, Specialization, Type,
+};
+use crate::{SymbolMap, TypeManifest, TypeOptions, TypeSurface};
 use proc_macro2::TokenStream;
+use quote::quote;
+use std::collections::BTreeMap;
 use std::iter::FromIterator;
+use std::path::Path;
 
 /// A namespaced tree of types
 #[derive(Default)]
 pub struct TypeTree {
+    /// The full name of the namespace, which is empty for the root of the tree
+    pub namespace: String,
     types: Vec<Type>,
+    specializations: Vec<Specialization>,
     namespaces: TypeNamespaces,
 }
 
@@ -15,35 +28,372 @@ impl TypeTree {
     ///
     /// This recursively searchs the tree for an entry corresponding to the namespace
     pub fn insert(&mut self, namespace: String, t: Type) {
+        self.descendant(&namespace).types.push(t);
+    }
+
+    /// Insert the IID of a generic interface's specialization, to be generated in the namespace
+    /// of the type that requires it
+    pub(crate) fn insert_specialization(&mut self, namespace: String, s: Specialization) {
+        self.descendant(&namespace).specializations.push(s);
+    }
+
+    fn descendant(&mut self, namespace: &str) -> &mut TypeTree {
         if let Some(pos) = namespace.find('.') {
-            self.namespaces
-                .0
-                .entry(namespace[..pos].to_string())
-                .or_default()
-                .insert(namespace[pos + 1..].to_string(), t);
+            self.child(&namespace[..pos])
+                .descendant(&namespace[pos + 1..])
         } else {
-            self.namespaces
-                .0
-                .entry(namespace)
-                .or_default()
-                .types
-                .push(t);
+            self.child(namespace)
         }
     }
 
+    fn child(&mut self, name: &str) -> &mut TypeTree {
+        let namespace = if self.namespace.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{}", self.namespace, name)
+        };
+
+        self.namespaces
+            .0
+            .entry(name.to_string())
+            .or_insert_with(|| TypeTree {
+                namespace,
+                ..Default::default()
+            })
+    }
+
     /// Turn the tree into a token stream for code generation
-    pub fn to_tokens(&self) -> TokenStream {
+    pub fn to_tokens(&self, options: &TypeOptions) -> TokenStream {
+        let mut code = LiteralPool::apply(self.to_module_tokens(options));
+        code.extend(self.to_prelude_tokens(options));
+        code.extend(self.to_alias_tokens(options));
+        to_visible_tokens(code, options)
+    }
+
+    /// The tokens for this part of the tree, before any literals are pooled
+    pub fn to_module_tokens(&self, options: &TypeOptions) -> TokenStream {
         TokenStream::from_iter(
             self.types
                 .iter()
-                .map(|t| t.to_tokens())
-                .chain(std::iter::once(self.namespaces.to_tokens())),
+                .map(|t| t.to_tokens(options))
+                .chain(std::iter::once(self.to_specialization_tokens(options)))
+                .chain(std::iter::once(self.to_traits_tokens(options)))
+                .chain(std::iter::once(self.namespaces.to_tokens(options))),
+        )
+    }
+
+    /// Turn the tree into one source file per namespace
+    ///
+    /// Returns the name and contents of each file. The root file, `bindings.rs`, is meant to be
+    /// pulled in with `include!` and each namespace module in turn includes the file named after
+    /// its namespace, e.g. `Windows.Foundation.rs`, from the same directory. Literals are pooled
+    /// within each file rather than across the whole tree so that files only change when the
+    /// namespace they were generated from does.
+    pub fn to_files(&self, options: &TypeOptions) -> BTreeMap<String, String> {
+        let mut files = BTreeMap::new();
+        self.insert_files(&mut files, options);
+        files
+    }
+
+    /// Writes the files returned by `to_files` to a directory, such as a build script's `OUT_DIR`
+    ///
+    /// Files whose contents haven't changed are left untouched so that their timestamps
+    /// don't trigger needless rebuilds.
+    pub fn write<P: AsRef<Path>>(&self, dir: P, options: &TypeOptions) -> std::io::Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+
+        for (name, contents) in self.to_files(options) {
+            let path = dir.join(name);
+
+            if std::fs::read_to_string(&path).ok().as_deref() != Some(contents.as_str()) {
+                std::fs::write(path, contents)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn insert_files(&self, files: &mut BTreeMap<String, String>, options: &TypeOptions) {
+        let name = if self.namespace.is_empty() {
+            "bindings.rs".to_string()
+        } else {
+            format!("{}.rs", self.namespace)
+        };
+
+        let types = TokenStream::from_iter(
+            self.types
+                .iter()
+                .map(|t| t.to_tokens(options))
+                .chain(std::iter::once(self.to_specialization_tokens(options)))
+                .chain(std::iter::once(self.to_traits_tokens(options))),
+        );
+
+        let mut code = LiteralPool::apply(types);
+        code.extend(self.namespaces.to_include_tokens(options));
+
+        if self.namespace.is_empty() {
+            code.extend(self.to_prelude_tokens(options));
+            code.extend(self.to_alias_tokens(options));
+        }
+        let code = to_visible_tokens(code, options).to_string();
+        files.insert(name, if options.format { rustfmt(code) } else { code });
+
+        for tree in self.namespaces.0.values() {
+            tree.insert_files(files, options);
+        }
+    }
+
+    /// The `prelude` module, which re-exports the traits of every namespace in the tree along
+    /// with the runtime's traits so that one glob import brings all of their methods into scope
+    fn to_prelude_tokens(&self, options: &TypeOptions) -> TokenStream {
+        if options.abi_only {
+            return TokenStream::new();
+        }
+
+        let mut traits = Vec::new();
+        self.insert_trait_exports(&mut traits, options);
+
+        quote! {
+            pub mod prelude {
+                pub use ::winrt::{ComInterface, RuntimeName, RuntimeType, TryInto};
+                #(#traits)*
+            }
+        }
+    }
+
+    fn insert_trait_exports(&self, traits: &mut Vec<TokenStream>, options: &TypeOptions) {
+        for tree in self.namespaces.0.values() {
+            // Only namespaces with interfaces have a `traits` module.
+            if tree.types.iter().any(|t| matches!(t, Type::Interface(_))) {
+                let path = to_module_path(&tree.namespace);
+                let gate = to_namespace_gate(&tree.namespace, options);
+
+                traits.push(quote! {
+                    #gate
+                    pub use super::#(#path)::*::traits::*;
+                });
+            }
+
+            tree.insert_trait_exports(traits, options);
+        }
+    }
+
+    /// Re-exports the namespace modules named by the `aliases` option under their aliases
+    ///
+    /// # Panics
+    ///
+    /// Panics if an alias names a namespace that isn't in the tree
+    fn to_alias_tokens(&self, options: &TypeOptions) -> TokenStream {
+        TokenStream::from_iter(options.aliases.iter().map(|(alias, namespace)| {
+            let tree = self.find(&to_rough_name(namespace)).unwrap_or_else(|| {
+                panic!(
+                    "Alias `{}` refers to `{}`, which is not an imported namespace",
+                    alias, namespace
+                )
+            });
+
+            let path = to_module_path(&tree.namespace);
+            let gate = to_namespace_gate(&tree.namespace, options);
+            let alias = format_ident(alias);
+
+            quote! {
+                #gate
+                pub use self::#(#path)::* as #alias;
+            }
+        }))
+    }
+
+    // Finds the namespace in the tree whose name matches a rough name, as given to `import!`.
+    fn find(&self, rough: &str) -> Option<&TypeTree> {
+        if !self.namespace.is_empty() && to_rough_name(&self.namespace) == rough {
+            return Some(self);
+        }
+
+        self.namespaces.0.values().find_map(|tree| tree.find(rough))
+    }
+
+    /// The IIDs of the specializations of generic interfaces required by this namespace's types
+    fn to_specialization_tokens(&self, options: &TypeOptions) -> TokenStream {
+        // Without the projection there are no generic interfaces to hold the IIDs.
+        if options.abi_only {
+            return TokenStream::new();
+        }
+
+        TokenStream::from_iter(
+            self.specializations
+                .iter()
+                .map(|s| s.to_tokens(&self.namespace, options)),
         )
     }
+
+    /// The `traits` module holding a trait for each interface in this namespace
+    fn to_traits_tokens(&self, options: &TypeOptions) -> TokenStream {
+        if options.abi_only {
+            return TokenStream::new();
+        }
+
+        let traits: Vec<TokenStream> = self
+            .types
+            .iter()
+            .filter_map(|t| match t {
+                Type::Interface(interface) => {
+                    let tokens = interface_trait_tokens(interface, options);
+
+                    Some(match t.contract() {
+                        Some(contract) if options.contract_features => {
+                            to_gated_tokens(&contract.to_gate_tokens(), tokens)
+                        }
+                        _ => tokens,
+                    })
+                }
+                _ => None,
+            })
+            .collect();
+
+        if traits.is_empty() {
+            return TokenStream::new();
+        }
+
+        quote! {
+            pub mod traits {
+                #(#traits)*
+            }
+        }
+    }
+
+    /// Returns a hash of the code generated for each namespace in the tree
+    ///
+    /// The hashes are recorded for the `import` that the tree was generated for, such as the
+    /// dependencies it was imported from, so that imports of the same namespace from different
+    /// metadata don't replace each other's hashes in a shared manifest.
+    pub fn manifest(&self, options: &TypeOptions, import: &str) -> TypeManifest {
+        let mut manifest = TypeManifest::default();
+        self.insert_hashes(&mut manifest, options, import, "");
+        manifest
+    }
+
+    fn insert_hashes(
+        &self,
+        manifest: &mut TypeManifest,
+        options: &TypeOptions,
+        import: &str,
+        namespace: &str,
+    ) {
+        for (name, tree) in self.namespaces.0.iter() {
+            let namespace = if namespace.is_empty() {
+                name.clone()
+            } else {
+                format!("{}.{}", namespace, name)
+            };
+
+            let code = TokenStream::from_iter(tree.types.iter().map(|t| t.to_tokens(options)));
+            manifest.insert(import, &namespace, &code.to_string());
+            tree.insert_hashes(manifest, options, import, &namespace);
+        }
+    }
+
+    /// Returns the public items generated for each namespace in the tree
+    pub fn surface(&self, options: &TypeOptions) -> TypeSurface {
+        let mut surface = TypeSurface::default();
+        self.insert_items(&mut surface, options, "");
+        surface
+    }
+
+    fn insert_items(&self, surface: &mut TypeSurface, options: &TypeOptions, namespace: &str) {
+        for (name, tree) in self.namespaces.0.iter() {
+            let namespace = if namespace.is_empty() {
+                name.clone()
+            } else {
+                format!("{}.{}", namespace, name)
+            };
+
+            for t in &tree.types {
+                surface.insert(&namespace, t.to_tokens(options));
+            }
+
+            tree.insert_items(surface, options, &namespace);
+        }
+    }
+
+    /// Returns the module path generated for each namespace in the tree
+    pub fn symbol_map(&self) -> SymbolMap {
+        let mut map = SymbolMap::default();
+        self.insert_symbols(&mut map, "", "");
+        map
+    }
+
+    fn insert_symbols(&self, map: &mut SymbolMap, module: &str, namespace: &str) {
+        for (name, tree) in self.namespaces.0.iter() {
+            let snake = case::to_snake(name, MethodKind::Normal);
+
+            let (module, namespace) = if module.is_empty() {
+                (snake, name.clone())
+            } else {
+                (
+                    format!("{}::{}", module, snake),
+                    format!("{}.{}", namespace, name),
+                )
+            };
+
+            map.insert(module.clone(), namespace.clone());
+            tree.insert_symbols(map, &module, &namespace);
+        }
+    }
+}
+
+// The path of the module generated for a namespace, relative to the root of the tree.
+fn to_module_path(namespace: &str) -> impl Iterator<Item = proc_macro2::Ident> + '_ {
+    namespace
+        .split('.')
+        .map(|name| format_ident(&case::to_snake(name, MethodKind::Normal)))
+}
+
+// Applies the `visibility` option, if any, once the code has been generated.
+fn to_visible_tokens(tokens: TokenStream, options: &TypeOptions) -> TokenStream {
+    match &options.visibility {
+        Some(visibility) => to_visibility_tokens(visibility, tokens),
+        None => tokens,
+    }
+}
+
+// Formats generated code with rustfmt, or whichever formatter `RUSTFMT` names as cargo does,
+// falling back to the code as it was if the formatter can't be run or fails.
+fn rustfmt(code: String) -> String {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let rustfmt = std::env::var_os("RUSTFMT").unwrap_or_else(|| "rustfmt".into());
+
+    let mut child = match Command::new(rustfmt)
+        .args(["--edition", "2018"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(_) => return code,
+    };
+
+    // Write from another thread so that a large file can't fill the pipe to rustfmt while
+    // rustfmt is blocked writing to ours.
+    let mut stdin = child.stdin.take().unwrap();
+    let input = code.clone();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+
+    match child.wait_with_output() {
+        Ok(output) if output.status.success() && matches!(writer.join(), Ok(Ok(()))) => {
+            String::from_utf8(output.stdout).unwrap_or(code)
+        }
+        _ => code,
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::rustfmt;
+    use crate::types::Type;
     use crate::TypeLimits;
     use crate::TypeReader;
     use crate::TypeStage;
@@ -58,7 +408,7 @@ mod tests {
 
         // Since Windows.Foundation depends on Windows.Foundation.Collections and
         // Windows.UI doesn't have dependencies, we should only see those namespaces.
-        let root = stage.into_tree();
+        let root = stage.into_tree(reader);
 
         // There is one root namespace.
         assert!(root.namespaces.0.len() == 1);
@@ -86,27 +436,176 @@ mod tests {
         assert!(windows.types.is_empty());
 
         // The UI namespace has all of its types.
-        assert!(ui.types.iter().any(|t| t.name().name == "Colors"));
-        assert!(ui.types.iter().any(|t| t.name().name == "IColorsStatics"));
+        assert!(ui.types.iter().any(|t| &*t.name().name == "Colors"));
+        assert!(ui.types.iter().any(|t| &*t.name().name == "IColorsStatics"));
 
         // The Foundation namespace has all of its types.
-        assert!(foundation.types.iter().any(|t| t.name().name == "Uri"));
+        assert!(foundation.types.iter().any(|t| &*t.name().name == "Uri"));
         assert!(foundation
             .types
             .iter()
-            .any(|t| t.name().name == "IStringable"));
+            .any(|t| &*t.name().name == "IStringable"));
 
         // The Collections namespace only has the needed types.
         assert!(collections
             .types
             .iter()
-            .any(|t| t.name().name == "IVectorView`1"));
+            .any(|t| &*t.name().name == "IVectorView`1"));
         assert!(
             collections
                 .types
                 .iter()
-                .any(|t| t.name().name == "PropertySet")
+                .any(|t| &*t.name().name == "PropertySet")
                 == false
         );
     }
+
+    #[test]
+    fn test_files() {
+        let reader = &TypeReader::from_os();
+        let mut limits = TypeLimits::default();
+        limits.insert(reader, "windows.foundation");
+        let files = TypeStage::from_limits(reader, &limits)
+            .into_tree(reader)
+            .to_files(&Default::default());
+
+        // The root includes Windows, which has no types of its own but includes Foundation,
+        // which in turn includes Collections.
+        assert!(files.len() == 4);
+        assert!(files["bindings.rs"].contains("include ! (\"Windows.rs\")"));
+        assert!(files["Windows.rs"].contains("include ! (\"Windows.Foundation.rs\")"));
+        assert!(files["Windows.Foundation.rs"]
+            .contains("include ! (\"Windows.Foundation.Collections.rs\")"));
+        assert!(files["Windows.Foundation.rs"].contains("pub struct Uri"));
+        assert!(files["Windows.Foundation.Collections.rs"].contains("pub struct IVectorView"));
+    }
+
+    #[test]
+    fn test_stable_order() {
+        let reader = &TypeReader::from_os();
+        let mut limits = TypeLimits::default();
+        limits.insert(reader, "windows.foundation");
+        let stage = TypeStage::from_limits(reader, &limits);
+
+        // The stage holds the types in the order of their rows in the metadata, which isn't the
+        // order of their names, so without sorting them the output would change along with it.
+        let names = |types: &mut dyn Iterator<Item = &Type>| -> Vec<String> {
+            types
+                .filter(|t| &*t.name().namespace == "Windows.Foundation")
+                .map(|t| t.name().name.to_string())
+                .collect()
+        };
+
+        let rows = names(&mut stage.0.values());
+        let mut sorted = rows.clone();
+        sorted.sort();
+        assert!(rows != sorted);
+
+        // Types within a namespace are generated in name order.
+        let root = stage.into_tree(reader);
+        let foundation = &root.namespaces.0["Windows"].namespaces.0["Foundation"];
+        assert!(names(&mut foundation.types.iter()) == sorted);
+    }
+
+    #[test]
+    fn test_rustfmt() {
+        // Code that rustfmt can't parse is left as it was.
+        assert!(rustfmt("fn (".to_string()) == "fn (");
+        assert!(
+            rustfmt("pub fn f ( ) -> u32 { 0 }".to_string()) == "pub fn f() -> u32 {\n    0\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_aliases() {
+        let reader = &TypeReader::from_os();
+        let mut limits = TypeLimits::default();
+        limits.insert(reader, "windows.foundation");
+        let tree = TypeStage::from_limits(reader, &limits).into_tree(reader);
+
+        let mut options = crate::TypeOptions::default();
+        options.insert_alias("windows.foundation.collections as collections");
+        let alias = "pub use self :: r#windows :: r#foundation :: r#collections as r#collections ;";
+
+        // Aliases are re-exported from the root, whether generating tokens or files.
+        assert!(tree.to_tokens(&options).to_string().contains(alias));
+        assert!(tree.to_files(&options)["bindings.rs"].contains(alias));
+    }
+
+    #[test]
+    fn test_prelude() {
+        let reader = &TypeReader::from_os();
+        let mut limits = TypeLimits::default();
+        limits.insert(reader, "windows.foundation");
+        let tree = TypeStage::from_limits(reader, &limits).into_tree(reader);
+        let tokens = tree.to_tokens(&Default::default()).to_string();
+
+        // The prelude includes the traits of each namespace with interfaces.
+        assert!(tokens.contains("pub mod prelude"));
+        assert!(tokens.contains("pub use super :: r#windows :: r#foundation :: traits :: * ;"));
+        assert!(tokens.contains(
+            "pub use super :: r#windows :: r#foundation :: r#collections :: traits :: * ;"
+        ));
+
+        // Windows itself has no types and so no traits.
+        assert!(!tokens.contains("pub use super :: r#windows :: traits :: * ;"));
+    }
+
+    #[test]
+    fn test_specializations() {
+        let reader = &TypeReader::from_os();
+        let mut limits = TypeLimits::default();
+        limits.insert(reader, "windows.foundation.collections");
+        let tree = TypeStage::from_limits(reader, &limits).into_tree(reader);
+        let collections = &tree.namespaces.0["Windows"].namespaces.0["Foundation"]
+            .namespaces
+            .0["Collections"];
+
+        // StringMap is the first type to require IMap<HString, HString> so its IID is
+        // generated only once, in StringMap's namespace.
+        let specializations: Vec<String> = collections
+            .specializations
+            .iter()
+            .map(|s| s.name.runtime_name())
+            .collect();
+
+        assert!(
+            specializations
+                .iter()
+                .filter(|name| *name == "Windows.Foundation.Collections.IMap`2<String, String>")
+                .count()
+                == 1
+        );
+
+        // IMapView<HString, HString> isn't required by any type but StringMap's get_view
+        // returns it.
+        assert!(specializations
+            .iter()
+            .any(|name| name == "Windows.Foundation.Collections.IMapView`2<String, String>"));
+
+        // Specializations with generic parameters are never included.
+        assert!(collections
+            .specializations
+            .iter()
+            .all(|s| s.name.is_concrete()));
+    }
+
+    #[test]
+    fn test_abi_only() {
+        let reader = &TypeReader::from_os();
+        let mut limits = TypeLimits::default();
+        limits.insert(reader, "windows.foundation");
+        let tree = TypeStage::from_limits(reader, &limits).into_tree(reader);
+
+        let mut options = crate::TypeOptions::default();
+        options.insert("abi_only");
+        let tokens = tree.to_tokens(&options).to_string();
+
+        // Only the vtables and layouts are generated, without classes, traits, or a prelude.
+        assert!(tokens.contains("pub struct abi_IStringable"));
+        assert!(tokens.contains("pub struct Point"));
+        assert!(!tokens.contains("pub struct Uri"));
+        assert!(!tokens.contains("pub mod traits"));
+        assert!(!tokens.contains("pub mod prelude"));
+    }
 }
@@ -0,0 +1,73 @@
+use crate::types::*;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// Provides a blocking `get` method for the well-known WinRT async interfaces. If the operation
+// hasn't completed yet, it sets a completion handler and sleeps until the handler is called,
+// and then returns its results. Since blocking an STA thread is very likely to deadlock, `get`
+// fails with `ErrorCode::WRONG_THREAD` on such threads.
+//
+// Operations whose results are collections, such as the common IAsyncOperation<IVectorView<T>>,
+// also get a `get_vec` method that collects the results into a Vec. It relies on the collection
+// implementing IntoIterator, rather than naming the collection interfaces, so that it builds
+// whether or not Windows.Foundation.Collections is generated.
+pub fn async_tokens(name: &TypeName, constraints: &TokenStream) -> TokenStream {
+    if &*name.namespace != "Windows.Foundation" {
+        return TokenStream::new();
+    }
+
+    let (return_type, operation, handler) = match &*name.name {
+        "IAsyncAction" => (quote! { () }, false, quote! { AsyncActionCompletedHandler }),
+        "IAsyncActionWithProgress`1" => (
+            quote! { () },
+            false,
+            quote! { AsyncActionWithProgressCompletedHandler::<TProgress> },
+        ),
+        "IAsyncOperation`1" => (
+            quote! { TResult },
+            true,
+            quote! { AsyncOperationCompletedHandler::<TResult> },
+        ),
+        "IAsyncOperationWithProgress`2" => (
+            quote! { TResult },
+            true,
+            quote! { AsyncOperationWithProgressCompletedHandler::<TResult, TProgress> },
+        ),
+        _ => return TokenStream::new(),
+    };
+
+    let name = name.to_tokens(&name.namespace);
+
+    let get_vec = if operation {
+        quote! {
+            impl<#constraints T> #name where TResult: ::std::iter::IntoIterator<Item = ::winrt::Result<T>> {
+                /// Blocks the calling thread until the operation completes and collects the
+                /// collection it results in, such as an `IVectorView<T>`, into a `Vec`, failing
+                /// if any of its elements can't be read
+                pub fn get_vec(&self) -> ::winrt::Result<::std::vec::Vec<T>> {
+                    self.get()?.into_iter().collect()
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    };
+
+    quote! {
+        impl<#constraints> #name {
+            /// Blocks the calling thread until the operation completes and returns its results
+            pub fn get(&self) -> ::winrt::Result<#return_type> {
+                if self.status()? == AsyncStatus::Started {
+                    ::winrt::wait_for(|signal| {
+                        self.set_completed(#handler::new(move |_, _| {
+                            signal.set();
+                            Ok(())
+                        }))
+                    })?;
+                }
+                self.get_results()
+            }
+        }
+        #get_vec
+    }
+}
@@ -0,0 +1,107 @@
+use crate::types::*;
+use crate::TypeOptions;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// Gives Windows.Storage.Streams.IBuffer and Windows.Foundation.IMemoryBufferReference, and the
+// classes that implement them, access to their bytes through IBufferByteAccess and
+// IMemoryBufferByteAccess, which aren't described in metadata. The bytes belong to the object
+// rather than to any one reference to it, so they're copied by the safe methods and only
+// borrowed by the unsafe ones.
+pub fn buffer_tokens(
+    name: &TypeName,
+    interfaces: &[RequiredInterface],
+    options: &TypeOptions,
+) -> TokenStream {
+    let implements = |namespace: &str, interface_name: &str| {
+        interfaces.iter().any(|interface| {
+            &*interface.name.namespace == namespace && &*interface.name.name == interface_name
+        })
+    };
+
+    let name = name.to_tokens(&name.namespace);
+    let allow = to_allow_unsafe_tokens(options);
+
+    if implements("Windows.Storage.Streams", "IBuffer") {
+        quote! {
+            impl #name {
+                /// The buffer's bytes, up to its length, copied into a vector
+                pub fn to_vec(&self) -> ::winrt::Result<::std::vec::Vec<u8>> {
+                    ::winrt::buffer_to_vec(self)
+                }
+                /// Copies the bytes to the start of the buffer and sets its length to theirs,
+                /// failing if they don't fit in its capacity
+                pub fn copy_from(&self, bytes: &[u8]) -> ::winrt::Result<()> {
+                    ::winrt::buffer_copy_from(self, bytes)
+                }
+                /// The buffer's bytes, up to its length, without copying them
+                ///
+                /// # Safety
+                /// The bytes belong to the buffer rather than to this reference to it, so while the
+                /// slice is in use they mustn't be written through another reference or by the
+                /// component that owns them, nor freed by closing the buffer.
+                #allow
+                pub unsafe fn as_slice(&self) -> ::winrt::Result<&[u8]> {
+                    ::winrt::buffer_as_slice(self)
+                }
+                /// The buffer's bytes, up to its length, for writing without copying
+                ///
+                /// Set the buffer's length first to write past its current length, up to its capacity.
+                ///
+                /// # Safety
+                /// As for `as_slice`, and the bytes mustn't be read through another reference while
+                /// the slice is in use either.
+                #allow
+                pub unsafe fn as_mut_slice(&mut self) -> ::winrt::Result<&mut [u8]> {
+                    ::winrt::buffer_as_mut_slice(self)
+                }
+            }
+        }
+    } else if implements("Windows.Foundation", "IMemoryBufferReference") {
+        quote! {
+            impl #name {
+                /// The referenced bytes, copied into a vector
+                pub fn to_vec(&self) -> ::winrt::Result<::std::vec::Vec<u8>> {
+                    ::winrt::memory_buffer_to_vec(self)
+                }
+                /// Copies the bytes to the start of the referenced bytes, failing if they don't
+                /// fit in its capacity
+                pub fn copy_from(&self, bytes: &[u8]) -> ::winrt::Result<()> {
+                    ::winrt::memory_buffer_copy_from(self, bytes)
+                }
+                /// The referenced bytes, without copying them
+                ///
+                /// # Safety
+                /// The bytes belong to the memory buffer rather than to this reference, so while
+                /// the slice is in use they mustn't be written through another reference or by the
+                /// component that owns them, nor freed by closing the reference or the memory buffer.
+                #allow
+                pub unsafe fn as_slice(&self) -> ::winrt::Result<&[u8]> {
+                    ::winrt::memory_buffer_as_slice(self)
+                }
+                /// The referenced bytes, for writing without copying
+                ///
+                /// # Safety
+                /// As for `as_slice`, and the bytes mustn't be read through another reference while
+                /// the slice is in use either.
+                #allow
+                pub unsafe fn as_mut_slice(&mut self) -> ::winrt::Result<&mut [u8]> {
+                    ::winrt::memory_buffer_as_mut_slice(self)
+                }
+                /// The referenced bytes, along with the reference so that it stays alive for as
+                /// long as they're used
+                ///
+                /// # Safety
+                /// Keeping the reference alive doesn't keep it open, so neither it nor the memory
+                /// buffer may be closed, and the bytes mustn't be used other than through the result,
+                /// until the result is dropped.
+                #allow
+                pub unsafe fn bytes(&self) -> ::winrt::Result<::winrt::MemoryBufferBytes> {
+                    ::winrt::MemoryBufferBytes::new(self)
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    }
+}
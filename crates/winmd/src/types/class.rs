@@ -1,8 +1,10 @@
+use crate::case::to_snake;
 use crate::tables::*;
 use crate::types::*;
-use crate::TypeReader;
+use crate::{TypeOptions, TypeReader};
 use proc_macro2::TokenStream;
 use quote::quote;
+use std::collections::BTreeSet;
 use std::iter::FromIterator;
 
 /// A WinRT Class
@@ -12,6 +14,13 @@ pub struct Class {
     pub bases: Vec<TypeName>,
     pub interfaces: Vec<RequiredInterface>,
     pub default_constructor: bool,
+    pub factories: Vec<TypeName>,
+    pub contract: Option<Contract>,
+    pub agile: bool,
+    pub composable: bool,
+    pub signature: String,
+    pub constants: Vec<(String, ConstantValue)>,
+    pub helper_dependencies: Vec<TypeDef>,
 }
 
 impl Class {
@@ -30,8 +39,8 @@ impl Class {
             }
 
             base = reader.resolve_type_def((namespace, name));
-            let namespace = namespace.to_string();
-            let name = name.to_string();
+            let namespace = reader.strings.intern(namespace);
+            let name = reader.strings.intern(name);
             let generics = Vec::new();
 
             let base = TypeName {
@@ -46,6 +55,11 @@ impl Class {
         }
 
         let mut default_constructor = false;
+        let mut factories = Vec::new();
+        let mut marshaling = None;
+        let mut threading = None;
+        let mut composable = false;
+        let contract = Contract::from_attributes(reader, def.attributes(reader));
 
         for attribute in def.attributes(reader) {
             match attribute.name(reader) {
@@ -62,20 +76,61 @@ impl Class {
                         Some(def) => {
                             let mut interface = RequiredInterface::from_type_def(reader, def);
                             interface.kind = InterfaceKind::Statics;
+                            factories.push(interface.name.clone());
                             interfaces.push(interface);
                         }
                         None => default_constructor = true,
                     }
                 }
+                ("Windows.Foundation.Metadata", "ComposableAttribute") => {
+                    let mut interface = RequiredInterface::from_type_def(
+                        reader,
+                        attribute_factory(reader, attribute).unwrap(),
+                    );
+                    interface.kind = InterfaceKind::Statics;
+                    interfaces.push(interface);
+                    composable = true;
+                }
+                ("Windows.Foundation.Metadata", "MarshalingBehaviorAttribute") => {
+                    marshaling = enum_arg(reader, attribute);
+                }
+                ("Windows.Foundation.Metadata", "ThreadingAttribute") => {
+                    threading = enum_arg(reader, attribute);
+                }
                 _ => {}
             }
         }
 
+        // Objects marshaled as MarshalingType::Agile may be used from any thread, unless the
+        // class is created in, and bound to, single-threaded apartments (ThreadingModel::STA).
+        let agile = marshaling == Some(MARSHALING_AGILE) && threading != Some(THREADING_STA);
+
+        // Static classes have no default interface and are never passed as values.
+        let signature = if interfaces[0].kind == InterfaceKind::Default {
+            format!(
+                "rc({}.{};{})",
+                name.namespace,
+                name.name,
+                interfaces[0].name.interface_signature(reader)
+            )
+        } else {
+            String::new()
+        };
+
+        let helper_dependencies = property_bag_dependencies(reader, &name);
+
         Self {
             name,
             interfaces,
             bases,
             default_constructor,
+            factories,
+            contract,
+            agile,
+            composable,
+            signature,
+            constants: ConstantValue::from_type_def(reader, def),
+            helper_dependencies,
         }
     }
 
@@ -84,40 +139,80 @@ impl Class {
             .iter()
             .flat_map(|i| i.name.dependencies())
             .chain(self.bases.iter().map(|i| i.def))
+            .chain(self.helper_dependencies.iter().copied())
             .collect()
     }
 
-    pub fn to_tokens(&self) -> TokenStream {
+    pub fn to_tokens(&self, options: &TypeOptions) -> TokenStream {
         let name = self.name.to_tokens(&self.name.namespace);
-        let type_name = self.type_name(&name);
-        let methods = to_method_tokens(&self.name.namespace, &self.interfaces);
+        let type_name = self.name.to_runtime_name_tokens();
+        let methods = to_method_tokens(&self.name.namespace, &self.interfaces, options);
+        let constants = to_constants_tokens(&self.constants);
 
         if self.interfaces[0].kind == InterfaceKind::Default {
             let guid = self.interfaces[0].guid.to_tokens();
             let conversions = TokenStream::from_iter(self.interfaces.iter().map(|interface| {
-                interface.to_conversions_tokens(&self.name.namespace, &name, &TokenStream::new())
+                to_gated_tokens(
+                    &interface.to_gate_tokens(options),
+                    interface.to_conversions_tokens(
+                        &self.name.namespace,
+                        &name,
+                        &TokenStream::new(),
+                        options,
+                    ),
+                )
             }));
 
             let new = if self.default_constructor {
                 quote! {
                     pub fn new() -> ::winrt::Result<Self> {
-                        ::winrt::activation::factory::<Self, ::winrt::IActivationFactory>()?.activate_instance::<Self>()
+                        ::winrt::activation::call_factory::<Self, ::winrt::IActivationFactory, _>(|factory| factory.activate_instance::<Self>())
                     }
                 }
             } else {
                 quote! {}
             };
 
+            let constructors = self.to_constructor_tokens(options);
             let bases = self.to_base_conversions_tokens(&self.name.namespace, &name);
-            let iterator = iterator_tokens(&self.name, &self.interfaces);
+            let iterator = iterator_tokens(&self.name, &self.interfaces, options);
+            let buffer = buffer_tokens(&self.name, &self.interfaces, options);
+            let dispatcher = dispatcher_tokens(&self.name);
+            let traits = trait_impl_tokens(&self.name, &self.interfaces, options);
+            let debug = debug_tokens(
+                &self.name.namespace,
+                &name,
+                &TokenStream::new(),
+                &self.interfaces,
+                options,
+            );
+            let identity = identity_tokens(&name, &TokenStream::new());
+
+            let overrides = if self.composable {
+                overrides_tokens(&self.name, &self.interfaces, options)
+            } else {
+                quote! {}
+            };
+
+            let agile = if self.agile {
+                quote! {
+                    unsafe impl ::std::marker::Send for #name {}
+                    unsafe impl ::std::marker::Sync for #name {}
+                }
+            } else {
+                quote! {}
+            };
 
             let abi_name = self.interfaces[0].name.to_abi_tokens(&self.name.namespace);
+            let signature = self.name.to_signature_tokens(&self.signature);
             quote! {
                 #[repr(transparent)]
-                #[derive(Default, Clone)]
+                #[derive(Clone)]
                 pub struct #name { ptr: ::winrt::ComPtr<#name> }
                 impl #name {
+                    #constants
                     #new
+                    #constructors
                     #methods
                 }
                 #type_name
@@ -133,20 +228,105 @@ impl Class {
                     fn set_abi(&mut self) -> *mut Self::Abi {
                         self.ptr.set()
                     }
+                    #signature
                 }
                 #conversions
                 #bases
                 #iterator
+                #buffer
+                #dispatcher
+                #debug
+                #identity
+                #agile
+                #overrides
+                #traits
             }
         } else {
+            let status = status_tokens(&self.name, &self.interfaces, options);
+            let variant = variant_tokens(&self.name);
+            let property_bag = property_bag_tokens(&self.name);
+
             quote! {
                 pub struct #name {}
-                impl #name { #methods }
+                impl #name {
+                    #constants
+                    #methods
+                }
                 #type_name
+                #status
+                #variant
+                #property_bag
             }
         }
     }
 
+    /// Constructors for each of the activation factory methods
+    ///
+    /// The first becomes `new` unless the class has a default constructor. The rest are named
+    /// after the factory method, so `CreateWithRelativeUri` becomes `new_with_relative_uri`, or
+    /// after their parameters if the method name says nothing more than `CreateInstance`.
+    fn to_constructor_tokens(&self, options: &TypeOptions) -> TokenStream {
+        let mut names: BTreeSet<String> = self
+            .interfaces
+            .iter()
+            .flat_map(|interface| {
+                interface
+                    .methods
+                    .iter()
+                    .map(|method| method.name.to_string())
+            })
+            .collect();
+
+        if self.default_constructor {
+            names.insert("new".to_string());
+        }
+
+        let class_name = to_snake(&self.name.name, MethodKind::Normal);
+        let mut tokens = Vec::new();
+
+        for interface in self
+            .interfaces
+            .iter()
+            .filter(|interface| self.factories.contains(&interface.name))
+        {
+            for method in &interface.methods {
+                match method.return_type.as_ref().map(|param| &param.kind) {
+                    Some(TypeKind::Class(class)) if *class == self.name => {}
+                    _ => continue,
+                }
+
+                let suffix = method.name.trim_start_matches("create");
+                let suffix = suffix.trim_start_matches('_');
+                let suffix = suffix.trim_start_matches("with_");
+
+                let constructor = if !names.contains("new") {
+                    "new".to_string()
+                } else if suffix.is_empty() || suffix == "instance" || suffix == class_name {
+                    let params: Vec<&str> = method.params.iter().map(|p| &*p.name).collect();
+                    format!("new_with_{}", params.join("_"))
+                } else {
+                    format!("new_with_{}", suffix)
+                };
+
+                if !names.insert(constructor.clone()) {
+                    continue;
+                }
+
+                tokens.push(to_gated_tokens(
+                    &interface.to_gate_tokens(options),
+                    method.to_constructor_tokens(
+                        &constructor,
+                        &self.name.namespace,
+                        interface,
+                        &interface.to_check_tokens(method, options),
+                    ),
+                ));
+            }
+        }
+
+        TokenStream::from_iter(tokens)
+    }
+
     pub fn to_base_conversions_tokens(
         &self,
         calling_namespace: &str,
@@ -178,15 +358,16 @@ impl Class {
             }
         }))
     }
+}
 
-    fn type_name(&self, class_name: &TokenStream) -> TokenStream {
-        let runtime_name = self.name.runtime_name();
+const MARSHALING_AGILE: i32 = 2;
+const THREADING_STA: i32 = 1;
 
-        quote! {
-            impl ::winrt::RuntimeName for #class_name {
-                const NAME: &'static str = #runtime_name;
-            }
-        }
+// The value of an attribute whose only argument is an enum, such as a `MarshalingType`.
+fn enum_arg(reader: &TypeReader, attribute: Attribute) -> Option<i32> {
+    match attribute.args(reader).first() {
+        Some((_, AttributeArg::I32(value))) => Some(*value),
+        _ => None,
     }
 }
 
@@ -218,7 +399,7 @@ mod tests {
         class
             .interfaces
             .iter()
-            .find(|interface| interface.name.name == name)
+            .find(|interface| &*interface.name.name == name)
             .unwrap()
     }
 
@@ -230,17 +411,27 @@ mod tests {
             .count()
     }
 
+    #[test]
+    fn test_agile() {
+        // XAML objects are bound to the thread they were created on.
+        assert!(!class(("Windows.UI.Xaml.Controls", "Button")).agile);
+        assert!(class(("Windows.Foundation.Collections", "PropertySet")).agile);
+    }
+
     #[test]
     fn test_uri() {
         let t = class(("Windows.Foundation", "Uri"));
         assert!(t.default_constructor == false);
+        assert!(t.agile);
+        assert!(t.factories.len() == 1);
+        assert!(&*t.factories[0].name == "IUriRuntimeClassFactory");
         assert!(t.bases.is_empty());
         assert!(t.interfaces.len() == 5);
 
         let interface = t
             .interfaces
             .iter()
-            .find(|interface| interface.name.name == "IUriRuntimeClass")
+            .find(|interface| &*interface.name.name == "IUriRuntimeClass")
             .unwrap();
 
         assert!(interface.kind == InterfaceKind::Default);
@@ -249,7 +440,7 @@ mod tests {
         let interface = t
             .interfaces
             .iter()
-            .find(|interface| interface.name.name == "IUriRuntimeClassWithAbsoluteCanonicalUri")
+            .find(|interface| &*interface.name.name == "IUriRuntimeClassWithAbsoluteCanonicalUri")
             .unwrap();
 
         assert!(interface.kind == InterfaceKind::NonDefault);
@@ -261,7 +452,7 @@ mod tests {
         let interface = t
             .interfaces
             .iter()
-            .find(|interface| interface.name.name == "IStringable")
+            .find(|interface| &*interface.name.name == "IStringable")
             .unwrap();
 
         assert!(interface.kind == InterfaceKind::NonDefault);
@@ -270,7 +461,7 @@ mod tests {
         let interface = t
             .interfaces
             .iter()
-            .find(|interface| interface.name.name == "IUriRuntimeClassFactory")
+            .find(|interface| &*interface.name.name == "IUriRuntimeClassFactory")
             .unwrap();
 
         assert!(interface.kind == InterfaceKind::Statics);
@@ -279,7 +470,7 @@ mod tests {
         let interface = t
             .interfaces
             .iter()
-            .find(|interface| interface.name.name == "IUriEscapeStatics")
+            .find(|interface| &*interface.name.name == "IUriEscapeStatics")
             .unwrap();
 
         assert!(interface.kind == InterfaceKind::Statics);
@@ -298,7 +489,7 @@ mod tests {
         let interface = t
             .interfaces
             .iter()
-            .find(|interface| interface.name.name == "IWwwFormUrlDecoderRuntimeClassFactory")
+            .find(|interface| &*interface.name.name == "IWwwFormUrlDecoderRuntimeClassFactory")
             .unwrap();
 
         assert!(interface.kind == InterfaceKind::Statics);
@@ -310,7 +501,7 @@ mod tests {
         let interface = t
             .interfaces
             .iter()
-            .find(|interface| interface.name.name == "IWwwFormUrlDecoderRuntimeClass")
+            .find(|interface| &*interface.name.name == "IWwwFormUrlDecoderRuntimeClass")
             .unwrap();
 
         assert!(interface.kind == InterfaceKind::Default);
@@ -321,7 +512,7 @@ mod tests {
         let interface = t
             .interfaces
             .iter()
-            .find(|interface| interface.name.name == "IIterable`1")
+            .find(|interface| &*interface.name.name == "IIterable`1")
             .unwrap();
 
         assert!(interface.kind == InterfaceKind::NonDefault);
@@ -330,7 +521,7 @@ mod tests {
         let interface = t
             .interfaces
             .iter()
-            .find(|interface| interface.name.name == "IVectorView`1")
+            .find(|interface| &*interface.name.name == "IVectorView`1")
             .unwrap();
 
         assert!(interface.kind == InterfaceKind::NonDefault);
@@ -387,6 +578,17 @@ mod tests {
         assert!(interface(&t, "ICompositionObject").kind == InterfaceKind::Default);
     }
 
+    #[test]
+    fn test_composable() {
+        let t = class(("Windows.UI.Xaml", "Application"));
+        assert!(t.composable);
+        assert!(interface(&t, "IApplicationOverrides").kind == InterfaceKind::Overrides);
+        assert!(interface(&t, "IApplicationFactory").kind == InterfaceKind::Statics);
+
+        let t = class(("Windows.Foundation", "Uri"));
+        assert!(!t.composable);
+    }
+
     #[test]
     fn test_class_with_default_constructor() {
         let t = class(("Windows.UI.Composition", "Compositor"));
@@ -397,10 +599,67 @@ mod tests {
         let interface = t
             .interfaces
             .iter()
-            .find(|interface| interface.name.name == "ICompositor")
+            .find(|interface| &*interface.name.name == "ICompositor")
             .unwrap();
 
         assert!(interface.kind == InterfaceKind::Default);
         assert!(interface.name.runtime_name() == "Windows.UI.Composition.ICompositor");
     }
+
+    #[test]
+    fn test_sorted_members() {
+        let t = class(("Windows.Foundation", "Uri"));
+        let position = |tokens: &str, method: &str| tokens.find(method).unwrap();
+
+        // IUriRuntimeClass has UserName ahead of Port in its vtable.
+        let tokens = t.to_tokens(&Default::default()).to_string();
+        assert!(position(&tokens, "pub fn user_name") < position(&tokens, "pub fn port"));
+
+        let mut options = TypeOptions::default();
+        options.insert("sorted_members");
+        let tokens = t.to_tokens(&options).to_string();
+        assert!(position(&tokens, "pub fn port") < position(&tokens, "pub fn user_name"));
+    }
+
+    #[test]
+    fn test_method_check() {
+        let tokens = |(namespace, type_name), target| {
+            let mut options = TypeOptions::default();
+            options.insert(target);
+            class((namespace, type_name))
+                .to_tokens(&options)
+                .to_string()
+        };
+
+        let check = |member: &str, kind: &str| {
+            format!("\"{}\" , :: winrt :: MemberKind :: {}", member, kind)
+        };
+
+        // AppDiagnosticInfo was introduced in 15063 and extended in 16299.
+        let info = tokens(("Windows.System", "AppDiagnosticInfo"), "target:10240");
+        assert!(info.contains(&check("AppInfo", "Property")));
+        assert!(info.contains(&check("RequestInfoAsync", "Method")));
+        assert!(info.contains(&check("CreateWatcher", "Method")));
+
+        let info = tokens(("Windows.System", "AppDiagnosticInfo"), "target:15063");
+        assert!(!info.contains(&check("AppInfo", "Property")));
+        assert!(!info.contains(&check("RequestInfoAsync", "Method")));
+        assert!(info.contains(&check("CreateWatcher", "Method")));
+
+        let watcher = tokens(
+            ("Windows.System", "AppDiagnosticInfoWatcher"),
+            "target:15063",
+        );
+        assert!(watcher.contains(&check("Added", "Event")));
+        assert!(!watcher.contains("\"add_Added\""));
+    }
+
+    #[test]
+    fn test_verify() {
+        // A class's default interface methods verify the interface, not the class.
+        let tokens = class(("Windows.Foundation", "Uri"))
+            .to_tokens(&Default::default())
+            .to_string();
+        assert!(tokens.contains(":: winrt :: verify_interface :: < IUriRuntimeClass >"));
+    }
 }
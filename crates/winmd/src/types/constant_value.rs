@@ -0,0 +1,140 @@
+use crate::case::to_snake;
+use crate::tables::*;
+use crate::types::*;
+use crate::TypeReader;
+
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+
+/// The value of a constant field
+#[derive(Clone, PartialEq, Debug)]
+pub enum ConstantValue {
+    Bool(bool),
+    Char(u16),
+    I8(i8),
+    U8(u8),
+    I16(i16),
+    U16(u16),
+    I32(i32),
+    U32(u32),
+    I64(i64),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+    String(String),
+}
+
+impl ConstantValue {
+    /// Reads the constant fields of a type, such as numeric limits or string names
+    ///
+    /// Constants of other types, such as null references, have no Rust equivalent and are skipped.
+    pub fn from_type_def(reader: &TypeReader, def: TypeDef) -> Vec<(String, ConstantValue)> {
+        let mut constants = Vec::new();
+
+        for field in def.fields(reader) {
+            if !field.flags(reader).literal() {
+                continue;
+            }
+
+            for constant in field.constants(reader) {
+                let mut blob = constant.value(reader);
+
+                let value = match constant.value_type(reader) {
+                    0x02 => Self::Bool(blob.read_u8() != 0),
+                    0x03 => Self::Char(blob.read_u16()),
+                    0x04 => Self::I8(blob.read_i8()),
+                    0x05 => Self::U8(blob.read_u8()),
+                    0x06 => Self::I16(blob.read_i16()),
+                    0x07 => Self::U16(blob.read_u16()),
+                    0x08 => Self::I32(blob.read_i32()),
+                    0x09 => Self::U32(blob.read_u32()),
+                    0x0A => Self::I64(blob.read_i64()),
+                    0x0B => Self::U64(blob.read_u64()),
+                    0x0C => Self::F32(f32::from_bits(blob.read_u32())),
+                    0x0D => Self::F64(f64::from_bits(blob.read_u64())),
+                    0x0E => Self::String(blob.read_utf16()),
+                    _ => continue,
+                };
+
+                constants.push((field.name(reader).to_string(), value));
+            }
+        }
+
+        constants
+    }
+
+    pub fn to_tokens(&self) -> TokenStream {
+        match self {
+            Self::Bool(value) => quote! { bool = #value },
+            Self::Char(value) => quote! { ::winrt::Char16 = ::winrt::Char16(#value) },
+            Self::I8(value) => quote! { i8 = #value },
+            Self::U8(value) => quote! { u8 = #value },
+            Self::I16(value) => quote! { i16 = #value },
+            Self::U16(value) => quote! { u16 = #value },
+            Self::I32(value) => quote! { i32 = #value },
+            Self::U32(value) => quote! { u32 = #value },
+            Self::I64(value) => quote! { i64 = #value },
+            Self::U64(value) => quote! { u64 = #value },
+            Self::F32(value) => {
+                let value = float_tokens(*value as f64, value.is_finite(), quote! { f32 });
+                quote! { f32 = #value }
+            }
+            Self::F64(value) => {
+                let value = float_tokens(*value, value.is_finite(), quote! { f64 });
+                quote! { f64 = #value }
+            }
+            Self::String(value) => quote! { &'static str = #value },
+        }
+    }
+}
+
+// Literals can't express infinity or NaN so those use the standard constants instead.
+fn float_tokens(value: f64, finite: bool, kind: TokenStream) -> TokenStream {
+    if finite {
+        let value = Literal::f64_unsuffixed(value);
+        quote! { #value }
+    } else if value.is_nan() {
+        quote! { ::std::#kind::NAN }
+    } else if value > 0.0 {
+        quote! { ::std::#kind::INFINITY }
+    } else {
+        quote! { ::std::#kind::NEG_INFINITY }
+    }
+}
+
+/// Emits the constants as associated constants named in upper snake case
+pub fn to_constants_tokens(constants: &[(String, ConstantValue)]) -> TokenStream {
+    let constants = constants.iter().map(|(name, value)| {
+        let name = quote::format_ident!("{}", to_snake(name, MethodKind::Normal).to_uppercase());
+        let value = value.to_tokens();
+        quote! { pub const #name: #value; }
+    });
+
+    quote! { #(#constants)* }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_tokens() {
+        let constants = vec![
+            ("MaxValue".to_string(), ConstantValue::I32(i32::MAX)),
+            ("Scale".to_string(), ConstantValue::F64(0.5)),
+            ("Missing".to_string(), ConstantValue::F32(f32::NAN)),
+            (
+                "PropertyName".to_string(),
+                ConstantValue::String("Name".to_string()),
+            ),
+        ];
+
+        assert!(
+            to_constants_tokens(&constants).to_string()
+                == "pub const MAX_VALUE : i32 = 2147483647i32 ; \
+                    pub const SCALE : f64 = 0.5 ; \
+                    pub const MISSING : f32 = :: std :: f32 :: NAN ; \
+                    pub const PROPERTY_NAME : & 'static str = \"Name\" ;"
+        );
+    }
+}
@@ -0,0 +1,285 @@
+use crate::tables::*;
+use crate::TypeReader;
+
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use quote::quote;
+
+use std::iter::FromIterator;
+
+/// The API contract, and the version of that contract, that introduced a type
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contract {
+    pub name: String,
+    pub version: u32,
+}
+
+impl Contract {
+    pub fn from_attributes<I: Iterator<Item = Attribute>>(
+        reader: &TypeReader,
+        attributes: I,
+    ) -> Option<Self> {
+        for attribute in attributes {
+            if attribute.name(reader) != ("Windows.Foundation.Metadata", "ContractVersionAttribute")
+            {
+                continue;
+            }
+
+            let mut name = String::new();
+            let mut version = 0;
+
+            for (_, arg) in attribute.args(reader) {
+                match arg {
+                    AttributeArg::String(value) => name = value,
+                    AttributeArg::TypeDef(def) => {
+                        let (namespace, type_name) = def.name(reader);
+                        name = format!("{}.{}", namespace, type_name);
+                    }
+                    AttributeArg::U32(value) => version = value,
+                    _ => {}
+                }
+            }
+
+            return Some(Self { name, version });
+        }
+
+        None
+    }
+
+    /// The major version of the contract
+    pub fn major(&self) -> u32 {
+        self.version >> 16
+    }
+
+    /// The Windows build that shipped this version of the contract
+    ///
+    /// Only versions of the UniversalApiContract map directly onto Windows releases, so other
+    /// contracts have no build. Versions newer than the table knows of, from a newer SDK, were
+    /// introduced no earlier than the latest build it knows of, so they're given that build
+    /// rather than being left ungated. See [`Contract::newer_than_known`].
+    pub fn windows_build(&self) -> Option<u32> {
+        if self.name != "Windows.Foundation.UniversalApiContract" {
+            return None;
+        }
+
+        let build = match self.major() {
+            1 => 10240,
+            2 => 10586,
+            3 => 14393,
+            4 => 15063,
+            5 => 16299,
+            6 => 17134,
+            7 => 17763,
+            8 => 18362,
+            9 | 10 => 19041,
+            11 | 12 => 20348,
+            13 | 14 => 22000,
+            15..=18 => 22621,
+            _ => 26100,
+        };
+
+        Some(build)
+    }
+
+    /// Whether this is a version of the UniversalApiContract newer than the Windows builds that
+    /// [`Contract::windows_build`] knows of
+    pub fn newer_than_known(&self) -> bool {
+        self.name == "Windows.Foundation.UniversalApiContract" && self.major() > 19
+    }
+
+    /// The feature gate for types introduced after the first Windows 10 release, e.g. `#[cfg(feature = "14393")]`
+    pub fn to_gate_tokens(&self) -> TokenStream {
+        match self.windows_build() {
+            Some(build) if build > 10240 => {
+                let feature = build.to_string();
+                quote! { #[cfg(feature = #feature)] }
+            }
+            _ => TokenStream::new(),
+        }
+    }
+}
+
+impl std::fmt::Display for Contract {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}.{}",
+            self.name,
+            self.major(),
+            self.version & 0xFFFF
+        )
+    }
+}
+
+/// Applies the gate to each of the items in the token stream
+pub fn to_gated_tokens(gate: &TokenStream, tokens: TokenStream) -> TokenStream {
+    if gate.is_empty() {
+        return tokens;
+    }
+
+    TokenStream::from_iter(split_items(tokens).into_iter().map(|item| {
+        let item = TokenStream::from_iter(item);
+        quote! { #gate #item }
+    }))
+}
+
+/// Splits a token stream of generated items into the tokens of each item
+///
+/// Items end with a `;`, and all but those that are initialized, such as consts and statics
+/// whose values may be struct expressions, also end with a `{ ... }` block. Attributes and
+/// visibility come before the keyword that tells them apart, so that's what is looked for.
+pub fn split_items(tokens: TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut result = Vec::new();
+    let mut item: Vec<TokenTree> = Vec::new();
+
+    for token in tokens {
+        let end = match &token {
+            TokenTree::Punct(punct) => punct.as_char() == ';',
+            TokenTree::Group(group) => {
+                group.delimiter() == Delimiter::Brace && !is_initialized(&item)
+            }
+            _ => false,
+        };
+
+        item.push(token);
+
+        if end {
+            result.push(std::mem::take(&mut item));
+        }
+    }
+
+    if !item.is_empty() {
+        result.push(item);
+    }
+
+    result
+}
+
+// Whether the item is a const, static, or type alias, which only end with a `;`.
+fn is_initialized(item: &[TokenTree]) -> bool {
+    let mut keywords = item.iter().filter_map(|token| match token {
+        TokenTree::Ident(ident) => Some(ident.to_string()),
+        _ => None,
+    });
+
+    loop {
+        match keywords.next().as_deref() {
+            Some("pub") | Some("crate") | Some("super") | Some("self") | Some("in") => continue,
+            Some("const") | Some("static") => {
+                return keywords.next().as_deref() != Some("fn");
+            }
+            Some("type") => return true,
+            _ => return false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gated_tokens() {
+        let gate = quote! { #[cfg(feature = "14393")] };
+        let tokens = quote! {
+            #[repr(C)]
+            pub struct Point { x: f32 }
+            impl Point { pub fn x(&self) -> f32 { self.x } }
+            pub const ORIGIN: [f32; 2] = [0.0, 0.0];
+        };
+
+        let expected = quote! {
+            #[cfg(feature = "14393")]
+            #[repr(C)]
+            pub struct Point { x: f32 }
+            #[cfg(feature = "14393")]
+            impl Point { pub fn x(&self) -> f32 { self.x } }
+            #[cfg(feature = "14393")]
+            pub const ORIGIN: [f32; 2] = [0.0, 0.0];
+        };
+
+        assert!(to_gated_tokens(&gate, tokens).to_string() == expected.to_string());
+    }
+
+    #[test]
+    fn test_split_items() {
+        let tokens = quote! {
+            #[doc(hidden)]
+            pub static ORIGIN: Point = Point { x: 0.0 };
+            pub(crate) const fn origin() -> Point { ORIGIN }
+            pub type Origin = Point;
+            impl<T> Iterator for Points<T> where T: Into<Point> { type Item = Point; }
+        };
+
+        let items: Vec<String> = split_items(tokens)
+            .into_iter()
+            .map(|item| TokenStream::from_iter(item).to_string())
+            .collect();
+
+        assert_eq!(
+            items,
+            [
+                quote! { #[doc(hidden)] pub static ORIGIN: Point = Point { x: 0.0 }; }.to_string(),
+                quote! { pub(crate) const fn origin() -> Point { ORIGIN } }.to_string(),
+                quote! { pub type Origin = Point; }.to_string(),
+                quote! { impl<T> Iterator for Points<T> where T: Into<Point> { type Item = Point; } }
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_windows_build() {
+        let contract = Contract {
+            name: "Windows.Foundation.UniversalApiContract".to_owned(),
+            version: 3 << 16,
+        };
+        assert!(contract.windows_build() == Some(14393));
+        assert!(
+            contract.to_gate_tokens().to_string()
+                == quote! { #[cfg(feature = "14393")] }.to_string()
+        );
+
+        let contract = Contract {
+            name: "Windows.Foundation.UniversalApiContract".to_owned(),
+            version: 1 << 16,
+        };
+        assert!(contract.to_gate_tokens().is_empty());
+
+        let contract = Contract {
+            name: "Windows.Foundation.FoundationContract".to_owned(),
+            version: 3 << 16,
+        };
+        assert!(contract.windows_build().is_none());
+        assert!(contract.to_gate_tokens().is_empty());
+        assert!(contract.to_string() == "Windows.Foundation.FoundationContract 3.0");
+
+        // Every version up to the latest known maps to a build.
+        for major in 1..=19 {
+            let contract = Contract {
+                name: "Windows.Foundation.UniversalApiContract".to_owned(),
+                version: major << 16,
+            };
+            assert!(contract.windows_build().is_some());
+        }
+    }
+
+    #[test]
+    fn test_unknown_windows_build() {
+        let known = Contract {
+            name: "Windows.Foundation.UniversalApiContract".to_owned(),
+            version: 19 << 16,
+        };
+        assert!(!known.newer_than_known());
+
+        // Newer versions are gated on the latest known build rather than stopping the generator.
+        let newer = Contract {
+            name: "Windows.Foundation.UniversalApiContract".to_owned(),
+            version: 20 << 16,
+        };
+        assert!(newer.windows_build() == Some(26100));
+        assert!(newer.newer_than_known());
+        assert!(
+            newer.to_gate_tokens().to_string() == quote! { #[cfg(feature = "26100")] }.to_string()
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use crate::types::*;
+use crate::TypeOptions;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// Provides Debug support for classes and interfaces. Types that implement IStringable also get
+// a Display implementation and both format the value returned by ToString. Anything else, or a
+// call to ToString that fails, falls back to printing the runtime class name and pointer.
+pub fn debug_tokens(
+    calling_namespace: &str,
+    name: &TokenStream,
+    constraints: &TokenStream,
+    interfaces: &[RequiredInterface],
+    options: &TypeOptions,
+) -> TokenStream {
+    let fallback = quote! {
+        ::std::fmt::Debug::fmt(&<Self as ::winrt::ComInterface>::query::<::winrt::Object>(self), f)
+    };
+
+    let stringable = interfaces.iter().find(|interface| {
+        &*interface.name.namespace == "Windows.Foundation"
+            && &*interface.name.name == "IStringable"
+            && interface.to_gate_tokens(options).is_empty()
+    });
+
+    let stringable = match stringable {
+        Some(stringable) => stringable,
+        None => {
+            return quote! {
+                impl<#constraints> ::std::fmt::Debug for #name {
+                    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                        #fallback
+                    }
+                }
+            };
+        }
+    };
+
+    let to_string = if stringable.kind == InterfaceKind::Default {
+        quote! { self.to_string() }
+    } else {
+        let stringable = stringable.name.to_tokens(calling_namespace);
+        quote! {
+            <#stringable as ::std::convert::TryFrom<&Self>>::try_from(self).and_then(|stringable| stringable.to_string())
+        }
+    };
+
+    quote! {
+        impl<#constraints> ::std::fmt::Display for #name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                if !<Self as ::winrt::ComInterface>::is_null(self) {
+                    if let ::std::result::Result::Ok(value) = #to_string {
+                        return ::std::write!(f, "{}", value);
+                    }
+                }
+                #fallback
+            }
+        }
+        impl<#constraints> ::std::fmt::Debug for #name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                ::std::fmt::Display::fmt(self, f)
+            }
+        }
+    }
+}
@@ -1,15 +1,19 @@
+use crate::case::to_snake;
 use crate::tables::*;
 use crate::types::*;
-use crate::TypeReader;
+use crate::{format_ident, TypeOptions, TypeReader};
 
 use proc_macro2::TokenStream;
 use quote::quote;
+use std::iter::FromIterator;
 
 #[derive(Debug)]
 pub struct Delegate {
     pub name: TypeName,
     pub method: Method,
     pub guid: TypeGuid,
+    pub contract: Option<Contract>,
+    pub signature: String,
 }
 
 impl Delegate {
@@ -21,14 +25,28 @@ impl Delegate {
             .unwrap();
         let method = Method::from_method_def(reader, method, &name.generics);
         let guid = TypeGuid::from_type_def(reader, def);
-        Self { name, method, guid }
+        let contract = Contract::from_attributes(reader, def.attributes(reader));
+
+        let signature = if name.generics.is_empty() {
+            name.delegate_signature(reader)
+        } else {
+            name.generic_signature(reader)
+        };
+
+        Self {
+            name,
+            method,
+            guid,
+            contract,
+            signature,
+        }
     }
 
     pub fn dependencies(&self) -> Vec<TypeDef> {
         self.method.dependencies()
     }
 
-    pub fn to_tokens(&self) -> TokenStream {
+    pub fn to_tokens(&self, options: &TypeOptions) -> TokenStream {
         let definition = self.name.to_definition_tokens(&self.name.namespace);
         let abi_definition = self.name.to_abi_definition_tokens(&self.name.namespace);
         let name = self.name.to_tokens(&self.name.namespace);
@@ -36,10 +54,15 @@ impl Delegate {
         let constraints = self.name.constraints();
         let abi_method = self.method.to_abi_tokens(&self.name, &self.name.namespace);
         let guid = self.guid.to_tokens();
+        let invoke = self.to_invoke_tokens();
+        let new = self.to_new_tokens(options);
+        let with_sender = self.to_with_sender_tokens();
+        let into = self.to_into_tokens();
+        let signature = self.name.to_signature_tokens(&self.signature);
+        let iid = self.name.to_iid_tokens();
 
         quote! {
             #[repr(transparent)]
-            #[derive(Default)]
             pub struct #definition where #constraints {
                 ptr: ::winrt::IUnknown,
                 #phantoms
@@ -47,6 +70,7 @@ impl Delegate {
             unsafe impl<#constraints> ::winrt::ComInterface for #name {
                 type VTable = #abi_definition;
                 const GUID: ::winrt::Guid = ::winrt::Guid::from_values(#guid);
+                #iid
             }
             impl<#constraints> ::std::clone::Clone for #name {
                 fn clone(&self) -> Self {
@@ -56,9 +80,10 @@ impl Delegate {
                     }
                 }
             }
+            // Delegates only implement IUnknown so `Invoke` follows its three methods.
             #[repr(C)]
             pub struct #abi_definition where #constraints {
-                __base: [usize; 6],
+                __base: [usize; 3],
                 #abi_method
                 #phantoms
             }
@@ -70,6 +95,275 @@ impl Delegate {
                 fn set_abi(&mut self) -> *mut Self::Abi {
                     self.ptr.set()
                 }
+                #signature
+            }
+            #invoke
+            #new
+            #with_sender
+            #into
+        }
+    }
+
+    /// Calls the delegate, as the source of an event does to raise it
+    fn to_invoke_tokens(&self) -> TokenStream {
+        let calling_namespace = &self.name.namespace;
+        let name = self.name.to_tokens(calling_namespace);
+        let abi_name = self.name.to_abi_tokens(calling_namespace);
+        let constraints = self.name.constraints();
+
+        // Delegates hold an `IUnknown`, so its pointer is cast to the delegate's vtable, which is
+        // verified like an interface's.
+        let cast = quote! {
+            let this = this as *const *const #abi_name;
+            ::winrt::verify_interface::<Self>(this)?;
+        };
+        let invoke = self.method.to_default_tokens(
+            calling_namespace,
+            &cast,
+            &TokenStream::new(),
+            &quote! { (*(*(this))).invoke },
+        );
+
+        quote! {
+            impl<#constraints> #name {
+                #invoke
+            }
+        }
+    }
+
+    /// Lets the delegate be implemented with a closure, provided it only has input parameters
+    ///
+    /// Reference types are passed to the closure by reference and everything else by value.
+    fn to_new_tokens(&self, options: &TypeOptions) -> TokenStream {
+        let calling_namespace = &self.name.namespace;
+
+        if self
+            .method
+            .params
+            .iter()
+            .any(|param| !param.input || param.array)
+            || self.method.return_type.iter().any(|param| param.array)
+        {
+            return TokenStream::new();
+        }
+
+        let name = self.name.to_tokens(calling_namespace);
+        let constraints = self.name.constraints();
+        let generics = self
+            .name
+            .generics
+            .iter()
+            .map(|generic| generic.to_tokens(""));
+
+        let abi_params = TokenStream::from_iter(self.method.params.iter().map(|param| {
+            let name = format_ident(&param.name);
+            let tokens = param.kind.to_abi_tokens(calling_namespace);
+            quote! { #name: #tokens }
+        }));
+
+        let args = TokenStream::from_iter(self.method.params.iter().map(|param| {
+            let name = format_ident(&param.name);
+            let tokens = param.kind.to_tokens(calling_namespace);
+
+            if param.kind == TypeKind::Bool {
+                quote! { bool::from(#name), }
+            } else if param.kind.blittable() {
+                quote! { *(&#name as *const _ as *const #tokens), }
+            } else {
+                quote! { &*(&#name as *const _ as *const #tokens), }
+            }
+        }));
+
+        let (return_param, write_result) = match &self.method.return_type {
+            Some(return_type) => {
+                let tokens = return_type.kind.to_tokens(calling_namespace);
+                let abi = return_type.kind.to_abi_tokens(calling_namespace);
+                (
+                    quote! { __result: *mut #abi },
+                    quote! { ::std::ptr::write(__result as *mut #tokens, __value.into()); },
+                )
+            }
+            None => (quote! {}, quote! { let () = __value; }),
+        };
+
+        let closure = self.to_closure_tokens();
+        let allow = to_allow_unsafe_tokens(options);
+
+        // Generic delegates infer their type arguments from the closure's parameter types.
+        let doc = if self.name.generics.is_empty() {
+            quote! {
+                /// Implements the delegate with a closure
+            }
+        } else {
+            quote! {
+                /// Implements the delegate with a closure
+                ///
+                /// The type arguments are inferred from the types of the closure's parameters, so
+                /// these only need to be named when the parameters are left for the compiler to infer.
+            }
+        };
+
+        quote! {
+            impl<#constraints> #name {
+                #doc
+                #allow
+                pub fn new<F: #closure>(invoke: F) -> Self {
+                    extern "system" fn __invoke<#constraints F: #closure>(this: ::winrt::RawPtr, #abi_params #return_param) -> ::winrt::ErrorCode {
+                        unsafe {
+                            let closure = ::winrt::Delegate::closure::<F>(this);
+                            match closure(#args) {
+                                Ok(__value) => {
+                                    #write_result
+                                    ::winrt::ErrorCode(0)
+                                }
+                                Err(error) => error.originate(),
+                            }
+                        }
+                    }
+                    unsafe { ::winrt::Delegate::create::<Self, F>(__invoke::<#(#generics,)* F> as *const (), invoke) }
+                }
+            }
+        }
+    }
+
+    /// The bound on the closures that implement the delegate
+    fn to_closure_tokens(&self) -> TokenStream {
+        let calling_namespace = &self.name.namespace;
+
+        let closure_params = self.method.params.iter().map(|param| {
+            let tokens = param.kind.to_value_tokens(calling_namespace);
+
+            if param.kind.blittable() {
+                quote! { #tokens }
+            } else {
+                quote! { &#tokens }
+            }
+        });
+
+        let return_type = match &self.method.return_type {
+            Some(return_type) => return_type.to_return_tokens(calling_namespace),
+            None => quote! { () },
+        };
+
+        quote! {
+            Fn(#(#closure_params),*) -> ::winrt::Result<#return_type> + ::std::marker::Send + ::std::marker::Sync + 'static
+        }
+    }
+
+    /// A trait that turns closures into a generic delegate, such as `IntoTypedEventHandler`, so
+    /// that the delegate's type arguments come from the closure's own type
+    ///
+    /// The trait's method is named after the delegate, e.g. `into_typed_event_handler`, as a
+    /// closure may implement several generic delegates.
+    fn to_into_tokens(&self) -> TokenStream {
+        if self.name.generics.is_empty() || self.to_new_tokens(&TypeOptions::default()).is_empty() {
+            return TokenStream::new();
+        }
+
+        let calling_namespace = &self.name.namespace;
+        let base_name = &self.name.name[..self.name.name.len() - 2];
+        let name = self.name.to_tokens(calling_namespace);
+        let trait_name = quote::format_ident!("Into{}", base_name);
+        let method_name = quote::format_ident!("into_{}", to_snake(base_name, MethodKind::Normal));
+        let constraints = self.name.constraints();
+        let generics = self
+            .name
+            .generics
+            .iter()
+            .map(|generic| generic.to_tokens(""));
+        let closure = self.to_closure_tokens();
+
+        let doc = format!(
+            "Implements `{0}` with a closure, whose parameter types name the delegate's type arguments\n\n\
+             Closures are turned into the delegate by calling `{1}` on them, so that neither the \
+             delegate nor its type arguments need to be named.",
+            base_name, method_name
+        );
+
+        quote! {
+            #[doc = #doc]
+            pub trait #trait_name<#constraints> {
+                fn #method_name(self) -> #name;
+            }
+            impl<#constraints F: #closure> #trait_name<#(#generics),*> for F {
+                fn #method_name(self) -> #name {
+                    <#name>::new(self)
+                }
+            }
+        }
+    }
+
+    /// Lets event handlers that receive their sender as an object be implemented with a closure
+    /// that takes the sender as a specific class or interface instead
+    ///
+    /// This applies to delegates whose sender is `Object`, such as `EventHandler<T>`, and to
+    /// delegates with a generic sender, such as `TypedEventHandler<TSender, TResult>`, when the
+    /// sender is specialized as `Object`.
+    fn to_with_sender_tokens(&self) -> TokenStream {
+        let calling_namespace = &self.name.namespace;
+
+        if self.to_new_tokens(&TypeOptions::default()).is_empty() {
+            return TokenStream::new();
+        }
+
+        let sender = match self.method.params.first() {
+            Some(param) if &*param.name == "sender" => param,
+            _ => return TokenStream::new(),
+        };
+
+        let mut name = self.name.clone();
+
+        match &sender.kind {
+            TypeKind::Object => {}
+            TypeKind::Generic(_) => {
+                for generic in name.generics.iter_mut() {
+                    if *generic == sender.kind {
+                        *generic = TypeKind::Object;
+                    }
+                }
+            }
+            _ => return TokenStream::new(),
+        }
+
+        let constraints = name.constraints();
+        let name = name.to_tokens(calling_namespace);
+        let params = &self.method.params[1..];
+
+        let closure_params = params.iter().map(|param| {
+            let tokens = param.kind.to_value_tokens(calling_namespace);
+
+            if param.kind.blittable() {
+                quote! { #tokens }
+            } else {
+                quote! { &#tokens }
+            }
+        });
+
+        let args: Vec<_> = params
+            .iter()
+            .map(|param| format_ident(&param.name))
+            .collect();
+
+        let return_type = match &self.method.return_type {
+            Some(return_type) => return_type.to_return_tokens(calling_namespace),
+            None => quote! { () },
+        };
+
+        let closure = quote! {
+            Fn(&S, #(#closure_params),*) -> ::winrt::Result<#return_type> + ::std::marker::Send + ::std::marker::Sync + 'static
+        };
+
+        quote! {
+            impl<#constraints> #name {
+                /// Like `new`, but the sender is cast to `S` before the closure is called
+                ///
+                /// The delegate fails with the error returned by the cast if the sender doesn't
+                /// implement `S`. A null sender, as passed by static events, stays null.
+                pub fn with_sender<S: ::winrt::ComInterface, F: #closure>(invoke: F) -> Self {
+                    Self::new(move |sender, #(#args),*| {
+                        invoke(&::winrt::ComInterface::try_query(sender)?, #(#args),*)
+                    })
+                }
             }
         }
     }
@@ -0,0 +1,96 @@
+use crate::types::*;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// Lets async code switch to a DispatcherQueue's thread, such as to update the UI after doing
+// work in the background, along the lines of C++/WinRT's `resume_foreground`.
+//
+// A Rust future can't move the task polling it to another thread, so rather than resuming the
+// whole task on the queue, `DispatcherQueue::run` runs a closure there and completes with its
+// result. `drained` is the same with nothing to run, which completes once the queue has run
+// what was enqueued before it. Both enqueue their handler when first polled.
+pub fn dispatcher_tokens(name: &TypeName) -> TokenStream {
+    match (&*name.namespace, &*name.name) {
+        ("Windows.System", "DispatcherQueue") | ("Microsoft.UI.Dispatching", "DispatcherQueue") => {
+        }
+        _ => return TokenStream::new(),
+    }
+
+    quote! {
+        impl DispatcherQueue {
+            /// Runs the closure on the queue's thread and returns a future that completes with
+            /// its result
+            ///
+            /// This is how async code runs something on the queue's thread, such as updating the
+            /// UI. The task awaiting the future stays on its own thread, so only what the closure
+            /// does happens on the queue's thread.
+            ///
+            /// The future fails with `ErrorCode::CLOSED` if the queue is shutting down.
+            pub fn run<T: ::std::marker::Send + 'static, F: ::std::ops::FnOnce() -> ::winrt::Result<T> + ::std::marker::Send + 'static>(&self, callback: F) -> DispatcherQueueFuture<T> {
+                DispatcherQueueFuture {
+                    pending: Some((self.clone(), ::std::boxed::Box::new(callback))),
+                    shared: ::std::sync::Arc::new(::std::sync::Mutex::new((None, None))),
+                }
+            }
+            /// Returns a future that completes once the queue has run everything enqueued before it
+            ///
+            /// The task awaiting the future doesn't move to the queue's thread, so use `run` for
+            /// work that must happen there.
+            pub fn drained(&self) -> DispatcherQueueFuture<()> {
+                self.run(|| Ok(()))
+            }
+        }
+        /// A closure running on a `DispatcherQueue`, returned by `DispatcherQueue::run`
+        pub struct DispatcherQueueFuture<T> {
+            pending: Option<(DispatcherQueue, ::std::boxed::Box<dyn ::std::ops::FnOnce() -> ::winrt::Result<T> + ::std::marker::Send>)>,
+            shared: ::std::sync::Arc<::std::sync::Mutex<(Option<::winrt::Result<T>>, Option<::std::task::Waker>)>>,
+        }
+        impl<T: ::std::marker::Send + 'static> ::std::future::Future for DispatcherQueueFuture<T> {
+            type Output = ::winrt::Result<T>;
+
+            fn poll(self: ::std::pin::Pin<&mut Self>, context: &mut ::std::task::Context) -> ::std::task::Poll<Self::Output> {
+                let this = self.get_mut();
+
+                {
+                    let mut shared = this.shared.lock().unwrap();
+
+                    if let Some(result) = shared.0.take() {
+                        return ::std::task::Poll::Ready(result);
+                    }
+
+                    shared.1 = Some(context.waker().clone());
+                }
+
+                if let Some((queue, callback)) = this.pending.take() {
+                    let shared = this.shared.clone();
+                    let callback = ::std::sync::Mutex::new(Some(callback));
+
+                    let handler = DispatcherQueueHandler::new(move || {
+                        if let Some(callback) = callback.lock().unwrap().take() {
+                            let result = callback();
+                            let waker = {
+                                let mut shared = shared.lock().unwrap();
+                                shared.0 = Some(result);
+                                shared.1.take()
+                            };
+
+                            if let Some(waker) = waker {
+                                waker.wake();
+                            }
+                        }
+
+                        Ok(())
+                    });
+
+                    match queue.try_enqueue(&handler) {
+                        Ok(true) => {}
+                        Ok(false) => return ::std::task::Poll::Ready(Err(::winrt::ErrorCode::CLOSED.into())),
+                        Err(error) => return ::std::task::Poll::Ready(Err(error)),
+                    }
+                }
+
+                ::std::task::Poll::Pending
+            }
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::tables::*;
 use crate::types::*;
-use crate::{format_ident, TypeReader};
+use crate::{format_ident, TypeOptions, TypeReader};
 use std::collections::*;
 
 use proc_macro2::TokenStream;
@@ -10,6 +10,8 @@ use quote::{format_ident, quote};
 pub struct Enum {
     pub name: TypeName,
     pub fields: Vec<(String, EnumConstant)>,
+    pub contract: Option<Contract>,
+    pub signature: String,
 }
 
 #[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Debug)]
@@ -38,12 +40,25 @@ impl Enum {
             }
         }
 
-        Self { name, fields }
+        let contract = Contract::from_attributes(reader, def.attributes(reader));
+        // Enums are signed unless flags, which are the only ones with unsigned values.
+        let underlying = match fields.first() {
+            Some((_, EnumConstant::U32(_))) => "u4",
+            _ => "i4",
+        };
+        let signature = format!("enum({}.{};{})", name.namespace, name.name, underlying);
+
+        Self {
+            name,
+            fields,
+            contract,
+            signature,
+        }
     }
 
     // TODO: need to model WinRT enums as structs rather than Rust enums as that would
     // avoid hte issue of duplicates below and also allow bit flags WinRT enums.
-    pub fn to_tokens(&self) -> TokenStream {
+    pub fn to_tokens(&self, options: &TypeOptions) -> TokenStream {
         let name = self.name.to_tokens(&self.name.namespace);
         let default = format_ident(&self.fields[0].0);
 
@@ -56,16 +71,20 @@ impl Enum {
         // so we remove any duplicates ensuring there is at least one of each value.
         let mut values = BTreeSet::new();
 
-        let fields = self.fields.iter().filter(|field| {
-            if values.contains(&field.1) {
-                false
-            } else {
-                values.insert(field.1);
-                true
-            }
-        });
+        let unique: Vec<&(String, EnumConstant)> = self
+            .fields
+            .iter()
+            .filter(|field| {
+                if values.contains(&field.1) {
+                    false
+                } else {
+                    values.insert(field.1);
+                    true
+                }
+            })
+            .collect();
 
-        let fields = fields.map(|field| {
+        let fields = unique.iter().map(|field| {
             let name = format_ident(&field.0);
 
             let value = match field.1 {
@@ -78,9 +97,37 @@ impl Enum {
             }
         });
 
+        let variants = unique.iter().map(|field| format_ident(&field.0));
+        let count = unique.len();
+
+        let display = unique.iter().map(|field| {
+            let variant = format_ident(&field.0);
+            let text = &field.0;
+            quote! { Self::#variant => #text, }
+        });
+
+        // Every name is accepted when parsing, including those of duplicate values.
+        let signature = self.name.to_signature_tokens(&self.signature);
+        let runtime_name = self.name.to_runtime_name_tokens();
+        let parse = self.fields.iter().map(|field| {
+            let variant =
+                format_ident(&unique.iter().find(|unique| unique.1 == field.1).unwrap().0);
+            let text = field.0.to_lowercase();
+            quote! { #text => ::std::result::Result::Ok(Self::#variant), }
+        });
+
+        let serde = if options.serde {
+            serde_tokens()
+        } else {
+            TokenStream::new()
+        };
+
+        // Values are ordered by their underlying values rather than by the order they're declared
+        // in, which follows the metadata and isn't always ascending.
         quote! {
             #[repr(#repr)]
-            #[derive(Copy, Clone, Debug, PartialEq)]
+            #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+            #serde
             pub enum #name {
                 #(#fields),*
             }
@@ -89,6 +136,16 @@ impl Enum {
                     Self::#default
                 }
             }
+            impl ::std::cmp::PartialOrd for #name {
+                fn partial_cmp(&self, other: &Self) -> ::std::option::Option<::std::cmp::Ordering> {
+                    ::std::option::Option::Some(::std::cmp::Ord::cmp(self, other))
+                }
+            }
+            impl ::std::cmp::Ord for #name {
+                fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+                    (*self as #repr).cmp(&(*other as #repr))
+                }
+            }
             unsafe impl ::winrt::RuntimeType for #name {
                 type Abi = Self;
                 fn abi(&self) -> Self::Abi {
@@ -97,6 +154,31 @@ impl Enum {
                 fn set_abi(&mut self) -> *mut Self::Abi {
                     self as *mut Self::Abi
                 }
+                #signature
+            }
+            #runtime_name
+            impl #name {
+                /// Returns an iterator over the values of the enum
+                pub fn iter() -> impl ::std::iter::Iterator<Item = Self> {
+                    const VALUES: [#name; #count] = [#(#name::#variants),*];
+                    VALUES.iter().copied()
+                }
+            }
+            impl ::std::fmt::Display for #name {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    f.write_str(match self {
+                        #(#display)*
+                    })
+                }
+            }
+            impl ::std::str::FromStr for #name {
+                type Err = ::winrt::Error;
+                fn from_str(value: &str) -> ::winrt::Result<Self> {
+                    match value.to_lowercase().as_str() {
+                        #(#parse)*
+                        _ => ::std::result::Result::Err(::winrt::ErrorCode::INVALID_ARGUMENT.into()),
+                    }
+                }
             }
         }
     }
@@ -0,0 +1,17 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// Provides equality for classes and interfaces based on COM identity. Each side is queried for
+// IUnknown and the resulting pointers compared, so two values referring to the same object are
+// equal even if they were obtained through different interfaces.
+pub fn identity_tokens(name: &TokenStream, constraints: &TokenStream) -> TokenStream {
+    quote! {
+        impl<#constraints> ::std::cmp::PartialEq for #name {
+            fn eq(&self, other: &Self) -> bool {
+                <Self as ::winrt::ComInterface>::query::<::winrt::IUnknown>(self)
+                    == <Self as ::winrt::ComInterface>::query::<::winrt::IUnknown>(other)
+            }
+        }
+        impl<#constraints> ::std::cmp::Eq for #name {}
+    }
+}
@@ -9,6 +9,9 @@ use std::iter::FromIterator;
 pub struct Interface {
     pub name: TypeName,
     pub interfaces: Vec<RequiredInterface>,
+    pub contract: Option<Contract>,
+    pub signature: String,
+    pub helper_dependencies: Vec<TypeDef>,
 }
 
 impl Interface {
@@ -22,8 +25,23 @@ impl Interface {
         interfaces.push(default_interface);
 
         RequiredInterface::append_required(reader, &name, &mut interfaces);
-
-        Self { name, interfaces }
+        let contract = Contract::from_attributes(reader, def.attributes(reader));
+
+        let signature = if name.generics.is_empty() {
+            name.interface_signature(reader)
+        } else {
+            name.generic_signature(reader)
+        };
+
+        let helper_dependencies = stream_dependencies(reader, &name);
+
+        Self {
+            name,
+            interfaces,
+            contract,
+            signature,
+            helper_dependencies,
+        }
     }
 
     pub fn dependencies(&self) -> Vec<TypeDef> {
@@ -39,10 +57,11 @@ impl Interface {
             }
         }
 
+        dependencies.extend(self.helper_dependencies.iter().copied());
         dependencies
     }
 
-    pub fn to_tokens(&self) -> TokenStream {
+    pub fn to_tokens(&self, options: &TypeOptions) -> TokenStream {
         let definition = self.name.to_definition_tokens(&self.name.namespace);
         let abi_definition = self.name.to_abi_definition_tokens(&self.name.namespace);
         let name = self.name.to_tokens(&self.name.namespace);
@@ -52,26 +71,51 @@ impl Interface {
         debug_assert!(default_interface.kind == InterfaceKind::Default);
         let guid = default_interface.guid.to_tokens();
         let conversions = TokenStream::from_iter(self.interfaces.iter().skip(1).map(|interface| {
-            interface.to_conversions_tokens(&self.name.namespace, &name, &constraints)
+            interface.to_conversions_tokens(&self.name.namespace, &name, &constraints, options)
         }));
 
-        let methods = to_method_tokens(&self.name.namespace, &self.interfaces);
-        let abi_methods = default_interface.to_abi_method_tokens(&default_interface.name.namespace);
-        let iterator = iterator_tokens(&self.name, &self.interfaces);
+        let methods = to_method_tokens(&self.name.namespace, &self.interfaces, options);
+        let abi_methods =
+            default_interface.to_abi_method_tokens(&default_interface.name.namespace, options);
+        let iterator = iterator_tokens(&self.name, &self.interfaces, options);
+        let buffer = buffer_tokens(&self.name, &self.interfaces, options);
+        let stream = stream_tokens(&self.name, options);
+        let debug = debug_tokens(
+            &self.name.namespace,
+            &name,
+            &constraints,
+            &self.interfaces,
+            options,
+        );
+        let identity = identity_tokens(&name, &constraints);
+        let get = async_tokens(&self.name, &constraints);
+        let variant = variant_tokens(&self.name);
+        let traits = trait_impl_tokens(&self.name, &self.interfaces, options);
+        let signature = self.name.to_signature_tokens(&self.signature);
+        let iid = self.name.to_iid_tokens();
+        let iid_const = if self.name.generics.is_empty() {
+            quote! {
+                pub const IID: ::winrt::Guid = <Self as ::winrt::ComInterface>::GUID;
+            }
+        } else {
+            TokenStream::new()
+        };
+        let runtime_name = self.name.to_runtime_name_tokens();
 
         quote! {
             #[repr(transparent)]
-            #[derive(Default)]
             pub struct #definition where #constraints {
                 ptr: ::winrt::ComPtr<#name>,
                 #phantoms
             }
             impl<#constraints> #name {
+                #iid_const
                 #methods
             }
             unsafe impl<#constraints> ::winrt::ComInterface for #name {
                 type VTable = #abi_definition;
                 const GUID: ::winrt::Guid = ::winrt::Guid::from_values(#guid);
+                #iid
             }
             impl<#constraints> ::std::clone::Clone for #name {
                 fn clone(&self) -> Self {
@@ -95,9 +139,18 @@ impl Interface {
                 fn set_abi(&mut self) -> *mut Self::Abi {
                     self.ptr.set()
                 }
+                #signature
             }
+            #runtime_name
             #conversions
             #iterator
+            #buffer
+            #stream
+            #debug
+            #identity
+            #get
+            #variant
+            #traits
         }
     }
 }
@@ -127,7 +180,7 @@ mod tests {
 
         assert!(t.methods.len() == 1);
         let method = &t.methods[0];
-        assert!(method.name == "to_string");
+        assert!(&*method.name == "to_string");
         assert!(method.kind == MethodKind::Normal);
 
         assert!(method.params.is_empty());
@@ -147,7 +200,7 @@ mod tests {
         let interface = t
             .interfaces
             .iter()
-            .find(|interface| interface.name.name == "IAsyncInfo")
+            .find(|interface| &*interface.name.name == "IAsyncInfo")
             .unwrap();
 
         assert!(interface.kind == InterfaceKind::NonDefault);
@@ -156,7 +209,7 @@ mod tests {
         let interface = t
             .interfaces
             .iter()
-            .find(|interface| interface.name.name == "IAsyncAction")
+            .find(|interface| &*interface.name.name == "IAsyncAction")
             .unwrap();
 
         assert!(interface.kind == InterfaceKind::Default);
@@ -172,18 +225,18 @@ mod tests {
         let default_interface = t
             .interfaces
             .iter()
-            .find(|required| required.name.name == "IObservableMap`2")
+            .find(|required| &*required.name.name == "IObservableMap`2")
             .unwrap();
 
         assert!(default_interface.kind == InterfaceKind::Default);
         assert!(default_interface.methods.len() == 2);
-        assert!(default_interface.methods[0].name == "map_changed");
-        assert!(default_interface.methods[1].name == "remove_map_changed");
+        assert!(&*default_interface.methods[0].name == "map_changed");
+        assert!(&*default_interface.methods[1].name == "remove_map_changed");
 
         let map = t
             .interfaces
             .iter()
-            .find(|required| required.name.name == "IMap`2")
+            .find(|required| &*required.name.name == "IMap`2")
             .unwrap();
 
         assert!(map.kind == InterfaceKind::NonDefault);
@@ -192,10 +245,28 @@ mod tests {
         let iterable = t
             .interfaces
             .iter()
-            .find(|required| required.name.name == "IIterable`1")
+            .find(|required| &*required.name.name == "IIterable`1")
             .unwrap();
 
         assert!(iterable.kind == InterfaceKind::NonDefault);
         assert!(iterable.name.runtime_name() == "Windows.Foundation.Collections.IIterable`1<Windows.Foundation.Collections.IKeyValuePair`2<K, V>>");
     }
+
+    #[test]
+    fn test_verify() {
+        let verify = |(namespace, type_name), name: &str| {
+            let tokens = interface((namespace, type_name))
+                .to_tokens(&Default::default())
+                .to_string();
+            tokens.contains(&format!(":: winrt :: verify_interface :: < {} >", name))
+        };
+
+        assert!(verify(("Windows.Foundation", "IStringable"), "IStringable"));
+
+        // Generic interfaces verify the GUID of the specialization being called.
+        assert!(verify(
+            ("Windows.Foundation.Collections", "IVector`1"),
+            "IVector < T >"
+        ));
+    }
 }
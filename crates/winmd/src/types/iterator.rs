@@ -1,4 +1,5 @@
 use crate::types::*;
+use crate::TypeOptions;
 use proc_macro2::TokenStream;
 use quote::quote;
 
@@ -7,90 +8,205 @@ use quote::quote;
 // only falls back to IIterator<T> if nothing faster is available. VectorIterator and
 // VectorViewIterator are faster iterators than IIterator<T> because they only require a single
 // vcall per iteration wheras IIterator<T> requires two.
-pub fn iterator_tokens(name: &TypeName, interfaces: &Vec<RequiredInterface>) -> TokenStream {
+//
+// The items are `winrt::Result<T>` since any of those calls may fail. Iterators other than
+// IIterator<T> itself stop after yielding an error, so collecting into a `Result<Vec<T>>` or
+// using `?` in a for loop stops at the first failure rather than skipping over it.
+//
+// Maps, and other types that implement IIterable<IKeyValuePair<K, V>>, yield `(K, V)` tuples
+// through KeyValueIterator rather than IKeyValuePair<K, V> so that pairs can be destructured
+// directly. IKeyValuePair<K, V> itself may also be converted into a tuple with TryFrom.
+pub fn iterator_tokens(
+    name: &TypeName,
+    interfaces: &[RequiredInterface],
+    options: &TypeOptions,
+) -> TokenStream {
     // If the type is IIterator<T> then simply implement the Iterator trait over top.
-    if name.name == "IIterator`1" && name.namespace == "Windows.Foundation.Collections" {
+    if &*name.name == "IIterator`1" && &*name.namespace == "Windows.Foundation.Collections" {
         return quote! {
             impl<T: ::winrt::RuntimeType> ::std::iter::Iterator for IIterator<T> {
-                type Item = T;
+                type Item = ::winrt::Result<T>;
 
                 fn next(&mut self) -> ::std::option::Option<Self::Item> {
-                    let result = self.current().ok();
+                    match self.has_current() {
+                        Ok(true) => {}
+                        Ok(false) => return None,
+                        Err(error) => return Some(Err(error)),
+                    }
+
+                    let result = self.current();
 
-                    if result.is_some() {
-                        self.move_next().ok()?;
+                    if result.is_ok() {
+                        if let Err(error) = self.move_next() {
+                            return Some(Err(error));
+                        }
                     }
 
-                    result
+                    Some(result)
                 }
             }
         };
     }
 
-    // If the type is IIterable<T> then implement the IntoIterator trait and rely on the resulting
-    // IIterator<T> returned by first() to implement the Iterator trait.
-    if name.name == "IIterable`1" && name.namespace == "Windows.Foundation.Collections" {
+    // If the type is IKeyValuePair<K, V> then provide the tuple conversions and the KeyValueIterator
+    // adapter that yields them.
+    if &*name.name == "IKeyValuePair`2" && &*name.namespace == "Windows.Foundation.Collections" {
         return quote! {
+            impl<K: ::winrt::RuntimeType + 'static, V: ::winrt::RuntimeType + 'static> ::std::convert::TryFrom<IKeyValuePair<K, V>> for (K, V) {
+                type Error = ::winrt::Error;
+
+                fn try_from(pair: IKeyValuePair<K, V>) -> ::winrt::Result<Self> {
+                    ::std::convert::TryFrom::try_from(&pair)
+                }
+            }
+            impl<K: ::winrt::RuntimeType + 'static, V: ::winrt::RuntimeType + 'static> ::std::convert::TryFrom<&IKeyValuePair<K, V>> for (K, V) {
+                type Error = ::winrt::Error;
+
+                fn try_from(pair: &IKeyValuePair<K, V>) -> ::winrt::Result<Self> {
+                    Ok((pair.key()?, pair.value()?))
+                }
+            }
+
+            pub struct KeyValueIterator<K: ::winrt::RuntimeType + 'static, V: ::winrt::RuntimeType + 'static> {
+                iterator: IterableIterator<IKeyValuePair<K, V>>,
+                done: bool,
+            }
+
+            impl<K: ::winrt::RuntimeType, V: ::winrt::RuntimeType> KeyValueIterator<K, V> {
+                pub fn new(iterable: IIterable<IKeyValuePair<K, V>>) -> Self {
+                    Self { iterator: IterableIterator::new(iterable), done: false }
+                }
+            }
+
+            impl<K: ::winrt::RuntimeType, V: ::winrt::RuntimeType> ::std::iter::Iterator for KeyValueIterator<K, V> {
+                type Item = ::winrt::Result<(K, V)>;
+
+                fn next(&mut self) -> Option<Self::Item> {
+                    if self.done {
+                        return None;
+                    }
+
+                    let result = self.iterator.next()?.and_then(::std::convert::TryFrom::try_from);
+                    self.done = result.is_err();
+                    Some(result)
+                }
+            }
+        };
+    }
+
+    // If the type is IIterable<T> then implement the IntoIterator trait with IterableIterator,
+    // which calls first() once iteration begins so that a failure is yielded rather than panicking.
+    if &*name.name == "IIterable`1" && &*name.namespace == "Windows.Foundation.Collections" {
+        return quote! {
+            pub struct IterableIterator<T: ::winrt::RuntimeType + 'static> {
+                iterable: IIterable<T>,
+                iterator: Option<IIterator<T>>,
+                done: bool,
+            }
+
+            impl<T: ::winrt::RuntimeType> IterableIterator<T> {
+                pub fn new(iterable: IIterable<T>) -> Self {
+                    Self { iterable, iterator: None, done: false }
+                }
+            }
+
+            impl<T: ::winrt::RuntimeType> ::std::iter::Iterator for IterableIterator<T> {
+                type Item = ::winrt::Result<T>;
+
+                fn next(&mut self) -> Option<Self::Item> {
+                    if self.done {
+                        return None;
+                    }
+
+                    if self.iterator.is_none() {
+                        match self.iterable.first() {
+                            Ok(iterator) => self.iterator = Some(iterator),
+                            Err(error) => {
+                                self.done = true;
+                                return Some(Err(error));
+                            }
+                        }
+                    }
+
+                    let result = self.iterator.as_mut()?.next();
+                    self.done = !matches!(result, Some(Ok(_)));
+                    result
+                }
+            }
+
             impl<T: ::winrt::RuntimeType> ::std::iter::IntoIterator for IIterable<T> {
-                type Item = T;
-                type IntoIter = IIterator<Self::Item>;
+                type Item = ::winrt::Result<T>;
+                type IntoIter = IterableIterator<T>;
 
                 fn into_iter(self) -> Self::IntoIter {
-                    self.first().unwrap()
+                    IterableIterator::new(self)
                 }
             }
             impl<'a, T: ::winrt::RuntimeType> ::std::iter::IntoIterator for &'a IIterable<T> {
-                type Item = T;
-                type IntoIter = IIterator<Self::Item>;
+                type Item = ::winrt::Result<T>;
+                type IntoIter = IterableIterator<T>;
 
                 fn into_iter(self) -> Self::IntoIter {
-                    self.first().unwrap()
+                    IterableIterator::new(::std::clone::Clone::clone(self))
                 }
             }
         };
     }
 
     // If the type is IVectorView<T> then provide the VectorViewIterator fast iterator.
-    if name.name == "IVectorView`1" && name.namespace == "Windows.Foundation.Collections" {
+    if &*name.name == "IVectorView`1" && &*name.namespace == "Windows.Foundation.Collections" {
         return quote! {
             pub struct VectorViewIterator<T: ::winrt::RuntimeType + 'static> {
                 vector: IVectorView<T>,
                 current: u32,
-                size: u32,
+                size: Option<u32>,
             }
 
             impl<T: ::winrt::RuntimeType> VectorViewIterator<T> {
                 pub fn new(vector: IVectorView<T>) -> Self {
-                    let size = vector.size().unwrap();
-                    Self { vector, current: 0, size }
+                    Self { vector, current: 0, size: None }
                 }
             }
 
             impl<T: ::winrt::RuntimeType> ::std::iter::Iterator for VectorViewIterator<T> {
-                type Item = T;
+                type Item = ::winrt::Result<T>;
 
                 fn next(&mut self) -> Option<Self::Item> {
-                    if self.current >= self.size {
+                    let size = match self.size {
+                        Some(size) => size,
+                        None => match self.vector.size() {
+                            Ok(size) => {
+                                self.size = Some(size);
+                                size
+                            }
+                            Err(error) => {
+                                self.size = Some(0);
+                                return Some(Err(error));
+                            }
+                        },
+                    };
+
+                    if self.current >= size {
                         return None;
                     }
 
                     let result = self.vector.get_at(self.current);
-                    self.current += 1;
-                    result.ok()
+                    self.current = if result.is_ok() { self.current + 1 } else { size };
+                    Some(result)
                 }
             }
 
             impl<T: ::winrt::RuntimeType> ::std::iter::IntoIterator for IVectorView<T> {
-                type Item = T;
-                type IntoIter = VectorViewIterator<Self::Item>;
+                type Item = ::winrt::Result<T>;
+                type IntoIter = VectorViewIterator<T>;
 
                 fn into_iter(self) -> Self::IntoIter {
                     VectorViewIterator::new(self)
                 }
             }
             impl<'a, T: ::winrt::RuntimeType> ::std::iter::IntoIterator for &'a IVectorView<T> {
-                type Item = T;
-                type IntoIter = VectorViewIterator<Self::Item>;
+                type Item = ::winrt::Result<T>;
+                type IntoIter = VectorViewIterator<T>;
 
                 fn into_iter(self) -> Self::IntoIter {
                     VectorViewIterator::new(::std::clone::Clone::clone(self))
@@ -100,46 +216,59 @@ pub fn iterator_tokens(name: &TypeName, interfaces: &Vec<RequiredInterface>) ->
     }
 
     // If the type is IVector<T> then provide the VectorIterator fast iterator.
-    if name.name == "IVector`1" && name.namespace == "Windows.Foundation.Collections" {
+    if &*name.name == "IVector`1" && &*name.namespace == "Windows.Foundation.Collections" {
         return quote! {
             pub struct VectorIterator<T: ::winrt::RuntimeType + 'static> {
                 vector: IVector<T>,
                 current: u32,
-                size: u32,
+                size: Option<u32>,
             }
 
             impl<T: ::winrt::RuntimeType> VectorIterator<T> {
                 pub fn new(vector: IVector<T>) -> Self {
-                    let size = vector.size().unwrap();
-                    Self { vector, current: 0, size }
+                    Self { vector, current: 0, size: None }
                 }
             }
 
             impl<T: ::winrt::RuntimeType> ::std::iter::Iterator for VectorIterator<T> {
-                type Item = T;
+                type Item = ::winrt::Result<T>;
 
                 fn next(&mut self) -> Option<Self::Item> {
-                    if self.current >= self.size {
+                    let size = match self.size {
+                        Some(size) => size,
+                        None => match self.vector.size() {
+                            Ok(size) => {
+                                self.size = Some(size);
+                                size
+                            }
+                            Err(error) => {
+                                self.size = Some(0);
+                                return Some(Err(error));
+                            }
+                        },
+                    };
+
+                    if self.current >= size {
                         return None;
                     }
 
                     let result = self.vector.get_at(self.current);
-                    self.current += 1;
-                    result.ok()
+                    self.current = if result.is_ok() { self.current + 1 } else { size };
+                    Some(result)
                 }
             }
 
             impl<T: ::winrt::RuntimeType> ::std::iter::IntoIterator for IVector<T> {
-                type Item = T;
-                type IntoIter = VectorIterator<Self::Item>;
+                type Item = ::winrt::Result<T>;
+                type IntoIter = VectorIterator<T>;
 
                 fn into_iter(self) -> Self::IntoIter {
                     VectorIterator::new(self)
                 }
             }
             impl<'a, T: ::winrt::RuntimeType> ::std::iter::IntoIterator for &'a IVector<T> {
-                type Item = T;
-                type IntoIter = VectorIterator<Self::Item>;
+                type Item = ::winrt::Result<T>;
+                type IntoIter = VectorIterator<T>;
 
                 fn into_iter(self) -> Self::IntoIter {
                     VectorIterator::new(::std::clone::Clone::clone(self))
@@ -152,9 +281,16 @@ pub fn iterator_tokens(name: &TypeName, interfaces: &Vec<RequiredInterface>) ->
 
     // If the class or interface is not one of the well-known collection interfaces, we then see whether it
     // implements any one of them. Here is where we favor IVectorView/IVector over IIterable.
+    // Interfaces that may be compiled out by contract features can't be relied upon. Those that
+    // remain are required by the type's metadata, so they're queried for infallibly, just as
+    // classes are converted to their base classes.
+    let interfaces = interfaces
+        .iter()
+        .filter(|interface| interface.to_gate_tokens(options).is_empty());
+
     for interface in interfaces {
-        if interface.name.name == "IVectorView`1"
-            && interface.name.namespace == "Windows.Foundation.Collections"
+        if &*interface.name.name == "IVectorView`1"
+            && &*interface.name.namespace == "Windows.Foundation.Collections"
         {
             let item = interface.name.generics[0].to_tokens(&name.namespace);
             let wfc = to_namespace_tokens(&interface.name.namespace, &name.namespace);
@@ -162,26 +298,26 @@ pub fn iterator_tokens(name: &TypeName, interfaces: &Vec<RequiredInterface>) ->
 
             return quote! {
                 impl ::std::iter::IntoIterator for #name {
-                    type Item = #item;
-                    type IntoIter = #wfc VectorViewIterator<Self::Item>;
+                    type Item = ::winrt::Result<#item>;
+                    type IntoIter = #wfc VectorViewIterator<#item>;
 
                     fn into_iter(self) -> Self::IntoIter {
-                        #wfc VectorViewIterator::new(self.into())
+                        #wfc VectorViewIterator::new(::winrt::ComInterface::query(&self))
                     }
                 }
                 impl<'a> ::std::iter::IntoIterator for &'a #name {
-                    type Item = #item;
-                    type IntoIter = #wfc VectorViewIterator<Self::Item>;
+                    type Item = ::winrt::Result<#item>;
+                    type IntoIter = #wfc VectorViewIterator<#item>;
 
                     fn into_iter(self) -> Self::IntoIter {
-                        #wfc VectorViewIterator::new(self.into())
+                        #wfc VectorViewIterator::new(::winrt::ComInterface::query(self))
                     }
                 }
             };
         }
 
-        if interface.name.name == "IVectorView`1"
-            && interface.name.namespace == "Windows.Foundation.Collections"
+        if &*interface.name.name == "IVectorView`1"
+            && &*interface.name.namespace == "Windows.Foundation.Collections"
         {
             let item = interface.name.generics[0].to_tokens(&name.namespace);
             let wfc = to_namespace_tokens(&interface.name.namespace, &name.namespace);
@@ -189,26 +325,26 @@ pub fn iterator_tokens(name: &TypeName, interfaces: &Vec<RequiredInterface>) ->
 
             return quote! {
                 impl ::std::iter::IntoIterator for #name {
-                    type Item = #item;
-                    type IntoIter = #wfc VectorIterator<Self::Item>;
+                    type Item = ::winrt::Result<#item>;
+                    type IntoIter = #wfc VectorIterator<#item>;
 
                     fn into_iter(self) -> Self::IntoIter {
-                        #wfc VectorIterator::new(self.into())
+                        #wfc VectorIterator::new(::winrt::ComInterface::query(&self))
                     }
                 }
                 impl<'a> ::std::iter::IntoIterator for &'a #name {
-                    type Item = #item;
-                    type IntoIter = #wfc VectorIterator<Self::Item>;
+                    type Item = ::winrt::Result<#item>;
+                    type IntoIter = #wfc VectorIterator<#item>;
 
                     fn into_iter(self) -> Self::IntoIter {
-                        #wfc VectorIterator::new(self.into())
+                        #wfc VectorIterator::new(::winrt::ComInterface::query(self))
                     }
                 }
             };
         }
 
-        if interface.name.name == "IIterable`1"
-            && interface.name.namespace == "Windows.Foundation.Collections"
+        if &*interface.name.name == "IIterable`1"
+            && &*interface.name.namespace == "Windows.Foundation.Collections"
         {
             iterable = Some(interface);
         }
@@ -220,26 +356,66 @@ pub fn iterator_tokens(name: &TypeName, interfaces: &Vec<RequiredInterface>) ->
             let constraints = name.constraints();
             let item = interface.name.generics[0].to_tokens(&name.namespace);
             let wfc = to_namespace_tokens(&interface.name.namespace, &name.namespace);
+            let pair = key_value_generics(&interface.name.generics[0]).map(|(key, value)| {
+                (
+                    key.to_tokens(&name.namespace),
+                    value.to_tokens(&name.namespace),
+                )
+            });
             let name = name.to_tokens(&name.namespace);
 
+            if let Some((key, value)) = pair {
+                return quote! {
+                    impl<#constraints> ::std::iter::IntoIterator for #name {
+                        type Item = ::winrt::Result<(#key, #value)>;
+                        type IntoIter = #wfc KeyValueIterator<#key, #value>;
+
+                        fn into_iter(self) -> Self::IntoIter {
+                            #wfc KeyValueIterator::new(::winrt::ComInterface::query(&self))
+                        }
+                    }
+                    impl<'a, #constraints> ::std::iter::IntoIterator for &'a #name {
+                        type Item = ::winrt::Result<(#key, #value)>;
+                        type IntoIter = #wfc KeyValueIterator<#key, #value>;
+
+                        fn into_iter(self) -> Self::IntoIter {
+                            #wfc KeyValueIterator::new(::winrt::ComInterface::query(self))
+                        }
+                    }
+                };
+            }
+
             quote! {
                impl<#constraints> ::std::iter::IntoIterator for #name {
-                    type Item = #item;
-                    type IntoIter = #wfc IIterator<Self::Item>;
+                    type Item = ::winrt::Result<#item>;
+                    type IntoIter = #wfc IterableIterator<#item>;
 
                     fn into_iter(self) -> Self::IntoIter {
-                        self.first().unwrap()
+                        #wfc IterableIterator::new(::winrt::ComInterface::query(&self))
                     }
                 }
                 impl<'a, #constraints> ::std::iter::IntoIterator for &'a #name {
-                    type Item = #item;
-                    type IntoIter = #wfc IIterator<Self::Item>;
+                    type Item = ::winrt::Result<#item>;
+                    type IntoIter = #wfc IterableIterator<#item>;
 
                     fn into_iter(self) -> Self::IntoIter {
-                        self.first().unwrap()
+                        #wfc IterableIterator::new(::winrt::ComInterface::query(self))
                     }
                 }
             }
         }
     }
 }
+
+// The key and value types if the item type is IKeyValuePair<K, V>
+fn key_value_generics(item: &TypeKind) -> Option<(&TypeKind, &TypeKind)> {
+    match item {
+        TypeKind::Interface(name)
+            if &*name.name == "IKeyValuePair`2"
+                && &*name.namespace == "Windows.Foundation.Collections" =>
+        {
+            Some((&name.generics[0], &name.generics[1]))
+        }
+        _ => None,
+    }
+}
@@ -1,22 +1,30 @@
-use crate::case::to_snake;
 use crate::tables::{AttributeArg, MethodDef, TypeDef};
+use crate::type_reader::ContextItem;
 use crate::types::TypeName;
 use crate::types::{Param, RequiredInterface, TypeKind};
 use crate::TypeReader;
 use crate::*;
-use proc_macro2::TokenStream;
+use proc_macro2::{Literal, TokenStream};
 use quote::quote;
 use std::iter::FromIterator;
+use std::rc::Rc;
 
 #[derive(Debug)]
 pub struct Method {
-    pub name: String,
+    pub name: Rc<str>,
+    pub runtime_name: Rc<str>,
     pub kind: MethodKind,
     pub params: Vec<Param>,
     pub return_type: Option<Param>,
+    /// The returned object may be null, so it is projected as an `Option`
+    pub nullable: bool,
+    /// The method is left out of the projection, though it remains in the vtable
+    pub skip: bool,
+    /// The method is marked as deprecated in metadata
+    pub deprecated: bool,
 }
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub enum MethodKind {
     Normal,
     Get,
@@ -31,24 +39,25 @@ impl Method {
         method: MethodDef,
         generics: &Vec<TypeKind>,
     ) -> Method {
+        let runtime_name = reader.strings.intern(method.name(reader));
+        let _context = reader.context(ContextItem::Method(method));
         let (name, kind) = if method.flags(reader).special() {
             let name = method.name(reader);
 
-            if name.starts_with("get") {
-                (case::to_snake(&name[4..], MethodKind::Get), MethodKind::Get)
+            let (name, kind) = if name.starts_with("get") {
+                (&name[4..], MethodKind::Get)
             } else if name.starts_with("put") {
-                (case::to_snake(&name[4..], MethodKind::Set), MethodKind::Set)
+                (&name[4..], MethodKind::Set)
             } else if name.starts_with("add") {
-                (case::to_snake(&name[4..], MethodKind::Add), MethodKind::Add)
+                (&name[4..], MethodKind::Add)
             } else if name.starts_with("remove") {
-                (
-                    case::to_snake(&name[7..], MethodKind::Remove),
-                    MethodKind::Remove,
-                )
+                (&name[7..], MethodKind::Remove)
             } else {
                 // A delegate's 'Invoke' method is "special" but lacks a preamble.
-                ("invoke".to_owned(), MethodKind::Normal)
-            }
+                (name, MethodKind::Normal)
+            };
+
+            (reader.strings.to_snake(name, kind), kind)
         } else {
             (Method::name(reader, method), MethodKind::Normal)
         };
@@ -66,8 +75,9 @@ impl Method {
         let return_type = if blob.read_expected(0x01) {
             None
         } else {
-            let name = String::new();
+            let name = reader.strings.intern("");
             let array = blob.peek_unsigned().0 == 0x1D;
+            let _context = reader.context(ContextItem::ReturnType);
             let kind = TypeKind::from_blob(&mut blob, generics);
             let input = false;
             let by_ref = true;
@@ -77,20 +87,36 @@ impl Method {
                 array,
                 input,
                 by_ref,
+                fixed: None,
             })
         };
 
         let mut params = Vec::with_capacity(param_count as usize);
+        let mut skip = false;
 
         for param in method.params(reader) {
             if return_type.is_none() || param.sequence(reader) != 0 {
-                let name = to_snake(param.name(reader), MethodKind::Normal);
+                let _context = reader.context(ContextItem::Param(param));
+                let name = reader
+                    .strings
+                    .to_snake(param.name(reader), MethodKind::Normal);
                 let input = param.flags(reader).input();
 
                 blob.read_modifiers();
                 let by_ref = blob.read_expected(0x10);
-                let array = blob.peek_unsigned().0 == 0x1D;
-                let kind = TypeKind::from_blob(&mut blob, generics);
+
+                let (kind, array, fixed) = match Param::read_fixed_array(&mut blob, generics) {
+                    Some((kind, Ok(len))) => (kind, true, Some(len)),
+                    // Other arrays have no projection, so the method is left out.
+                    Some((kind, Err(_))) => {
+                        skip = true;
+                        (kind, true, None)
+                    }
+                    None => {
+                        let array = blob.peek_unsigned().0 == 0x1D;
+                        (TypeKind::from_blob(&mut blob, generics), array, None)
+                    }
+                };
 
                 params.push(Param {
                     name,
@@ -98,15 +124,27 @@ impl Method {
                     array,
                     input,
                     by_ref,
+                    fixed,
                 });
             }
         }
 
+        let deprecated = method
+            .find_attribute(
+                reader,
+                ("Windows.Foundation.Metadata", "DeprecatedAttribute"),
+            )
+            .is_some();
+
         Method {
             name,
+            runtime_name,
             kind,
             params,
             return_type,
+            nullable: false,
+            skip,
+            deprecated,
         }
     }
 
@@ -118,23 +156,85 @@ impl Method {
             .collect()
     }
 
-    fn name(reader: &TypeReader, method: MethodDef) -> String {
+    /// A name for this overload of `base`, whose name it would otherwise share
+    ///
+    /// The overload is named after the parameters that `base` doesn't have, keeping any
+    /// `_async` suffix last, so `CreateAsync(options)` becomes `create_with_options_async` when
+    /// there is also a `CreateAsync()`. If that doesn't distinguish it then the number of
+    /// parameters is appended, and then a counter, until the name isn't `taken`.
+    pub fn overload_name<F: Fn(&str) -> bool>(&self, base: &Method, taken: F) -> String {
+        let mut params: Vec<&str> = self
+            .params
+            .iter()
+            .filter(|param| !base.params.iter().any(|other| other.name == param.name))
+            .map(|param| &*param.name)
+            .collect();
+
+        if params.is_empty() {
+            params = self.params.iter().map(|param| &*param.name).collect();
+        }
+
+        let (stem, suffix) = match self.name.strip_suffix("_async") {
+            Some(stem) => (stem, "_async"),
+            None => (&*self.name, ""),
+        };
+
+        let stem = if params.is_empty() {
+            stem.to_string()
+        } else {
+            format!("{}_with_{}", stem, params.join("_"))
+        };
+
+        let name = format!("{}{}", stem, suffix);
+
+        if !taken(&name) {
+            return name;
+        }
+
+        let stem = format!("{}{}", stem, self.params.len());
+        let mut name = format!("{}{}", stem, suffix);
+        let mut count = 2;
+
+        while taken(&name) {
+            name = format!("{}_{}{}", stem, count, suffix);
+            count += 1;
+        }
+
+        name
+    }
+
+    fn name(reader: &TypeReader, method: MethodDef) -> Rc<str> {
         if let Some(attribute) =
             method.find_attribute(reader, ("Windows.Foundation.Metadata", "OverloadAttribute"))
         {
             for (_, arg) in attribute.args(reader) {
                 if let AttributeArg::String(name) = arg {
-                    return case::to_snake(&name, MethodKind::Normal);
+                    return reader.strings.to_snake(&name, MethodKind::Normal);
                 }
             }
         }
 
-        case::to_snake(method.name(reader), MethodKind::Normal)
+        reader
+            .strings
+            .to_snake(method.name(reader), MethodKind::Normal)
     }
 
     pub fn to_abi_tokens(&self, self_name: &TypeName, calling_namespace: &str) -> TokenStream {
-        let abi_name = self_name.to_abi_tokens(calling_namespace);
         let name = format_ident(&self.name);
+        let signature = self.to_abi_signature_tokens(self_name, calling_namespace);
+
+        quote! {
+            pub #name: #signature,
+        }
+    }
+
+    /// The type of the method's function pointer in the vtable
+    pub fn to_abi_signature_tokens(
+        &self,
+        self_name: &TypeName,
+        calling_namespace: &str,
+    ) -> TokenStream {
+        let abi_name = self_name.to_abi_tokens(calling_namespace);
         let params = TokenStream::from_iter(
             self.params
                 .iter()
@@ -143,7 +243,7 @@ impl Method {
         );
 
         quote! {
-            pub #name: extern "system" fn(*const *const #abi_name, #params) -> ::winrt::ErrorCode,
+            extern "system" fn(*const *const #abi_name, #params) -> ::winrt::ErrorCode
         }
     }
 
@@ -167,17 +267,38 @@ impl Method {
         TokenStream::from_iter(self.params.iter().map(|param| param.to_abi_arg_tokens()))
     }
 
+    fn to_return_tokens(&self, calling_namespace: &str) -> TokenStream {
+        match &self.return_type {
+            Some(return_type) if self.nullable => {
+                let tokens = return_type.to_return_tokens(calling_namespace);
+                quote! { ::std::option::Option<#tokens> }
+            }
+            Some(return_type) => return_type.to_return_tokens(calling_namespace),
+            None => quote! { () },
+        }
+    }
+
     fn to_constraint_tokens(&self, calling_namespace: &str) -> TokenStream {
         let mut tokens = Vec::new();
 
         for (position, param) in self.params.iter().enumerate() {
-            if !param.input || param.array {
+            if !param.input {
+                continue;
+            }
+
+            // Input arrays are taken as slices, which vectors and arrays coerce to.
+            if param.array {
                 continue;
             }
 
             match param.kind {
-                TypeKind::String
-                | TypeKind::Object
+                // Strings have a parameter type of their own that passes a `&str` without
+                // allocating it.
+                TypeKind::String => {
+                    let name = quote::format_ident!("__{}", position);
+                    tokens.push(quote! { #name: ::std::convert::Into<::winrt::StringParam<'a>>, });
+                }
+                TypeKind::Object
                 | TypeKind::Guid
                 | TypeKind::Class(_)
                 | TypeKind::Interface(_)
@@ -199,26 +320,61 @@ impl Method {
         TokenStream::from_iter(tokens)
     }
 
-    pub fn to_default_tokens(&self, calling_namespace: &str) -> TokenStream {
+    pub fn to_default_tokens(
+        &self,
+        calling_namespace: &str,
+        verify: &TokenStream,
+        check: &TokenStream,
+        vcall: &TokenStream,
+    ) -> TokenStream {
         let method_name = format_ident(&self.name);
         let params = self.to_param_tokens(calling_namespace);
         let constraints = self.to_constraint_tokens(calling_namespace);
         let args = self.to_abi_arg_tokens();
 
         if let Some(return_type) = &self.return_type {
-            let return_arg = return_type.to_abi_return_arg_tokens(calling_namespace);
+            let return_arg =
+                return_type.to_abi_return_arg_tokens(calling_namespace, &quote! { &mut __ok });
+            let value = return_type.to_return_value_tokens(&quote! { __ok });
+            let return_abi_type = return_type.to_return_abi_value_tokens(calling_namespace);
             let return_type = return_type.to_return_tokens(calling_namespace);
 
+            if self.nullable {
+                let option = self.to_return_tokens(calling_namespace);
+
+                return quote! {
+                    pub fn #method_name<#constraints>(&self, #params) -> ::winrt::Result<#option> {
+                        let this = self.ptr.get();
+                        if this.is_null() {
+                            panic!("The `this` pointer was null when calling method");
+                        }
+                        #verify
+                        #check
+                        unsafe {
+                            let mut __ok: #return_type = ::std::mem::zeroed();
+                            (#vcall)(this, #args #return_arg).ok()?;
+                            if ::winrt::ComInterface::is_null(&__ok) {
+                                Ok(None)
+                            } else {
+                                Ok(Some(__ok))
+                            }
+                        }
+                    }
+                };
+            }
+
             quote! {
                 pub fn #method_name<#constraints>(&self, #params) -> ::winrt::Result<#return_type> {
                     let this = self.ptr.get();
                     if this.is_null() {
                         panic!("The `this` pointer was null when calling method");
                     }
+                    #verify
+                    #check
                     unsafe {
-                        let mut __ok: #return_type = ::std::mem::zeroed();
-                        ((*(*(this))).#method_name)(this, #args #return_arg)
-                            .and_then(|| __ok )
+                        let mut __ok: #return_abi_type = ::std::mem::zeroed();
+                        (#vcall)(this, #args #return_arg)
+                            .and_then(|| #value)
                     }
                 }
             }
@@ -229,34 +385,144 @@ impl Method {
                     if this.is_null() {
                         panic!("The `this` pointer was null when calling method");
                     }
+                    #verify
+                    #check
                     unsafe {
-                        ((*(*(this))).#method_name)(this, #args).ok()
+                        (#vcall)(this, #args).ok()
                     }
                 }
             }
         }
     }
 
+    /// A default interface method that calls through the vtable slot `index` with the runtime's
+    /// ABI helpers rather than with pointer casts of its own, for the `safe_api` option
+    ///
+    /// The helpers are unsafe since only the generator knows the slot and its signature, so the
+    /// call is the method's one `unsafe` block and the method allows it.
+    pub fn to_safe_default_tokens(
+        &self,
+        calling_namespace: &str,
+        verify: &TokenStream,
+        check: &TokenStream,
+        index: usize,
+    ) -> TokenStream {
+        let method_name = format_ident(&self.name);
+        let params = self.to_param_tokens(calling_namespace);
+        let constraints = self.to_constraint_tokens(calling_namespace);
+        // Strings and objects are converted before the call, since the arguments are built by a
+        // closure and what they refer to must outlive it.
+        let bindings = TokenStream::from_iter(
+            self.params
+                .iter()
+                .map(|param| param.to_binding_tokens(calling_namespace)),
+        );
+        let args = TokenStream::from_iter(
+            self.params
+                .iter()
+                .map(|param| param.to_bound_abi_arg_tokens()),
+        );
+        let index = Literal::usize_unsuffixed(index);
+
+        // The argument types are spelled out so that pointer casts can infer their targets.
+        let abi_types = TokenStream::from_iter(
+            self.params
+                .iter()
+                .chain(self.return_type.iter())
+                .map(|param| param.to_abi_tokens(calling_namespace)),
+        );
+
+        let call = match &self.return_type {
+            Some(return_type) => {
+                let return_arg =
+                    return_type.to_abi_return_arg_tokens(calling_namespace, &quote! { __ok });
+                let convert = if return_type.return_converted() {
+                    quote! { .map(bool::from) }
+                } else {
+                    TokenStream::new()
+                };
+                let return_type = return_type.to_return_abi_value_tokens(calling_namespace);
+
+                quote! {
+                    unsafe { ::winrt::abi_call_return::<_, #return_type, (#abi_types), _>(self, #index, |__ok| (#args #return_arg)) } #convert
+                }
+            }
+            None => quote! {
+                unsafe { ::winrt::abi_call::<_, (#abi_types)>(self, #index, (#args)) }
+            },
+        };
+
+        let call = if self.nullable {
+            quote! {
+                #call.map(|__ok| if ::winrt::ComInterface::is_null(&__ok) { None } else { Some(__ok) })
+            }
+        } else {
+            call
+        };
+
+        let return_type = self.to_return_tokens(calling_namespace);
+
+        quote! {
+            #[allow(unsafe_code)]
+            pub fn #method_name<#constraints>(&self, #params) -> ::winrt::Result<#return_type> {
+                let this = self.ptr.get();
+                if this.is_null() {
+                    panic!("The `this` pointer was null when calling method");
+                }
+                #verify
+                #check
+                #bindings
+                #call
+            }
+        }
+    }
+
+    /// A trait method that forwards to the interface, for use in the interface's trait
+    pub fn to_trait_tokens(&self, calling_namespace: &str, interface: &TokenStream) -> TokenStream {
+        let method_name = format_ident(&self.name);
+        let params = self.to_param_tokens(calling_namespace);
+        let constraints = self.to_constraint_tokens(calling_namespace);
+        let args = self.to_arg_tokens();
+        let return_type = self.to_return_tokens(calling_namespace);
+
+        quote! {
+            fn #method_name<#constraints>(&self, #params) -> ::winrt::Result<#return_type> {
+                ::winrt::ComInterface::try_query::<#interface>(self)?.#method_name(#args)
+            }
+        }
+    }
+
     pub fn to_non_default_tokens(
         &self,
         calling_namespace: &str,
         interface: &RequiredInterface,
+        check: &TokenStream,
+    ) -> TokenStream {
+        self.to_named_non_default_tokens(&self.name, calling_namespace, interface, check)
+    }
+
+    /// A method that forwards to the interface's method under another name, for overloads
+    /// whose name is already taken by another interface
+    pub fn to_named_non_default_tokens(
+        &self,
+        name: &str,
+        calling_namespace: &str,
+        interface: &RequiredInterface,
+        check: &TokenStream,
     ) -> TokenStream {
+        let name = format_ident(name);
         let method_name = format_ident(&self.name);
         let params = self.to_param_tokens(calling_namespace);
         let constraints = self.to_constraint_tokens(calling_namespace);
         let args = self.to_arg_tokens();
         let interface = interface.name.to_tokens(calling_namespace);
 
-        let return_type = if let Some(return_type) = &self.return_type {
-            return_type.to_return_tokens(calling_namespace)
-        } else {
-            quote! { () }
-        };
+        let return_type = self.to_return_tokens(calling_namespace);
 
         quote! {
-            pub fn #method_name<#constraints>(&self, #params) -> ::winrt::Result<#return_type> {
-                <#interface as ::std::convert::From<&Self>>::from(self).#method_name(#args)
+            pub fn #name<#constraints>(&self, #params) -> ::winrt::Result<#return_type> {
+                #check
+                <#interface as ::std::convert::TryFrom<&Self>>::try_from(self)?.#method_name(#args)
             }
         }
     }
@@ -265,22 +531,42 @@ impl Method {
         &self,
         calling_namespace: &str,
         interface: &RequiredInterface,
+        check: &TokenStream,
+    ) -> TokenStream {
+        self.to_named_static_tokens(&self.name, calling_namespace, interface, check)
+    }
+
+    /// A constructor that forwards to the activation factory method
+    pub fn to_constructor_tokens(
+        &self,
+        constructor: &str,
+        calling_namespace: &str,
+        interface: &RequiredInterface,
+        check: &TokenStream,
+    ) -> TokenStream {
+        self.to_named_static_tokens(constructor, calling_namespace, interface, check)
+    }
+
+    pub fn to_named_static_tokens(
+        &self,
+        name: &str,
+        calling_namespace: &str,
+        interface: &RequiredInterface,
+        check: &TokenStream,
     ) -> TokenStream {
+        let name = format_ident(name);
         let method_name = format_ident(&self.name);
         let params = self.to_param_tokens(calling_namespace);
         let constraints = self.to_constraint_tokens(calling_namespace);
         let args = self.to_arg_tokens();
         let interface = interface.name.to_tokens(calling_namespace);
 
-        let return_type = if let Some(return_type) = &self.return_type {
-            return_type.to_return_tokens(calling_namespace)
-        } else {
-            quote! { () }
-        };
+        let return_type = self.to_return_tokens(calling_namespace);
 
         quote! {
-            pub fn #method_name<#constraints>(#params) -> ::winrt::Result<#return_type> {
-                ::winrt::activation::factory::<Self, #interface>()?.#method_name(#args)
+            pub fn #name<#constraints>(#params) -> ::winrt::Result<#return_type> {
+                #check
+                ::winrt::activation::call_factory::<Self, #interface, _>(|factory| factory.#method_name(#args))
             }
         }
     }
@@ -302,7 +588,7 @@ mod tests {
 
         for interface in t.interfaces {
             for method in interface.methods {
-                if method.name == method_name {
+                if &*method.name == method_name {
                     return method;
                 }
             }
@@ -351,12 +637,8 @@ mod tests {
         assert!(token.input == false);
         assert!(token.by_ref == true);
 
-        let token = match &token.kind {
-            TypeKind::Struct(token) => token,
-            _ => panic!("Wrong type"),
-        };
-
-        assert!(token.runtime_name() == "Windows.Foundation.EventRegistrationToken");
+        assert!(token.kind == TypeKind::EventToken);
+        assert!(token.kind.runtime_name() == "Windows.Foundation.EventRegistrationToken");
     }
 
     #[test]
@@ -374,11 +656,246 @@ mod tests {
         assert!(token.input == true);
         assert!(token.by_ref == false);
 
-        let token = match &token.kind {
-            TypeKind::Struct(token) => token,
+        assert!(token.kind == TypeKind::EventToken);
+        assert!(token.kind.runtime_name() == "Windows.Foundation.EventRegistrationToken");
+    }
+
+    #[test]
+    fn test_generic_delegate_param() {
+        let method = method(("Windows.Foundation", "IAsyncOperation`1"), "set_completed");
+        let handler = &method.params[0];
+
+        let handler = match &handler.kind {
+            TypeKind::Delegate(handler) => handler,
+            _ => panic!("Wrong type"),
+        };
+
+        assert!(
+            handler.runtime_name()
+                == "Windows.Foundation.AsyncOperationCompletedHandler`1<TResult>"
+        );
+        assert!(
+            method.params[0]
+                .to_abi_tokens("Windows.Foundation")
+                .to_string()
+                == ":: winrt :: RawPtr ,"
+        );
+    }
+
+    #[test]
+    fn test_generic_interface_param() {
+        let method = method(
+            ("Windows.ApplicationModel.DataTransfer", "IDataPackage"),
+            "set_storage_items",
+        );
+
+        let items = &method.params[0];
+        assert!(items.input == true);
+        assert!(items.array == false);
+
+        let items = match &items.kind {
+            TypeKind::Interface(items) => items,
             _ => panic!("Wrong type"),
         };
 
-        assert!(token.runtime_name() == "Windows.Foundation.EventRegistrationToken");
+        assert!(
+            items.runtime_name()
+                == "Windows.Foundation.Collections.IIterable`1<Windows.Storage.IStorageItem>"
+        );
+
+        let abi = method.params[0]
+            .to_abi_tokens("Windows.ApplicationModel.DataTransfer")
+            .to_string();
+        assert!(abi.contains("IIterable :: < super :: super :: storage :: IStorageItem >"));
+    }
+
+    #[test]
+    fn test_return_array() {
+        let method = method(
+            ("Windows.Graphics.Imaging", "IPixelDataProvider"),
+            "detach_pixel_data",
+        );
+
+        let result = method.return_type.as_ref().unwrap();
+        assert!(result.array == true);
+        assert!(result.kind == TypeKind::U8);
+        assert!(result.to_return_tokens("").to_string() == ":: winrt :: Array < u8 >");
+        assert!(result.to_abi_tokens("").to_string() == "* mut u32 , * mut * mut u8 ,");
+    }
+
+    #[test]
+    fn test_struct_abi() {
+        // Structs are passed by value and returned through a pointer, never in registers, so
+        // the C ABI's rules for each architecture, such as for aggregates of floats on ARM64,
+        // only apply to parameters and are left to `extern "system"`.
+        let create = method(
+            ("Windows.Foundation", "IPropertyValueStatics"),
+            "create_point",
+        );
+        assert!(
+            create.params[0]
+                .to_abi_tokens("Windows.Foundation")
+                .to_string()
+                == "< Point as :: winrt :: RuntimeType > :: Abi ,"
+        );
+
+        let get = method(("Windows.Foundation", "IPropertyValue"), "get_rect");
+        assert!(get.params.is_empty());
+        assert!(
+            get.return_type
+                .as_ref()
+                .unwrap()
+                .to_abi_tokens("Windows.Foundation")
+                .to_string()
+                == "* mut < Rect as :: winrt :: RuntimeType > :: Abi ,"
+        );
+    }
+
+    #[test]
+    fn test_fill_array() {
+        let method = method(("Windows.Storage.Streams", "IDataReader"), "read_bytes");
+
+        assert!(method.params.len() == 1);
+        let param = &method.params[0];
+        assert!(param.array == true);
+        assert!(param.input == false);
+        assert!(param.by_ref == false);
+        assert!(param.kind == TypeKind::U8);
+        assert!(param.to_tokens("", 0).to_string() == "value : & mut [u8] ,");
+        assert!(param.to_abi_tokens("").to_string() == "u32 , * mut u8 ,");
+    }
+
+    #[test]
+    fn test_overload_name() {
+        fn overload(name: &str, params: &[&str]) -> Method {
+            Method {
+                name: name.into(),
+                runtime_name: "".into(),
+                kind: MethodKind::Normal,
+                params: params
+                    .iter()
+                    .map(|name| Param {
+                        name: (*name).into(),
+                        kind: TypeKind::I32,
+                        array: false,
+                        input: true,
+                        by_ref: false,
+                        fixed: None,
+                    })
+                    .collect(),
+                return_type: None,
+                nullable: false,
+                skip: false,
+                deprecated: false,
+            }
+        }
+
+        let base = overload("create_async", &[]);
+        let options = overload("create_async", &["options"]);
+        assert!(options.overload_name(&base, |_| false) == "create_with_options_async");
+
+        let base = overload("find", &["name"]);
+        let scoped = overload("find", &["name", "scope"]);
+        assert!(scoped.overload_name(&base, |_| false) == "find_with_scope");
+
+        // Overloads with the same parameter names are named after all of them.
+        let same = overload("find", &["name"]);
+        assert!(same.overload_name(&base, |_| false) == "find_with_name");
+
+        // The parameter count and then a counter are appended until the name is free.
+        let taken = |name: &str| name == "find_with_name" || name == "find_with_name1";
+        assert!(same.overload_name(&base, taken) == "find_with_name1_2");
+        assert!(same.overload_name(&base, |name| name == "find_with_name") == "find_with_name1");
+
+        let property = overload("name", &[]);
+        assert!(property.overload_name(&property, |name| name == "name") == "name0");
+    }
+
+    #[test]
+    fn test_fixed_array() {
+        let mut param = Param {
+            name: "token".into(),
+            kind: TypeKind::U8,
+            array: true,
+            input: true,
+            by_ref: false,
+            fixed: Some(16),
+        };
+
+        assert!(param.to_tokens("", 0).to_string() == "r#token : & [u8 ; 16] ,");
+        assert!(
+            param.to_abi_arg_tokens().to_string()
+                == "r#token . len () as u32 , r#token . as_ptr () as * const _ ,"
+        );
+
+        param.input = false;
+        assert!(param.to_tokens("", 0).to_string() == "r#token : & mut [u8 ; 16] ,");
+        assert!(param.to_abi_tokens("").to_string() == "u32 , * mut u8 ,");
+
+        use crate::types::param::fixed_len;
+        assert!(fixed_len(1, &[16], &[]) == Ok(16));
+        assert!(fixed_len(1, &[16], &[0]) == Ok(16));
+        assert!(fixed_len(1, &[16], &[1]).is_err());
+        assert!(fixed_len(1, &[], &[]).is_err());
+        assert!(fixed_len(2, &[4, 4], &[]).is_err());
+    }
+
+    #[test]
+    fn test_bool() {
+        let mut param = Param {
+            name: "value".into(),
+            kind: TypeKind::Bool,
+            array: false,
+            input: true,
+            by_ref: false,
+            fixed: None,
+        };
+
+        // Single booleans are converted by value to and from a byte at the ABI.
+        assert!(param.to_tokens("", 0).to_string() == "r#value : bool ,");
+        assert!(param.to_abi_tokens("").to_string() == ":: winrt :: Boolean ,");
+        assert!(param.to_abi_arg_tokens().to_string() == ":: winrt :: Boolean :: from (r#value) ,");
+
+        param.input = false;
+        assert!(param.to_return_tokens("").to_string() == "bool");
+        assert!(param.to_return_abi_value_tokens("").to_string() == ":: winrt :: Boolean");
+        assert!(
+            param.to_return_value_tokens(&quote! { __ok }).to_string() == "bool :: from (__ok)"
+        );
+
+        // Outputs are written to the byte itself rather than to a `bool`.
+        assert!(param.to_tokens("", 0).to_string() == "r#value : & mut :: winrt :: Boolean ,");
+        assert!(param.to_abi_tokens("").to_string() == "* mut :: winrt :: Boolean ,");
+
+        param.array = true;
+        assert!(
+            param.to_return_tokens("").to_string() == ":: winrt :: Array < :: winrt :: Boolean >"
+        );
+        assert!(param.to_return_value_tokens(&quote! { __ok }).to_string() == "__ok");
+    }
+
+    #[test]
+    fn test_char() {
+        let mut param = Param {
+            name: "value".into(),
+            kind: TypeKind::Char,
+            array: false,
+            input: true,
+            by_ref: false,
+            fixed: None,
+        };
+
+        // Chars are projected as `Char16` but remain a `u16` at the ABI.
+        assert!(param.to_tokens("", 0).to_string() == "r#value : :: winrt :: Char16 ,");
+        assert!(param.to_abi_tokens("").to_string() == "u16 ,");
+        assert!(
+            param.to_abi_arg_tokens().to_string() == ":: winrt :: RuntimeType :: abi (& r#value) ,"
+        );
+
+        param.input = false;
+        assert!(
+            param.to_abi_arg_tokens().to_string()
+                == ":: winrt :: RuntimeType :: set_abi (r#value) ,"
+        );
     }
 }
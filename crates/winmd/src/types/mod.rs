@@ -1,31 +1,69 @@
+mod r#async;
+mod buffer;
 mod class;
+mod constant_value;
+mod contract;
+mod debug;
 mod delegate;
+mod dispatcher;
 mod r#enum;
+mod identity;
 mod interface;
 mod iterator;
 mod method;
 mod namespace;
+mod overrides;
 mod param;
+mod property_bag;
+mod raw;
 mod required_interface;
 mod required_interfaces;
+mod safe_api;
+mod serde;
+mod specialization;
+mod status;
+mod stream;
 mod r#struct;
+mod time;
+mod traits;
 mod r#type;
 mod type_guid;
 mod type_kind;
 mod type_name;
+mod variant;
+mod visibility;
 
+pub(crate) use buffer::*;
 pub(crate) use class::Class;
+pub(crate) use constant_value::*;
+pub(crate) use contract::*;
+pub(crate) use debug::*;
 pub(crate) use delegate::Delegate;
+pub(crate) use dispatcher::*;
+pub(crate) use identity::*;
 pub(crate) use interface::Interface;
 pub(crate) use iterator::*;
 pub(crate) use method::*;
 pub(crate) use namespace::*;
+pub(crate) use overrides::*;
 pub(crate) use param::Param;
-pub(crate) use r#enum::Enum;
+pub(crate) use property_bag::*;
+pub(crate) use r#async::*;
+pub(crate) use r#enum::{Enum, EnumConstant};
 pub(crate) use r#struct::Struct;
 pub(crate) use r#type::Type;
+pub(crate) use raw::*;
 pub(crate) use required_interface::*;
 pub(crate) use required_interfaces::*;
+pub(crate) use safe_api::*;
+pub(crate) use serde::*;
+pub(crate) use specialization::Specialization;
+pub(crate) use status::*;
+pub(crate) use stream::*;
+pub(crate) use time::*;
+pub(crate) use traits::*;
 pub(crate) use type_guid::{GuidConstant, TypeGuid};
 pub(crate) use type_kind::TypeKind;
 pub(crate) use type_name::TypeName;
+pub(crate) use variant::*;
+pub(crate) use visibility::*;
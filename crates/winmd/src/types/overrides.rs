@@ -0,0 +1,257 @@
+use crate::types::*;
+use crate::{format_ident, TypeOptions};
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::collections::BTreeSet;
+use std::iter::FromIterator;
+
+// Composable classes may let derived classes override the members of their overridable
+// interfaces. The generated `{Class}Overrides` trait has a method for each overridable member
+// with a default implementation that calls the base class. The class's `compose` function then
+// builds an outer object that routes calls on the overridable interfaces to the trait, and
+// passes it to the class's composable factory so that the class calls back into Rust.
+//
+// Members with array or output parameters aren't part of the trait and are always forwarded to
+// the base class.
+pub fn overrides_tokens(
+    name: &TypeName,
+    interfaces: &[RequiredInterface],
+    options: &TypeOptions,
+) -> TokenStream {
+    let calling_namespace = &name.namespace;
+
+    let (factory, create) = match composable_factory(name, interfaces) {
+        Some(factory) => factory,
+        None => return TokenStream::new(),
+    };
+
+    let overrides: Vec<&RequiredInterface> = interfaces
+        .iter()
+        .filter(|interface| interface.kind == InterfaceKind::Overrides)
+        .collect();
+
+    if overrides.is_empty() {
+        return TokenStream::new();
+    }
+
+    let class_name = name.to_tokens(calling_namespace);
+    let trait_name = quote::format_ident!("{}Overrides", &*name.name);
+    let factory_name = factory.name.to_tokens(calling_namespace);
+    let create = format_ident(&create.name);
+
+    let gates = TokenStream::from_iter(
+        overrides
+            .iter()
+            .copied()
+            .chain(std::iter::once(factory))
+            .map(|interface| interface.to_gate_tokens(options)),
+    );
+
+    let allow = to_allow_unsafe_tokens(options);
+    let mut names = BTreeSet::new();
+    let mut members = Vec::new();
+    let mut thunks = Vec::new();
+    let mut vtables = Vec::new();
+
+    for (index, interface) in overrides.iter().enumerate() {
+        let interface_name = interface.name.to_tokens(calling_namespace);
+        let mut methods = Vec::new();
+
+        for (method_index, method) in interface.methods.iter().enumerate() {
+            let thunk = quote::format_ident!("__{}_{}", index, &*method.name);
+            let (abi_params, abi_args) = abi_params(method, calling_namespace);
+            methods.push(quote! { #thunk::<T> as *const (), });
+
+            // Members whose names collide with earlier members are forwarded like unsupported ones.
+            if overridable(method) && names.insert(&method.name) {
+                let method_name = format_ident(&method.name);
+                let params = TokenStream::from_iter(method.params.iter().map(|param| {
+                    let name = format_ident(&param.name);
+                    let tokens = param.kind.to_value_tokens(calling_namespace);
+
+                    if param.kind.blittable() {
+                        quote! { #name: #tokens, }
+                    } else {
+                        quote! { #name: &#tokens, }
+                    }
+                }));
+                let args = TokenStream::from_iter(method.params.iter().map(|param| {
+                    let name = format_ident(&param.name);
+                    quote! { #name, }
+                }));
+                let abi_as_args = TokenStream::from_iter(method.params.iter().map(|param| {
+                    let name = format_ident(&param.name);
+                    let tokens = param.kind.to_tokens(calling_namespace);
+
+                    if param.kind == TypeKind::Bool {
+                        quote! { bool::from(#name), }
+                    } else if param.kind.blittable() {
+                        quote! { *(&#name as *const _ as *const #tokens), }
+                    } else {
+                        quote! { &*(&#name as *const _ as *const #tokens), }
+                    }
+                }));
+
+                let (return_type, write_result) = match &method.return_type {
+                    Some(return_type) => {
+                        let tokens = return_type.kind.to_tokens(calling_namespace);
+                        (
+                            return_type.to_return_tokens(calling_namespace),
+                            quote! { ::std::ptr::write(__result as *mut #tokens, __value.into()); },
+                        )
+                    }
+                    None => (quote! { () }, quote! { let () = __value; }),
+                };
+
+                members.push(quote! {
+                    fn #method_name(&self, base: &#interface_name, #params) -> ::winrt::Result<#return_type> {
+                        base.#method_name(#args)
+                    }
+                });
+
+                thunks.push(quote! {
+                    #allow
+                    extern "system" fn #thunk<T: #trait_name>(this: ::winrt::RawPtr, #abi_params) -> ::winrt::ErrorCode {
+                        unsafe {
+                            let (overrides, base) = match ::winrt::Composer::<T>::resolve::<#interface_name>(this) {
+                                Ok(value) => value,
+                                Err(error) => return error.originate(),
+                            };
+                            match overrides.#method_name(&base, #abi_as_args) {
+                                Ok(__value) => {
+                                    #write_result
+                                    ::winrt::ErrorCode(0)
+                                }
+                                Err(error) => error.originate(),
+                            }
+                        }
+                    }
+                });
+            } else {
+                let vcall = interface.to_vcall_tokens(
+                    &quote! { __this },
+                    method_index,
+                    calling_namespace,
+                    options,
+                );
+
+                thunks.push(quote! {
+                    #allow
+                    extern "system" fn #thunk<T: #trait_name>(this: ::winrt::RawPtr, #abi_params) -> ::winrt::ErrorCode {
+                        unsafe {
+                            let base = match ::winrt::Composer::<T>::resolve::<#interface_name>(this) {
+                                Ok((_, base)) => base,
+                                Err(error) => return error.originate(),
+                            };
+                            let __this = ::winrt::RuntimeType::abi(&base);
+                            (#vcall)(__this, #abi_args)
+                        }
+                    }
+                });
+            }
+        }
+
+        vtables.push(quote! {
+            .interface(<#interface_name as ::winrt::ComInterface>::GUID, &[#(#methods)*])
+        });
+    }
+
+    let members = TokenStream::from_iter(members);
+    let thunks = TokenStream::from_iter(thunks);
+    let vtables = TokenStream::from_iter(vtables);
+
+    quote! {
+        #gates
+        pub trait #trait_name: 'static {
+            #members
+        }
+        impl #class_name {
+            #gates
+            pub fn compose<T: #trait_name>(overrides: T) -> ::winrt::Result<Self> {
+                #thunks
+                ::winrt::Composer::new(overrides)
+                    #vtables
+                    .create(|outer, inner| {
+                        ::winrt::activation::call_factory::<Self, #factory_name, _>(|factory| factory.#create(outer, inner))
+                    })
+            }
+        }
+    }
+}
+
+// Finds the composable factory method that only takes the outer and inner objects.
+fn composable_factory<'a>(
+    name: &TypeName,
+    interfaces: &'a [RequiredInterface],
+) -> Option<(&'a RequiredInterface, &'a Method)> {
+    interfaces
+        .iter()
+        .filter(|interface| interface.kind == InterfaceKind::Statics)
+        .find_map(|interface| {
+            interface
+                .methods
+                .iter()
+                .find(|method| {
+                    method.params.len() == 2
+                        && method.params[0].kind == TypeKind::Object
+                        && method.params[0].input
+                        && method.params[1].kind == TypeKind::Object
+                        && !method.params[1].input
+                        && match &method.return_type {
+                            Some(Param {
+                                kind: TypeKind::Class(class),
+                                ..
+                            }) => class == name,
+                            _ => false,
+                        }
+                })
+                .map(|method| (interface, method))
+        })
+}
+
+fn overridable(method: &Method) -> bool {
+    method
+        .params
+        .iter()
+        .all(|param| param.input && !param.array)
+        && method.return_type.iter().all(|param| !param.array)
+}
+
+// Returns the named ABI parameters of a method along with the arguments that pass them on.
+fn abi_params(method: &Method, calling_namespace: &str) -> (TokenStream, TokenStream) {
+    let mut params = Vec::new();
+    let mut args = Vec::new();
+
+    for param in method.params.iter().chain(method.return_type.iter()) {
+        let name = if param.name.is_empty() {
+            quote::format_ident!("__result")
+        } else {
+            format_ident(&param.name)
+        };
+
+        let len = quote::format_ident!("{}_len", name);
+        let tokens = param.kind.to_abi_tokens(calling_namespace);
+
+        if param.array {
+            if param.input {
+                params.push(quote! { #len: u32, #name: *const #tokens });
+            } else if param.by_ref {
+                params.push(quote! { #len: *mut u32, #name: *mut *mut #tokens });
+            } else {
+                params.push(quote! { #len: u32, #name: *mut #tokens });
+            }
+
+            args.push(quote! { #len, #name, });
+        } else {
+            if param.input {
+                params.push(quote! { #name: #tokens });
+            } else {
+                params.push(quote! { #name: *mut #tokens });
+            }
+
+            args.push(quote! { #name, });
+        }
+    }
+
+    (TokenStream::from_iter(params), TokenStream::from_iter(args))
+}
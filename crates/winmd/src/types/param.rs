@@ -1,23 +1,60 @@
+use crate::blob::Blob;
 use crate::types::*;
 use crate::*;
 use proc_macro2::TokenStream;
 use quote::quote;
+use std::rc::Rc;
 
 #[derive(Debug)]
 pub struct Param {
-    pub name: String,
+    pub name: Rc<str>,
     pub kind: TypeKind,
     pub array: bool,
     pub input: bool,
     pub by_ref: bool,
+    /// The length of a fixed-size array, if the metadata declares one
+    pub fixed: Option<u32>,
 }
 
 impl Param {
+    /// Reads an `ELEMENT_TYPE_ARRAY`, returning the element type and its fixed length
+    ///
+    /// WinRT arrays are otherwise `ELEMENT_TYPE_SZARRAY`s whose length is only known at runtime.
+    /// The whole array is read even if it doesn't have a single dimension of fixed length, in
+    /// which case the length is an error.
+    pub fn read_fixed_array(
+        blob: &mut Blob,
+        generics: &Vec<TypeKind>,
+    ) -> Option<(TypeKind, Result<u32, String>)> {
+        if !blob.read_expected(0x14) {
+            return None;
+        }
+
+        let kind = TypeKind::from_blob(blob, generics);
+        let rank = blob.read_unsigned();
+        let sizes: Vec<u32> = (0..blob.read_unsigned())
+            .map(|_| blob.read_unsigned())
+            .collect();
+        let lower_bounds: Vec<u32> = (0..blob.read_unsigned())
+            .map(|_| blob.read_unsigned())
+            .collect();
+
+        Some((kind, fixed_len(rank, &sizes, &lower_bounds)))
+    }
+
     pub fn to_tokens(&self, calling_namespace: &str, position: usize) -> TokenStream {
         let name = format_ident(&self.name);
         let tokens = self.kind.to_tokens(calling_namespace);
 
-        if self.array {
+        if let (true, Some(len), false) = (self.array, self.fixed, self.by_ref) {
+            let len = proc_macro2::Literal::u32_unsuffixed(len);
+
+            if self.input {
+                quote! { #name: &[#tokens; #len], }
+            } else {
+                quote! { #name: &mut [#tokens; #len], }
+            }
+        } else if self.array {
             if self.input {
                 quote! { #name: &[#tokens], }
             } else if self.by_ref {
@@ -38,26 +75,63 @@ impl Param {
                     let tokens = quote::format_ident!("__{}", position);
                     quote! { #name: #tokens, }
                 }
-                _ => quote! { #name: #tokens, },
+                _ => {
+                    let tokens = self.kind.to_value_tokens(calling_namespace);
+                    quote! { #name: #tokens, }
+                }
             }
+        } else if self.kind.object() {
+            // Objects that a method writes may be null, so they're received into an `Option`.
+            quote! { #name: &mut ::std::option::Option<#tokens>, }
         } else {
             quote! { #name: &mut #tokens, }
         }
     }
 
     pub fn to_return_tokens(&self, calling_namespace: &str) -> TokenStream {
-        let tokens = self.kind.to_tokens(calling_namespace);
-
         if self.array {
+            let tokens = self.kind.to_tokens(calling_namespace);
             quote! { ::winrt::Array<#tokens> }
         } else {
-            quote! { #tokens }
+            self.kind.to_value_tokens(calling_namespace)
+        }
+    }
+
+    /// The type of the value that a method writes its return value to, which is only converted
+    /// to the returned type once the method has written it, see `to_return_value_tokens`
+    pub fn to_return_abi_value_tokens(&self, calling_namespace: &str) -> TokenStream {
+        if self.array {
+            self.to_return_tokens(calling_namespace)
+        } else {
+            self.kind.to_tokens(calling_namespace)
+        }
+    }
+
+    /// Whether the value that a method writes is converted before it's returned
+    pub fn return_converted(&self) -> bool {
+        !self.array && self.kind == TypeKind::Bool
+    }
+
+    /// Converts the value `ok` that a method wrote into the returned type
+    pub fn to_return_value_tokens(&self, ok: &TokenStream) -> TokenStream {
+        if self.return_converted() {
+            quote! { bool::from(#ok) }
+        } else {
+            ok.clone()
         }
     }
 
     pub fn to_abi_tokens(&self, calling_namespace: &str) -> TokenStream {
-        let tokens = self.kind.to_abi_tokens(calling_namespace);
+        self.to_abi_shape_tokens(self.kind.to_abi_tokens(calling_namespace))
+    }
+
+    /// The parameter's type in the vtables generated by the `abi_only` option
+    pub fn to_raw_tokens(&self, calling_namespace: &str) -> TokenStream {
+        self.to_abi_shape_tokens(self.kind.to_raw_tokens(calling_namespace))
+    }
 
+    // Arrays are passed as a length and a pointer and outputs as pointers to the ABI type.
+    fn to_abi_shape_tokens(&self, tokens: TokenStream) -> TokenStream {
         if self.array {
             if self.input {
                 quote! { u32, *const #tokens }
@@ -73,13 +147,18 @@ impl Param {
         }
     }
 
-    pub fn to_abi_return_arg_tokens(&self, calling_namespace: &str) -> TokenStream {
+    /// The arguments that receive the return value, where `ok` borrows the value mutably
+    pub fn to_abi_return_arg_tokens(
+        &self,
+        calling_namespace: &str,
+        ok: &TokenStream,
+    ) -> TokenStream {
         let return_type = self.kind.to_tokens(calling_namespace);
 
         if self.array {
-            quote! { ::winrt::Array::<#return_type>::set_abi_len(&mut __ok), winrt::Array::<#return_type>::set_abi(&mut __ok), }
+            quote! { ::winrt::Array::<#return_type>::set_abi_len(#ok), ::winrt::Array::<#return_type>::set_abi(#ok), }
         } else {
-            quote! { <#return_type as ::winrt::RuntimeType>::set_abi(&mut __ok) }
+            quote! { <#return_type as ::winrt::RuntimeType>::set_abi(#ok), }
         }
     }
 
@@ -88,14 +167,14 @@ impl Param {
 
         if self.array {
             if self.input {
-                quote! { #name.len() as u32, ::std::mem::transmute(#name.as_ptr()), }
+                quote! { #name.len() as u32, #name.as_ptr() as *const _, }
             } else if self.by_ref {
                 quote! { #name.set_abi_len(), #name.set_abi(), }
             } else {
-                quote! { #name.len() as u32, ::std::mem::transmute_copy(&#name), }
+                quote! { #name.len() as u32, ::winrt::fill_array_abi(#name) as *mut _, }
             }
         } else if self.input {
-            if self.kind.blittable() {
+            if self.kind.blittable() && !self.kind.converted() {
                 quote! { #name, }
             } else {
                 match self.kind {
@@ -107,13 +186,79 @@ impl Param {
                     | TypeKind::Struct(_)
                     | TypeKind::Delegate(_)
                     | TypeKind::Generic(_) => quote! { #name.into().abi(), },
-                    _ => quote! { ::winrt::RuntimeType::abi(#name), },
+                    TypeKind::Bool => quote! { ::winrt::Boolean::from(#name), },
+                    _ => quote! { ::winrt::RuntimeType::abi(&#name), },
                 }
             }
-        } else if self.kind.blittable() {
+        } else if self.kind.blittable() && !self.kind.converted() {
             quote! { #name, }
+        } else if self.kind.object() {
+            quote! { ::winrt::OptionOut::new(#name).abi(), }
         } else {
             quote! { ::winrt::RuntimeType::set_abi(#name), }
         }
     }
+
+    // Whether the argument is converted into a `Param`, or an `OptionOut` for an object out
+    // parameter, whose ABI may refer to the converted argument.
+    fn converted_to_param(&self) -> bool {
+        if !self.input {
+            return !self.array && self.kind.object();
+        }
+
+        !self.array
+            && matches!(
+                self.kind,
+                TypeKind::String
+                    | TypeKind::Object
+                    | TypeKind::Guid
+                    | TypeKind::Class(_)
+                    | TypeKind::Interface(_)
+                    | TypeKind::Struct(_)
+                    | TypeKind::Delegate(_)
+                    | TypeKind::Generic(_)
+            )
+    }
+
+    /// Converts the argument into a `Param` ahead of a call whose arguments are built by a
+    /// closure, so that the `Param` outlives the closure, for `to_bound_abi_arg_tokens`
+    pub fn to_binding_tokens(&self, calling_namespace: &str) -> TokenStream {
+        if !self.converted_to_param() {
+            return TokenStream::new();
+        }
+
+        let name = format_ident(&self.name);
+        let tokens = self.kind.to_tokens(calling_namespace);
+
+        if !self.input {
+            quote! { let mut #name = ::winrt::OptionOut::new(#name); }
+        } else if let TypeKind::String = self.kind {
+            quote! { let mut #name: ::winrt::StringParam = #name.into(); }
+        } else {
+            quote! { let mut #name: ::winrt::Param<#tokens> = #name.into(); }
+        }
+    }
+
+    /// The ABI arguments once the argument has been bound by `to_binding_tokens`
+    pub fn to_bound_abi_arg_tokens(&self) -> TokenStream {
+        if self.converted_to_param() {
+            let name = format_ident(&self.name);
+            quote! { #name.abi(), }
+        } else {
+            self.to_abi_arg_tokens()
+        }
+    }
+}
+
+// The length of an array with the given dimensions, which must be a single dimension of known
+// size starting at zero to be projected as a Rust array.
+pub(crate) fn fixed_len(rank: u32, sizes: &[u32], lower_bounds: &[u32]) -> Result<u32, String> {
+    match (rank, sizes, lower_bounds) {
+        (1, [len], []) | (1, [len], [0]) => Ok(*len),
+        (1, [_], _) => {
+            Err("arrays with a lower bound other than zero aren't supported".to_string())
+        }
+        (1, _, _) => Err("arrays without a fixed length aren't supported".to_string()),
+        _ => Err(format!("arrays of rank {} aren't supported", rank)),
+    }
 }
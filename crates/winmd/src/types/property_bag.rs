@@ -0,0 +1,137 @@
+use crate::tables::TypeDef;
+use crate::types::*;
+use crate::TypeReader;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+// The Windows.Foundation structs that PropertyValue can box, whose PropertyValue methods are
+// named after them. Other values are boxed by `winrt::Boxable`.
+const STRUCTS: &[&str] = &["DateTime", "TimeSpan", "Point", "Size", "Rect"];
+
+// The property bag is generated alongside PropertyValue and depends on IMap<String, Object>,
+// which Windows.Foundation doesn't otherwise use.
+pub fn property_bag_dependencies(reader: &TypeReader, name: &TypeName) -> Vec<TypeDef> {
+    if &*name.namespace != "Windows.Foundation" || &*name.name != "PropertyValue" {
+        return Vec::new();
+    }
+
+    vec![
+        reader.resolve_type_def(("Windows.Foundation.Collections", "IMap`2")),
+        reader.resolve_type_def(("Windows.Foundation", "IPropertyValue")),
+    ]
+}
+
+// Wraps any object implementing IMap<String, Object>, such as a PropertySet, ValueSet, or XAML
+// ResourceDictionary, with typed get and insert methods that box and unbox values with
+// `winrt::Boxable` and `winrt::Unboxable`, which are implemented here for the Windows.Foundation
+// structs that PropertyValue can box. The ToValueSet and FromValueSet traits are implemented by
+// the derive macros of the same name.
+pub fn property_bag_tokens(name: &TypeName) -> TokenStream {
+    if &*name.namespace != "Windows.Foundation" || &*name.name != "PropertyValue" {
+        return TokenStream::new();
+    }
+
+    let values = STRUCTS.iter().map(|name| {
+        let ty = format_ident!("{}", name);
+        let create = format_ident!("create_{}", name.to_lowercase());
+        let get = format_ident!("get_{}", name.to_lowercase());
+
+        quote! {
+            impl ::winrt::Boxable for #ty {
+                fn box_value(self) -> ::winrt::Result<::winrt::Object> {
+                    PropertyValue::#create(self)
+                }
+            }
+            impl ::winrt::Boxable for &#ty {
+                fn box_value(self) -> ::winrt::Result<::winrt::Object> {
+                    PropertyValue::#create(self)
+                }
+            }
+            impl ::winrt::Unboxable for #ty {
+                fn unbox(object: &::winrt::Object) -> ::winrt::Result<Self> {
+                    let value: IPropertyValue = ::winrt::TryInto::try_into(object)?;
+                    value.#get()
+                }
+            }
+        }
+    });
+
+    quote! {
+        #(#values)*
+        /// A type that can be stored in a [`PropertyBag`], usually with `#[derive(winrt::ToValueSet)]`
+        pub trait ToValueSet {
+            /// Stores the value's fields in the bag
+            fn to_value_set(&self, bag: &PropertyBag) -> ::winrt::Result<()>;
+        }
+        /// A type that can be read from a [`PropertyBag`], usually with `#[derive(winrt::FromValueSet)]`
+        pub trait FromValueSet: Sized {
+            /// Reads the value's fields from the bag
+            fn from_value_set(bag: &PropertyBag) -> ::winrt::Result<Self>;
+        }
+        /// Typed access to the values of an object implementing `IMap<String, Object>`
+        ///
+        /// Values are boxed with `PropertyValue` when inserted and unboxed when retrieved, so
+        /// `bag.get::<i32>("count")` replaces a lookup, a cast to `IPropertyValue`, and a call
+        /// to `get_int32`.
+        #[derive(Clone)]
+        pub struct PropertyBag {
+            map: collections::IMap<::winrt::HString, ::winrt::Object>,
+        }
+        impl PropertyBag {
+            /// Wraps an object, failing if it doesn't implement `IMap<String, Object>`
+            pub fn new<T: ::winrt::ComInterface>(object: &T) -> ::winrt::Result<Self> {
+                Ok(Self {
+                    map: ::winrt::ComInterface::try_query(object)?,
+                })
+            }
+            /// The underlying map
+            pub fn map(&self) -> &collections::IMap<::winrt::HString, ::winrt::Object> {
+                &self.map
+            }
+            /// Returns the value for the key, or `None` if there isn't one
+            ///
+            /// This fails if the value can't be unboxed as `T`.
+            pub fn get<T: ::winrt::Unboxable>(&self, key: &str) -> ::winrt::Result<Option<T>> {
+                let object = match self.map.lookup(key) {
+                    Ok(object) => object,
+                    Err(error) if Self::missing(&error) => return Ok(None),
+                    Err(error) => return Err(error),
+                };
+
+                if ::winrt::ComInterface::is_null(&object) {
+                    return Ok(None);
+                }
+
+                T::unbox(&object).map(Some)
+            }
+            /// Boxes the value and inserts it, returning true if it replaced an existing value
+            pub fn insert<T: ::winrt::Boxable>(&self, key: &str, value: T) -> ::winrt::Result<bool> {
+                self.map.insert(key, value.box_value()?)
+            }
+            /// Returns true if the bag has a value for the key
+            pub fn contains_key(&self, key: &str) -> ::winrt::Result<bool> {
+                self.map.has_key(key)
+            }
+            /// Removes the value for the key, if there is one
+            pub fn remove(&self, key: &str) -> ::winrt::Result<()> {
+                match self.map.remove(key) {
+                    Err(error) if !Self::missing(&error) => Err(error),
+                    _ => Ok(()),
+                }
+            }
+            /// The number of values in the bag
+            pub fn len(&self) -> ::winrt::Result<u32> {
+                self.map.size()
+            }
+            /// Returns true if the bag has no values
+            pub fn is_empty(&self) -> ::winrt::Result<bool> {
+                Ok(self.map.size()? == 0)
+            }
+            // Maps report a missing key by failing the lookup, usually with E_BOUNDS.
+            fn missing(error: &::winrt::Error) -> bool {
+                let code = error.code();
+                code == ::winrt::ErrorCode::OUT_OF_BOUNDS || code == ::winrt::ErrorCode::NOT_FOUND
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use crate::types::*;
+use crate::{format_abi_ident, format_ident};
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+use std::iter::FromIterator;
+
+// Generates only the ABI of a type for the `abi_only` option: vtables of interfaces and
+// delegates along with their IIDs, and plain `repr(C)` structs and enums whose fields are ABI
+// types. Objects and strings are passed as `RawPtr`. Classes and generic definitions have no
+// layout of their own so nothing is generated for them.
+pub fn raw_tokens(t: &Type) -> TokenStream {
+    match t {
+        Type::Interface(t) if t.name.generics.is_empty() => {
+            let default = &t.interfaces[0];
+            raw_vtable_tokens(&t.name, &default.guid, &default.methods, 6)
+        }
+        Type::Delegate(t) if t.name.generics.is_empty() => {
+            raw_vtable_tokens(&t.name, &t.guid, std::slice::from_ref(&t.method), 3)
+        }
+        Type::Struct(t) => raw_struct_tokens(t),
+        Type::Enum(t) => raw_enum_tokens(t),
+        _ => TokenStream::new(),
+    }
+}
+
+// Interfaces start with the six methods of IUnknown and IInspectable and delegates with the
+// three of IUnknown.
+fn raw_vtable_tokens(
+    name: &TypeName,
+    guid: &TypeGuid,
+    methods: &[Method],
+    base: usize,
+) -> TokenStream {
+    let abi_name = format_abi_ident(&name.name);
+    let guid = guid.to_tokens();
+    let base = Literal::usize_unsuffixed(base);
+
+    let methods = methods.iter().map(|method| {
+        let method_name = format_ident(&method.name);
+        let params = TokenStream::from_iter(
+            method
+                .params
+                .iter()
+                .chain(method.return_type.iter())
+                .map(|param| param.to_raw_tokens(&name.namespace)),
+        );
+
+        quote! {
+            pub #method_name: extern "system" fn(::winrt::RawPtr, #params) -> ::winrt::ErrorCode,
+        }
+    });
+
+    quote! {
+        #[repr(C)]
+        pub struct #abi_name {
+            pub base: [usize; #base],
+            #(#methods)*
+        }
+        impl #abi_name {
+            pub const IID: ::winrt::Guid = ::winrt::Guid::from_values(#guid);
+        }
+    }
+}
+
+fn raw_struct_tokens(t: &Struct) -> TokenStream {
+    let name = t.name.to_tokens(&t.name.namespace);
+
+    if (&*t.name.namespace, &*t.name.name) == ("Windows.Foundation", "EventRegistrationToken") {
+        return quote! {
+            pub type #name = ::winrt::EventToken;
+        };
+    }
+
+    let fields = t.fields.iter().map(|(field, kind)| {
+        let field = format_ident(field);
+        let kind = kind.to_raw_tokens(&t.name.namespace);
+        quote! { pub #field: #kind }
+    });
+
+    quote! {
+        #[repr(C)]
+        #[derive(Copy, Clone, Debug)]
+        pub struct #name {
+            #(#fields)*
+        }
+    }
+}
+
+// Enums are integers with a constant for each value, since a Rust enum can't hold values that
+// a newer version of Windows might add.
+fn raw_enum_tokens(t: &Enum) -> TokenStream {
+    let name = t.name.to_tokens(&t.name.namespace);
+
+    let repr = match t.fields.first() {
+        Some((_, EnumConstant::U32(_))) => quote! { u32 },
+        _ => quote! { i32 },
+    };
+
+    let values = t.fields.iter().map(|(field, value)| {
+        let field = format_ident(field);
+        let value = match value {
+            EnumConstant::U32(value) => quote! { #value },
+            EnumConstant::I32(value) => quote! { #value },
+        };
+        quote! { pub const #field: Self = Self(#value); }
+    });
+
+    quote! {
+        #[repr(transparent)]
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+        pub struct #name(pub #repr);
+        #[allow(non_upper_case_globals)]
+        impl #name {
+            #(#values)*
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use crate::tables::*;
 use crate::types::*;
-use crate::TypeReader;
-use proc_macro2::TokenStream;
+use crate::{format_ident, TypeOptions, TypeReader};
+use proc_macro2::{Literal, TokenStream};
 use quote::quote;
 use std::collections::*;
 use std::iter::FromIterator;
@@ -12,6 +12,7 @@ pub struct RequiredInterface {
     pub guid: TypeGuid,
     pub methods: Vec<Method>,
     pub kind: InterfaceKind,
+    pub contract: Option<Contract>,
 }
 
 #[derive(Copy, Clone, PartialEq, Debug)]
@@ -27,18 +28,20 @@ impl RequiredInterface {
         let name = TypeName::from_type_def(reader, def);
         let guid = TypeGuid::from_type_def(reader, def);
 
-        let mut methods = def
+        let mut methods: Vec<Method> = def
             .methods(reader)
             .map(|method| Method::from_method_def(reader, method, &name.generics))
             .collect();
 
         rename_collisions(&mut methods);
+        let contract = Contract::from_attributes(reader, def.attributes(reader));
 
         Self {
             name,
             guid,
             methods,
             kind: InterfaceKind::NonDefault,
+            contract,
         }
     }
 
@@ -50,19 +53,21 @@ impl RequiredInterface {
     ) -> Self {
         let guid = name.guid(reader, generics);
 
-        let mut methods = name
+        let mut methods: Vec<Method> = name
             .def
             .methods(reader)
             .map(|method| Method::from_method_def(reader, method, &name.generics))
             .collect();
 
         rename_collisions(&mut methods);
+        let contract = Contract::from_attributes(reader, name.def.attributes(reader));
 
         Self {
             name,
             guid,
             methods,
             kind,
+            contract,
         }
     }
 
@@ -110,7 +115,93 @@ impl RequiredInterface {
         }
     }
 
-    pub fn to_abi_method_tokens(&self, calling_namespace: &str) -> TokenStream {
+    /// The feature gate for members provided by this interface, if contract features are enabled
+    pub fn to_gate_tokens(&self, options: &TypeOptions) -> TokenStream {
+        match &self.contract {
+            Some(contract) if options.contract_features => contract.to_gate_tokens(),
+            _ => TokenStream::new(),
+        }
+    }
+
+    /// Verifies that the object implements the interface before calling through its vtable when
+    /// the runtime's `verify_interfaces` feature is enabled
+    ///
+    /// This names the interface rather than the type the method is generated for, so a class's
+    /// default interface methods verify the interface and specializations of generic interfaces
+    /// verify the specialization's GUID.
+    pub fn to_verify_tokens(&self, calling_namespace: &str) -> TokenStream {
+        let interface = self.name.to_tokens(calling_namespace);
+        quote! { ::winrt::verify_interface::<#interface>(this)?; }
+    }
+
+    /// Checks that the member is present before calling it if the interface was introduced
+    /// after the targeted Windows build
+    ///
+    /// Properties and events are checked by their names, rather than by their accessors'.
+    pub fn to_check_tokens(&self, method: &Method, options: &TypeOptions) -> TokenStream {
+        let target = match options.target_build {
+            Some(target) => target,
+            None => return TokenStream::new(),
+        };
+
+        match self.contract.as_ref().and_then(|c| c.windows_build()) {
+            Some(build) if build > target => {
+                let type_name = format!("{}.{}", self.name.namespace, self.name.name);
+                let runtime_name = &*method.runtime_name;
+
+                let (member_name, kind) = match method.kind {
+                    MethodKind::Normal => (runtime_name, quote! { Method }),
+                    MethodKind::Get | MethodKind::Set => (&runtime_name[4..], quote! { Property }),
+                    MethodKind::Add => (&runtime_name[4..], quote! { Event }),
+                    MethodKind::Remove => (&runtime_name[7..], quote! { Event }),
+                };
+
+                quote! {
+                    static CHECK: ::winrt::MethodCheck = ::winrt::MethodCheck::new(#type_name, #member_name, ::winrt::MemberKind::#kind);
+                    CHECK.check()?;
+                }
+            }
+            _ => TokenStream::new(),
+        }
+    }
+
+    /// Whether the vtable is generated as a table of method slots, see `TypeOptions::compact_vtables`
+    pub fn is_compact(&self, options: &TypeOptions) -> bool {
+        options
+            .compact_vtables
+            .is_some_and(|methods| self.methods.len() >= methods)
+    }
+
+    /// The function called for the method at `index`, through the interface pointer `this`
+    pub fn to_vcall_tokens(
+        &self,
+        this: &TokenStream,
+        index: usize,
+        calling_namespace: &str,
+        options: &TypeOptions,
+    ) -> TokenStream {
+        let method = &self.methods[index];
+
+        if self.is_compact(options) {
+            let signature = method.to_abi_signature_tokens(&self.name, calling_namespace);
+            let index = Literal::usize_unsuffixed(index);
+            quote! { ::winrt::vtable_method::<#signature>(#this as ::winrt::RawPtr, #index) }
+        } else {
+            let method_name = format_ident(&method.name);
+            quote! { (*(*(#this))).#method_name }
+        }
+    }
+
+    pub fn to_abi_method_tokens(
+        &self,
+        calling_namespace: &str,
+        options: &TypeOptions,
+    ) -> TokenStream {
+        if self.is_compact(options) {
+            let methods = Literal::usize_unsuffixed(self.methods.len());
+            return quote! { __methods: [usize; #methods], };
+        }
+
         TokenStream::from_iter(
             self.methods
                 .iter()
@@ -123,14 +214,22 @@ impl RequiredInterface {
         calling_namespace: &str,
         from: &TokenStream,
         constraints: &TokenStream,
+        options: &TypeOptions,
     ) -> TokenStream {
         match self.kind {
             InterfaceKind::Default => {
                 let into = self.name.to_tokens(calling_namespace);
+                let convert = if options.safe_api {
+                    quote! { unsafe { ::winrt::default_interface(value) } }
+                } else {
+                    quote! { unsafe { ::std::mem::transmute(value) } }
+                };
+                let allow = to_allow_unsafe_tokens(options);
                 quote! {
                     impl<#constraints> ::std::convert::From<#from> for #into {
+                        #allow
                         fn from(value: #from) -> #into {
-                            unsafe { ::std::mem::transmute(value) }
+                            #convert
                         }
                     }
                     impl<#constraints> ::std::convert::From<&#from> for #into {
@@ -140,35 +239,42 @@ impl RequiredInterface {
                     }
                 }
             }
-            InterfaceKind::NonDefault => {
+            InterfaceKind::NonDefault | InterfaceKind::Overrides => {
                 let into = self.name.to_tokens(calling_namespace);
-                if self.name.generics.is_empty() {
+                let allow = if self.name.generics.is_empty() {
+                    TokenStream::new()
+                } else {
+                    to_allow_unsafe_tokens(options)
+                };
+                let query = if self.name.generics.is_empty() {
                     quote! {
-                        impl<#constraints> ::std::convert::From<#from> for #into {
-                            fn from(value: #from) -> #into {
-                                ::std::convert::From::from(&value)
-                            }
-                        }
-                        impl<#constraints> ::std::convert::From<&#from> for #into {
-                            fn from(value: &#from) -> #into {
-                                <#from as ::winrt::ComInterface>::query(value)
-                            }
-                        }
+                        <#from as ::winrt::ComInterface>::try_query(value)
                     }
                 } else {
                     let guid = self.guid.to_tokens();
-
+                    let query = if options.safe_api {
+                        quote! { unsafe { ::winrt::query_with_guid(value, &GUID) } }
+                    } else {
+                        quote! { unsafe { <#from as ::winrt::ComInterface>::try_query_with_guid(value, &GUID) } }
+                    };
                     quote! {
-                        impl<#constraints> ::std::convert::From<#from> for #into {
-                            fn from(value: #from) -> #into {
-                                ::std::convert::From::from(&value)
-                            }
+                        const GUID: ::winrt::Guid = ::winrt::Guid::from_values(#guid);
+                        #query
+                    }
+                };
+
+                quote! {
+                    impl<#constraints> ::std::convert::TryFrom<#from> for #into {
+                        type Error = ::winrt::Error;
+                        fn try_from(value: #from) -> ::winrt::Result<#into> {
+                            ::std::convert::TryFrom::try_from(&value)
                         }
-                        impl<#constraints> ::std::convert::From<&#from> for #into {
-                            fn from(value: &#from) -> #into {
-                                const GUID: ::winrt::Guid = ::winrt::Guid::from_values(#guid);
-                                unsafe { <#from as ::winrt::ComInterface>::query_with_guid(value, &GUID) }
-                            }
+                    }
+                    impl<#constraints> ::std::convert::TryFrom<&#from> for #into {
+                        type Error = ::winrt::Error;
+                        #allow
+                        fn try_from(value: &#from) -> ::winrt::Result<#into> {
+                            #query
                         }
                     }
                 }
@@ -181,40 +287,80 @@ impl RequiredInterface {
 pub fn to_method_tokens(
     calling_namespace: &str,
     interfaces: &Vec<RequiredInterface>,
+    options: &TypeOptions,
 ) -> TokenStream {
-    let mut tokens = Vec::new();
-    let mut names = BTreeSet::new();
+    let mut tokens: Vec<(String, TokenStream)> = Vec::new();
+    let mut names: BTreeMap<String, &Method> = BTreeMap::new();
 
     for interface in interfaces {
-        for method in &interface.methods {
-            // If there are any collisions just drop and caller can QI for the actual interface.
-            if names.contains(&method.name) {
+        let gate = interface.to_gate_tokens(options);
+
+        for (index, method) in interface.methods.iter().enumerate() {
+            if method.skip {
                 continue;
             }
 
-            names.insert(&method.name);
+            // Overloads from different interfaces are named after the parameters that set them
+            // apart. The default interface comes first so its methods keep their names.
+            let name = match names.get(&*method.name) {
+                Some(base) => method.overload_name(base, |name| names.contains_key(name)),
+                None => method.name.to_string(),
+            };
 
-            tokens.push(match interface.kind {
-                InterfaceKind::Default => method.to_default_tokens(calling_namespace),
-                InterfaceKind::NonDefault | InterfaceKind::Overrides => {
-                    method.to_non_default_tokens(calling_namespace, interface)
-                }
-                InterfaceKind::Statics => method.to_static_tokens(calling_namespace, interface),
-            });
+            names.insert(name.clone(), method);
+
+            let method = match interface.kind {
+                InterfaceKind::Default if options.safe_api => method.to_safe_default_tokens(
+                    calling_namespace,
+                    &interface.to_verify_tokens(calling_namespace),
+                    &interface.to_check_tokens(method, options),
+                    index,
+                ),
+                InterfaceKind::Default => method.to_default_tokens(
+                    calling_namespace,
+                    &interface.to_verify_tokens(calling_namespace),
+                    &interface.to_check_tokens(method, options),
+                    &interface.to_vcall_tokens(&quote! { this }, index, calling_namespace, options),
+                ),
+                InterfaceKind::NonDefault | InterfaceKind::Overrides => method
+                    .to_named_non_default_tokens(
+                        &name,
+                        calling_namespace,
+                        interface,
+                        &interface.to_check_tokens(method, options),
+                    ),
+                InterfaceKind::Statics => method.to_named_static_tokens(
+                    &name,
+                    calling_namespace,
+                    interface,
+                    &interface.to_check_tokens(method, options),
+                ),
+            };
+
+            tokens.push((name, quote! { #gate #method }));
         }
     }
 
-    TokenStream::from_iter(tokens)
+    // Names are only assigned in vtable order, so that overloads are named the same either way.
+    if options.sorted_members {
+        tokens.sort_by(|(a, _), (b, _)| a.cmp(b));
+    }
+
+    TokenStream::from_iter(tokens.into_iter().map(|(_, tokens)| tokens))
 }
 
-fn rename_collisions(methods: &mut Vec<Method>) {
-    let mut names = BTreeSet::new();
+// Overloads within an interface that lack an OverloadAttribute would otherwise collide.
+fn rename_collisions(methods: &mut [Method]) {
+    for index in 1..methods.len() {
+        let (previous, rest) = methods.split_at_mut(index);
+        let method = &mut rest[0];
 
-    for method in methods {
-        if names.contains(&method.name) {
-            method.name = format!("{}2", method.name);
-        } else {
-            names.insert(&method.name);
+        if let Some(base) = previous.iter().find(|base| base.name == method.name) {
+            method.name = method
+                .overload_name(base, |name| {
+                    previous.iter().any(|other| &*other.name == name)
+                })
+                .into();
         }
     }
 }
@@ -0,0 +1,66 @@
+use crate::types::split_items;
+use crate::TypeOptions;
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::quote;
+
+use std::iter::FromIterator;
+
+/// Allows `unsafe` code on a generated item that can't avoid it, such as an ABI thunk, when
+/// the bindings are generated with the `safe_api` option
+pub fn to_allow_unsafe_tokens(options: &TypeOptions) -> TokenStream {
+    if options.safe_api {
+        quote! { #[allow(unsafe_code)] }
+    } else {
+        TokenStream::new()
+    }
+}
+
+/// Allows `unsafe` code on each of the `unsafe impl` items in the token stream
+///
+/// Items are split the same way as `to_gated_tokens` does. Only the top level of each item is
+/// searched, so an `unsafe` block inside a function body doesn't count.
+pub fn to_allowed_unsafe_impl_tokens(tokens: TokenStream) -> TokenStream {
+    TokenStream::from_iter(split_items(tokens).into_iter().map(|item| {
+        let allow = if is_unsafe_impl(&item) {
+            quote! { #[allow(unsafe_code)] }
+        } else {
+            TokenStream::new()
+        };
+
+        let item = TokenStream::from_iter(item);
+        quote! { #allow #item }
+    }))
+}
+
+fn is_unsafe_impl(item: &[TokenTree]) -> bool {
+    item.windows(2).any(|pair| match pair {
+        [TokenTree::Ident(first), TokenTree::Ident(second)] => {
+            first == "unsafe" && second == "impl"
+        }
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowed_unsafe_impl_tokens() {
+        let tokens = quote! {
+            pub struct Uri { ptr: ::winrt::ComPtr<Uri> }
+            unsafe impl ::std::marker::Send for Uri {}
+            impl Uri { pub fn close(&self) { unsafe { close(self) } } }
+        };
+
+        let expected = quote! {
+            pub struct Uri { ptr: ::winrt::ComPtr<Uri> }
+            #[allow(unsafe_code)]
+            unsafe impl ::std::marker::Send for Uri {}
+            impl Uri { pub fn close(&self) { unsafe { close(self) } } }
+        };
+
+        assert!(to_allowed_unsafe_impl_tokens(tokens).to_string() == expected.to_string());
+    }
+}
@@ -0,0 +1,11 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// Derives serde's traits through the runtime's re-export so that the importing crate only needs
+// to enable the runtime's `serde` feature rather than depend on serde itself.
+pub fn serde_tokens() -> TokenStream {
+    quote! {
+        #[derive(::winrt::serde::Serialize, ::winrt::serde::Deserialize)]
+        #[serde(crate = "::winrt::serde")]
+    }
+}
@@ -0,0 +1,38 @@
+use crate::types::*;
+use crate::TypeOptions;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// A specialization of a generic interface that a generated type requires, such as the
+/// `IMap<HString, HString>` implemented by `StringMap`
+///
+/// Generic interfaces can't have a constant IID since it depends on their type arguments, so
+/// each specialization gets its own, computed from its signature when the bindings are
+/// generated.
+#[derive(Debug)]
+pub struct Specialization {
+    pub name: TypeName,
+    pub guid: TypeGuid,
+    /// The contract of the type that requires the specialization
+    pub contract: Option<Contract>,
+}
+
+impl Specialization {
+    pub fn to_tokens(&self, calling_namespace: &str, options: &TypeOptions) -> TokenStream {
+        let name = self.name.to_tokens(calling_namespace);
+        let guid = self.guid.to_tokens();
+
+        let tokens = quote! {
+            impl #name {
+                pub const IID: ::winrt::Guid = ::winrt::Guid::from_values(#guid);
+            }
+        };
+
+        match &self.contract {
+            Some(contract) if options.contract_features => {
+                to_gated_tokens(&contract.to_gate_tokens(), tokens)
+            }
+            _ => tokens,
+        }
+    }
+}
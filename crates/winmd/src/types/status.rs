@@ -0,0 +1,232 @@
+use crate::types::*;
+use crate::{format_ident, TypeOptions};
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::iter::FromIterator;
+
+// Provides a typed snapshot of a static class's properties along with a subscription that yields
+// a new snapshot whenever any of them change, such as `PowerManager::status()` and
+// `PowerManager::changes()` in Windows.System.Power. The snapshot, named `{Class}Status`, is
+// built from each static property that has a matching `{Property}Changed` event whose handler is
+// an `EventHandler<T>` or `TypedEventHandler<TSender, TResult>`. Classes without such properties
+// get neither.
+//
+// The subscription, named `{Class}StatusChanges`, can be used as a blocking iterator or polled
+// from async code with its `poll_next` method. With the `futures_core` option it also implements
+// the futures `Stream` trait.
+pub fn status_tokens(
+    name: &TypeName,
+    interfaces: &[RequiredInterface],
+    options: &TypeOptions,
+) -> TokenStream {
+    let calling_namespace = &name.namespace;
+    let mut gates = Vec::new();
+    let mut properties: Vec<(&Method, &Method, &Param)> = Vec::new();
+
+    for interface in interfaces {
+        if interface.kind != InterfaceKind::Statics {
+            continue;
+        }
+
+        for method in &interface.methods {
+            if method.kind != MethodKind::Get || !method.params.is_empty() || method.skip {
+                continue;
+            }
+
+            let event = format!("{}_changed", method.name);
+
+            let add = interface
+                .methods
+                .iter()
+                .find(|m| m.kind == MethodKind::Add && *m.name == event && !m.skip);
+
+            let (add, return_type) = match (add, &method.return_type) {
+                (Some(add), Some(return_type)) if !return_type.array => (add, return_type),
+                _ => continue,
+            };
+
+            // Every event is handled by the same closure, so they must share a handler type.
+            let handler = &add.params[0].kind;
+
+            if !event_handler(handler) {
+                continue;
+            }
+
+            if let Some((_, first, _)) = properties.first() {
+                if first.params[0].kind != *handler {
+                    continue;
+                }
+            }
+
+            gates.push(interface.to_gate_tokens(options));
+            properties.push((method, add, return_type));
+        }
+    }
+
+    if properties.is_empty() {
+        return TokenStream::new();
+    }
+
+    let gates = TokenStream::from_iter(gates);
+    let class_name = name.to_tokens(calling_namespace);
+    let status = quote::format_ident!("{}Status", &*name.name);
+    let changes = quote::format_ident!("{}StatusChanges", &*name.name);
+    let state = quote::format_ident!("__{}StatusState", &*name.name);
+
+    let fields = properties.iter().map(|(method, _, return_type)| {
+        let name = format_ident(&method.name);
+        let tokens = return_type.kind.to_tokens(calling_namespace);
+        quote! { pub #name: #tokens, }
+    });
+
+    let values = properties.iter().map(|(method, _, _)| {
+        let name = format_ident(&method.name);
+        quote! { #name: #class_name::#name()?, }
+    });
+
+    let token_fields = properties.iter().map(|(_, add, _)| {
+        let name = format_ident(&add.name);
+        let tokens = add
+            .return_type
+            .as_ref()
+            .unwrap()
+            .kind
+            .to_tokens(calling_namespace);
+        quote! { #name: #tokens, }
+    });
+
+    let handler = properties[0].1.params[0].kind.to_tokens(calling_namespace);
+
+    let add = properties.iter().map(|(_, add, _)| {
+        let name = format_ident(&add.name);
+        quote! { #name: #class_name::#name(&handler)?, }
+    });
+
+    let remove = properties.iter().map(|(_, add, _)| {
+        let name = format_ident(&add.name);
+        let remove = quote::format_ident!("remove_{}", &*add.name);
+        quote! { let _ = #class_name::#remove(self.#name); }
+    });
+
+    let stream = if options.futures_core {
+        quote! {
+            #gates
+            impl ::winrt::futures_core::Stream for #changes {
+                type Item = #status;
+                fn poll_next(
+                    self: ::std::pin::Pin<&mut Self>,
+                    cx: &mut ::std::task::Context,
+                ) -> ::std::task::Poll<::std::option::Option<Self::Item>> {
+                    #changes::poll_next(self, cx)
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    };
+
+    quote! {
+        /// A snapshot of the class's properties that raise an event when they change
+        #gates
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct #status {
+            #(#fields)*
+        }
+        #gates
+        impl #class_name {
+            /// Returns the current values of the properties
+            pub fn status() -> ::winrt::Result<#status> {
+                Ok(#status {
+                    #(#values)*
+                })
+            }
+            /// Returns a subscription that yields the values of the properties whenever any of them change
+            ///
+            /// Changes that arrive before the previous status has been taken replace it.
+            pub fn changes() -> ::winrt::Result<#changes> {
+                let state = ::std::sync::Arc::new(#state::default());
+                let handler = {
+                    let state = ::std::clone::Clone::clone(&state);
+                    #handler::new(move |_, _| {
+                        let status = #class_name::status()?;
+                        let mut pending = state.pending.lock().unwrap();
+                        pending.0 = Some(status);
+                        if let Some(waker) = pending.1.take() {
+                            waker.wake();
+                        }
+                        state.changed.notify_all();
+                        Ok(())
+                    })
+                };
+                Ok(#changes {
+                    state,
+                    #(#add)*
+                })
+            }
+        }
+        #gates
+        #[derive(Default)]
+        struct #state {
+            pending: ::std::sync::Mutex<(::std::option::Option<#status>, ::std::option::Option<::std::task::Waker>)>,
+            changed: ::std::sync::Condvar,
+        }
+        /// A subscription to changes in the class's properties
+        ///
+        /// The event handlers are removed when this is dropped.
+        #gates
+        pub struct #changes {
+            state: ::std::sync::Arc<#state>,
+            #(#token_fields)*
+        }
+        #gates
+        impl #changes {
+            /// Polls for the next change, matching the signature of the futures `Stream` trait
+            pub fn poll_next(
+                self: ::std::pin::Pin<&mut Self>,
+                cx: &mut ::std::task::Context,
+            ) -> ::std::task::Poll<::std::option::Option<#status>> {
+                let mut pending = self.state.pending.lock().unwrap();
+                match pending.0.take() {
+                    Some(status) => ::std::task::Poll::Ready(Some(status)),
+                    None => {
+                        pending.1 = Some(::std::clone::Clone::clone(cx.waker()));
+                        ::std::task::Poll::Pending
+                    }
+                }
+            }
+        }
+        #gates
+        impl ::std::iter::Iterator for #changes {
+            type Item = #status;
+            /// Blocks until the status changes
+            fn next(&mut self) -> ::std::option::Option<Self::Item> {
+                let mut pending = self.state.pending.lock().unwrap();
+                loop {
+                    if let Some(status) = pending.0.take() {
+                        return Some(status);
+                    }
+                    pending = self.state.changed.wait(pending).unwrap();
+                }
+            }
+        }
+        #gates
+        impl ::std::ops::Drop for #changes {
+            fn drop(&mut self) {
+                #(#remove)*
+            }
+        }
+        #stream
+    }
+}
+
+// The closure passed to the handler's `new` function takes a sender and arguments, which only the
+// generic Windows.Foundation event handlers are known to have.
+fn event_handler(kind: &TypeKind) -> bool {
+    match kind {
+        TypeKind::Delegate(name) => {
+            &*name.namespace == "Windows.Foundation"
+                && (&*name.name == "EventHandler`1" || &*name.name == "TypedEventHandler`2")
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,302 @@
+use crate::tables::TypeDef;
+use crate::types::*;
+use crate::{TypeOptions, TypeReader};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// The std::io adapters copy bytes through a Buffer and read with InputStreamOptions, which the
+// stream interfaces don't otherwise depend on.
+pub fn stream_dependencies(reader: &TypeReader, name: &TypeName) -> Vec<TypeDef> {
+    if &*name.namespace != "Windows.Storage.Streams" {
+        return Vec::new();
+    }
+
+    match &*name.name {
+        "IInputStream" | "IRandomAccessStream" => vec![
+            reader.resolve_type_def(("Windows.Storage.Streams", "Buffer")),
+            reader.resolve_type_def(("Windows.Storage.Streams", "InputStreamOptions")),
+        ],
+        "IOutputStream" => vec![reader.resolve_type_def(("Windows.Storage.Streams", "Buffer"))],
+        _ => Vec::new(),
+    }
+}
+
+// Implements std::io::Read for IInputStream, std::io::Write for IOutputStream, and both along
+// with std::io::Seek for IRandomAccessStream, so that WinRT streams can be used with std::io.
+// Each call blocks on the stream's async method, as `get` does, so these fail with
+// `ErrorCode::WRONG_THREAD` on single-threaded apartment threads. Classes convert to the
+// interfaces to be used this way.
+pub fn stream_tokens(name: &TypeName, options: &TypeOptions) -> TokenStream {
+    if &*name.namespace != "Windows.Storage.Streams" {
+        return TokenStream::new();
+    }
+
+    let (read, write, seek) = match &*name.name {
+        "IInputStream" => (true, false, false),
+        "IOutputStream" => (false, true, false),
+        "IRandomAccessStream" => (true, true, true),
+        _ => return TokenStream::new(),
+    };
+
+    let input = &*name.name == "IInputStream";
+    let output = &*name.name == "IOutputStream";
+    let name = name.to_tokens(&name.namespace);
+    let mut tokens = TokenStream::new();
+
+    if read {
+        tokens.extend(quote! {
+            impl ::std::io::Read for #name {
+                fn read(&mut self, buf: &mut [u8]) -> ::std::io::Result<usize> {
+                    if buf.is_empty() {
+                        return Ok(0);
+                    }
+                    let len = ::std::cmp::min(buf.len(), u32::MAX as usize) as u32;
+                    let buffer: IBuffer = Buffer::new(len)?.into();
+                    let buffer = self.read_async(&buffer, len, InputStreamOptions::Partial)?.get()?;
+                    let bytes = buffer.as_slice()?;
+                    buf[..bytes.len()].copy_from_slice(bytes);
+                    Ok(bytes.len())
+                }
+            }
+        });
+    }
+
+    if write {
+        tokens.extend(quote! {
+            impl ::std::io::Write for #name {
+                fn write(&mut self, buf: &[u8]) -> ::std::io::Result<usize> {
+                    if buf.is_empty() {
+                        return Ok(0);
+                    }
+                    let len = ::std::cmp::min(buf.len(), u32::MAX as usize) as u32;
+                    let mut buffer: IBuffer = Buffer::new(len)?.into();
+                    buffer.set_length(len)?;
+                    buffer.as_mut_slice()?.copy_from_slice(&buf[..len as usize]);
+                    Ok(self.write_async(&buffer)?.get()? as usize)
+                }
+                fn flush(&mut self) -> ::std::io::Result<()> {
+                    self.flush_async()?.get()?;
+                    Ok(())
+                }
+            }
+        });
+    }
+
+    if options.futures_io && input {
+        tokens.extend(async_input_tokens());
+    }
+
+    if options.futures_io && output {
+        tokens.extend(async_output_tokens());
+    }
+
+    if seek {
+        tokens.extend(quote! {
+            impl ::std::io::Seek for #name {
+                fn seek(&mut self, pos: ::std::io::SeekFrom) -> ::std::io::Result<u64> {
+                    let offset = |base: u64, offset: i64| {
+                        if offset >= 0 {
+                            base.checked_add(offset as u64)
+                        } else {
+                            base.checked_sub(offset.unsigned_abs())
+                        }
+                    };
+                    let position = match pos {
+                        ::std::io::SeekFrom::Start(position) => Some(position),
+                        ::std::io::SeekFrom::End(delta) => offset(self.size()?, delta),
+                        ::std::io::SeekFrom::Current(delta) => offset(self.position()?, delta),
+                    };
+                    let position = position.ok_or_else(|| {
+                        ::std::io::Error::new(::std::io::ErrorKind::InvalidInput, "invalid seek to a negative or overflowing position")
+                    })?;
+                    #name::seek(self, position)?;
+                    Ok(position)
+                }
+            }
+        });
+    }
+
+    tokens
+}
+
+// The async adapters start the stream's async method on the first poll and set its completion
+// handler to wake whichever task last polled it. Later polls check the operation's status, as
+// `get` does, and take its results once it has completed.
+fn async_input_tokens() -> TokenStream {
+    let foundation = to_namespace_tokens("Windows.Foundation", "Windows.Storage.Streams");
+    let pending = pending_tokens(
+        &foundation,
+        &quote! { AsyncOperationWithProgressCompletedHandler::<IBuffer, u32> },
+        &quote! { IAsyncOperationWithProgress<IBuffer, u32> },
+    );
+
+    quote! {
+        /// Reads from an input stream asynchronously, implementing `futures_io::AsyncRead`
+        ///
+        /// Each read starts the stream's `read_async` and completes along with it. A read that
+        /// is polled again with a shorter buffer than the first time keeps the bytes that don't
+        /// fit, which the next reads return before reading from the stream again.
+        pub struct AsyncInputStream {
+            stream: IInputStream,
+            reading: ::std::option::Option<(#foundation IAsyncOperationWithProgress<IBuffer, u32>, ::std::sync::Arc<::std::sync::Mutex<::std::option::Option<::std::task::Waker>>>)>,
+            leftover: ::std::collections::VecDeque<u8>,
+        }
+        impl AsyncInputStream {
+            pub fn new(stream: IInputStream) -> Self {
+                Self { stream, reading: None, leftover: ::std::collections::VecDeque::new() }
+            }
+            pub fn into_inner(self) -> IInputStream {
+                self.stream
+            }
+        }
+        impl ::winrt::futures_io::AsyncRead for AsyncInputStream {
+            fn poll_read(
+                self: ::std::pin::Pin<&mut Self>,
+                cx: &mut ::std::task::Context<'_>,
+                buf: &mut [u8],
+            ) -> ::std::task::Poll<::std::io::Result<usize>> {
+                let this = self.get_mut();
+                if buf.is_empty() {
+                    return ::std::task::Poll::Ready(Ok(0));
+                }
+                if !this.leftover.is_empty() {
+                    return ::std::task::Poll::Ready(::std::io::Read::read(&mut this.leftover, buf));
+                }
+                if this.reading.is_none() {
+                    let len = ::std::cmp::min(buf.len(), u32::MAX as usize) as u32;
+                    let buffer: IBuffer = Buffer::new(len)?.into();
+                    let operation = this.stream.read_async(&buffer, len, InputStreamOptions::Partial)?;
+                    this.reading = Some(#pending);
+                }
+                let (operation, waker) = this.reading.as_ref().unwrap();
+                *waker.lock().unwrap() = Some(::std::clone::Clone::clone(cx.waker()));
+                if operation.status()? == #foundation AsyncStatus::Started {
+                    return ::std::task::Poll::Pending;
+                }
+                let (operation, _) = this.reading.take().unwrap();
+                let buffer = operation.get_results()?;
+                let bytes = buffer.as_slice()?;
+                let len = ::std::cmp::min(bytes.len(), buf.len());
+                buf[..len].copy_from_slice(&bytes[..len]);
+                this.leftover.extend(&bytes[len..]);
+                ::std::task::Poll::Ready(Ok(len))
+            }
+        }
+    }
+}
+
+fn async_output_tokens() -> TokenStream {
+    let foundation = to_namespace_tokens("Windows.Foundation", "Windows.Storage.Streams");
+    let write = pending_tokens(
+        &foundation,
+        &quote! { AsyncOperationWithProgressCompletedHandler::<u32, u32> },
+        &quote! { IAsyncOperationWithProgress<u32, u32> },
+    );
+    let flush = pending_tokens(
+        &foundation,
+        &quote! { AsyncOperationCompletedHandler::<bool> },
+        &quote! { IAsyncOperation<bool> },
+    );
+
+    quote! {
+        /// Writes to an output stream asynchronously, implementing `futures_io::AsyncWrite`
+        ///
+        /// Each write starts the stream's `write_async` and completes along with it. A write
+        /// that is polled again has already copied the bytes it was first given. Closing flushes
+        /// the stream and then closes it.
+        pub struct AsyncOutputStream {
+            stream: IOutputStream,
+            writing: ::std::option::Option<(#foundation IAsyncOperationWithProgress<u32, u32>, ::std::sync::Arc<::std::sync::Mutex<::std::option::Option<::std::task::Waker>>>)>,
+            flushing: ::std::option::Option<(#foundation IAsyncOperation<bool>, ::std::sync::Arc<::std::sync::Mutex<::std::option::Option<::std::task::Waker>>>)>,
+        }
+        impl AsyncOutputStream {
+            pub fn new(stream: IOutputStream) -> Self {
+                Self { stream, writing: None, flushing: None }
+            }
+            pub fn into_inner(self) -> IOutputStream {
+                self.stream
+            }
+        }
+        impl ::winrt::futures_io::AsyncWrite for AsyncOutputStream {
+            fn poll_write(
+                self: ::std::pin::Pin<&mut Self>,
+                cx: &mut ::std::task::Context<'_>,
+                buf: &[u8],
+            ) -> ::std::task::Poll<::std::io::Result<usize>> {
+                let this = self.get_mut();
+                if buf.is_empty() {
+                    return ::std::task::Poll::Ready(Ok(0));
+                }
+                if this.writing.is_none() {
+                    let len = ::std::cmp::min(buf.len(), u32::MAX as usize) as u32;
+                    let mut buffer: IBuffer = Buffer::new(len)?.into();
+                    buffer.set_length(len)?;
+                    buffer.as_mut_slice()?.copy_from_slice(&buf[..len as usize]);
+                    let operation = this.stream.write_async(&buffer)?;
+                    this.writing = Some(#write);
+                }
+                let (operation, waker) = this.writing.as_ref().unwrap();
+                *waker.lock().unwrap() = Some(::std::clone::Clone::clone(cx.waker()));
+                if operation.status()? == #foundation AsyncStatus::Started {
+                    return ::std::task::Poll::Pending;
+                }
+                let (operation, _) = this.writing.take().unwrap();
+                ::std::task::Poll::Ready(Ok(operation.get_results()? as usize))
+            }
+            fn poll_flush(
+                self: ::std::pin::Pin<&mut Self>,
+                cx: &mut ::std::task::Context<'_>,
+            ) -> ::std::task::Poll<::std::io::Result<()>> {
+                let this = self.get_mut();
+                if this.flushing.is_none() {
+                    let operation = this.stream.flush_async()?;
+                    this.flushing = Some(#flush);
+                }
+                let (operation, waker) = this.flushing.as_ref().unwrap();
+                *waker.lock().unwrap() = Some(::std::clone::Clone::clone(cx.waker()));
+                if operation.status()? == #foundation AsyncStatus::Started {
+                    return ::std::task::Poll::Pending;
+                }
+                let (operation, _) = this.flushing.take().unwrap();
+                operation.get_results()?;
+                ::std::task::Poll::Ready(Ok(()))
+            }
+            fn poll_close(
+                mut self: ::std::pin::Pin<&mut Self>,
+                cx: &mut ::std::task::Context<'_>,
+            ) -> ::std::task::Poll<::std::io::Result<()>> {
+                match ::winrt::futures_io::AsyncWrite::poll_flush(self.as_mut(), cx) {
+                    ::std::task::Poll::Ready(Ok(())) => {
+                        self.stream.close()?;
+                        ::std::task::Poll::Ready(Ok(()))
+                    }
+                    poll => poll,
+                }
+            }
+        }
+    }
+}
+
+// Pairs a newly started `operation` with the waker that its completion handler wakes.
+fn pending_tokens(
+    foundation: &TokenStream,
+    handler: &TokenStream,
+    operation: &TokenStream,
+) -> TokenStream {
+    quote! {
+        {
+            let waker = ::std::sync::Arc::new(::std::sync::Mutex::new(None::<::std::task::Waker>));
+            let handler = {
+                let waker = ::std::sync::Arc::clone(&waker);
+                #foundation #handler::new(move |_: &#foundation #operation, _: #foundation AsyncStatus| {
+                    if let Some(waker) = waker.lock().unwrap().take() {
+                        waker.wake();
+                    }
+                    Ok(())
+                })
+            };
+            operation.set_completed(&handler)?;
+            (operation, waker)
+        }
+    }
+}
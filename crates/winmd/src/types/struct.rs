@@ -1,14 +1,21 @@
-use crate::case::to_snake;
 use crate::tables::*;
+use crate::type_reader::ContextItem;
 use crate::types::*;
-use crate::{format_ident, TypeReader};
+use crate::{format_ident, TypeOptions, TypeReader};
 use proc_macro2::TokenStream;
 use quote::quote;
+use std::rc::Rc;
 
 #[derive(Debug)]
 pub struct Struct {
     pub name: TypeName,
-    pub fields: Vec<(String, TypeKind)>, // TODO: might have to be a full Type to ensure we can write out nested structs for ABI layout
+    pub fields: Vec<(Rc<str>, TypeKind)>, // TODO: might have to be a full Type to ensure we can write out nested structs for ABI layout
+    pub contract: Option<Contract>,
+    pub hashable: bool,
+    pub blittable: bool,
+    pub describes_type: Option<DescribesType>,
+    pub signature: String,
+    pub constants: Vec<(String, ConstantValue)>,
 }
 
 impl Struct {
@@ -16,13 +23,35 @@ impl Struct {
         let name = TypeName::from_type_def(reader, def);
         let mut fields = Vec::new();
 
-        for field in def.fields(reader) {
-            let name = to_snake(field.name(reader), MethodKind::Normal);
+        for field in def
+            .fields(reader)
+            .filter(|field| !field.flags(reader).literal())
+        {
+            let _context = reader.context(ContextItem::Field(field));
+            let name = reader
+                .strings
+                .to_snake(field.name(reader), MethodKind::Normal);
             let kind = TypeKind::from_field(reader, field);
             fields.push((name, kind));
         }
 
-        Self { name, fields }
+        let contract = Contract::from_attributes(reader, def.attributes(reader));
+        let hashable = fields.iter().all(|(_, kind)| hashable(reader, kind));
+        let blittable = fields.iter().all(|(_, kind)| blittable(reader, kind));
+        let describes_type = DescribesType::from_fields(reader, &fields);
+        let signature = name.struct_signature(reader);
+        let constants = ConstantValue::from_type_def(reader, def);
+
+        Self {
+            name,
+            fields,
+            contract,
+            hashable,
+            blittable,
+            describes_type,
+            signature,
+            constants,
+        }
     }
 
     pub fn dependencies(&self) -> Vec<TypeDef> {
@@ -32,9 +61,18 @@ impl Struct {
             .collect()
     }
 
-    pub fn to_tokens(&self) -> TokenStream {
+    pub fn to_tokens(&self, options: &TypeOptions) -> TokenStream {
         let name = self.name.to_tokens(&self.name.namespace);
 
+        // All events share the runtime's token, which the struct name refers to for convenience.
+        if (&*self.name.namespace, &*self.name.name)
+            == ("Windows.Foundation", "EventRegistrationToken")
+        {
+            return quote! {
+                pub type #name = ::winrt::EventToken;
+            };
+        }
+
         let fields = self.fields.iter().map(|field| {
             let name = format_ident(&field.0);
             let kind = field.1.to_tokens(&self.name.namespace);
@@ -43,21 +81,256 @@ impl Struct {
             }
         });
 
+        // Floating point values have no total equality so structs containing them only get
+        // PartialEq. Structs of integers, such as `TimeSpan`, are ordered field by field so that
+        // they can be used as keys of sorted collections.
+        let derive = if self.hashable {
+            quote! { #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)] }
+        } else {
+            quote! { #[derive(Clone, Debug, PartialEq)] }
+        };
+
+        // Structs holding strings or objects can't be deserialized into their ABI layout.
+        let serde = if options.serde && self.blittable {
+            serde_tokens()
+        } else {
+            TokenStream::new()
+        };
+
+        // Objects don't implement Default, so object fields, such as an `IReference<T>` holding
+        // an optional value, default to empty instead.
+        let (derive_default, default) = if self.fields.iter().any(|(_, kind)| kind.object()) {
+            let fields = self.fields.iter().map(|(name, kind)| {
+                let name = format_ident(name);
+
+                // Object fields may be null, as an `IReference<T>` with no value is.
+                if kind.object() {
+                    quote! { #name: unsafe { ::winrt::ComInterface::none() } }
+                } else {
+                    quote! { #name: ::std::default::Default::default() }
+                }
+            });
+
+            let allow = to_allow_unsafe_tokens(options);
+
+            let default = quote! {
+                impl ::std::default::Default for #name {
+                    #allow
+                    fn default() -> Self {
+                        Self { #(#fields),* }
+                    }
+                }
+            };
+
+            (TokenStream::new(), default)
+        } else {
+            (quote! { #[derive(Default)] }, TokenStream::new())
+        };
+
+        let signature = self.name.to_signature_tokens(&self.signature);
+
+        let constants = if self.constants.is_empty() {
+            TokenStream::new()
+        } else {
+            let constants = to_constants_tokens(&self.constants);
+            quote! { impl #name { #constants } }
+        };
+
+        // Structs are passed to methods by value, but the callee only borrows any strings they
+        // hold. The ABI of a struct holding strings is therefore a bitwise copy that's never
+        // dropped, rather than a clone that would add a reference to each string that nothing
+        // releases. Plain data structs are simply copied.
+        let runtime_type = if self.blittable {
+            quote! {
+                unsafe impl ::winrt::RuntimeType for #name {
+                    type Abi = Self;
+                    fn abi(&self) -> Self::Abi {
+                        self.clone()
+                    }
+                    fn set_abi(&mut self) -> *mut Self::Abi {
+                        self as *mut Self::Abi
+                    }
+                    #signature
+                }
+            }
+        } else {
+            quote! {
+                unsafe impl ::winrt::RuntimeType for #name {
+                    type Abi = ::std::mem::ManuallyDrop<Self>;
+                    fn abi(&self) -> Self::Abi {
+                        unsafe { ::std::mem::transmute_copy(self) }
+                    }
+                    fn set_abi(&mut self) -> *mut Self::Abi {
+                        self as *mut Self as *mut Self::Abi
+                    }
+                    #signature
+                }
+            }
+        };
+
+        let runtime_name = self.name.to_runtime_name_tokens();
+        let type_name = match &self.describes_type {
+            Some(describes_type) => describes_type.to_tokens(&self.name),
+            None => TokenStream::new(),
+        };
+        let time = time_tokens(&self.name, options);
+
         quote! {
             #[repr(C)]
-            #[derive(Clone, Default, Debug, PartialEq)]
+            #derive
+            #derive_default
+            #serde
             pub struct #name {
                 #(#fields),*
             }
-            unsafe impl ::winrt::RuntimeType for #name {
-                type Abi = Self;
-                fn abi(&self) -> Self::Abi {
-                    self.clone()
-                }
-                fn set_abi(&mut self) -> *mut Self::Abi {
-                    self as *mut Self::Abi
+            #default
+            #runtime_type
+            #runtime_name
+            #constants
+            #type_name
+            #time
+        }
+    }
+}
+
+/// Returns true if the field type is plain data that can be copied bit for bit
+fn blittable(reader: &TypeReader, kind: &TypeKind) -> bool {
+    match kind {
+        TypeKind::Struct(name) => Struct::from_type_def(reader, name.def).blittable,
+        TypeKind::Guid => true,
+        kind => kind.blittable(),
+    }
+}
+
+/// The fields of a struct that describes a type by its name and whether it's a primitive or comes
+/// from metadata
+///
+/// XAML APIs that take a type, such as `Frame::navigate`, describe it this way with a `TypeName`.
+/// Such structs are recognized by their fields, a string and an enum with `Primitive` and
+/// `Metadata` values, so that they can be built from any type's `RuntimeName`.
+#[derive(Debug)]
+pub struct DescribesType {
+    pub name: Rc<str>,
+    pub kind: Rc<str>,
+    pub kind_type: TypeName,
+}
+
+impl DescribesType {
+    fn from_fields(reader: &TypeReader, fields: &[(Rc<str>, TypeKind)]) -> Option<Self> {
+        let (name, kind, kind_type) = match fields {
+            [(name, TypeKind::String), (kind, TypeKind::Enum(kind_type))]
+            | [(kind, TypeKind::Enum(kind_type)), (name, TypeKind::String)] => {
+                (name, kind, kind_type)
+            }
+            _ => return None,
+        };
+
+        let values = Enum::from_type_def(reader, kind_type.def).fields;
+
+        if !["Primitive", "Metadata"]
+            .iter()
+            .all(|value| values.iter().any(|(name, _)| name == value))
+        {
+            return None;
+        }
+
+        Some(Self {
+            name: name.clone(),
+            kind: kind.clone(),
+            kind_type: kind_type.clone(),
+        })
+    }
+
+    fn to_tokens(&self, struct_name: &TypeName) -> TokenStream {
+        let kind_type = self.kind_type.to_tokens(&struct_name.namespace);
+        let struct_name = struct_name.to_tokens(&struct_name.namespace);
+        let name = format_ident(&self.name);
+        let kind = format_ident(&self.kind);
+
+        quote! {
+            impl #struct_name {
+                /// Describes a type by its runtime name, such as that of a generated class
+                pub fn of<T: ::winrt::RuntimeName>() -> Self {
+                    Self {
+                        #name: T::NAME.into(),
+                        #kind: if T::PRIMITIVE { #kind_type::Primitive } else { #kind_type::Metadata },
+                    }
                 }
             }
         }
     }
 }
+
+/// Returns true if the field type supports Eq and Hash, which excludes floating point values and
+/// anything that isn't plain data
+fn hashable(reader: &TypeReader, kind: &TypeKind) -> bool {
+    match kind {
+        TypeKind::Bool
+        | TypeKind::Char
+        | TypeKind::I8
+        | TypeKind::U8
+        | TypeKind::I16
+        | TypeKind::U16
+        | TypeKind::I32
+        | TypeKind::U32
+        | TypeKind::I64
+        | TypeKind::U64
+        | TypeKind::Guid
+        | TypeKind::EventToken
+        | TypeKind::Enum(_) => true,
+        TypeKind::Struct(name) => Struct::from_type_def(reader, name.def).hashable,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn struct_type((namespace, type_name): (&str, &str)) -> Struct {
+        let reader = &TypeReader::from_os();
+        let def = reader.resolve_type_def((namespace, type_name));
+
+        match def.into_type(reader) {
+            Type::Struct(t) => t,
+            _ => panic!("Type not a struct"),
+        }
+    }
+
+    #[test]
+    fn test_describes_type() {
+        let type_name = struct_type(("Windows.UI.Xaml.Interop", "TypeName"));
+        let describes_type = type_name.describes_type.unwrap();
+        assert!(&*describes_type.name == "name");
+        assert!(&*describes_type.kind == "kind");
+        assert!(&*describes_type.kind_type.name == "TypeKind");
+
+        assert!(struct_type(("Windows.Foundation", "Point"))
+            .describes_type
+            .is_none());
+    }
+
+    #[test]
+    fn test_runtime_type_abi() {
+        let options = &TypeOptions::default();
+
+        let type_name = struct_type(("Windows.UI.Xaml.Interop", "TypeName"));
+        assert!(!type_name.blittable);
+        let tokens = type_name.to_tokens(options).to_string();
+        assert!(tokens.contains("type Abi = :: std :: mem :: ManuallyDrop < Self > ;"));
+        assert!(tokens.contains(":: std :: mem :: transmute_copy (self)"));
+
+        let point = struct_type(("Windows.Foundation", "Point"));
+        assert!(point.blittable);
+        let tokens = point.to_tokens(options).to_string();
+        assert!(tokens.contains("type Abi = Self ;"));
+
+        // Methods take and return structs as their ABI type.
+        assert!(
+            TypeKind::Struct(type_name.name.clone())
+                .to_abi_tokens("Windows.UI.Xaml.Interop")
+                .to_string()
+                == "< TypeName as :: winrt :: RuntimeType > :: Abi ,"
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use crate::types::*;
+use crate::TypeOptions;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// Converts Windows.Foundation.DateTime and TimeSpan to and from their std::time counterparts.
+// Both count 100-nanosecond ticks, with DateTime counting from January 1, 1601 (UTC) rather
+// than the Unix epoch. Neither type's range contains the other's, and TimeSpan may be negative,
+// which Duration can't be, so every conversion is fallible. Times are rounded down to whole
+// ticks, so a time just before the Unix epoch becomes the tick before it rather than the epoch.
+pub fn time_tokens(name: &TypeName, options: &TypeOptions) -> TokenStream {
+    if &*name.namespace != "Windows.Foundation" {
+        return TokenStream::new();
+    }
+
+    let chrono = if options.chrono {
+        chrono_tokens(name)
+    } else {
+        TokenStream::new()
+    };
+
+    let std = match &*name.name {
+        "DateTime" => quote! {
+            impl DateTime {
+                /// The number of ticks between January 1, 1601 and the Unix epoch
+                const UNIX_EPOCH: i64 = 116_444_736_000_000_000;
+
+                /// Returns the current system time
+                pub fn now() -> Self {
+                    <Self as ::std::convert::TryFrom<_>>::try_from(::std::time::SystemTime::now())
+                        .expect("the current time fits in a DateTime")
+                }
+            }
+            impl ::std::convert::TryFrom<::std::time::SystemTime> for DateTime {
+                type Error = ::winrt::Error;
+
+                /// Rounds down to whole ticks, including before the Unix epoch, and fails with
+                /// `E_INVALIDARG` if the time is too far from 1601 to be counted in ticks.
+                fn try_from(time: ::std::time::SystemTime) -> ::winrt::Result<Self> {
+                    let ticks = match time.duration_since(::std::time::UNIX_EPOCH) {
+                        Ok(since) => (since.as_nanos() / 100) as i128,
+                        Err(before) => -(((before.duration().as_nanos() + 99) / 100) as i128),
+                    } + Self::UNIX_EPOCH as i128;
+                    if ticks < i64::MIN as i128 || ticks > i64::MAX as i128 {
+                        return Err(::winrt::ErrorCode::INVALID_ARGUMENT.into());
+                    }
+                    Ok(Self {
+                        universal_time: ticks as i64,
+                    })
+                }
+            }
+            impl ::std::convert::TryFrom<DateTime> for ::std::time::SystemTime {
+                type Error = ::winrt::Error;
+
+                /// Fails with `E_INVALIDARG` if the platform can't represent the time, such as a
+                /// time before 1601 on Windows.
+                fn try_from(time: DateTime) -> ::winrt::Result<Self> {
+                    let ticks = time.universal_time as i128 - DateTime::UNIX_EPOCH as i128;
+                    let since = ::std::time::Duration::new(
+                        (ticks.abs() / 10_000_000) as u64,
+                        (ticks.abs() % 10_000_000) as u32 * 100,
+                    );
+                    let time = if ticks >= 0 {
+                        ::std::time::UNIX_EPOCH.checked_add(since)
+                    } else {
+                        ::std::time::UNIX_EPOCH.checked_sub(since)
+                    };
+                    time.ok_or_else(|| ::winrt::ErrorCode::INVALID_ARGUMENT.into())
+                }
+            }
+        },
+        "TimeSpan" => quote! {
+            impl ::std::convert::TryFrom<::std::time::Duration> for TimeSpan {
+                type Error = ::winrt::Error;
+
+                /// Rounds down to whole ticks and fails with `E_INVALIDARG` if the duration is
+                /// longer than about 29,000 years.
+                fn try_from(duration: ::std::time::Duration) -> ::winrt::Result<Self> {
+                    let ticks = duration.as_nanos() / 100;
+                    if ticks > i64::MAX as u128 {
+                        return Err(::winrt::ErrorCode::INVALID_ARGUMENT.into());
+                    }
+                    Ok(Self {
+                        duration: ticks as i64,
+                    })
+                }
+            }
+            impl ::std::convert::TryFrom<TimeSpan> for ::std::time::Duration {
+                type Error = ::winrt::Error;
+
+                /// Fails with `E_INVALIDARG` if the time span is negative.
+                fn try_from(span: TimeSpan) -> ::winrt::Result<Self> {
+                    if span.duration < 0 {
+                        return Err(::winrt::ErrorCode::INVALID_ARGUMENT.into());
+                    }
+                    Ok(::std::time::Duration::new(
+                        (span.duration / 10_000_000) as u64,
+                        (span.duration % 10_000_000) as u32 * 100,
+                    ))
+                }
+            }
+        },
+        _ => TokenStream::new(),
+    };
+
+    quote! {
+        #std
+        #chrono
+    }
+}
+
+// With the `chrono` option, DateTime also converts to and from `chrono::DateTime<Utc>` and
+// TimeSpan to and from `chrono::Duration`. Every DateTime and TimeSpan fits in chrono's range, but
+// not the other way around, so conversions from chrono are fallible just like those from
+// std::time, and round down to whole ticks in the same way.
+fn chrono_tokens(name: &TypeName) -> TokenStream {
+    match &*name.name {
+        "DateTime" => quote! {
+            impl ::std::convert::TryFrom<::winrt::chrono::DateTime<::winrt::chrono::Utc>> for DateTime {
+                type Error = ::winrt::Error;
+
+                /// Fails with `E_INVALIDARG` if the time is too far from 1601 to be counted in ticks.
+                fn try_from(time: ::winrt::chrono::DateTime<::winrt::chrono::Utc>) -> ::winrt::Result<Self> {
+                    let ticks = time.timestamp() as i128 * 10_000_000
+                        + (time.timestamp_subsec_nanos() / 100) as i128
+                        + Self::UNIX_EPOCH as i128;
+                    if ticks < i64::MIN as i128 || ticks > i64::MAX as i128 {
+                        return Err(::winrt::ErrorCode::INVALID_ARGUMENT.into());
+                    }
+                    Ok(Self {
+                        universal_time: ticks as i64,
+                    })
+                }
+            }
+            impl ::std::convert::From<DateTime> for ::winrt::chrono::DateTime<::winrt::chrono::Utc> {
+                fn from(time: DateTime) -> Self {
+                    let ticks = time.universal_time as i128 - DateTime::UNIX_EPOCH as i128;
+                    ::winrt::chrono::DateTime::from_timestamp(
+                        ticks.div_euclid(10_000_000) as i64,
+                        ticks.rem_euclid(10_000_000) as u32 * 100,
+                    )
+                    .expect("DateTime in chrono's range")
+                }
+            }
+        },
+        "TimeSpan" => quote! {
+            impl ::std::convert::TryFrom<::winrt::chrono::Duration> for TimeSpan {
+                type Error = ::winrt::Error;
+
+                /// Rounds down to whole ticks and fails with `E_INVALIDARG` if the duration is
+                /// longer than about 29,000 years.
+                fn try_from(duration: ::winrt::chrono::Duration) -> ::winrt::Result<Self> {
+                    let ticks = duration.num_seconds() as i128 * 10_000_000
+                        + (duration.subsec_nanos() as i128).div_euclid(100);
+                    if ticks < i64::MIN as i128 || ticks > i64::MAX as i128 {
+                        return Err(::winrt::ErrorCode::INVALID_ARGUMENT.into());
+                    }
+                    Ok(Self {
+                        duration: ticks as i64,
+                    })
+                }
+            }
+            impl ::std::convert::From<TimeSpan> for ::winrt::chrono::Duration {
+                fn from(span: TimeSpan) -> Self {
+                    ::winrt::chrono::Duration::seconds(span.duration.div_euclid(10_000_000))
+                        + ::winrt::chrono::Duration::nanoseconds(
+                            span.duration.rem_euclid(10_000_000) * 100,
+                        )
+                }
+            }
+        },
+        _ => TokenStream::new(),
+    }
+}
@@ -0,0 +1,76 @@
+use crate::types::*;
+use crate::{format_ident, TypeOptions};
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::iter::FromIterator;
+
+// Each interface gets a trait of the same name in the `traits` module of its namespace so that
+// generic code can accept anything that implements the interface, such as `impl IClosable`.
+// The interfaces an interface requires are its supertraits, and the trait is implemented for
+// the interface itself and for every class that implements it. Its methods forward to the
+// interface, querying for it first.
+//
+// Required interfaces that may be compiled out by contract features aren't supertraits as
+// that can't depend on a feature.
+pub fn interface_trait_tokens(interface: &Interface, options: &TypeOptions) -> TokenStream {
+    let namespace = format!("{}.traits", interface.name.namespace);
+    let self_name = interface.name.to_tokens(&namespace);
+
+    let name = if interface.name.generics.is_empty() {
+        format_ident(&interface.name.name)
+    } else {
+        format_ident(&interface.name.name[..interface.name.name.len() - 2])
+    };
+
+    let constraints = interface.name.constraints();
+
+    let supertraits = interface
+        .interfaces
+        .iter()
+        .skip(1)
+        .filter(|required| required.to_gate_tokens(options).is_empty())
+        .map(|required| {
+            let tokens = required.name.to_trait_tokens(&namespace);
+            quote! { + #tokens }
+        });
+
+    let methods = interface.interfaces[0]
+        .methods
+        .iter()
+        .filter(|method| !method.skip)
+        .map(|method| method.to_trait_tokens(&namespace, &self_name));
+
+    let methods = TokenStream::from_iter(methods);
+    let supertraits = TokenStream::from_iter(supertraits);
+
+    quote! {
+        pub trait #name<#constraints>: ::winrt::ComInterface #supertraits {
+            #methods
+        }
+    }
+}
+
+// Implements the traits of each of the interfaces for a class or interface, skipping statics.
+pub fn trait_impl_tokens(
+    name: &TypeName,
+    interfaces: &[RequiredInterface],
+    options: &TypeOptions,
+) -> TokenStream {
+    let self_name = name.to_tokens(&name.namespace);
+    let constraints = name.constraints();
+
+    let impls = interfaces
+        .iter()
+        .filter(|interface| interface.kind != InterfaceKind::Statics)
+        .map(|interface| {
+            let gate = interface.to_gate_tokens(options);
+            let interface = interface.name.to_trait_tokens(&name.namespace);
+
+            quote! {
+                #gate
+                impl<#constraints> #interface for #self_name {}
+            }
+        });
+
+    TokenStream::from_iter(impls)
+}
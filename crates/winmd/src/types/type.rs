@@ -1,7 +1,8 @@
 use crate::flags::*;
 use crate::tables::*;
+use crate::type_reader::ContextItem;
 use crate::types::*;
-use crate::TypeReader;
+use crate::{TypeOptions, TypeReader};
 
 use proc_macro2::TokenStream;
 
@@ -17,7 +18,10 @@ pub enum Type {
 impl Type {
     // TODO: add generics param to test generic specializations?
     pub fn from_type_def(reader: &TypeReader, def: TypeDef) -> Self {
-        match def.category(reader) {
+        let category = def.category(reader);
+        let _context = reader.context(ContextItem::Type(def));
+
+        match category {
             TypeCategory::Interface => Self::Interface(Interface::from_type_def(reader, def)),
             TypeCategory::Class => Self::Class(Class::from_type_def(reader, def)),
             TypeCategory::Enum => Self::Enum(Enum::from_type_def(reader, def)),
@@ -26,13 +30,27 @@ impl Type {
         }
     }
 
-    pub fn to_tokens(&self) -> TokenStream {
-        match self {
-            Type::Class(t) => t.to_tokens(),
-            Type::Interface(t) => t.to_tokens(),
-            Type::Enum(t) => t.to_tokens(),
-            Type::Struct(t) => t.to_tokens(),
-            Type::Delegate(t) => t.to_tokens(),
+    pub fn to_tokens(&self, options: &TypeOptions) -> TokenStream {
+        let tokens = match self {
+            _ if options.abi_only => raw_tokens(self),
+            Type::Class(t) => t.to_tokens(options),
+            Type::Interface(t) => t.to_tokens(options),
+            Type::Enum(t) => t.to_tokens(options),
+            Type::Struct(t) => t.to_tokens(options),
+            Type::Delegate(t) => t.to_tokens(options),
+        };
+
+        let tokens = if options.safe_api {
+            to_allowed_unsafe_impl_tokens(tokens)
+        } else {
+            tokens
+        };
+
+        match self.contract() {
+            Some(contract) if options.contract_features => {
+                to_gated_tokens(&contract.to_gate_tokens(), tokens)
+            }
+            _ => tokens,
         }
     }
 
@@ -46,6 +64,25 @@ impl Type {
         }
     }
 
+    pub fn contract(&self) -> Option<&Contract> {
+        match self {
+            Type::Class(t) => t.contract.as_ref(),
+            Type::Interface(t) => t.contract.as_ref(),
+            Type::Enum(t) => t.contract.as_ref(),
+            Type::Struct(t) => t.contract.as_ref(),
+            Type::Delegate(t) => t.contract.as_ref(),
+        }
+    }
+
+    /// The interfaces that a class or interface requires, including an interface's own
+    pub fn interfaces(&self) -> &[RequiredInterface] {
+        match self {
+            Type::Class(t) => &t.interfaces,
+            Type::Interface(t) => &t.interfaces,
+            _ => &[],
+        }
+    }
+
     // TODO: ideally this would return an iterator to avoid repeated allocations
     pub fn dependencies(&self) -> Vec<TypeDef> {
         match self {
@@ -25,6 +25,7 @@ pub enum TypeKind {
     String,
     Object,
     Guid,
+    EventToken,
     Class(TypeName),
     Interface(TypeName),
     Enum(TypeName),
@@ -34,6 +35,19 @@ pub enum TypeKind {
 }
 
 impl TypeKind {
+    /// Whether the type has no generic parameters left to fill in
+    pub fn is_concrete(&self) -> bool {
+        match self {
+            Self::Generic(_) => false,
+            Self::Class(name)
+            | Self::Interface(name)
+            | Self::Enum(name)
+            | Self::Struct(name)
+            | Self::Delegate(name) => name.is_concrete(),
+            _ => true,
+        }
+    }
+
     pub fn signature(&self, reader: &TypeReader) -> String {
         match self {
             Self::Bool => "b1".to_owned(),
@@ -51,6 +65,7 @@ impl TypeKind {
             Self::String => "string".to_owned(),
             Self::Object => "cinterface(IInspectable)".to_owned(),
             Self::Guid => "g16".to_owned(),
+            Self::EventToken => "struct(Windows.Foundation.EventRegistrationToken;i8)".to_owned(),
             Self::Class(name) => name.class_signature(reader),
             Self::Interface(name) => name.interface_signature(reader),
             Self::Enum(name) => name.enum_signature(reader),
@@ -77,6 +92,7 @@ impl TypeKind {
             Self::String => "String".to_owned(),
             Self::Object => "Object".to_owned(),
             Self::Guid => "Guid".to_owned(),
+            Self::EventToken => "Windows.Foundation.EventRegistrationToken".to_owned(),
             Self::Class(name) => name.runtime_name(),
             Self::Interface(name) => name.runtime_name(),
             Self::Enum(name) => name.runtime_name(),
@@ -87,6 +103,11 @@ impl TypeKind {
     }
 
     fn from_type_name(reader: &TypeReader, name: TypeName) -> Self {
+        // EventRegistrationToken is shared by all events so it is provided by the runtime.
+        if (&*name.namespace, &*name.name) == ("Windows.Foundation", "EventRegistrationToken") {
+            return TypeKind::EventToken;
+        }
+
         match name.def.category(reader) {
             TypeCategory::Interface => TypeKind::Interface(name),
             TypeCategory::Class => TypeKind::Class(name),
@@ -109,8 +130,9 @@ impl TypeKind {
         }
     }
 
+    // Type specs are usually generic interfaces but may also be generic delegates.
     pub fn from_type_spec(reader: &TypeReader, spec: TypeSpec, generics: &Vec<TypeKind>) -> Self {
-        TypeKind::Interface(TypeName::from_type_spec(reader, spec, generics))
+        Self::from_type_name(reader, TypeName::from_type_spec(reader, spec, generics))
     }
 
     pub fn from_type_def_or_ref(
@@ -175,10 +197,36 @@ impl TypeKind {
         }
     }
 
+    /// The concrete specializations of generic interfaces within the type, such as both the
+    /// `IAsyncOperation<IVectorView<HString>>` and the `IVectorView<HString>` within it
+    pub fn specializations(&self) -> Vec<&TypeName> {
+        let name = match self {
+            TypeKind::Class(name)
+            | TypeKind::Interface(name)
+            | TypeKind::Struct(name)
+            | TypeKind::Delegate(name) => name,
+            _ => return Vec::new(),
+        };
+
+        let mut result: Vec<&TypeName> = name
+            .generics
+            .iter()
+            .flat_map(|generic| generic.specializations())
+            .collect();
+
+        if let TypeKind::Interface(name) = self {
+            if !name.generics.is_empty() && name.is_concrete() {
+                result.push(name);
+            }
+        }
+
+        result
+    }
+
     pub fn to_tokens(&self, calling_namespace: &str) -> TokenStream {
         match self {
-            Self::Bool => quote! { bool },
-            Self::Char => quote! { u16 },
+            Self::Bool => quote! { ::winrt::Boolean },
+            Self::Char => quote! { ::winrt::Char16 },
             Self::I8 => quote! { i8 },
             Self::U8 => quote! { u8 },
             Self::I16 => quote! { i16 },
@@ -192,6 +240,7 @@ impl TypeKind {
             Self::String => quote! { ::winrt::HString },
             Self::Object => quote! { ::winrt::Object },
             Self::Guid => quote! { ::winrt::Guid },
+            Self::EventToken => quote! { ::winrt::EventToken },
             Self::Class(name) => name.to_tokens(calling_namespace),
             Self::Interface(name) => name.to_tokens(calling_namespace),
             Self::Enum(name) => name.to_tokens(calling_namespace),
@@ -206,7 +255,7 @@ impl TypeKind {
 
     pub fn to_abi_tokens(&self, calling_namespace: &str) -> TokenStream {
         match self {
-            Self::Bool => quote! { bool, },
+            Self::Bool => quote! { ::winrt::Boolean, },
             Self::Char => quote! { u16, },
             Self::I8 => quote! { i8, },
             Self::U8 => quote! { u8, },
@@ -225,6 +274,7 @@ impl TypeKind {
                 quote! { <::winrt::Object as ::winrt::RuntimeType>::Abi, }
             }
             Self::Guid => quote! { ::winrt::Guid, },
+            Self::EventToken => quote! { ::winrt::EventToken, },
             Self::Class(c) => {
                 let name = c.to_tokens(calling_namespace);
                 quote! { <#name as ::winrt::RuntimeType>::Abi, }
@@ -239,7 +289,7 @@ impl TypeKind {
             }
             Self::Struct(name) => {
                 let name = name.to_tokens(calling_namespace);
-                quote! { #name, }
+                quote! { <#name as ::winrt::RuntimeType>::Abi, }
             }
             Self::Delegate(_) => quote! { ::winrt::RawPtr, },
             Self::Generic(name) => {
@@ -249,6 +299,49 @@ impl TypeKind {
         }
     }
 
+    /// The type's ABI for the `abi_only` option, in which objects and strings are raw pointers
+    /// and structs and enums have only their ABI layout
+    pub fn to_raw_tokens(&self, calling_namespace: &str) -> TokenStream {
+        match self {
+            Self::String
+            | Self::Object
+            | Self::Class(_)
+            | Self::Interface(_)
+            | Self::Delegate(_) => {
+                quote! { ::winrt::RawPtr, }
+            }
+            Self::Enum(name) | Self::Struct(name) => {
+                let name = name.to_tokens(calling_namespace);
+                quote! { #name, }
+            }
+            Self::Generic(_) => unreachable!("generic definitions have no raw ABI"),
+            _ => self.to_abi_tokens(calling_namespace),
+        }
+    }
+
+    /// Whether the type is converted to and from its ABI type when passed to methods, rather
+    /// than passed as it is
+    pub fn converted(&self) -> bool {
+        matches!(self, Self::Bool | Self::Char)
+    }
+
+    /// The type of a single value passed to or returned from a method, which is `bool` for
+    /// booleans that are otherwise held as their ABI byte, see `winrt::Boolean`
+    pub fn to_value_tokens(&self, calling_namespace: &str) -> TokenStream {
+        match self {
+            Self::Bool => quote! { bool },
+            _ => self.to_tokens(calling_namespace),
+        }
+    }
+
+    /// Whether the type is a reference counted object, which may be null
+    pub fn object(&self) -> bool {
+        matches!(
+            self,
+            Self::Object | Self::Class(_) | Self::Interface(_) | Self::Delegate(_)
+        )
+    }
+
     // TODO: should return CallSemantics enum with Blittable/Convertible/Generic
     pub fn blittable(&self) -> bool {
         match self {
@@ -264,6 +357,7 @@ impl TypeKind {
             | Self::U64
             | Self::F32
             | Self::F64
+            | Self::EventToken
             | Self::Enum(_) => true,
 
             Self::String
@@ -8,11 +8,12 @@ use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 
 use std::iter::FromIterator;
+use std::rc::Rc;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord)]
 pub struct TypeName {
-    pub namespace: String,
-    pub name: String,
+    pub namespace: Rc<str>,
+    pub name: Rc<str>,
     pub generics: Vec<TypeKind>,
     pub def: TypeDef,
 }
@@ -117,7 +118,11 @@ impl TypeName {
     pub fn struct_signature(&self, reader: &TypeReader) -> String {
         let mut result = format!("struct({}.{}", self.namespace, self.name);
 
-        for field in self.def.fields(reader) {
+        for field in self
+            .def
+            .fields(reader)
+            .filter(|field| !field.flags(reader).literal())
+        {
             result.push(';');
             result.push_str(&TypeKind::from_field(reader, field).signature(reader));
         }
@@ -153,8 +158,8 @@ impl TypeName {
 
     pub fn from_type_def(reader: &TypeReader, def: TypeDef) -> Self {
         let (namespace, name) = def.name(reader);
-        let namespace = namespace.to_string();
-        let name = name.to_string();
+        let namespace = reader.strings.intern(namespace);
+        let name = reader.strings.intern(name);
         let mut generics = Vec::new();
 
         for generic in def.generics(reader) {
@@ -179,8 +184,8 @@ impl TypeName {
             args.push(TypeKind::from_blob(blob, generics));
         }
         let (namespace, name) = def.name(blob.reader);
-        let namespace = namespace.to_string();
-        let name = name.to_string();
+        let namespace = blob.reader.strings.intern(namespace);
+        let name = blob.reader.strings.intern(name);
         let generics = args;
 
         Self {
@@ -219,6 +224,12 @@ impl TypeName {
         result
     }
 
+    /// Whether every type argument is filled in, as in `IMap<HString, HString>` but not in
+    /// `IMap<K, V>` or `IIterable<IKeyValuePair<K, V>>`
+    pub fn is_concrete(&self) -> bool {
+        self.generics.iter().all(TypeKind::is_concrete)
+    }
+
     pub fn dependencies(&self) -> Vec<TypeDef> {
         std::iter::once(self.def)
             .chain(self.generics.iter().flat_map(|i| i.dependencies()))
@@ -279,6 +290,20 @@ impl TypeName {
         }
     }
 
+    /// The path to the interface's trait in the `traits` module of its namespace
+    pub fn to_trait_tokens(&self, calling_namespace: &str) -> TokenStream {
+        let namespace = to_namespace_tokens(&self.namespace, calling_namespace);
+
+        if self.generics.is_empty() {
+            let name = format_ident(&self.name);
+            quote! { #namespace traits::#name }
+        } else {
+            let name = format_ident(&self.name[..self.name.len() - 2]);
+            let generics = self.generics.iter().map(|g| g.to_tokens(calling_namespace));
+            quote! { #namespace traits::#name<#(#generics),*> }
+        }
+    }
+
     pub fn phantoms(&self) -> TokenStream {
         if self.generics.is_empty() {
             return TokenStream::new();
@@ -301,6 +326,69 @@ impl TypeName {
 
         TokenStream::from_iter(generics)
     }
+
+    /// The signature of a generic definition, which only includes its piid as the rest
+    /// depends on the type arguments
+    pub fn generic_signature(&self, reader: &TypeReader) -> String {
+        format!("{{{:#?}}}", TypeGuid::from_type_def(reader, self.def))
+    }
+
+    /// Implements `RuntimeType::signature`, and `ComInterface::iid` for generic types
+    ///
+    /// Generic types build their signature from those of their type arguments at runtime and
+    /// cache the resulting GUID, which can't be a constant.
+    pub fn to_signature_tokens(&self, signature: &str) -> TokenStream {
+        if self.generics.is_empty() {
+            return quote! {
+                fn signature() -> ::std::string::String {
+                    #signature.to_owned()
+                }
+            };
+        }
+
+        let prefix = format!("pinterface({}", signature);
+        let generics = self.generics.iter().map(|generic| generic.to_tokens(""));
+
+        quote! {
+            fn signature() -> ::std::string::String {
+                let mut signature = ::std::string::String::from(#prefix);
+                #(
+                    signature.push(';');
+                    signature.push_str(&<#generics as ::winrt::RuntimeType>::signature());
+                )*
+                signature.push(')');
+                signature
+            }
+        }
+    }
+
+    /// Implements `RuntimeName` for types that aren't generic, whose names are known up front
+    pub fn to_runtime_name_tokens(&self) -> TokenStream {
+        if !self.generics.is_empty() {
+            return TokenStream::new();
+        }
+
+        let name = self.to_tokens(&self.namespace);
+        let runtime_name = self.runtime_name();
+
+        quote! {
+            impl ::winrt::RuntimeName for #name {
+                const NAME: &'static str = #runtime_name;
+            }
+        }
+    }
+
+    pub fn to_iid_tokens(&self) -> TokenStream {
+        if self.generics.is_empty() {
+            return TokenStream::new();
+        }
+
+        quote! {
+            fn iid() -> ::winrt::Guid {
+                ::winrt::generic_guid::<Self>(<Self as ::winrt::RuntimeType>::signature)
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -312,8 +400,8 @@ mod tests {
     #[test]
     fn runtime_name() {
         let mut type_name = TypeName {
-            name: String::from("MyType"),
-            namespace: String::from("Outer.Inner"),
+            name: "MyType".into(),
+            namespace: "Outer.Inner".into(),
             generics: vec![],
             def: TypeDef(Row {
                 index: 0,
@@ -0,0 +1,78 @@
+use crate::types::*;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+// The WinRT array types that have a SAFEARRAY equivalent along with their VARTYPE and the
+// PropertyValue method suffix used to box and unbox them.
+const ARRAYS: &[(&str, u16, &str)] = &[
+    ("u8", 17, "UInt8"),
+    ("i16", 2, "Int16"),
+    ("u16", 18, "UInt16"),
+    ("i32", 3, "Int32"),
+    ("u32", 19, "UInt32"),
+    ("i64", 20, "Int64"),
+    ("u64", 21, "UInt64"),
+    ("f32", 4, "Single"),
+    ("f64", 5, "Double"),
+    ("::winrt::Boolean", 11, "Boolean"),
+    ("::winrt::HString", 8, "String"),
+];
+
+// Bridges boxed WinRT arrays with the SAFEARRAYs used by automation APIs. PropertyValue gets a
+// `from_variant` function that boxes a VARIANT's SAFEARRAY and IPropertyValue gets a
+// `to_variant` method that copies a boxed array into a new VARIANT.
+pub fn variant_tokens(name: &TypeName) -> TokenStream {
+    if &*name.namespace != "Windows.Foundation" {
+        return TokenStream::new();
+    }
+
+    match &*name.name {
+        "PropertyValue" => {
+            let arms = ARRAYS.iter().map(|(ty, vt, suffix)| {
+                let ty: TokenStream = ty.parse().unwrap();
+                let create = format_ident!("create_{}_array", suffix.to_lowercase());
+                quote! {
+                    Some(#vt) => Self::#create(&variant.to_vec::<#ty>()?),
+                }
+            });
+
+            quote! {
+                impl PropertyValue {
+                    /// Boxes the one-dimensional SAFEARRAY held by the VARIANT
+                    pub fn from_variant(variant: &::winrt::Variant) -> ::winrt::Result<::winrt::Object> {
+                        match variant.array_type() {
+                            #(#arms)*
+                            _ => Err(::winrt::ErrorCode::INVALID_ARGUMENT.into()),
+                        }
+                    }
+                }
+            }
+        }
+        "IPropertyValue" => {
+            let arms = ARRAYS.iter().map(|(_, _, suffix)| {
+                let property_type = format_ident!("{}Array", suffix);
+                let get = format_ident!("get_{}_array", suffix.to_lowercase());
+                quote! {
+                    PropertyType::#property_type => {
+                        let mut array = ::winrt::Array::new();
+                        self.#get(&mut array)?;
+                        ::winrt::Variant::from_slice(&array)
+                    }
+                }
+            });
+
+            quote! {
+                impl IPropertyValue {
+                    /// Copies the boxed array into a VARIANT holding a SAFEARRAY
+                    pub fn to_variant(&self) -> ::winrt::Result<::winrt::Variant> {
+                        match self.r#type()? {
+                            #(#arms)*
+                            _ => Err(::winrt::ErrorCode::INVALID_ARGUMENT.into()),
+                        }
+                    }
+                }
+            }
+        }
+        _ => TokenStream::new(),
+    }
+}
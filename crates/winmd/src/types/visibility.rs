@@ -0,0 +1,65 @@
+use proc_macro2::{Group, TokenStream, TokenTree};
+
+use std::iter::FromIterator;
+
+/// Replaces the `pub` of each generated item, field, and method with another visibility
+///
+/// Visibilities that are already restricted, such as `pub(crate)`, are left alone.
+pub fn to_visibility_tokens(visibility: &TokenStream, tokens: TokenStream) -> TokenStream {
+    let mut result = Vec::new();
+    let mut tokens = tokens.into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(ident) if ident == "pub" => match tokens.peek() {
+                Some(TokenTree::Group(group))
+                    if group.delimiter() == proc_macro2::Delimiter::Parenthesis =>
+                {
+                    result.push(TokenTree::Ident(ident))
+                }
+                _ => result.extend(visibility.clone()),
+            },
+            TokenTree::Group(group) => {
+                let mut replaced = Group::new(
+                    group.delimiter(),
+                    to_visibility_tokens(visibility, group.stream()),
+                );
+                replaced.set_span(group.span());
+                result.push(TokenTree::Group(replaced));
+            }
+            token => result.push(token),
+        }
+    }
+
+    TokenStream::from_iter(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    #[test]
+    fn test_visibility_tokens() {
+        let tokens = quote! {
+            pub mod foundation {
+                pub struct Point { pub x: f32 }
+                impl Point { pub fn x(&self) -> f32 { self.x } }
+                pub(crate) fn helper() {}
+                unsafe impl ::winrt::RuntimeType for Point {}
+            }
+        };
+
+        let expected = quote! {
+            pub(crate) mod foundation {
+                pub(crate) struct Point { pub(crate) x: f32 }
+                impl Point { pub(crate) fn x(&self) -> f32 { self.x } }
+                pub(crate) fn helper() {}
+                unsafe impl ::winrt::RuntimeType for Point {}
+            }
+        };
+
+        let visibility = quote! { pub(crate) };
+        assert!(to_visibility_tokens(&visibility, tokens).to_string() == expected.to_string());
+    }
+}
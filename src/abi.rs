@@ -0,0 +1,110 @@
+use crate::*;
+
+// These are the helpers that code generated with the `safe_api` option calls in place of its
+// own pointer casts and transmutes. Each relies on the generator having derived the vtable slot,
+// argument types, and interface relationships from metadata, so they're unsafe to call from
+// anywhere else.
+
+/// The ABI arguments passed to a method through its vtable slot
+#[doc(hidden)]
+pub trait AbiArgs {
+    /// Calls the method at the address `method` with `this` followed by the arguments
+    ///
+    /// # Safety
+    /// `method` must be the address of a method taking these arguments.
+    unsafe fn call(self, this: RawPtr, method: *const ()) -> ErrorCode;
+}
+
+macro_rules! abi_args {
+    ($($name:ident)*) => {
+        impl<$($name,)*> AbiArgs for ($($name,)*) {
+            #[allow(non_snake_case)]
+            unsafe fn call(self, this: RawPtr, method: *const ()) -> ErrorCode {
+                let ($($name,)*) = self;
+                let method: extern "system" fn(RawPtr, $($name),*) -> ErrorCode =
+                    std::mem::transmute_copy(&method);
+                method(this, $($name),*)
+            }
+        }
+    };
+}
+
+abi_args!();
+abi_args!(A);
+abi_args!(A B);
+abi_args!(A B C);
+abi_args!(A B C D);
+abi_args!(A B C D E);
+abi_args!(A B C D E F);
+abi_args!(A B C D E F G);
+abi_args!(A B C D E F G H);
+abi_args!(A B C D E F G H I);
+abi_args!(A B C D E F G H I J);
+abi_args!(A B C D E F G H I J K);
+abi_args!(A B C D E F G H I J K L);
+abi_args!(A B C D E F G H I J K L M);
+abi_args!(A B C D E F G H I J K L M N);
+abi_args!(A B C D E F G H I J K L M N O);
+abi_args!(A B C D E F G H I J K L M N O P);
+
+/// Calls the method in the vtable slot `index`, after those of IInspectable, with the ABI
+/// arguments `args`
+///
+/// # Safety
+/// The slot `index` of `T`'s vtable must hold a method taking `args` and returning an
+/// `ErrorCode`, and `args` must be valid for it, such as pointers that the method may write to.
+#[doc(hidden)]
+pub unsafe fn abi_call<T: ComInterface, A: AbiArgs>(this: &T, index: usize, args: A) -> Result<()> {
+    check_this(this);
+    let method = vtable_method::<*const ()>(this.as_raw(), index);
+    args.call(this.as_raw(), method).ok()
+}
+
+/// Calls the method in the vtable slot `index` with the ABI arguments returned by `args`,
+/// which is given the zeroed, and so empty, value that the method writes its result to
+///
+/// # Safety
+/// As for `abi_call`, and the method must write a valid `R`, or leave it zeroed, through the
+/// arguments that `args` derives from the result.
+#[doc(hidden)]
+pub unsafe fn abi_call_return<T: ComInterface, R, A: AbiArgs, F: FnOnce(&mut R) -> A>(
+    this: &T,
+    index: usize,
+    args: F,
+) -> Result<R> {
+    check_this(this);
+    let mut result: R = std::mem::zeroed();
+    let method = vtable_method::<*const ()>(this.as_raw(), index);
+    args(&mut result)
+        .call(this.as_raw(), method)
+        .and_then(|| result)
+}
+
+// The vtable of an interface that doesn't refer to an object can't be read, so calling one of its
+// methods panics like the methods generated without the `safe_api` option.
+fn check_this<T: ComInterface>(this: &T) {
+    if this.is_null() {
+        panic!("The `this` pointer was null when calling method");
+    }
+}
+
+/// Converts a class into its default interface, which shares its vtable
+///
+/// # Safety
+/// `Into` must be the default interface of `From`.
+#[doc(hidden)]
+pub unsafe fn default_interface<From: ComInterface, Into: ComInterface>(value: From) -> Into {
+    Into::from_raw(value.into_raw())
+}
+
+/// Queries for a specialization of a generic interface given its GUID
+///
+/// # Safety
+/// `guid` must be the GUID of `Into`, see `ComInterface::query_with_guid`.
+#[doc(hidden)]
+pub unsafe fn query_with_guid<From: ComInterface, Into: ComInterface>(
+    value: &From,
+    guid: &Guid,
+) -> Result<Into> {
+    value.try_query_with_guid(guid)
+}
@@ -1,33 +1,275 @@
-use crate::runtime;
 use crate::*;
+use crate::{apartment, delegate, hstring, runtime, startup};
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use std::thread::ThreadId;
 
-// TODO: this should return `Result<&I>` e.g. a reference pointing to the factory cache.
-// So this function needs to be implemented as some sort of atomic/singleton where RoGetActivationFactory
-// is only called once and the result is then cached. Here's how I do it in C++ - it's critical
-// that this is super fast. Also, load RoGetActivationFactory dynamically and fall back to LoadLibrary
-// and implement DLL garbage collection for those. Version 0.1 can probably just pin everything.
+// TODO: load RoGetActivationFactory dynamically and implement DLL garbage collection for the
+// libraries loaded for unregistered classes. Version 0.1 can probably just pin everything.
 // https://github.com/microsoft/cppwinrt/blob/master/strings/base_activation.h
+//
+// Generated code calls factories through `call_factory` so that this is only called once per
+// class and factory interface.
 pub fn factory<C: RuntimeName, I: ComInterface>() -> Result<I> {
-    let mut ptr = std::ptr::null_mut();
-    unsafe {
-        let mut code =
-            runtime::RoGetActivationFactory(HString::from(C::NAME).abi(), &I::GUID, &mut ptr);
+    startup::measure(StartupKind::Activation, C::NAME, || {
+        let name = startup::measure(StartupKind::String, C::NAME, || HString::from(C::NAME));
+        let mut ptr = std::ptr::null_mut();
 
-        if code == ErrorCode::NOT_INITIALIZED {
-            let mut _cookie = std::ptr::null_mut();
-            runtime::CoIncrementMTAUsage(&mut _cookie);
+        unsafe {
+            let mut code = runtime::RoGetActivationFactory(name.abi(), &I::GUID, &mut ptr);
+
+            if code == ErrorCode::NOT_INITIALIZED {
+                apartment::ensure_mta();
+                code = runtime::RoGetActivationFactory(name.abi(), &I::GUID, &mut ptr);
+            }
+
+            if code == ErrorCode::CLASS_NOT_REGISTERED {
+                if let Some(factory) = library_factory(&name) {
+                    return factory.try_query();
+                }
+            }
+
+            code.and_then(|| std::mem::transmute_copy(&ptr))
+                .map_err(|error| error.with_context(format!("Could not activate {}", C::NAME)))
+        }
+    })
+}
+
+static LIBRARIES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Registers a DLL implementing classes that aren't registered with the system
+///
+/// Unpackaged apps can use third-party components without registering them, either by listing
+/// their classes in an activation manifest or by letting the runtime find the DLL. When a class
+/// isn't registered, its factory is requested from the `DllGetActivationFactory` export of each
+/// library registered here, in order, and then of the DLLs named after the class's namespace,
+/// from the most to the least specific, such as `Contoso.Widgets.dll` and then `Contoso.dll` for
+/// `Contoso.Widgets.Button`. Libraries that aren't given as a full path are only looked for in
+/// the app's directory, System32, and directories added with `AddDllDirectory`, as C++/WinRT
+/// does, so that a DLL planted in the current directory or on the `PATH` isn't loaded instead.
+pub fn register_library(path: &str) {
+    LIBRARIES.lock().unwrap().push(path.to_owned());
+}
+
+fn library_factory(name: &HString) -> Option<IActivationFactory> {
+    let registered = LIBRARIES.lock().unwrap().clone();
+    let class = name.to_string();
+    let mut namespace = class.as_str();
+
+    let named = std::iter::from_fn(|| {
+        namespace = &namespace[..namespace.rfind('.')?];
+        Some(format!("{}.dll", namespace))
+    });
+
+    registered
+        .into_iter()
+        .chain(named)
+        .find_map(|library| unsafe { get_library_factory(&library, name) })
+}
+
+// Libraries that provide a factory stay loaded for the rest of the process since the factory's
+// code, and that of every object it creates, lives there.
+unsafe fn get_library_factory(library: &str, name: &HString) -> Option<IActivationFactory> {
+    let path: Vec<u16> = library.encode_utf16().chain(std::iter::once(0)).collect();
+    let library = runtime::LoadLibraryExW(
+        path.as_ptr(),
+        std::ptr::null_mut(),
+        runtime::LOAD_LIBRARY_SEARCH_DEFAULT_DIRS,
+    );
+
+    if library.is_null() {
+        return None;
+    }
+
+    let get = runtime::GetProcAddress(library, b"DllGetActivationFactory\0".as_ptr());
+
+    if !get.is_null() {
+        let get: extern "system" fn(*mut hstring::Header, *mut RawPtr) -> ErrorCode =
+            std::mem::transmute(get);
+        let mut ptr = std::ptr::null_mut();
+
+        if get(name.abi(), &mut ptr).is_ok() && !ptr.is_null() {
+            return Some(std::mem::transmute_copy(&ptr));
+        }
+    }
+
+    runtime::FreeLibrary(library);
+    None
+}
+
+// Agile factories are cached per class and factory interface. Each is only valid while the
+// apartment it was created in is alive, so the cache remembers that apartment and an apartment
+// guard evicts the factories created in the apartment it's about to uninitialize.
+static FACTORIES: RwLock<Option<HashMap<(&'static str, Guid), CachedFactory>>> = RwLock::new(None);
 
-            code =
-                runtime::RoGetActivationFactory(HString::from(C::NAME).abi(), &I::GUID, &mut ptr);
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum FactoryApartment {
+    MultiThreaded,
+    SingleThreaded(ThreadId),
+}
+
+impl FactoryApartment {
+    fn current() -> Option<Self> {
+        match ApartmentType::current()? {
+            ApartmentType::MultiThreaded => Some(Self::MultiThreaded),
+            ApartmentType::SingleThreaded => {
+                Some(Self::SingleThreaded(std::thread::current().id()))
+            }
         }
+    }
+}
+
+// The cache's own reference to a factory, which is released once the factory is evicted.
+struct CachedFactory {
+    ptr: RawPtr,
+    apartment: FactoryApartment,
+}
+
+// Only agile factories are cached, which may be called and released from any thread.
+unsafe impl Send for CachedFactory {}
+unsafe impl Sync for CachedFactory {}
 
-        code.and_then(|| std::mem::transmute_copy(&ptr))
+impl Drop for CachedFactory {
+    fn drop(&mut self) {
+        unsafe { drop(IUnknown::from_raw(self.ptr)) }
     }
 }
 
+/// Calls the class's activation factory, getting and caching it on first use
+///
+/// Generated constructors and static methods call this so that calling them again doesn't get
+/// the factory, and allocate its class name, every time. Only agile factories are cached, as
+/// others may only be called from the apartment they were created in. A factory whose apartment
+/// went away without an apartment guard, such as when host code calls `CoUninitialize`, fails
+/// the call with a disconnected error, in which case it's evicted so that the next call gets
+/// the factory again.
+#[doc(hidden)]
+pub fn call_factory<C: RuntimeName, I: ComInterface, T>(
+    call: impl FnOnce(&I) -> Result<T>,
+) -> Result<T> {
+    call_cached_factory(C::NAME, factory::<C, I>, call)
+}
+
+fn call_cached_factory<I: ComInterface, T>(
+    class: &'static str,
+    get: impl FnOnce() -> Result<I>,
+    call: impl FnOnce(&I) -> Result<T>,
+) -> Result<T> {
+    let factory = cache_factory(class, get)?;
+    let result = call(&factory);
+
+    if let Err(error) = &result {
+        if disconnected(error.code()) {
+            evict_factory(class, &factory);
+        }
+    }
+
+    result
+}
+
+fn cache_factory<I: ComInterface>(
+    class: &'static str,
+    get: impl FnOnce() -> Result<I>,
+) -> Result<I> {
+    let key = (class, I::iid());
+
+    if let Some(cached) = FACTORIES
+        .read()
+        .unwrap()
+        .as_ref()
+        .and_then(|factories| factories.get(&key))
+    {
+        // Callers hold a reference of their own so that evicting the factory doesn't release it
+        // while they're calling it.
+        return Ok(unsafe { add_ref(cached.ptr) });
+    }
+
+    let factory = get()?;
+
+    if unsafe { factory.query_with_guid::<IUnknown>(&delegate::IAGILE_OBJECT) }.is_null() {
+        return Ok(factory);
+    }
+
+    if let Some(apartment) = FactoryApartment::current() {
+        let mut factories = FACTORIES.write().unwrap();
+
+        factories
+            .get_or_insert_with(HashMap::new)
+            .entry(key)
+            .or_insert_with(|| CachedFactory {
+                ptr: unsafe { add_ref::<IUnknown>(factory.as_raw()) }.into_raw(),
+                apartment,
+            });
+    }
+
+    Ok(factory)
+}
+
+// The errors returned by calls through a proxy whose apartment, or the server behind it, is gone.
+fn disconnected(code: ErrorCode) -> bool {
+    code == ErrorCode::CLOSED
+        || code == ErrorCode::DISCONNECTED
+        || code == ErrorCode::OBJECT_NOT_CONNECTED
+        || code == ErrorCode::SERVER_DIED
+        || code == ErrorCode::SERVER_DIED_DNE
+}
+
+// Evicts the cached factory if it's still the one that failed, rather than one that another
+// thread has cached again since.
+fn evict_factory<I: ComInterface>(class: &'static str, factory: &I) {
+    let evicted = FACTORIES.write().unwrap().as_mut().and_then(|factories| {
+        let key = (class, I::iid());
+
+        match factories.get(&key) {
+            Some(cached) if cached.ptr == factory.as_raw() => factories.remove(&key),
+            _ => None,
+        }
+    });
+
+    // The factory is released once the lock is no longer held, in case releasing it calls back
+    // into the runtime.
+    drop(evicted);
+}
+
+unsafe fn add_ref<I: ComInterface>(ptr: RawPtr) -> I {
+    std::mem::forget(IUnknown::from_raw_borrowed(&ptr).unwrap().clone());
+    I::from_raw(ptr)
+}
+
+/// Evicts the factories created in the calling thread's apartment of the given kind, before an
+/// apartment guard uninitializes it
+///
+/// The multithreaded apartment is only torn down with its last thread, and never once the crate
+/// is keeping it alive, so its factories are only evicted while that may still happen.
+pub(crate) fn evict_factories(kind: ApartmentType) {
+    let apartment = match kind {
+        ApartmentType::SingleThreaded => {
+            FactoryApartment::SingleThreaded(std::thread::current().id())
+        }
+        ApartmentType::MultiThreaded if apartment::mta_pinned() => return,
+        ApartmentType::MultiThreaded => FactoryApartment::MultiThreaded,
+    };
+
+    let mut evicted = Vec::new();
+
+    if let Some(factories) = FACTORIES.write().unwrap().as_mut() {
+        let keys: Vec<_> = factories
+            .iter()
+            .filter(|(_, cached)| cached.apartment == apartment)
+            .map(|(key, _)| *key)
+            .collect();
+
+        evicted.extend(keys.iter().filter_map(|key| factories.remove(key)));
+    }
+
+    // Factories are released once the lock is no longer held, in case releasing one calls back
+    // into the runtime.
+    drop(evicted);
+}
+
 /// An [activation factory](https://docs.microsoft.com/en-us/windows/win32/api/activation/nn-activation-iactivationfactory) for activating WinRT types.
 #[repr(transparent)]
-#[derive(Default, Clone)]
+#[derive(Clone)]
 pub struct IActivationFactory {
     ptr: ComPtr<IActivationFactory>,
 }
@@ -38,7 +280,7 @@ impl IActivationFactory {
             panic!("The `this` pointer was null when calling method");
         }
 
-        let mut object = Object::default();
+        let mut object = unsafe { Object::none() };
         unsafe {
             ((*(*(self.ptr.get()))).activate_instance)(self.ptr.get(), object.set_abi())
                 .and_then(|| object.query())
@@ -64,3 +306,127 @@ pub struct abi_IActivationFactory {
         *mut <Object as RuntimeType>::Abi,
     ) -> ErrorCode,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[repr(transparent)]
+    #[derive(Clone)]
+    struct IFactory {
+        ptr: ComPtr<IFactory>,
+    }
+
+    unsafe impl ComInterface for IFactory {
+        type VTable = [usize; 4];
+        const GUID: Guid = Guid::from_values(
+            0x6E4B_2C8A,
+            0x51D3,
+            0x4C77,
+            [0x9B, 0x0E, 0x2F, 0x61, 0xA4, 0x38, 0xD5, 0x1C],
+        );
+    }
+
+    struct Released(Arc<AtomicBool>);
+
+    impl Drop for Released {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    // Delegates are agile, so they stand in for a factory that gets cached.
+    fn fake(released: &Arc<AtomicBool>) -> IFactory {
+        let released = Released(released.clone());
+        unsafe { Delegate::create(std::ptr::null(), released) }
+    }
+
+    #[test]
+    fn cache_evict_refill() {
+        let gets = AtomicUsize::new(0);
+        let released = Arc::new(AtomicBool::new(false));
+
+        let get = || {
+            gets.fetch_add(1, Ordering::SeqCst);
+            Ok(fake(&released))
+        };
+
+        let apartment = init_apartment(ApartmentType::SingleThreaded).unwrap();
+        let first = cache_factory("Test.Activation.Refill", get).unwrap();
+        let second = cache_factory("Test.Activation.Refill", get).unwrap();
+        assert!(gets.load(Ordering::SeqCst) == 1);
+        assert!(first.as_raw() == second.as_raw());
+
+        drop(first);
+        drop(second);
+        assert!(!released.load(Ordering::SeqCst));
+
+        // The cache releases its reference before the apartment is uninitialized.
+        drop(apartment);
+        assert!(released.load(Ordering::SeqCst));
+
+        let _apartment = init_apartment(ApartmentType::SingleThreaded).unwrap();
+        drop(cache_factory("Test.Activation.Refill", get).unwrap());
+        drop(cache_factory("Test.Activation.Refill", get).unwrap());
+        assert!(gets.load(Ordering::SeqCst) == 2);
+    }
+
+    #[test]
+    fn evict_other_apartment() {
+        let released = Arc::new(AtomicBool::new(false));
+        let _apartment = init_apartment(ApartmentType::SingleThreaded).unwrap();
+        drop(cache_factory("Test.Activation.Other", || Ok(fake(&released))).unwrap());
+
+        // Tearing down another thread's apartment leaves this thread's factories cached.
+        std::thread::spawn(|| {
+            let apartment = init_apartment(ApartmentType::SingleThreaded).unwrap();
+            drop(apartment);
+        })
+        .join()
+        .unwrap();
+
+        assert!(!released.load(Ordering::SeqCst));
+        let cached = cache_factory("Test.Activation.Other", || -> Result<IFactory> {
+            panic!("the factory should be cached")
+        })
+        .unwrap();
+        drop(cached);
+    }
+
+    #[test]
+    fn evict_disconnected() {
+        let gets = AtomicUsize::new(0);
+        let released = Arc::new(AtomicBool::new(false));
+
+        let get = || {
+            gets.fetch_add(1, Ordering::SeqCst);
+            Ok(fake(&released))
+        };
+
+        let call = |code: ErrorCode| {
+            call_cached_factory("Test.Activation.Disconnected", get, |_: &IFactory| {
+                Err::<(), _>(code.into())
+            })
+        };
+
+        let _apartment = init_apartment(ApartmentType::SingleThreaded).unwrap();
+
+        // Errors returned by the factory itself leave it cached.
+        assert!(
+            call(ErrorCode::INVALID_ARGUMENT).unwrap_err().code() == ErrorCode::INVALID_ARGUMENT
+        );
+        assert!(call(ErrorCode::INVALID_ARGUMENT).is_err());
+        assert!(gets.load(Ordering::SeqCst) == 1);
+        assert!(!released.load(Ordering::SeqCst));
+
+        // A factory whose apartment went away is evicted and released, and then got again.
+        assert!(call(ErrorCode::DISCONNECTED).unwrap_err().code() == ErrorCode::DISCONNECTED);
+        assert!(released.load(Ordering::SeqCst));
+
+        call_cached_factory("Test.Activation.Disconnected", get, |_| Ok(())).unwrap();
+        call_cached_factory("Test.Activation.Disconnected", get, |_| Ok(())).unwrap();
+        assert!(gets.load(Ordering::SeqCst) == 2);
+    }
+}
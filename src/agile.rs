@@ -0,0 +1,86 @@
+use crate::*;
+use std::marker::PhantomData;
+
+/// A reference to an object that may be resolved from any thread
+///
+/// Objects that aren't agile, such as most UI objects, may only be called from the apartment
+/// they were created in. An agile reference can be sent to another thread and resolved there
+/// into an interface that marshals calls back to the object's apartment.
+pub struct AgileRef<T: ComInterface> {
+    reference: IAgileReference,
+    phantom: PhantomData<T>,
+}
+
+impl<T: ComInterface> AgileRef<T> {
+    /// Creates an agile reference to the object
+    pub fn new(object: &T) -> Result<AgileRef<T>> {
+        let mut reference = unsafe { IAgileReference::none() };
+
+        unsafe {
+            runtime::RoGetAgileReference(
+                0, // AGILEREFERENCE_DEFAULT
+                &T::iid(),
+                object.as_raw(),
+                reference.ptr.set() as *mut RawPtr,
+            )
+            .and_then(|| AgileRef {
+                reference,
+                phantom: PhantomData,
+            })
+        }
+    }
+
+    /// Resolves the reference into the interface for use on the calling thread
+    pub fn resolve(&self) -> Result<T> {
+        let this = self.reference.ptr.get();
+        let mut object = std::ptr::null_mut();
+
+        unsafe {
+            ((*(*(this))).resolve)(this, &T::iid(), &mut object).and_then(|| T::from_raw(object))
+        }
+    }
+}
+
+impl<T: ComInterface> Clone for AgileRef<T> {
+    fn clone(&self) -> Self {
+        AgileRef {
+            reference: self.reference.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: ComInterface> std::fmt::Debug for AgileRef<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AgileRef({:?})", self.reference.ptr.get())
+    }
+}
+
+// The agile reference itself may be used from any thread and only hands out interfaces that
+// are valid on the thread resolving it.
+unsafe impl<T: ComInterface> Send for AgileRef<T> {}
+unsafe impl<T: ComInterface> Sync for AgileRef<T> {}
+
+/// The [IAgileReference interface](https://docs.microsoft.com/en-us/windows/win32/api/objidl/nn-objidl-iagilereference)
+/// that resolves an agile reference
+#[repr(transparent)]
+#[derive(Clone)]
+struct IAgileReference {
+    ptr: ComPtr<IAgileReference>,
+}
+
+unsafe impl ComInterface for IAgileReference {
+    type VTable = abi_IAgileReference;
+    const GUID: Guid = Guid::from_values(
+        0xC03F_6A43,
+        0x65A4,
+        0x9818,
+        [0x98, 0x7E, 0xE0, 0xB8, 0x10, 0xD2, 0xA6, 0xF2],
+    );
+}
+
+#[repr(C)]
+struct abi_IAgileReference {
+    __base: [usize; 3],
+    resolve: extern "system" fn(*const *const abi_IAgileReference, &Guid, *mut RawPtr) -> ErrorCode,
+}
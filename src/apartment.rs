@@ -0,0 +1,111 @@
+use crate::*;
+use crate::{runtime, startup};
+
+/// The kind of apartment to initialize a thread into
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ApartmentType {
+    /// A single-threaded apartment, as used by UI threads
+    SingleThreaded = 0,
+    /// The process-wide multithreaded apartment
+    MultiThreaded = 1,
+}
+
+impl ApartmentType {
+    fn name(self) -> &'static str {
+        match self {
+            Self::SingleThreaded => "SingleThreaded",
+            Self::MultiThreaded => "MultiThreaded",
+        }
+    }
+
+    /// The kind of apartment the calling thread is in
+    ///
+    /// Threads that haven't initialized an apartment are in the multithreaded apartment once
+    /// it's alive, such as after the first call to a WinRT API. Returns `None` for threads that
+    /// aren't in any apartment yet or are executing in the neutral apartment.
+    pub fn current() -> Option<ApartmentType> {
+        const APTTYPE_STA: i32 = 0;
+        const APTTYPE_MTA: i32 = 1;
+        const APTTYPE_MAINSTA: i32 = 3;
+
+        let mut kind = 0;
+        let mut qualifier = 0;
+
+        if unsafe { runtime::CoGetApartmentType(&mut kind, &mut qualifier) }.is_err() {
+            return None;
+        }
+
+        match kind {
+            APTTYPE_STA | APTTYPE_MAINSTA => Some(Self::SingleThreaded),
+            APTTYPE_MTA => Some(Self::MultiThreaded),
+            _ => None,
+        }
+    }
+}
+
+/// Initializes the calling thread's apartment
+///
+/// The apartment is uninitialized when the returned `Apartment` is dropped, which must be on
+/// the same thread, so objects created in a single-threaded apartment should be dropped first.
+/// Initializing an apartment the thread is already in succeeds and is balanced the same way.
+/// Fails with `RPC_E_CHANGED_MODE` if the thread is already in a different kind of apartment.
+///
+/// Initializing an apartment is optional. Threads without one use the multithreaded
+/// apartment, which the crate keeps alive for the rest of the process once it is first
+/// needed, so objects and factories remain usable after other threads tear theirs down.
+///
+/// ```no_run
+/// let _apartment = winrt::init_apartment(winrt::ApartmentType::SingleThreaded)?;
+/// # Ok::<(), winrt::Error>(())
+/// ```
+pub fn init_apartment(kind: ApartmentType) -> Result<Apartment> {
+    startup::measure(StartupKind::Apartment, kind.name(), || unsafe {
+        runtime::RoInitialize(kind as i32).ok()
+    })?;
+
+    Ok(Apartment {
+        kind,
+        _not_send: std::marker::PhantomData,
+    })
+}
+
+/// A thread's initialization of its apartment, which is uninitialized when dropped
+#[must_use = "the apartment is uninitialized as soon as this is dropped"]
+#[derive(Debug)]
+pub struct Apartment {
+    kind: ApartmentType,
+    // The apartment must be uninitialized by the thread that initialized it.
+    _not_send: std::marker::PhantomData<*const ()>,
+}
+
+impl Drop for Apartment {
+    fn drop(&mut self) {
+        // Factories cached in the apartment are released while it's still alive.
+        activation::evict_factories(self.kind);
+        unsafe { runtime::RoUninitialize() }
+    }
+}
+
+static MTA: std::sync::Once = std::sync::Once::new();
+
+/// Keeps the multithreaded apartment alive for the rest of the process
+///
+/// This is only done once, no matter how many threads call it, so that the apartment isn't
+/// torn down when the last thread that explicitly joined it leaves.
+pub(crate) fn ensure_mta() {
+    MTA.call_once(|| {
+        startup::measure(
+            StartupKind::Apartment,
+            "implicit MultiThreaded",
+            || unsafe {
+                let mut _cookie = std::ptr::null_mut();
+                runtime::CoIncrementMTAUsage(&mut _cookie);
+            },
+        )
+    });
+}
+
+/// Whether the multithreaded apartment is being kept alive for the rest of the process
+pub(crate) fn mta_pinned() -> bool {
+    MTA.is_completed()
+}
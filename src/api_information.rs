@@ -0,0 +1,204 @@
+use crate::*;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+const UNKNOWN: u8 = 0;
+const PRESENT: u8 = 1;
+const MISSING: u8 = 2;
+
+/// A cached check that a member is present on the running version of Windows
+///
+/// Generated code uses this to guard calls to members introduced after the targeted
+/// Windows build. The check calls `ApiInformation` the first time it is used and remembers
+/// the answer.
+#[doc(hidden)]
+pub struct MethodCheck {
+    type_name: &'static str,
+    member_name: &'static str,
+    kind: MemberKind,
+    state: AtomicU8,
+}
+
+/// The kind of member a `MethodCheck` looks for, since properties and events are checked by
+/// their own names rather than by the names of their accessors
+#[doc(hidden)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum MemberKind {
+    Method,
+    Property,
+    Event,
+}
+
+impl MethodCheck {
+    pub const fn new(type_name: &'static str, member_name: &'static str, kind: MemberKind) -> Self {
+        Self {
+            type_name,
+            member_name,
+            kind,
+            state: AtomicU8::new(UNKNOWN),
+        }
+    }
+
+    /// Returns an error with the `ErrorCode::OLD_WIN_VERSION` code if the member is missing
+    pub fn check(&self) -> Result<()> {
+        let mut state = self.state.load(Ordering::Relaxed);
+
+        if state == UNKNOWN {
+            let present = match self.kind {
+                MemberKind::Method => is_method_present(self.type_name, self.member_name)?,
+                MemberKind::Property => is_property_present(self.type_name, self.member_name)?,
+                MemberKind::Event => is_event_present(self.type_name, self.member_name)?,
+            };
+
+            state = if present { PRESENT } else { MISSING };
+            self.state.store(state, Ordering::Relaxed);
+        }
+
+        if state == PRESENT {
+            Ok(())
+        } else {
+            Err(missing(self.type_name, self.member_name))
+        }
+    }
+}
+
+fn missing(type_name: &str, member_name: &str) -> Error {
+    Error::new(
+        ErrorCode::OLD_WIN_VERSION,
+        &format!(
+            "`{}.{}` isn't present on this version of Windows",
+            type_name, member_name
+        ),
+    )
+}
+
+fn call(callback: impl FnOnce(Statics, &mut Boolean) -> ErrorCode) -> Result<bool> {
+    activation::call_factory::<ApiInformation, IApiInformationStatics, _>(|statics| {
+        let mut present = Boolean::default();
+        callback(statics.ptr.get(), &mut present).and_then(|| present.into())
+    })
+}
+
+/// Returns true if the type, such as `"Windows.UI.Xaml.Controls.NavigationView"`, is present on
+/// the running version of Windows
+///
+/// Apps that support older versions of Windows can check before calling APIs added later.
+/// Generated methods that are newer than the `"target"` option already check for themselves.
+pub fn is_type_present(type_name: &str) -> Result<bool> {
+    call(|this, present| unsafe {
+        ((*(*(this))).is_type_present)(this, HString::from(type_name).abi(), present)
+    })
+}
+
+/// Returns true if the type has a method with the given metadata name, such as
+/// `("Windows.Foundation.Uri", "CombineUri")`
+pub fn is_method_present(type_name: &str, method_name: &str) -> Result<bool> {
+    call(|this, present| unsafe {
+        ((*(*(this))).is_method_present)(
+            this,
+            HString::from(type_name).abi(),
+            HString::from(method_name).abi(),
+            present,
+        )
+    })
+}
+
+/// Returns true if the type has a property with the given metadata name
+pub fn is_property_present(type_name: &str, property_name: &str) -> Result<bool> {
+    call(|this, present| unsafe {
+        ((*(*(this))).is_property_present)(
+            this,
+            HString::from(type_name).abi(),
+            HString::from(property_name).abi(),
+            present,
+        )
+    })
+}
+
+/// Returns true if the type has an event with the given metadata name
+pub fn is_event_present(type_name: &str, event_name: &str) -> Result<bool> {
+    call(|this, present| unsafe {
+        ((*(*(this))).is_event_present)(
+            this,
+            HString::from(type_name).abi(),
+            HString::from(event_name).abi(),
+            present,
+        )
+    })
+}
+
+/// Returns true if the API contract, such as `"Windows.Foundation.UniversalApiContract"`, is
+/// present with at least the given major and minor version
+///
+/// Each release of Windows raises the version of the contracts it updates, so this answers
+/// whether a whole set of APIs is available at once.
+pub fn is_api_contract_present(contract_name: &str, major: u16, minor: u16) -> Result<bool> {
+    call(|this, present| unsafe {
+        ((*(*(this))).is_api_contract_present)(
+            this,
+            HString::from(contract_name).abi(),
+            major,
+            minor,
+            present,
+        )
+    })
+}
+
+struct ApiInformation;
+
+impl RuntimeName for ApiInformation {
+    const NAME: &'static str = "Windows.Foundation.Metadata.ApiInformation";
+}
+
+#[repr(transparent)]
+struct IApiInformationStatics {
+    ptr: ComPtr<IApiInformationStatics>,
+}
+
+unsafe impl ComInterface for IApiInformationStatics {
+    type VTable = abi_IApiInformationStatics;
+    const GUID: Guid = Guid::from_values(
+        0x9974_39FE,
+        0xF681,
+        0x4A11,
+        [0xB4, 0x16, 0xC1, 0x3A, 0x47, 0xE8, 0xBA, 0x36],
+    );
+}
+
+type Statics = *const *const abi_IApiInformationStatics;
+
+#[repr(C)]
+struct abi_IApiInformationStatics {
+    __base: [usize; 6],
+    is_type_present:
+        extern "system" fn(Statics, <HString as RuntimeType>::Abi, *mut Boolean) -> ErrorCode,
+    is_method_present: extern "system" fn(
+        Statics,
+        <HString as RuntimeType>::Abi,
+        <HString as RuntimeType>::Abi,
+        *mut Boolean,
+    ) -> ErrorCode,
+    __is_method_present_with_arity: usize,
+    is_event_present: extern "system" fn(
+        Statics,
+        <HString as RuntimeType>::Abi,
+        <HString as RuntimeType>::Abi,
+        *mut Boolean,
+    ) -> ErrorCode,
+    is_property_present: extern "system" fn(
+        Statics,
+        <HString as RuntimeType>::Abi,
+        <HString as RuntimeType>::Abi,
+        *mut Boolean,
+    ) -> ErrorCode,
+    __is_read_only_property_present: usize,
+    __is_writeable_property_present: usize,
+    __is_enum_named_value_present: usize,
+    __is_api_contract_present_by_major: usize,
+    is_api_contract_present: extern "system" fn(
+        Statics,
+        <HString as RuntimeType>::Abi,
+        u16,
+        u16,
+        *mut Boolean,
+    ) -> ErrorCode,
+}
@@ -1,6 +1,9 @@
 use crate::*;
 
 /// A WinRT array
+///
+/// Arrays returned by WinRT methods are allocated by the callee with `CoTaskMemAlloc`. The
+/// `Array` owns that allocation along with its elements and frees both when dropped.
 pub struct Array<T> {
     data: *mut T,
     len: u32,
@@ -15,13 +18,23 @@ impl<T> Default for Array<T> {
     }
 }
 
-impl<T: RuntimeType> Array<T> {
-    pub fn new() -> Self {
-        Self::default()
-    }
-
+impl<T> Array<T> {
+    /// Drops the elements and frees the allocation, leaving the array empty
     pub fn clear(&mut self) {
-        // TODO: drop members, CoTastkMemFree, zero members
+        if self.data.is_null() {
+            return;
+        }
+
+        unsafe {
+            let elements: *mut [T] =
+                std::ptr::slice_from_raw_parts_mut(self.data, self.len as usize);
+            std::ptr::drop_in_place(elements);
+
+            runtime::CoTaskMemFree(self.data as RawPtr);
+        }
+
+        self.data = std::ptr::null_mut();
+        self.len = 0;
     }
 
     pub fn as_slice(&self) -> &[T] {
@@ -31,18 +44,95 @@ impl<T: RuntimeType> Array<T> {
         unsafe { std::slice::from_raw_parts(self.data, self.len as usize) }
     }
 
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        if self.data.is_null() {
+            return &mut [];
+        }
+        unsafe { std::slice::from_raw_parts_mut(self.data, self.len as usize) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.as_slice().is_empty()
+    }
+}
+
+impl<T: RuntimeType> Array<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The ABI pointer to the length that a callee sets along with the data
+    ///
+    /// # Safety
+    /// The length must only be written together with the data, see `set_abi`.
     pub unsafe fn set_abi_len(&mut self) -> *mut u32 {
         &mut self.len
     }
 
+    /// Clears the array and returns the ABI pointer to the data that a callee allocates
+    ///
+    /// # Safety
+    /// The data must be allocated with `CoTaskMemAlloc` and hold as many valid elements as the
+    /// length written through `set_abi_len`.
     pub unsafe fn set_abi(&mut self) -> *mut *mut T::Abi {
         self.clear();
         &mut self.data as *mut _ as *mut _
     }
 }
 
+/// Prepares a caller-allocated buffer for the callee to fill and returns its ABI pointer
+///
+/// The callee writes each element without releasing what was there, so strings and objects
+/// left in the buffer are released first and the elements zeroed, which is their empty state.
+#[doc(hidden)]
+pub fn fill_array_abi<T: RuntimeType>(values: &mut [T]) -> *mut T::Abi {
+    if std::mem::needs_drop::<T>() {
+        for value in values.iter_mut() {
+            unsafe {
+                std::ptr::drop_in_place(value);
+                std::ptr::write_bytes(value, 0, 1);
+            }
+        }
+    }
+
+    values.as_mut_ptr() as *mut T::Abi
+}
+
 impl<T> Drop for Array<T> {
     fn drop(&mut self) {
-        // TODO: CoTaskMemFree
+        self.clear();
+    }
+}
+
+impl<T> std::ops::Deref for Array<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T> std::ops::DerefMut for Array<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Array<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_slice().iter()
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for Array<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self.as_slice(), f)
     }
 }
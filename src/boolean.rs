@@ -0,0 +1,65 @@
+use crate::*;
+
+/// A WinRT Boolean as it's passed to and from methods
+///
+/// A Boolean is a byte at the ABI that is true for any value other than zero, whereas a Rust
+/// `bool` may only be 0 or 1. Methods take and return a single value as a `bool`, converting it
+/// by value at the call, while structs, arrays, output parameters, and type arguments hold the
+/// byte itself so that other values written by a component are never read as a `bool`.
+#[repr(transparent)]
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Boolean(pub u8);
+
+impl From<bool> for Boolean {
+    fn from(value: bool) -> Self {
+        Self(value as u8)
+    }
+}
+
+impl From<Boolean> for bool {
+    fn from(value: Boolean) -> Self {
+        value.0 != 0
+    }
+}
+
+impl PartialEq<bool> for Boolean {
+    fn eq(&self, other: &bool) -> bool {
+        bool::from(*self) == *other
+    }
+}
+
+unsafe impl RuntimeType for Boolean {
+    type Abi = Self;
+
+    fn abi(&self) -> Self::Abi {
+        *self
+    }
+
+    fn set_abi(&mut self) -> *mut Self::Abi {
+        self as *mut Self::Abi
+    }
+
+    fn signature() -> String {
+        "b1".to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boolean_works() {
+        assert_eq!(Boolean::from(true), Boolean(1));
+        assert_eq!(Boolean::from(false), Boolean(0));
+        assert!(bool::from(Boolean(1)));
+        assert!(!bool::from(Boolean(0)));
+
+        // A component may write any non-zero byte for true.
+        let mut value = Boolean::default();
+        unsafe { *value.set_abi() = Boolean(0xFF) };
+        assert!(bool::from(value));
+        assert!(value == true);
+    }
+}
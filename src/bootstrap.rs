@@ -0,0 +1,94 @@
+use crate::runtime;
+use crate::*;
+
+/// The Windows App SDK (WinUI 3) bootstrapper for unpackaged apps
+///
+/// Packaged apps get the Windows App SDK framework package from their manifest. Unpackaged apps
+/// must instead add a dynamic dependency on the framework package, via `MddBootstrapInitialize`,
+/// before any Microsoft.UI.* classes can be activated. The dependency is removed when the
+/// `WindowsAppSdk` is dropped.
+///
+/// ```no_run
+/// // Windows App SDK 1.2 with no version tag (e.g. a stable release).
+/// let _sdk = winrt::WindowsAppSdk::initialize(0x0001_0002, "", 0)?;
+/// # Ok::<(), winrt::Error>(())
+/// ```
+pub struct WindowsAppSdk {
+    library: RawPtr,
+    shutdown: extern "system" fn(),
+}
+
+impl WindowsAppSdk {
+    /// Initialize the Windows App SDK bootstrapper
+    ///
+    /// The `major_minor_version` is encoded as `0xMMMMNNNN` (e.g. `0x00010002` for 1.2), the `version_tag`
+    /// selects a pre-release channel such as "preview1" (or "" for stable releases) and `min_version` is
+    /// the minimum `PACKAGE_VERSION` of the framework package (or 0 for any version).
+    pub fn initialize(
+        major_minor_version: u32,
+        version_tag: &str,
+        min_version: u64,
+    ) -> Result<Self> {
+        let library: Vec<u16> = BOOTSTRAP_DLL
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+        let version_tag: Vec<u16> = version_tag
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+
+        unsafe {
+            // The bootstrapper ships alongside the app, so there's no need to search elsewhere.
+            let library = runtime::LoadLibraryExW(
+                library.as_ptr(),
+                std::ptr::null_mut(),
+                runtime::LOAD_LIBRARY_SEARCH_DEFAULT_DIRS,
+            );
+
+            if library.is_null() {
+                return Err(ErrorCode::from_last_error().into());
+            }
+
+            let initialize = runtime::GetProcAddress(library, b"MddBootstrapInitialize\0".as_ptr());
+            let shutdown = runtime::GetProcAddress(library, b"MddBootstrapShutdown\0".as_ptr());
+
+            if initialize.is_null() || shutdown.is_null() {
+                let error = ErrorCode::from_last_error();
+                runtime::FreeLibrary(library);
+                return Err(error.into());
+            }
+
+            let initialize = std::mem::transmute::<
+                RawPtr,
+                extern "system" fn(u32, *const u16, u64) -> ErrorCode,
+            >(initialize);
+
+            if let Err(error) =
+                initialize(major_minor_version, version_tag.as_ptr(), min_version).ok()
+            {
+                runtime::FreeLibrary(library);
+                return Err(error);
+            }
+
+            Ok(Self {
+                library,
+                shutdown: std::mem::transmute::<RawPtr, extern "system" fn()>(shutdown),
+            })
+        }
+    }
+}
+
+// A module handle may be used and freed from any thread.
+unsafe impl Send for WindowsAppSdk {}
+unsafe impl Sync for WindowsAppSdk {}
+
+// The bootstrapper is only unloaded once the dependency it added has been removed.
+impl Drop for WindowsAppSdk {
+    fn drop(&mut self) {
+        (self.shutdown)();
+        unsafe { runtime::FreeLibrary(self.library) };
+    }
+}
+
+const BOOTSTRAP_DLL: &str = "Microsoft.WindowsAppRuntime.Bootstrap.dll";
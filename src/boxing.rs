@@ -0,0 +1,215 @@
+use crate::*;
+
+/// Boxes a value with `Windows.Foundation.PropertyValue` so that it can be passed where an
+/// object is expected, such as a `ValueSet` or the `Content` of a XAML control
+///
+/// Numbers, `bool`, `Char16`, `Guid`, and strings can be boxed, either by value or by reference.
+/// An `Object` is already boxed and is passed through as is.
+pub fn box_value<T: Boxable>(value: T) -> Result<Object> {
+    value.box_value()
+}
+
+/// Unboxes a value boxed by `box_value` or by a WinRT API
+///
+/// Fails if the object isn't a boxed value or holds a value that can't be converted to `T`.
+/// Numbers are converted between types as long as the value fits, so a boxed `u8` can be
+/// unboxed as an `i32` but not as a string.
+pub fn unbox<T: Unboxable>(object: &Object) -> Result<T> {
+    T::unbox(object)
+}
+
+/// A value that can be boxed with `box_value`
+pub trait Boxable {
+    fn box_value(self) -> Result<Object>;
+}
+
+/// A value that can be unboxed with `unbox`
+pub trait Unboxable: Sized {
+    fn unbox(object: &Object) -> Result<Self>;
+}
+
+fn create(f: impl FnOnce(Statics, *mut RawPtr) -> ErrorCode) -> Result<Object> {
+    activation::call_factory::<PropertyValue, IPropertyValueStatics, _>(|statics| {
+        let mut object: Object = unsafe { ComInterface::none() };
+        f(statics.ptr.get(), object.set_abi() as *mut RawPtr).and_then(|| object)
+    })
+}
+
+macro_rules! boxable {
+    ($ty:ty, $create:ident, $get:ident) => {
+        impl Boxable for $ty {
+            fn box_value(self) -> Result<Object> {
+                create(|this, object| unsafe { ((*(*this)).$create)(this, self.into(), object) })
+            }
+        }
+
+        impl Boxable for &$ty {
+            fn box_value(self) -> Result<Object> {
+                self.clone().box_value()
+            }
+        }
+
+        impl Unboxable for $ty {
+            fn unbox(object: &Object) -> Result<Self> {
+                let value: IPropertyValue = object.try_query()?;
+                let this = value.ptr.get();
+                let mut result = Default::default();
+                unsafe { ((*(*this)).$get)(this, &mut result).and_then(|| result.into()) }
+            }
+        }
+    };
+}
+
+boxable!(u8, create_uint8, get_uint8);
+boxable!(i16, create_int16, get_int16);
+boxable!(u16, create_uint16, get_uint16);
+boxable!(i32, create_int32, get_int32);
+boxable!(u32, create_uint32, get_uint32);
+boxable!(i64, create_int64, get_int64);
+boxable!(u64, create_uint64, get_uint64);
+boxable!(f32, create_single, get_single);
+boxable!(f64, create_double, get_double);
+boxable!(Char16, create_char16, get_char16);
+boxable!(bool, create_boolean, get_boolean);
+boxable!(Guid, create_guid, get_guid);
+
+impl Boxable for &HString {
+    fn box_value(self) -> Result<Object> {
+        create(|this, object| unsafe { ((*(*this)).create_string)(this, self.abi(), object) })
+    }
+}
+
+impl Boxable for HString {
+    fn box_value(self) -> Result<Object> {
+        (&self).box_value()
+    }
+}
+
+impl Boxable for &str {
+    fn box_value(self) -> Result<Object> {
+        HString::from(self).box_value()
+    }
+}
+
+impl Boxable for &String {
+    fn box_value(self) -> Result<Object> {
+        self.as_str().box_value()
+    }
+}
+
+impl Boxable for String {
+    fn box_value(self) -> Result<Object> {
+        self.as_str().box_value()
+    }
+}
+
+impl Boxable for Object {
+    fn box_value(self) -> Result<Object> {
+        Ok(self)
+    }
+}
+
+impl Boxable for &Object {
+    fn box_value(self) -> Result<Object> {
+        Ok(self.clone())
+    }
+}
+
+impl Unboxable for Object {
+    fn unbox(object: &Object) -> Result<Self> {
+        Ok(object.clone())
+    }
+}
+
+impl Unboxable for HString {
+    fn unbox(object: &Object) -> Result<Self> {
+        let value: IPropertyValue = object.try_query()?;
+        let this = value.ptr.get();
+        let mut result = HString::default();
+        unsafe { ((*(*this)).get_string)(this, result.set_abi()).and_then(|| result) }
+    }
+}
+
+impl Unboxable for String {
+    fn unbox(object: &Object) -> Result<Self> {
+        HString::unbox(object).map(|value| value.to_string())
+    }
+}
+
+struct PropertyValue;
+
+impl RuntimeName for PropertyValue {
+    const NAME: &'static str = "Windows.Foundation.PropertyValue";
+}
+
+#[repr(transparent)]
+struct IPropertyValueStatics {
+    ptr: ComPtr<IPropertyValueStatics>,
+}
+
+unsafe impl ComInterface for IPropertyValueStatics {
+    type VTable = abi_IPropertyValueStatics;
+    const GUID: Guid = Guid::from_values(
+        0x629B_DBC8,
+        0xD932,
+        0x4FF4,
+        [0x96, 0xB9, 0x8D, 0x96, 0xC5, 0xC1, 0xE8, 0x58],
+    );
+}
+
+type Statics = *const *const abi_IPropertyValueStatics;
+
+#[repr(C)]
+struct abi_IPropertyValueStatics {
+    __base: [usize; 7],
+    create_uint8: extern "system" fn(Statics, u8, *mut RawPtr) -> ErrorCode,
+    create_int16: extern "system" fn(Statics, i16, *mut RawPtr) -> ErrorCode,
+    create_uint16: extern "system" fn(Statics, u16, *mut RawPtr) -> ErrorCode,
+    create_int32: extern "system" fn(Statics, i32, *mut RawPtr) -> ErrorCode,
+    create_uint32: extern "system" fn(Statics, u32, *mut RawPtr) -> ErrorCode,
+    create_int64: extern "system" fn(Statics, i64, *mut RawPtr) -> ErrorCode,
+    create_uint64: extern "system" fn(Statics, u64, *mut RawPtr) -> ErrorCode,
+    create_single: extern "system" fn(Statics, f32, *mut RawPtr) -> ErrorCode,
+    create_double: extern "system" fn(Statics, f64, *mut RawPtr) -> ErrorCode,
+    create_char16: extern "system" fn(Statics, Char16, *mut RawPtr) -> ErrorCode,
+    create_boolean: extern "system" fn(Statics, Boolean, *mut RawPtr) -> ErrorCode,
+    create_string:
+        extern "system" fn(Statics, <HString as RuntimeType>::Abi, *mut RawPtr) -> ErrorCode,
+    __create_inspectable: usize,
+    create_guid: extern "system" fn(Statics, Guid, *mut RawPtr) -> ErrorCode,
+}
+
+#[repr(transparent)]
+struct IPropertyValue {
+    ptr: ComPtr<IPropertyValue>,
+}
+
+unsafe impl ComInterface for IPropertyValue {
+    type VTable = abi_IPropertyValue;
+    const GUID: Guid = Guid::from_values(
+        0x4BD6_82DD,
+        0x7554,
+        0x40E9,
+        [0x9A, 0x9B, 0x82, 0x65, 0x4E, 0xDE, 0x7E, 0x62],
+    );
+}
+
+type Value = *const *const abi_IPropertyValue;
+
+#[repr(C)]
+struct abi_IPropertyValue {
+    __base: [usize; 8],
+    get_uint8: extern "system" fn(Value, *mut u8) -> ErrorCode,
+    get_int16: extern "system" fn(Value, *mut i16) -> ErrorCode,
+    get_uint16: extern "system" fn(Value, *mut u16) -> ErrorCode,
+    get_int32: extern "system" fn(Value, *mut i32) -> ErrorCode,
+    get_uint32: extern "system" fn(Value, *mut u32) -> ErrorCode,
+    get_int64: extern "system" fn(Value, *mut i64) -> ErrorCode,
+    get_uint64: extern "system" fn(Value, *mut u64) -> ErrorCode,
+    get_single: extern "system" fn(Value, *mut f32) -> ErrorCode,
+    get_double: extern "system" fn(Value, *mut f64) -> ErrorCode,
+    get_char16: extern "system" fn(Value, *mut Char16) -> ErrorCode,
+    get_boolean: extern "system" fn(Value, *mut Boolean) -> ErrorCode,
+    get_string: extern "system" fn(Value, *mut <HString as RuntimeType>::Abi) -> ErrorCode,
+    get_guid: extern "system" fn(Value, *mut Guid) -> ErrorCode,
+}
@@ -0,0 +1,281 @@
+use crate::*;
+
+/// The bytes of a `Windows.Storage.Streams.IBuffer`, up to its length, copied into a vector
+///
+/// Generated code uses this for the `to_vec` method of `IBuffer` and the classes that
+/// implement it. The bytes are read through the buffer's `IBufferByteAccess` interface.
+#[doc(hidden)]
+pub fn buffer_to_vec<T: ComInterface>(buffer: &T) -> Result<Vec<u8>> {
+    let (data, len) = buffer_data(buffer)?;
+    Ok(unsafe { bytes(data, len) }.to_vec())
+}
+
+/// Copies `bytes` to the start of a `Windows.Storage.Streams.IBuffer` and sets its length to
+/// theirs, failing with `ErrorCode::INVALID_ARGUMENT` if they don't fit in its capacity
+#[doc(hidden)]
+pub fn buffer_copy_from<T: ComInterface>(buffer: &T, bytes: &[u8]) -> Result<()> {
+    let buffer: IBuffer = buffer.try_query()?;
+    let this = buffer.ptr.get();
+    let mut capacity = 0;
+    unsafe { ((*(*(this))).capacity)(this, &mut capacity).ok()? };
+
+    if bytes.len() > capacity as usize {
+        return Err(ErrorCode::INVALID_ARGUMENT.into());
+    }
+
+    unsafe { ((*(*(this))).set_length)(this, bytes.len() as u32).ok()? };
+    let (data, _) = buffer_data(&buffer)?;
+    unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), data, bytes.len()) };
+    Ok(())
+}
+
+/// The bytes of a `Windows.Storage.Streams.IBuffer`, up to its length
+///
+/// Generated code uses this for the `as_slice` method of `IBuffer` and the classes that
+/// implement it.
+///
+/// # Safety
+/// The bytes belong to the buffer rather than to the reference to it, so while the slice is in
+/// use they mustn't be written, whether through another reference to the buffer or by the
+/// component that owns them, nor freed by closing the buffer.
+#[doc(hidden)]
+pub unsafe fn buffer_as_slice<T: ComInterface>(buffer: &T) -> Result<&[u8]> {
+    let (data, len) = buffer_data(buffer)?;
+    Ok(bytes(data, len))
+}
+
+/// The bytes of a `Windows.Storage.Streams.IBuffer`, up to its length, for writing
+///
+/// # Safety
+/// As for `buffer_as_slice`, and the bytes mustn't be read other than through the slice
+/// while it's in use either.
+#[doc(hidden)]
+pub unsafe fn buffer_as_mut_slice<T: ComInterface>(buffer: &mut T) -> Result<&mut [u8]> {
+    let (data, len) = buffer_data(buffer)?;
+    Ok(bytes_mut(data, len))
+}
+
+// A buffer of no length may not have any bytes to point to.
+unsafe fn bytes<'a>(data: *const u8, len: usize) -> &'a [u8] {
+    if len == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(data, len)
+    }
+}
+
+unsafe fn bytes_mut<'a>(data: *mut u8, len: usize) -> &'a mut [u8] {
+    if len == 0 {
+        &mut []
+    } else {
+        std::slice::from_raw_parts_mut(data, len)
+    }
+}
+
+/// The bytes of a `Windows.Foundation.IMemoryBufferReference`, copied into a vector
+///
+/// Generated code uses this for the `to_vec` method of `IMemoryBufferReference` and the
+/// classes that implement it. The bytes are read through the reference's
+/// `IMemoryBufferByteAccess` interface.
+#[doc(hidden)]
+pub fn memory_buffer_to_vec<T: ComInterface>(reference: &T) -> Result<Vec<u8>> {
+    let (data, len) = memory_buffer_data(&reference.try_query()?)?;
+    Ok(unsafe { bytes(data, len) }.to_vec())
+}
+
+/// Copies `bytes` to the start of a `Windows.Foundation.IMemoryBufferReference`, failing with
+/// `ErrorCode::INVALID_ARGUMENT` if they don't fit in its capacity
+#[doc(hidden)]
+pub fn memory_buffer_copy_from<T: ComInterface>(reference: &T, bytes: &[u8]) -> Result<()> {
+    let (data, len) = memory_buffer_data(&reference.try_query()?)?;
+
+    if bytes.len() > len {
+        return Err(ErrorCode::INVALID_ARGUMENT.into());
+    }
+
+    unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), data, bytes.len()) };
+    Ok(())
+}
+
+/// The bytes of a `Windows.Foundation.IMemoryBufferReference`
+///
+/// Generated code uses this for the `as_slice` method of `IMemoryBufferReference` and the
+/// classes that implement it.
+///
+/// # Safety
+/// The bytes belong to the memory buffer rather than to the reference, so while the slice is
+/// in use they mustn't be written, whether through another reference or by the component that
+/// owns them, nor freed by closing the reference or the memory buffer.
+#[doc(hidden)]
+pub unsafe fn memory_buffer_as_slice<T: ComInterface>(reference: &T) -> Result<&[u8]> {
+    let (data, len) = memory_buffer_data(&reference.try_query()?)?;
+    Ok(bytes(data, len))
+}
+
+/// The bytes of a `Windows.Foundation.IMemoryBufferReference`, for writing
+///
+/// # Safety
+/// As for `memory_buffer_as_slice`, and the bytes mustn't be read other than through the
+/// slice while it's in use either.
+#[doc(hidden)]
+pub unsafe fn memory_buffer_as_mut_slice<T: ComInterface>(reference: &mut T) -> Result<&mut [u8]> {
+    let (data, len) = memory_buffer_data(&reference.try_query()?)?;
+    Ok(bytes_mut(data, len))
+}
+
+/// The bytes of a memory buffer reference, which keeps the reference alive until it's dropped
+///
+/// This is returned by the unsafe `bytes` method of `IMemoryBufferReference` and the classes
+/// that implement it, such as the reference created by `BitmapBuffer::create_reference`, so
+/// that the bytes can be used after the reference it was created from goes out of scope. It
+/// dereferences to a slice of the bytes.
+///
+/// Keeping the reference alive doesn't keep it open, so the caller of `bytes` promises that
+/// neither the reference nor the memory buffer is closed, and that the bytes aren't used
+/// through anything else, until this is dropped.
+pub struct MemoryBufferBytes {
+    access: IMemoryBufferByteAccess,
+    data: *mut u8,
+    len: usize,
+}
+
+impl MemoryBufferBytes {
+    /// # Safety
+    /// The reference and its memory buffer must stay open, and their bytes mustn't be used
+    /// other than through the result, until it's dropped.
+    #[doc(hidden)]
+    pub unsafe fn new<T: ComInterface>(reference: &T) -> Result<MemoryBufferBytes> {
+        let access = reference.try_query()?;
+        let (data, len) = memory_buffer_data(&access)?;
+        Ok(MemoryBufferBytes { access, data, len })
+    }
+}
+
+impl std::ops::Deref for MemoryBufferBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { bytes(self.data, self.len) }
+    }
+}
+
+impl std::ops::DerefMut for MemoryBufferBytes {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { bytes_mut(self.data, self.len) }
+    }
+}
+
+impl std::fmt::Debug for MemoryBufferBytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "MemoryBufferBytes({:?}, {} bytes)",
+            self.access.ptr.get(),
+            self.len
+        )
+    }
+}
+
+// The buffer's bytes stay where they are for as long as the buffer is alive and open.
+fn buffer_data<T: ComInterface>(buffer: &T) -> Result<(*mut u8, usize)> {
+    let buffer: IBuffer = buffer.try_query()?;
+    let access: IBufferByteAccess = buffer.try_query()?;
+    let mut len = 0;
+    let mut data = std::ptr::null_mut();
+
+    unsafe {
+        let this = buffer.ptr.get();
+        ((*(*(this))).length)(this, &mut len).ok()?;
+
+        let this = access.ptr.get();
+        ((*(*(this))).buffer)(this, &mut data).and_then(|| (data, len as usize))
+    }
+}
+
+/// The `Windows.Storage.Streams.IBuffer` interface, for its capacity and length
+#[repr(transparent)]
+#[derive(Clone)]
+struct IBuffer {
+    ptr: ComPtr<IBuffer>,
+}
+
+unsafe impl ComInterface for IBuffer {
+    type VTable = abi_IBuffer;
+    const GUID: Guid = Guid::from_values(
+        0x905A_0FE0,
+        0xBC53,
+        0x11DF,
+        [0x8C, 0x49, 0x00, 0x1E, 0x4F, 0xC6, 0x86, 0xDA],
+    );
+}
+
+#[repr(C)]
+struct abi_IBuffer {
+    __base: [usize; 6],
+    capacity: extern "system" fn(*const *const abi_IBuffer, *mut u32) -> ErrorCode,
+    length: extern "system" fn(*const *const abi_IBuffer, *mut u32) -> ErrorCode,
+    set_length: extern "system" fn(*const *const abi_IBuffer, u32) -> ErrorCode,
+}
+
+/// The [IBufferByteAccess interface](https://docs.microsoft.com/en-us/windows/win32/api/robuffer/ns-robuffer-ibufferbyteaccess)
+/// that exposes a buffer's bytes
+#[repr(transparent)]
+#[derive(Clone)]
+struct IBufferByteAccess {
+    ptr: ComPtr<IBufferByteAccess>,
+}
+
+unsafe impl ComInterface for IBufferByteAccess {
+    type VTable = abi_IBufferByteAccess;
+    const GUID: Guid = Guid::from_values(
+        0x905A_0FEF,
+        0xBC53,
+        0x11DF,
+        [0x8C, 0x49, 0x00, 0x1E, 0x4F, 0xC6, 0x86, 0xDA],
+    );
+}
+
+#[repr(C)]
+struct abi_IBufferByteAccess {
+    __base: [usize; 3],
+    buffer: extern "system" fn(*const *const abi_IBufferByteAccess, *mut *mut u8) -> ErrorCode,
+}
+
+fn memory_buffer_data(access: &IMemoryBufferByteAccess) -> Result<(*mut u8, usize)> {
+    let this = access.ptr.get();
+    let mut data = std::ptr::null_mut();
+    let mut capacity = 0;
+
+    unsafe {
+        ((*(*(this))).get_buffer)(this, &mut data, &mut capacity)
+            .and_then(|| (data, capacity as usize))
+    }
+}
+
+/// The [IMemoryBufferByteAccess interface](https://docs.microsoft.com/en-us/windows/win32/api/memorybuffer/nn-memorybuffer-imemorybufferbyteaccess)
+/// that exposes the bytes of a memory buffer reference
+#[repr(transparent)]
+#[derive(Clone)]
+struct IMemoryBufferByteAccess {
+    ptr: ComPtr<IMemoryBufferByteAccess>,
+}
+
+unsafe impl ComInterface for IMemoryBufferByteAccess {
+    type VTable = abi_IMemoryBufferByteAccess;
+    const GUID: Guid = Guid::from_values(
+        0x5B0D_3235,
+        0x4DBA,
+        0x4D44,
+        [0x86, 0x5E, 0x8F, 0x1D, 0x0E, 0x4F, 0xD0, 0x4D],
+    );
+}
+
+#[repr(C)]
+struct abi_IMemoryBufferByteAccess {
+    __base: [usize; 3],
+    get_buffer: extern "system" fn(
+        *const *const abi_IMemoryBufferByteAccess,
+        *mut *mut u8,
+        *mut u32,
+    ) -> ErrorCode,
+}
@@ -0,0 +1,89 @@
+use crate::*;
+use std::cell::RefCell;
+
+/// An object wrapper that caches the interfaces it is queried for
+///
+/// Calling a method from a class's non-default interface queries the object for that
+/// interface on every call. In hot loops, wrap the object and call such methods through
+/// the interfaces returned by `query`, which only asks the object for each interface once.
+///
+/// ```ignore
+/// let uri = winrt::Cached::new(Uri::create_uri("http://kennykerr.ca")?);
+///
+/// for _ in 0..1000 {
+///     let text = uri.query::<IStringable>()?.to_string()?;
+/// }
+/// ```
+///
+/// The wrapper dereferences to the object so that default interface methods can be
+/// called directly.
+pub struct Cached<T: ComInterface> {
+    object: T,
+    cache: RefCell<Vec<(Guid, IUnknown)>>,
+}
+
+impl<T: ComInterface> Cached<T> {
+    pub fn new(object: T) -> Self {
+        Self {
+            object,
+            cache: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Returns the interface, querying the object only if it wasn't previously cached
+    pub fn query<I: ComInterface>(&self) -> Result<I> {
+        unsafe { self.query_with_guid(&I::iid()) }
+    }
+
+    /// Returns the interface, querying the object only if it wasn't previously cached
+    ///
+    /// # Safety
+    /// The guid parameter must be a valid guid for the returned ComInterface.
+    /// See `ComInterface::query_with_guid` for details.
+    pub unsafe fn query_with_guid<I: ComInterface>(&self, guid: &Guid) -> Result<I> {
+        if let Some((_, unknown)) = self.cache.borrow().iter().find(|(g, _)| g == guid) {
+            return Ok(to_interface(unknown));
+        }
+
+        let unknown: IUnknown = self.object.try_query_with_guid(guid)?;
+
+        if unknown.is_null() {
+            return Ok(to_interface(&unknown));
+        }
+
+        let interface = to_interface(&unknown);
+        self.cache.borrow_mut().push((*guid, unknown));
+        Ok(interface)
+    }
+
+    /// Releases the cached interfaces
+    pub fn clear(&self) {
+        self.cache.borrow_mut().clear();
+    }
+
+    /// Returns the object, releasing the cached interfaces
+    pub fn into_inner(self) -> T {
+        self.object
+    }
+}
+
+impl<T: ComInterface> std::ops::Deref for Cached<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.object
+    }
+}
+
+impl<T: ComInterface> From<T> for Cached<T> {
+    fn from(object: T) -> Self {
+        Self::new(object)
+    }
+}
+
+fn to_interface<I: ComInterface>(unknown: &IUnknown) -> I {
+    let unknown = unknown.clone();
+    let interface = unsafe { std::mem::transmute_copy(&unknown) };
+    std::mem::forget(unknown);
+    interface
+}
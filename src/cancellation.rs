@@ -0,0 +1,209 @@
+use crate::*;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+/// Cancels a set of WinRT async operations and Rust tasks together
+///
+/// Each [`CancellationToken`] handed out by the source can register WinRT async operations,
+/// which are cancelled with `IAsyncInfo::Cancel` when the source is cancelled, and can be checked
+/// or awaited from Rust code. This lets a pipeline that mixes both stop as a whole.
+pub struct CancellationSource {
+    state: Arc<State>,
+}
+
+impl CancellationSource {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(State {
+                cancelled: AtomicBool::new(false),
+                pending: Mutex::new(Pending::default()),
+            }),
+        }
+    }
+
+    /// A token observing this source
+    pub fn token(&self) -> CancellationToken {
+        CancellationToken {
+            state: self.state.clone(),
+        }
+    }
+
+    /// Cancels every registered operation and wakes any tasks waiting on a token
+    ///
+    /// Operations that have already completed ignore the request. Cancelling more than once
+    /// has no further effect.
+    pub fn cancel(&self) {
+        if self.state.cancelled.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        // The lock is released before calling out so that completion handlers may use the token.
+        let pending = std::mem::take(&mut *self.state.pending.lock().unwrap());
+
+        for operation in pending.operations {
+            operation.cancel();
+        }
+
+        for waker in pending.wakers {
+            waker.wake();
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+impl Default for CancellationSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Observes a [`CancellationSource`]
+#[derive(Clone)]
+pub struct CancellationToken {
+    state: Arc<State>,
+}
+
+impl CancellationToken {
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Returns an error with `ErrorCode::CANCELLED` if the source has been cancelled
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            Err(ErrorCode::CANCELLED.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Registers an async operation, or action, to be cancelled along with the source
+    ///
+    /// The operation is cancelled right away if the source has already been cancelled. This
+    /// fails if the object doesn't implement `IAsyncInfo`.
+    pub fn register<T: ComInterface>(&self, operation: &T) -> Result<()> {
+        let operation: AsyncInfo = operation.try_query()?;
+        let mut pending = self.state.pending.lock().unwrap();
+
+        if self.is_cancelled() {
+            drop(pending);
+            operation.cancel();
+            return Ok(());
+        }
+
+        // Operations that have since completed no longer need to be held.
+        pending.operations.retain(AsyncInfo::is_started);
+        pending.operations.push(operation);
+        Ok(())
+    }
+
+    /// A future that completes when the source is cancelled
+    pub fn cancelled(&self) -> Cancelled {
+        Cancelled {
+            state: self.state.clone(),
+        }
+    }
+}
+
+/// The future returned by [`CancellationToken::cancelled`]
+pub struct Cancelled {
+    state: Arc<State>,
+}
+
+impl Future for Cancelled {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.state.cancelled.load(Ordering::SeqCst) {
+            return Poll::Ready(());
+        }
+
+        let mut pending = self.state.pending.lock().unwrap();
+
+        // The source may have been cancelled while the lock was being acquired.
+        if self.state.cancelled.load(Ordering::SeqCst) {
+            return Poll::Ready(());
+        }
+
+        if !pending
+            .wakers
+            .iter()
+            .any(|waker| waker.will_wake(cx.waker()))
+        {
+            pending.wakers.push(cx.waker().clone());
+        }
+
+        Poll::Pending
+    }
+}
+
+struct State {
+    cancelled: AtomicBool,
+    pending: Mutex<Pending>,
+}
+
+#[derive(Default)]
+struct Pending {
+    operations: Vec<AsyncInfo>,
+    wakers: Vec<Waker>,
+}
+
+// The IAsyncInfo interface implemented by every WinRT async operation and action.
+#[repr(transparent)]
+#[derive(Clone)]
+struct AsyncInfo {
+    ptr: ComPtr<AsyncInfo>,
+}
+
+// WinRT async operations are agile so they may be cancelled from any thread.
+unsafe impl Send for AsyncInfo {}
+unsafe impl Sync for AsyncInfo {}
+
+// AsyncStatus::Started
+const STARTED: i32 = 0;
+
+impl AsyncInfo {
+    fn is_started(&self) -> bool {
+        let this = self.ptr.get();
+        let mut status = STARTED;
+        unsafe {
+            ((*(*(this))).status)(this, &mut status)
+                .and_then(|| status == STARTED)
+                .unwrap_or(false)
+        }
+    }
+
+    fn cancel(&self) {
+        let this = self.ptr.get();
+        // Cancel only fails if the operation has been closed, in which case there's nothing to do.
+        let _ = unsafe { ((*(*(this))).cancel)(this) };
+    }
+}
+
+unsafe impl ComInterface for AsyncInfo {
+    type VTable = abi_IAsyncInfo;
+    const GUID: Guid = Guid::from_values(
+        0x0000_0036,
+        0x0000,
+        0x0000,
+        [0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46],
+    );
+}
+
+type AsyncInfoPtr = *const *const abi_IAsyncInfo;
+
+#[repr(C)]
+struct abi_IAsyncInfo {
+    __base: [usize; 6],
+    id: extern "system" fn(AsyncInfoPtr, *mut u32) -> ErrorCode,
+    status: extern "system" fn(AsyncInfoPtr, *mut i32) -> ErrorCode,
+    error_code: extern "system" fn(AsyncInfoPtr, *mut ErrorCode) -> ErrorCode,
+    cancel: extern "system" fn(AsyncInfoPtr) -> ErrorCode,
+    close: extern "system" fn(AsyncInfoPtr) -> ErrorCode,
+}
@@ -0,0 +1,93 @@
+use crate::*;
+
+/// A WinRT Char16, a UTF-16 code unit
+///
+/// A Char16 is a `u16` at the ABI. It converts to a `char` unless it's half of a surrogate
+/// pair, which only encodes a `char` along with the other half, as in a string.
+#[repr(transparent)]
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Char16(pub u16);
+
+impl Char16 {
+    /// Returns the code unit of a `char` unless it needs a surrogate pair, which is the case
+    /// for characters outside the Basic Multilingual Plane, such as most emoji
+    pub fn from_char(value: char) -> Option<Self> {
+        let mut buffer = [0; 2];
+
+        match value.encode_utf16(&mut buffer) {
+            [unit] => Some(Self(*unit)),
+            _ => None,
+        }
+    }
+
+    /// Returns the `char` unless this is half of a surrogate pair
+    pub fn to_char(self) -> Option<char> {
+        std::char::from_u32(self.0 as u32)
+    }
+
+    /// Whether this is half of a surrogate pair
+    pub fn is_surrogate(self) -> bool {
+        (0xD800..=0xDFFF).contains(&self.0)
+    }
+}
+
+impl From<u16> for Char16 {
+    fn from(value: u16) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Char16> for u16 {
+    fn from(value: Char16) -> Self {
+        value.0
+    }
+}
+
+// Half of a surrogate pair is shown as the replacement character, as it is in strings.
+impl std::fmt::Display for Char16 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use std::fmt::Write;
+        f.write_char(self.to_char().unwrap_or(std::char::REPLACEMENT_CHARACTER))
+    }
+}
+
+unsafe impl RuntimeType for Char16 {
+    type Abi = u16;
+
+    fn abi(&self) -> Self::Abi {
+        self.0
+    }
+
+    fn set_abi(&mut self) -> *mut Self::Abi {
+        &mut self.0
+    }
+
+    fn signature() -> String {
+        "c2".to_owned()
+    }
+}
+
+impl RuntimeName for Char16 {
+    const NAME: &'static str = "Char16";
+    const PRIMITIVE: bool = true;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn char16_works() {
+        assert_eq!(Char16::from_char('a'), Some(Char16(0x61)));
+        assert_eq!(Char16(0x61).to_char(), Some('a'));
+        assert_eq!(Char16::from_char('€').map(u16::from), Some(0x20AC));
+
+        // Characters outside the Basic Multilingual Plane need a surrogate pair.
+        assert_eq!(Char16::from_char('🦀'), None);
+        assert!(Char16(0xD83E).is_surrogate());
+        assert_eq!(Char16(0xD83E).to_char(), None);
+        assert_eq!(Char16(0xD83E).to_string(), "\u{FFFD}");
+        assert!(!Char16(0x61).is_surrogate());
+    }
+}
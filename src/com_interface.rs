@@ -14,18 +14,133 @@ pub unsafe trait ComInterface: Sized {
     const GUID: Guid;
     type VTable;
 
+    /// The interface's GUID
+    ///
+    /// This is `GUID` except for specializations of generic interfaces and delegates, whose
+    /// GUIDs depend on their type arguments and are computed, and cached, on first use.
+    fn iid() -> Guid {
+        Self::GUID
+    }
+
     fn as_vtable(&self) -> *const *const Self::VTable {
         unsafe { std::mem::transmute_copy(self) }
     }
 
     fn query<Into: ComInterface>(&self) -> Into {
-        unsafe { self.query_with_guid(&Into::GUID) }
+        unsafe { self.query_with_guid(&Into::iid()) }
+    }
+
+    /// Use QueryInterface to cast a ComInterface into another, returning an error
+    /// if the object does not implement the requested interface.
+    ///
+    /// To probe for an optional capability, use `query_optional::<I>()` to get an `Option`, or
+    /// `is::<I>()` when the interface itself isn't needed.
+    fn try_query<Into: ComInterface>(&self) -> Result<Into> {
+        unsafe { self.try_query_with_guid(&Into::iid()) }
+    }
+
+    /// Use QueryInterface to cast a ComInterface into another, returning `None` if the object
+    /// does not implement the requested interface
+    ///
+    /// Unlike `try_query`, a missing interface isn't an error, so no error details are looked
+    /// up when probing for an optional capability.
+    fn query_optional<Into: ComInterface>(&self) -> Option<Into> {
+        let into: Into = unsafe { self.query_with_guid(&Into::iid()) };
+
+        if into.is_null() {
+            None
+        } else {
+            Some(into)
+        }
+    }
+
+    /// Returns true if the object implements the interface
+    fn is<Into: ComInterface>(&self) -> bool {
+        let unknown: IUnknown = unsafe { self.query_with_guid(&Into::iid()) };
+        !unknown.is_null()
+    }
+
+    /// Creates a weak reference to the object, which doesn't keep it alive
+    ///
+    /// Fails if the object doesn't support weak references.
+    fn downgrade(&self) -> Result<WeakRef<Self>> {
+        WeakRef::new(self)
+    }
+
+    /// An interface that doesn't refer to any object
+    ///
+    /// This is only a slot for a method to write an interface to, such as the elements of a
+    /// buffer passed to a method that fills an array. Hold an interface that may be absent in
+    /// an `Option` instead, and check `is_null` for an interface received from elsewhere.
+    ///
+    /// # Safety
+    /// The interface must be written to before it's used. Its methods panic, and code that
+    /// expects an object, such as the implementations of traits, may not check for null.
+    unsafe fn none() -> Self {
+        std::mem::zeroed()
     }
 
     fn is_null(&self) -> bool {
         self.as_vtable().is_null()
     }
 
+    /// The raw interface pointer, without adding a reference
+    ///
+    /// This is for passing the interface to other COM libraries, which all share the same
+    /// IUnknown ABI, for the duration of a call.
+    fn as_raw(&self) -> RawPtr {
+        self.as_vtable() as RawPtr
+    }
+
+    /// Converts the interface into a raw interface pointer, transferring its reference to the
+    /// caller
+    ///
+    /// The reference must eventually be released, such as by passing the pointer to
+    /// `from_raw` or to another COM library that takes ownership of it.
+    fn into_raw(self) -> RawPtr {
+        let raw = self.as_raw();
+        std::mem::forget(self);
+        raw
+    }
+
+    /// Takes ownership of a raw interface pointer, such as one returned by `into_raw` or by
+    /// another COM library
+    ///
+    /// # Safety
+    /// The pointer must be null or point to an object implementing this interface, and the
+    /// caller's reference is transferred to the returned interface.
+    unsafe fn from_raw(raw: RawPtr) -> Self {
+        std::mem::transmute_copy(&raw)
+    }
+
+    /// Borrows a raw interface pointer as this interface without adding a reference
+    ///
+    /// Returns `None` if the pointer is null.
+    ///
+    /// # Safety
+    /// The pointer must point to an object implementing this interface and remain valid for
+    /// the lifetime of the borrow.
+    unsafe fn from_raw_borrowed(raw: &RawPtr) -> Option<&Self> {
+        if raw.is_null() {
+            None
+        } else {
+            Some(&*(raw as *const RawPtr as *const Self))
+        }
+    }
+
+    /// Queries a raw IUnknown pointer owned by another COM library for this interface
+    ///
+    /// The caller keeps its reference to the object and the returned interface holds a new one.
+    ///
+    /// # Safety
+    /// The pointer must be null or point to a valid COM object.
+    unsafe fn query_raw(raw: RawPtr) -> Result<Self> {
+        match IUnknown::from_raw_borrowed(&raw) {
+            Some(unknown) => unknown.try_query(),
+            None => Err(ErrorCode::INVALID_ARGUMENT.into()),
+        }
+    }
+
     /// Use QueryInterface to cast a ComInterface into another.
     ///
     /// If the call to QueryInterface fails, the returned ComInterface will be null.
@@ -48,4 +163,27 @@ pub unsafe trait ComInterface: Sized {
         }
         std::mem::transmute_copy(&into)
     }
+
+    /// Use QueryInterface to cast a ComInterface into another, returning an error
+    /// if the object does not implement the requested interface.
+    ///
+    /// A null ComInterface is converted to a null ComInterface.
+    ///
+    /// # Safety
+    /// The guid parameter must be a valid guid for the returned ComInterface.
+    /// See `query_with_guid` for details.
+    unsafe fn try_query_with_guid<Into: ComInterface>(&self, guid: &Guid) -> Result<Into> {
+        let mut into = std::ptr::null_mut();
+        let from = self.as_vtable() as *const *const <IUnknown as ComInterface>::VTable;
+
+        if !from.is_null() {
+            ((*(*(from))).query)(from, guid, &mut into).ok()?;
+
+            if (cfg!(debug_assertions) || cfg!(feature = "strict")) && into.is_null() {
+                panic!("QueryInterface succeeded without returning an interface");
+            }
+        }
+
+        Ok(std::mem::transmute_copy(&into))
+    }
 }
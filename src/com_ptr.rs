@@ -1,5 +1,5 @@
 use crate::unknown::abi_IUnknown;
-use crate::ComInterface;
+use crate::{ComInterface, RawPtr};
 
 /// A reference counted pointer to a COM interface
 #[repr(transparent)]
@@ -31,6 +31,55 @@ impl<T: ComInterface> ComPtr<T> {
     pub fn is_null(&self) -> bool {
         self.ptr.is_null()
     }
+
+    /// The raw interface pointer, without adding a reference
+    pub fn as_raw(&self) -> RawPtr {
+        self.ptr as RawPtr
+    }
+
+    /// Converts the pointer into a raw interface pointer, transferring its reference to the
+    /// caller
+    pub fn into_raw(self) -> RawPtr {
+        let raw = self.as_raw();
+        std::mem::forget(self);
+        raw
+    }
+
+    /// Takes ownership of a raw interface pointer, such as one returned by `into_raw`, another
+    /// COM library, or a C callback
+    ///
+    /// # Safety
+    /// The pointer must be null or point to an object implementing `T`, and the caller's
+    /// reference is transferred to the returned pointer.
+    pub unsafe fn from_raw(raw: RawPtr) -> Self {
+        ComPtr { ptr: raw as _ }
+    }
+
+    /// Adds a reference to the object, such as for a C callback that releases the pointer
+    /// it's handed, and returns the new reference count
+    ///
+    /// The count is only meant for debugging. Nothing is done for a null pointer.
+    pub fn addref(&self) -> u32 {
+        if self.ptr.is_null() {
+            return 0;
+        }
+
+        unsafe { ((*(*(self.get_iunknown()))).addref)(self.get_iunknown()) }
+    }
+
+    /// Releases a reference to the object, such as one added by `addref`, and returns the
+    /// new reference count
+    ///
+    /// # Safety
+    /// The reference must have been added for this pointer, otherwise the object may be
+    /// destroyed while the pointer still refers to it.
+    pub unsafe fn release(&self) -> u32 {
+        if self.ptr.is_null() {
+            return 0;
+        }
+
+        ((*(*(self.get_iunknown()))).release)(self.get_iunknown())
+    }
 }
 
 impl<T: ComInterface> Clone for ComPtr<T> {
@@ -0,0 +1,378 @@
+use crate::ref_count::RefCount;
+use crate::*;
+use std::any::Any;
+
+/// Composes a Rust implementation of a class's overridable interfaces with the class itself
+///
+/// The Rust implementation lives in an outer object that is passed to the class's composable
+/// factory. The outer object implements each overridable interface with the given vtable and
+/// forwards every other interface to the inner object returned by the factory. Generated
+/// `compose` functions on composable classes build the vtables and call this.
+#[doc(hidden)]
+pub struct Composer<T> {
+    overrides: T,
+    interfaces: Vec<(Guid, Vec<*const ()>)>,
+}
+
+impl<T: Any> Composer<T> {
+    pub fn new(overrides: T) -> Self {
+        Self {
+            overrides,
+            interfaces: Vec::new(),
+        }
+    }
+
+    /// Adds an overridable interface with the given methods, in vtable order
+    pub fn interface(mut self, guid: Guid, methods: &[*const ()]) -> Self {
+        self.interfaces.push((guid, methods.to_vec()));
+        self
+    }
+
+    /// Creates the outer object and passes it to `create`, which should call the composable factory
+    pub fn create<C, F>(self, create: F) -> Result<C>
+    where
+        F: FnOnce(&Object, &mut Object) -> Result<C>,
+    {
+        let outer = Box::into_raw(Box::new(Outer {
+            vtable: &OUTER_VTABLE,
+            count: RefCount::new(1),
+            inner: unsafe { Object::none() },
+            tear_offs: Vec::new(),
+            overrides: Box::new(self.overrides),
+        }));
+
+        unsafe {
+            // The tear-offs are all in place before the outer object is handed out, so the
+            // vector never grows and the tear-offs stay where `outer_query` finds them.
+            (*outer).tear_offs = self
+                .interfaces
+                .into_iter()
+                .map(|(guid, methods)| {
+                    let mut vtable = vec![
+                        tear_off_query as *const (),
+                        tear_off_addref as *const (),
+                        tear_off_release as *const (),
+                        tear_off_iids as *const (),
+                        tear_off_type_name as *const (),
+                        tear_off_trust_level as *const (),
+                    ];
+
+                    vtable.extend(methods);
+
+                    TearOff {
+                        vtable: vtable.as_ptr(),
+                        outer,
+                        guid,
+                        methods: vtable,
+                    }
+                })
+                .collect();
+
+            // The initial reference is released when `object` is dropped, whether or not
+            // the factory succeeds, leaving the composed object to own the outer object.
+            let object: Object = std::mem::transmute(outer);
+            let mut inner = Object::none();
+            let result = create(&object, &mut inner);
+            (*outer).inner = inner;
+            result
+        }
+    }
+
+    /// Returns the Rust implementation and the base class's implementation of the interface
+    /// for a call made through one of the outer object's vtables
+    ///
+    /// # Safety
+    /// `this` must be the `this` pointer passed to a method of a vtable given to `interface`.
+    pub unsafe fn resolve<'a, I: ComInterface>(this: RawPtr) -> Result<(&'a T, I)> {
+        let outer = &*(*(this as *const TearOff)).outer;
+        let overrides = outer.overrides.downcast_ref::<T>().unwrap();
+        let base = outer.inner.try_query::<I>()?;
+        Ok((overrides, base))
+    }
+}
+
+#[repr(C)]
+struct Outer {
+    vtable: *const abi_IInspectable,
+    count: RefCount,
+    inner: Object,
+    tear_offs: Vec<TearOff>,
+    overrides: Box<dyn Any>,
+}
+
+#[repr(C)]
+struct TearOff {
+    vtable: *const *const (),
+    outer: *mut Outer,
+    guid: Guid,
+    methods: Vec<*const ()>,
+}
+
+#[repr(C)]
+struct abi_IInspectable {
+    query: extern "system" fn(RawPtr, &Guid, *mut RawPtr) -> ErrorCode,
+    addref: extern "system" fn(RawPtr) -> u32,
+    release: extern "system" fn(RawPtr) -> u32,
+    iids: extern "system" fn(RawPtr, *mut u32, *mut *mut Guid) -> ErrorCode,
+    type_name: extern "system" fn(RawPtr, *mut <HString as RuntimeType>::Abi) -> ErrorCode,
+    trust_level: extern "system" fn(RawPtr, *mut i32) -> ErrorCode,
+}
+
+static OUTER_VTABLE: abi_IInspectable = abi_IInspectable {
+    query: outer_query,
+    addref: outer_addref,
+    release: outer_release,
+    iids: outer_iids,
+    type_name: outer_type_name,
+    trust_level: outer_trust_level,
+};
+
+fn inner(this: RawPtr) -> Option<*const *const abi_IInspectable> {
+    let outer = unsafe { &*(this as *const Outer) };
+
+    if outer.inner.is_null() {
+        None
+    } else {
+        Some(outer.inner.abi() as *const *const abi_IInspectable)
+    }
+}
+
+extern "system" fn outer_query(this: RawPtr, iid: &Guid, result: *mut RawPtr) -> ErrorCode {
+    unsafe {
+        let outer = &*(this as *const Outer);
+        *result = std::ptr::null_mut();
+
+        if *iid == IUnknown::GUID || *iid == Object::GUID {
+            *result = this;
+        } else if let Some(tear_off) = outer.tear_offs.iter().find(|t| t.guid == *iid) {
+            *result = tear_off as *const TearOff as RawPtr;
+        } else if let Some(inner) = inner(this) {
+            return ((*(*inner)).query)(inner as RawPtr, iid, result);
+        } else {
+            return ErrorCode::NO_INTERFACE;
+        }
+
+        outer.count.addref();
+        ErrorCode(0)
+    }
+}
+
+extern "system" fn outer_addref(this: RawPtr) -> u32 {
+    unsafe { (*(this as *const Outer)).count.addref() }
+}
+
+extern "system" fn outer_release(this: RawPtr) -> u32 {
+    unsafe {
+        let remaining = (*(this as *const Outer)).count.release();
+
+        if remaining == 0 {
+            drop(Box::from_raw(this as *mut Outer));
+        }
+
+        remaining
+    }
+}
+
+extern "system" fn outer_iids(this: RawPtr, count: *mut u32, iids: *mut *mut Guid) -> ErrorCode {
+    match inner(this) {
+        Some(inner) => unsafe { ((*(*inner)).iids)(inner as RawPtr, count, iids) },
+        None => ErrorCode::NOT_IMPLEMENTED,
+    }
+}
+
+extern "system" fn outer_type_name(
+    this: RawPtr,
+    name: *mut <HString as RuntimeType>::Abi,
+) -> ErrorCode {
+    match inner(this) {
+        Some(inner) => unsafe { ((*(*inner)).type_name)(inner as RawPtr, name) },
+        None => ErrorCode::NOT_IMPLEMENTED,
+    }
+}
+
+extern "system" fn outer_trust_level(this: RawPtr, level: *mut i32) -> ErrorCode {
+    match inner(this) {
+        Some(inner) => unsafe { ((*(*inner)).trust_level)(inner as RawPtr, level) },
+        None => ErrorCode::NOT_IMPLEMENTED,
+    }
+}
+
+fn outer(this: RawPtr) -> RawPtr {
+    unsafe { (*(this as *const TearOff)).outer as RawPtr }
+}
+
+extern "system" fn tear_off_query(this: RawPtr, iid: &Guid, result: *mut RawPtr) -> ErrorCode {
+    outer_query(outer(this), iid, result)
+}
+
+extern "system" fn tear_off_addref(this: RawPtr) -> u32 {
+    outer_addref(outer(this))
+}
+
+extern "system" fn tear_off_release(this: RawPtr) -> u32 {
+    outer_release(outer(this))
+}
+
+extern "system" fn tear_off_iids(this: RawPtr, count: *mut u32, iids: *mut *mut Guid) -> ErrorCode {
+    outer_iids(outer(this), count, iids)
+}
+
+extern "system" fn tear_off_type_name(
+    this: RawPtr,
+    name: *mut <HString as RuntimeType>::Abi,
+) -> ErrorCode {
+    outer_type_name(outer(this), name)
+}
+
+extern "system" fn tear_off_trust_level(this: RawPtr, level: *mut i32) -> ErrorCode {
+    outer_trust_level(outer(this), level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    // The base class's implementation of an interface, which the composed object can call.
+    #[repr(transparent)]
+    struct IBase {
+        ptr: ComPtr<IBase>,
+    }
+
+    unsafe impl ComInterface for IBase {
+        type VTable = abi_IBase;
+        const GUID: Guid = Guid::from_values(
+            0x3C1E_5A27,
+            0x8D4F,
+            0x4B61,
+            [0xA2, 0x9E, 0x71, 0x0C, 0xB5, 0x44, 0xE8, 0x93],
+        );
+    }
+
+    #[repr(C)]
+    struct abi_IBase {
+        __base: [usize; 3],
+        value: extern "system" fn(RawPtr, *mut i32) -> ErrorCode,
+    }
+
+    // An overridable interface implemented in Rust.
+    #[repr(transparent)]
+    struct IOverrides {
+        ptr: ComPtr<IOverrides>,
+    }
+
+    unsafe impl ComInterface for IOverrides {
+        type VTable = abi_IOverrides;
+        const GUID: Guid = Guid::from_values(
+            0x9F02_6B4D,
+            0x1E83,
+            0x47C5,
+            [0x8B, 0x3A, 0xD6, 0x29, 0x50, 0x7F, 0x1C, 0xE4],
+        );
+    }
+
+    #[repr(C)]
+    struct abi_IOverrides {
+        __base: [usize; 6],
+        value: extern "system" fn(RawPtr, *mut i32) -> ErrorCode,
+    }
+
+    struct Overrides {
+        value: i32,
+        dropped: Arc<AtomicBool>,
+    }
+
+    impl Drop for Overrides {
+        fn drop(&mut self) {
+            self.dropped.store(true, Ordering::SeqCst);
+        }
+    }
+
+    extern "system" fn base_value(_: RawPtr, value: *mut i32) -> ErrorCode {
+        unsafe { *value = 10 };
+        ErrorCode(0)
+    }
+
+    // Adds the Rust implementation's value to the base class's.
+    extern "system" fn overridden_value(this: RawPtr, value: *mut i32) -> ErrorCode {
+        unsafe {
+            let (overrides, base) = match Composer::<Overrides>::resolve::<IBase>(this) {
+                Ok(resolved) => resolved,
+                Err(error) => return error.code(),
+            };
+
+            let base = base.ptr.get();
+            let mut base_value = 0;
+            ((*(*base)).value)(base as RawPtr, &mut base_value).unwrap();
+            *value = overrides.value + base_value;
+            ErrorCode(0)
+        }
+    }
+
+    fn compose(dropped: &Arc<AtomicBool>, fail: bool) -> Result<Object> {
+        let overrides = Overrides {
+            value: 5,
+            dropped: dropped.clone(),
+        };
+
+        Composer::new(overrides)
+            .interface(IOverrides::GUID, &[overridden_value as *const ()])
+            .create(|outer, inner| {
+                // A delegate stands in for the inner object created by the factory.
+                *inner = unsafe {
+                    Object::from_raw(Delegate::create_raw(
+                        IBase::GUID,
+                        base_value as *const (),
+                        (),
+                    ))
+                };
+
+                if fail {
+                    Err(ErrorCode::INVALID_ARGUMENT.into())
+                } else {
+                    Ok(outer.clone())
+                }
+            })
+    }
+
+    fn base_value_of(object: &Object) -> i32 {
+        let base: IBase = object.query();
+        let this = base.ptr.get();
+        let mut value = 0;
+        unsafe { ((*(*this)).value)(this as RawPtr, &mut value).unwrap() };
+        value
+    }
+
+    fn overridden_value_of(object: &Object) -> i32 {
+        let overrides: IOverrides = object.query();
+        let this = overrides.ptr.get();
+        let mut value = 0;
+        unsafe { ((*(*this)).value)(this as RawPtr, &mut value).unwrap() };
+        value
+    }
+
+    #[test]
+    fn override_and_base() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let object = compose(&dropped, false).unwrap();
+
+        // The override is called through the outer object and calls the base class in turn.
+        assert!(overridden_value_of(&object) == 15);
+
+        // Interfaces that aren't overridden are the base class's own.
+        assert!(base_value_of(&object) == 10);
+        assert!(object.is::<IBase>());
+
+        assert!(!dropped.load(Ordering::SeqCst));
+        drop(object);
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn failed_factory() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        assert!(compose(&dropped, true).is_err());
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+}
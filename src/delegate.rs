@@ -0,0 +1,210 @@
+use crate::ref_count::RefCount;
+use crate::*;
+
+/// Implements a delegate with a Rust closure
+///
+/// The resulting object is agile so the closure may be called from any thread. Generated
+/// delegate `new` functions provide the `Invoke` thunk and call this.
+#[doc(hidden)]
+pub struct Delegate;
+
+impl Delegate {
+    /// Returns an owned reference to a new delegate that calls `invoke` with the closure
+    pub fn create_raw<F: Send + Sync + 'static>(
+        guid: Guid,
+        invoke: *const (),
+        closure: F,
+    ) -> RawPtr {
+        let mut delegate = Box::new(Inner {
+            vtable: std::ptr::null(),
+            methods: [
+                query as *const (),
+                addref::<F> as *const (),
+                release::<F> as *const (),
+                invoke,
+            ],
+            count: RefCount::new(1),
+            guid,
+            closure,
+        });
+
+        delegate.vtable = delegate.methods.as_ptr();
+        Box::into_raw(delegate) as RawPtr
+    }
+
+    /// Returns a new delegate of type `T` that calls `invoke` with the closure
+    ///
+    /// # Safety
+    /// `invoke` must be the `Invoke` thunk of `T` for the closure type `F`.
+    pub unsafe fn create<T: ComInterface, F: Send + Sync + 'static>(
+        invoke: *const (),
+        closure: F,
+    ) -> T {
+        T::from_raw(Self::create_raw(T::iid(), invoke, closure))
+    }
+
+    /// Returns the closure of a delegate created with `create` or `create_raw`
+    ///
+    /// # Safety
+    /// `this` must be the `this` pointer passed to the `invoke` thunk given to `create_raw`.
+    pub unsafe fn closure<'a, F: 'static>(this: RawPtr) -> &'a F {
+        &(*(this as *const Inner<F>)).closure
+    }
+}
+
+#[repr(C)]
+struct Inner<F> {
+    vtable: *const *const (),
+    methods: [*const (); 4],
+    count: RefCount,
+    guid: Guid,
+    closure: F,
+}
+
+// Only the leading fields are used here so it doesn't matter which closure type this is.
+type Header = Inner<()>;
+
+pub(crate) const IAGILE_OBJECT: Guid = Guid::from_values(
+    0x94EA_2B94,
+    0xE9CC,
+    0x49E0,
+    [0xC0, 0xFF, 0xEE, 0x64, 0xCA, 0x8F, 0x5B, 0x90],
+);
+
+extern "system" fn query(this: RawPtr, iid: &Guid, result: *mut RawPtr) -> ErrorCode {
+    unsafe {
+        let delegate = &*(this as *const Header);
+
+        if *iid == delegate.guid || *iid == IUnknown::GUID || *iid == IAGILE_OBJECT {
+            delegate.count.addref();
+            *result = this;
+            ErrorCode(0)
+        } else {
+            *result = std::ptr::null_mut();
+            ErrorCode::NO_INTERFACE
+        }
+    }
+}
+
+extern "system" fn addref<F>(this: RawPtr) -> u32 {
+    unsafe { (*(this as *const Inner<F>)).count.addref() }
+}
+
+extern "system" fn release<F>(this: RawPtr) -> u32 {
+    unsafe {
+        let remaining = (*(this as *const Inner<F>)).count.release();
+
+        if remaining == 0 {
+            drop(Box::from_raw(this as *mut Inner<F>));
+        }
+
+        remaining
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+    use std::sync::Arc;
+
+    #[repr(transparent)]
+    #[derive(Clone)]
+    struct IHandler {
+        ptr: ComPtr<IHandler>,
+    }
+
+    unsafe impl ComInterface for IHandler {
+        type VTable = abi_IHandler;
+        const GUID: Guid = Guid::from_values(
+            0x6A0E_27D3,
+            0x94B1,
+            0x4F2C,
+            [0x8E, 0x15, 0x3B, 0xD7, 0x02, 0x6C, 0xA9, 0x4F],
+        );
+    }
+
+    #[repr(C)]
+    struct abi_IHandler {
+        __base: [usize; 3],
+        invoke: extern "system" fn(RawPtr, i32) -> ErrorCode,
+    }
+
+    impl IHandler {
+        fn invoke(&self, value: i32) -> Result<()> {
+            let this = self.ptr.get();
+            unsafe { ((*(*this)).invoke)(this as RawPtr, value).ok() }
+        }
+    }
+
+    extern "system" fn invoke<F: Fn(i32) -> Result<()> + 'static>(
+        this: RawPtr,
+        value: i32,
+    ) -> ErrorCode {
+        match unsafe { Delegate::closure::<F>(this) }(value) {
+            Ok(()) => ErrorCode(0),
+            Err(error) => error.code(),
+        }
+    }
+
+    fn handler<F: Fn(i32) -> Result<()> + Send + Sync + 'static>(closure: F) -> IHandler {
+        unsafe { Delegate::create(invoke::<F> as *const (), closure) }
+    }
+
+    struct Released(Arc<AtomicBool>);
+
+    impl Drop for Released {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn invoke_closure() {
+        let total = Arc::new(AtomicI32::new(0));
+        let handler = {
+            let total = total.clone();
+            handler(move |value| {
+                if value < 0 {
+                    return Err(ErrorCode::INVALID_ARGUMENT.into());
+                }
+
+                total.fetch_add(value, Ordering::SeqCst);
+                Ok(())
+            })
+        };
+
+        handler.invoke(1).unwrap();
+        handler.invoke(2).unwrap();
+        assert!(total.load(Ordering::SeqCst) == 3);
+
+        // Errors returned by the closure are returned to the caller.
+        assert!(handler.invoke(-1).unwrap_err().code() == ErrorCode::INVALID_ARGUMENT);
+    }
+
+    #[test]
+    fn query_and_release() {
+        let released = Arc::new(AtomicBool::new(false));
+        let handler = {
+            let released = Released(released.clone());
+            handler(move |_| {
+                let _ = &released;
+                Ok(())
+            })
+        };
+
+        // Delegates implement their own interface along with IUnknown and IAgileObject.
+        assert!(handler.is::<IUnknown>());
+        assert!(handler.is::<IHandler>());
+        assert!(!unsafe { handler.query_with_guid::<IUnknown>(&IAGILE_OBJECT) }.is_null());
+        assert!(!handler.is::<Object>());
+
+        let clone = handler.clone();
+        drop(handler);
+        assert!(!released.load(Ordering::SeqCst));
+
+        // The closure is dropped along with the last reference.
+        drop(clone);
+        assert!(released.load(Ordering::SeqCst));
+    }
+}
@@ -1,20 +1,291 @@
 #![allow(overflowing_literals)]
 
+use crate::{runtime, ComInterface, ComPtr, Guid, HString, RuntimeType};
+
 /// An alias for `std::result::Result<T, winrt::Error>`
 #[must_use]
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// A WinRT related error
+///
+/// Along with its code, an error carries the details that the API reporting it recorded with
+/// [IRestrictedErrorInfo](https://docs.microsoft.com/en-us/windows/win32/api/restrictederrorinfo/nn-restrictederrorinfo-irestrictederrorinfo),
+/// such as why access was denied or the capability the package is missing.
+///
+/// Errors can be given context, such as the API or class that failed, with `with_context`. The
+/// error with context displays that context and reports the original error as its `source`.
 #[derive(Debug)]
 pub struct Error {
     code: ErrorCode,
-    // TODO: add `info: IErrorInfo`
+    info: Option<Box<ErrorInfo>>,
+    context: Option<Box<(String, Error)>>,
+}
+
+#[derive(Debug)]
+struct ErrorInfo {
+    // The info recorded by the API that failed, kept so that it can be passed on unchanged when
+    // the error is returned from a method implemented in Rust.
+    restricted: Option<IRestrictedErrorInfo>,
+    description: Option<String>,
+    restricted_description: Option<String>,
+    capability_sid: Option<String>,
 }
 
 impl Error {
+    /// Creates an error with a message, such as one returned from a delegate or an overridden
+    /// method implemented in Rust, which callers in other languages see as the failure's
+    /// message rather than only its code
+    pub fn new(code: ErrorCode, message: &str) -> Error {
+        Error {
+            code,
+            info: Some(Box::new(ErrorInfo {
+                restricted: None,
+                description: None,
+                restricted_description: Some(message.to_string()),
+                capability_sid: None,
+            })),
+            context: None,
+        }
+    }
+
+    /// Creates an error for a failed HRESULT returned by a WinRT or COM method, along with the
+    /// details the method recorded in the thread's restricted error info
+    ///
+    /// The details are only taken if they were recorded for the same code, so details left over
+    /// from an earlier, handled failure aren't attached to an unrelated error. `ErrorCode::ok`
+    /// calls this, whereas converting an `ErrorCode` with `From` doesn't look for any details.
+    pub fn from_abi(code: ErrorCode) -> Error {
+        Error {
+            code,
+            info: ErrorInfo::take(code).map(Box::new),
+            context: None,
+        }
+    }
+
+    /// Wraps the error with context describing what failed, such as
+    /// "Could not activate Windows.Foundation.Uri"
+    ///
+    /// The new error has the same code and details, displays only the context, and returns the
+    /// original error from `source`, so that error reporters such as anyhow show both.
+    pub fn with_context<S: Into<String>>(self, context: S) -> Error {
+        Error {
+            code: self.code,
+            info: None,
+            context: Some(Box::new((context.into(), self))),
+        }
+    }
+
+    /// The context given with `with_context`, if any
+    pub fn context(&self) -> Option<&str> {
+        self.context.as_ref().map(|context| context.0.as_str())
+    }
+
     pub fn code(&self) -> ErrorCode {
         self.code
     }
+
+    // The error that holds the details, which for an error with context is the one it wraps.
+    fn inner(&self) -> &Error {
+        match &self.context {
+            Some(context) => context.1.inner(),
+            None => self,
+        }
+    }
+
+    /// Records the error's details as the thread's restricted error info, as is expected of
+    /// methods implemented in Rust when they fail, and returns its code for the method to
+    /// return
+    ///
+    /// An error returned by another method passes on the restricted error info that method
+    /// recorded, so callers in other languages, and debuggers, see the original failure.
+    /// Otherwise the message is recorded with `RoOriginateError`. Errors without a message only
+    /// return their code.
+    pub fn originate(self) -> ErrorCode {
+        if let Some(context) = self.context {
+            return context.1.originate();
+        }
+
+        if let Some(restricted) = self.info.as_ref().and_then(|info| info.restricted.as_ref()) {
+            unsafe { runtime::SetRestrictedErrorInfo(restricted.ptr.get() as _) };
+        } else if let Some(message) = self.message() {
+            let message = HString::from(message);
+            unsafe { runtime::RoOriginateError(self.code, message.abi()) };
+        }
+
+        self.code
+    }
+
+    /// The most specific message recorded for the error, such as "Access is denied" along
+    /// with the reason for this particular failure, if any
+    pub fn message(&self) -> Option<&str> {
+        let info = self.inner().info.as_ref()?;
+
+        info.restricted_description
+            .as_deref()
+            .or(info.description.as_deref())
+    }
+
+    /// The generic description of the error code, as opposed to the particular failure
+    pub fn description(&self) -> Option<&str> {
+        self.inner().info.as_ref()?.description.as_deref()
+    }
+
+    /// The security identifier of the capability the app is missing, for errors reported
+    /// because a package doesn't declare a capability the API requires
+    pub fn capability_sid(&self) -> Option<&str> {
+        self.inner().info.as_ref()?.capability_sid.as_deref()
+    }
+}
+
+impl From<ErrorCode> for Error {
+    fn from(code: ErrorCode) -> Self {
+        Error {
+            code,
+            info: None,
+            context: None,
+        }
+    }
+}
+
+/// Shows the error's message and code, e.g. "Access is denied. (0x80070005)", falling back to
+/// the system's text for the code when the API didn't record a message. Errors with context only
+/// show the context, leaving the message to their `source`.
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(context) = self.context() {
+            return f.write_str(context);
+        }
+
+        let message = match self.message() {
+            Some(message) => message.to_string(),
+            None => self.code.message(),
+        };
+
+        if !message.is_empty() {
+            write!(f, "{} ", message.trim_end())?;
+        }
+
+        match self.capability_sid() {
+            Some(sid) => write!(f, "({}, missing capability {})", self.code, sid),
+            None => write!(f, "({})", self.code),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.context
+            .as_ref()
+            .map(|context| &context.1 as &(dyn std::error::Error + 'static))
+    }
+}
+
+/// Lets WinRT calls be made with `?` in implementations of the `std::io` traits, such as those
+/// of the stream interfaces. The error is kept as the source of the `std::io::Error`.
+impl From<Error> for std::io::Error {
+    fn from(error: Error) -> Self {
+        std::io::Error::other(error)
+    }
+}
+
+impl ErrorInfo {
+    fn take(code: ErrorCode) -> Option<ErrorInfo> {
+        let mut restricted: IRestrictedErrorInfo = unsafe { ComInterface::none() };
+
+        unsafe {
+            // Retrieving the info clears it from the thread, just as the caller handling the
+            // error would have in other languages.
+            if runtime::GetRestrictedErrorInfo(restricted.ptr.set() as _).is_err()
+                || restricted.ptr.is_null()
+            {
+                return None;
+            }
+
+            let ptr = restricted.ptr.get();
+            let mut description = std::ptr::null_mut();
+            let mut error = ErrorCode(0);
+            let mut restricted_description = std::ptr::null_mut();
+            let mut capability_sid = std::ptr::null_mut();
+
+            let result = ((*(*ptr)).get_error_details)(
+                ptr,
+                &mut description,
+                &mut error,
+                &mut restricted_description,
+                &mut capability_sid,
+            );
+
+            let info = ErrorInfo {
+                restricted: Some(restricted),
+                description: take_bstr(description),
+                restricted_description: take_bstr(restricted_description),
+                capability_sid: take_bstr(capability_sid),
+            };
+
+            if result.is_ok() && error == code {
+                Some(info)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+// Converts a BSTR returned by `GetErrorDetails` and frees it. Null and empty strings mean that
+// the detail wasn't recorded.
+unsafe fn take_bstr(bstr: *mut u16) -> Option<String> {
+    if bstr.is_null() {
+        return None;
+    }
+
+    let wide = std::slice::from_raw_parts(bstr, runtime::SysStringLen(bstr) as usize);
+    let value = String::from_utf16_lossy(wide);
+    runtime::SysFreeString(bstr);
+
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+#[repr(transparent)]
+struct IRestrictedErrorInfo {
+    ptr: ComPtr<IRestrictedErrorInfo>,
+}
+
+unsafe impl ComInterface for IRestrictedErrorInfo {
+    type VTable = abi_IRestrictedErrorInfo;
+    const GUID: Guid = Guid::from_values(
+        0x82BA_7092,
+        0x4C88,
+        0x427D,
+        [0xA7, 0xBC, 0x16, 0xDD, 0x93, 0xFE, 0xB6, 0x7E],
+    );
+}
+
+// Restricted error info objects are free-threaded.
+unsafe impl Send for IRestrictedErrorInfo {}
+unsafe impl Sync for IRestrictedErrorInfo {}
+
+impl std::fmt::Debug for IRestrictedErrorInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "IRestrictedErrorInfo({:?})", self.ptr.get())
+    }
+}
+
+type RestrictedErrorInfo = *const *const abi_IRestrictedErrorInfo;
+
+#[repr(C)]
+struct abi_IRestrictedErrorInfo {
+    __base: [usize; 3],
+    get_error_details: extern "system" fn(
+        RestrictedErrorInfo,
+        *mut *mut u16,
+        *mut ErrorCode,
+        *mut *mut u16,
+        *mut *mut u16,
+    ) -> ErrorCode,
 }
 
 type HRESULT = i32;
@@ -24,6 +295,13 @@ type HRESULT = i32;
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct ErrorCode(pub HRESULT);
 
+/// Shows the code in hex, as HRESULTs are usually written, e.g. "0x80070005"
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "0x{:08X}", self.0)
+    }
+}
+
 impl ErrorCode {
     #[inline]
     pub fn is_ok(self) -> bool {
@@ -45,7 +323,7 @@ impl ErrorCode {
         if self.is_ok() {
             Ok(())
         } else {
-            Err(Error { code: self })
+            Err(Error::from_abi(self))
         }
     }
 
@@ -58,5 +336,186 @@ impl ErrorCode {
         Ok(value())
     }
 
+    /// Converts a Win32 error code into an ErrorCode (a.k.a HRESULT_FROM_WIN32)
+    #[inline]
+    pub fn from_win32(error: u32) -> ErrorCode {
+        if error as i32 <= 0 {
+            ErrorCode(error as i32)
+        } else {
+            ErrorCode(((error & 0x0000_FFFF) | (7 << 16) | 0x8000_0000) as i32)
+        }
+    }
+
+    /// The system's text for the code, such as "Access is denied.", or an empty string for
+    /// codes that it has no text for
+    pub fn message(self) -> String {
+        const FORMAT_MESSAGE_FROM_SYSTEM: u32 = 0x0000_1000;
+        const FORMAT_MESSAGE_IGNORE_INSERTS: u32 = 0x0000_0200;
+
+        let mut buffer = [0u16; 512];
+
+        let len = unsafe {
+            runtime::FormatMessageW(
+                FORMAT_MESSAGE_FROM_SYSTEM | FORMAT_MESSAGE_IGNORE_INSERTS,
+                std::ptr::null(),
+                self.0 as u32,
+                0,
+                buffer.as_mut_ptr(),
+                buffer.len() as u32,
+                std::ptr::null(),
+            )
+        };
+
+        String::from_utf16_lossy(&buffer[..len as usize])
+            .trim_end()
+            .to_string()
+    }
+
+    pub(crate) fn from_last_error() -> ErrorCode {
+        ErrorCode::from_win32(unsafe { crate::runtime::GetLastError() })
+    }
+
     pub(crate) const NOT_INITIALIZED: ErrorCode = ErrorCode(0x8004_01F0);
+    pub(crate) const NOT_IMPLEMENTED: ErrorCode = ErrorCode(0x8000_4001);
+    pub(crate) const NO_INTERFACE: ErrorCode = ErrorCode(0x8000_4002);
+    pub(crate) const OUT_OF_MEMORY: ErrorCode = ErrorCode(0x8007_000E);
+    pub(crate) const CLASS_NOT_REGISTERED: ErrorCode = ErrorCode(0x8004_0154);
+    pub(crate) const DISCONNECTED: ErrorCode = ErrorCode(0x8001_0108);
+    pub(crate) const OBJECT_NOT_CONNECTED: ErrorCode = ErrorCode(0x8004_01FD);
+    pub(crate) const SERVER_DIED: ErrorCode = ErrorCode(0x8001_0007);
+    pub(crate) const SERVER_DIED_DNE: ErrorCode = ErrorCode(0x8001_0012);
+
+    /// An argument, such as the name of an enum value, is not valid (a.k.a E_INVALIDARG)
+    pub const INVALID_ARGUMENT: ErrorCode = ErrorCode(0x8007_0057);
+
+    /// The call would block a single-threaded apartment (a.k.a RPC_E_WRONG_THREAD)
+    pub const WRONG_THREAD: ErrorCode = ErrorCode(0x8001_010E);
+
+    /// The member is not supported on this version of Windows (a.k.a HRESULT_FROM_WIN32(ERROR_NOT_SUPPORTED))
+    pub const NOT_SUPPORTED: ErrorCode = ErrorCode(0x8007_0032);
+
+    /// The member was introduced in a newer version of Windows than the one running (a.k.a HRESULT_FROM_WIN32(ERROR_OLD_WIN_VERSION))
+    pub const OLD_WIN_VERSION: ErrorCode = ErrorCode(0x8007_047E);
+
+    /// The object has been closed, such as a dispatcher queue that is shutting down (a.k.a RO_E_CLOSED)
+    pub const CLOSED: ErrorCode = ErrorCode(0x8000_0013);
+
+    /// The operation was cancelled (a.k.a HRESULT_FROM_WIN32(ERROR_CANCELLED))
+    pub const CANCELLED: ErrorCode = ErrorCode(0x8007_04C7);
+
+    /// An index or key is out of range, such as a key missing from a map (a.k.a E_BOUNDS)
+    pub const OUT_OF_BOUNDS: ErrorCode = ErrorCode(0x8000_000B);
+
+    /// The element was not found (a.k.a HRESULT_FROM_WIN32(ERROR_NOT_FOUND))
+    pub const NOT_FOUND: ErrorCode = ErrorCode(0x8007_0490);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn originate(code: ErrorCode, message: &str) {
+        Error::new(code, message).originate();
+    }
+
+    #[test]
+    fn restricted_info() {
+        originate(ErrorCode::INVALID_ARGUMENT, "The widget is missing");
+        let error = ErrorCode::INVALID_ARGUMENT.ok().unwrap_err();
+        assert!(error.code() == ErrorCode::INVALID_ARGUMENT);
+        assert!(error.message() == Some("The widget is missing"));
+        assert!(error.to_string() == "The widget is missing (0x80070057)");
+        assert!(error.description().is_some());
+        assert!(error.capability_sid().is_none());
+
+        // The info was taken by the first error.
+        let error = ErrorCode::INVALID_ARGUMENT.ok().unwrap_err();
+        assert!(error.message().is_none());
+
+        // The system's text for the code is shown instead, in whatever language Windows uses.
+        let text = error.to_string();
+        assert!(text.ends_with(" (0x80070057)") && text.len() > " (0x80070057)".len());
+
+        // Info recorded for another code isn't attached.
+        originate(ErrorCode::NOT_SUPPORTED, "Not this one");
+        let error = ErrorCode::INVALID_ARGUMENT.ok().unwrap_err();
+        assert!(error.message().is_none());
+
+        // Converting a code doesn't take the info, which is left for the caller that failed.
+        originate(ErrorCode::INVALID_ARGUMENT, "Left for the caller");
+        assert!(Error::from(ErrorCode::INVALID_ARGUMENT).message().is_none());
+        let error = Error::from_abi(ErrorCode::INVALID_ARGUMENT);
+        assert!(error.message() == Some("Left for the caller"));
+    }
+
+    #[test]
+    fn propagate() {
+        originate(ErrorCode::INVALID_ARGUMENT, "The widget is missing");
+        let error = ErrorCode::INVALID_ARGUMENT.ok().unwrap_err();
+
+        // Returning the error passes on the info that was originally recorded.
+        assert!(error.originate() == ErrorCode::INVALID_ARGUMENT);
+        let error = ErrorCode::INVALID_ARGUMENT.ok().unwrap_err();
+        assert!(error.message() == Some("The widget is missing"));
+        assert!(error.description().is_some());
+    }
+
+    #[test]
+    fn display() {
+        assert!(ErrorCode::NOT_SUPPORTED.to_string() == "0x80070032");
+        assert!(!ErrorCode::NOT_SUPPORTED.message().is_empty());
+        assert!(ErrorCode(0x8000_0000 | 0x1234_5678).message().is_empty());
+
+        let error: Box<dyn std::error::Error + Send + Sync> =
+            Error::from(ErrorCode(0x8123_4567)).into();
+        assert!(error.to_string() == "(0x81234567)");
+    }
+
+    #[test]
+    fn new() {
+        let error = Error::new(ErrorCode::INVALID_ARGUMENT, "The widget is missing");
+        assert!(error.message() == Some("The widget is missing"));
+        assert!(error.description().is_none());
+        assert!(error.originate() == ErrorCode::INVALID_ARGUMENT);
+
+        // The originated message is seen by the caller.
+        let error = ErrorCode::INVALID_ARGUMENT.ok().unwrap_err();
+        assert!(error.message() == Some("The widget is missing"));
+
+        // Errors without messages don't originate any.
+        assert!(
+            Error::from(ErrorCode::INVALID_ARGUMENT).originate() == ErrorCode::INVALID_ARGUMENT
+        );
+        let error = ErrorCode::INVALID_ARGUMENT.ok().unwrap_err();
+        assert!(error.message().is_none());
+    }
+
+    #[test]
+    fn context() {
+        use std::error::Error as _;
+
+        let error = Error::new(ErrorCode::INVALID_ARGUMENT, "The widget is missing")
+            .with_context("Could not activate Contoso.Widget");
+
+        assert!(error.code() == ErrorCode::INVALID_ARGUMENT);
+        assert!(error.context() == Some("Could not activate Contoso.Widget"));
+        assert!(error.message() == Some("The widget is missing"));
+        assert!(error.to_string() == "Could not activate Contoso.Widget");
+
+        let source = error.source().unwrap();
+        assert!(source.to_string() == "The widget is missing (0x80070057)");
+        assert!(source.source().is_none());
+
+        // The chain is kept when the error is passed on as an std::io::Error.
+        let io = std::io::Error::from(error);
+        let error = io.get_ref().unwrap().downcast_ref::<Error>().unwrap();
+        assert!(error.source().is_some());
+
+        // Originating the error records the original message, not the context.
+        let error = Error::new(ErrorCode::INVALID_ARGUMENT, "The widget is missing")
+            .with_context("Could not activate Contoso.Widget");
+        assert!(error.originate() == ErrorCode::INVALID_ARGUMENT);
+        let error = ErrorCode::INVALID_ARGUMENT.ok().unwrap_err();
+        assert!(error.message() == Some("The widget is missing"));
+    }
 }
@@ -0,0 +1,35 @@
+use crate::*;
+
+/// A token identifying a registered event handler
+///
+/// Adding a handler to an event returns a token that is later used to remove it. This is the
+/// `Windows.Foundation.EventRegistrationToken` struct, which every event shares.
+#[repr(C)]
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EventToken {
+    value: i64,
+}
+
+impl EventToken {
+    /// The raw value of the token
+    pub fn value(&self) -> i64 {
+        self.value
+    }
+}
+
+unsafe impl RuntimeType for EventToken {
+    type Abi = Self;
+
+    fn abi(&self) -> Self::Abi {
+        *self
+    }
+
+    fn set_abi(&mut self) -> *mut Self::Abi {
+        self as *mut Self::Abi
+    }
+
+    fn signature() -> String {
+        "struct(Windows.Foundation.EventRegistrationToken;i8)".to_owned()
+    }
+}
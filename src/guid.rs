@@ -1,13 +1,16 @@
-use super::RuntimeType;
+use super::{runtime, Error, ErrorCode, Result, RuntimeType};
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::Mutex;
 
 /// A globally unique identifier [(GUID)](https://docs.microsoft.com/en-us/dotnet/api/system.guid?view=netcore-3.1)
 #[repr(C)]
-#[derive(Clone, Default, PartialEq)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Guid {
-    data1: u32,
-    data2: u16,
-    data3: u16,
-    data4: [u8; 8],
+    pub(crate) data1: u32,
+    pub(crate) data2: u16,
+    pub(crate) data3: u16,
+    pub(crate) data4: [u8; 8],
 }
 
 impl Guid {
@@ -19,25 +22,158 @@ impl Guid {
             data4,
         }
     }
+
+    /// Creates a new random GUID, for authored components and correlation IDs
+    ///
+    /// The GUID is generated by `CoCreateGuid` so it's a version 4 GUID that's unique for all
+    /// practical purposes.
+    pub fn new() -> Result<Guid> {
+        let mut guid = Guid::default();
+        unsafe { runtime::CoCreateGuid(&mut guid).ok()? };
+        Ok(guid)
+    }
+
+    /// Creates a GUID from its value as written, so that `0x96369F54_8EB6_48F0_ABCE_C1B211E627C3`
+    /// is the GUID `96369F54-8EB6-48F0-ABCE-C1B211E627C3`
+    pub const fn from_u128(value: u128) -> Guid {
+        let data4 = (value as u64).to_be_bytes();
+        Guid::from_values(
+            (value >> 96) as u32,
+            (value >> 80) as u16,
+            (value >> 64) as u16,
+            data4,
+        )
+    }
+
+    /// The GUID's value as written, the inverse of `from_u128`
+    pub fn to_u128(&self) -> u128 {
+        (self.data1 as u128) << 96
+            | (self.data2 as u128) << 80
+            | (self.data3 as u128) << 64
+            | u64::from_be_bytes(self.data4) as u128
+    }
+
+    /// Creates a GUID from the 16 bytes that it's stored as, in which the first three fields
+    /// are little-endian
+    pub fn from_bytes(bytes: [u8; 16]) -> Guid {
+        let mut data4 = [0; 8];
+        data4.copy_from_slice(&bytes[8..]);
+
+        Guid::from_values(
+            u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            u16::from_le_bytes([bytes[4], bytes[5]]),
+            u16::from_le_bytes([bytes[6], bytes[7]]),
+            data4,
+        )
+    }
+
+    /// The 16 bytes that the GUID is stored as, such as in files and the registry
+    pub fn to_bytes(&self) -> [u8; 16] {
+        let mut bytes = [0; 16];
+        bytes[..4].copy_from_slice(&self.data1.to_le_bytes());
+        bytes[4..6].copy_from_slice(&self.data2.to_le_bytes());
+        bytes[6..8].copy_from_slice(&self.data3.to_le_bytes());
+        bytes[8..].copy_from_slice(&self.data4);
+        bytes
+    }
+
+    /// The first field, which is the first group of 8 digits as written
+    pub fn data1(&self) -> u32 {
+        self.data1
+    }
+
+    /// The second field, which is the second group of 4 digits as written
+    pub fn data2(&self) -> u16 {
+        self.data2
+    }
+
+    /// The third field, which is the third group of 4 digits as written
+    pub fn data3(&self) -> u16 {
+        self.data3
+    }
+
+    /// The last 8 bytes, which are the remaining 16 digits as written
+    pub fn data4(&self) -> [u8; 8] {
+        self.data4
+    }
+
+    /// Derives the GUID of a parameterized type from its signature
+    ///
+    /// This is the name-based (version 5) GUID of the signature in the WinRT namespace, as
+    /// used for specializations of generic interfaces and delegates.
+    pub fn from_signature(signature: &str) -> Guid {
+        let mut data = vec![
+            0x11, 0xf4, 0x7a, 0xd5, 0x7b, 0x73, 0x42, 0xc0, 0xab, 0xae, 0x87, 0x8b, 0x1e, 0x16,
+            0xad, 0xee,
+        ];
+        data.extend_from_slice(signature.as_bytes());
+        let bytes = crate::sha1::sha1(&data);
+
+        let data1 = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let data2 = u16::from_be_bytes([bytes[4], bytes[5]]);
+        let data3 = (u16::from_be_bytes([bytes[6], bytes[7]]) & 0x0fff) | (5 << 12);
+
+        let mut data4 = [0; 8];
+        data4.copy_from_slice(&bytes[8..16]);
+        data4[0] = (data4[0] & 0x3f) | 0x80;
+
+        Guid::from_values(data1, data2, data3, data4)
+    }
+}
+
+static GENERIC_GUIDS: Mutex<Option<HashMap<TypeId, Guid>>> = Mutex::new(None);
+
+/// Returns the GUID of a generic specialization, computing it from the signature on first use
+///
+/// The GUIDs depend on the type arguments so they can't be constants. Instead they are cached
+/// per type, so the signature is only built and hashed once.
+pub fn generic_guid<T: 'static>(signature: impl FnOnce() -> String) -> Guid {
+    let id = TypeId::of::<T>();
+
+    if let Some(guid) = GENERIC_GUIDS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|guids| guids.get(&id))
+    {
+        return *guid;
+    }
+
+    // The lock isn't held while the signature is built and hashed.
+    let guid = Guid::from_signature(&signature());
+
+    GENERIC_GUIDS
+        .lock()
+        .unwrap()
+        .get_or_insert_with(HashMap::new)
+        .insert(id, guid);
+
+    guid
 }
 
 unsafe impl RuntimeType for Guid {
     type Abi = Self;
 
     fn abi(&self) -> Self::Abi {
-        self.clone()
+        *self
     }
 
     fn set_abi(&mut self) -> *mut Self::Abi {
         self as *mut Self::Abi
     }
+
+    fn signature() -> String {
+        "g16".to_owned()
+    }
 }
 
-impl std::fmt::Debug for Guid {
+// GUIDs are shown in their usual form, such as `96369F54-8EB6-48F0-ABCE-C1B211E627C3`, which
+// is also how they're parsed.
+impl std::fmt::Display for Guid {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{:08X?}-{:04X?}-{:04X?}-{:02X?}{:02X?}-{:02X?}{:02X?}{:02X?}{:02X?}{:02X?}{:02X?}",
+            "{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}",
             self.data1,
             self.data2,
             self.data3,
@@ -53,62 +189,168 @@ impl std::fmt::Debug for Guid {
     }
 }
 
+impl std::fmt::Debug for Guid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl std::str::FromStr for Guid {
+    type Err = Error;
+
+    /// Parses a GUID such as `96369F54-8EB6-48F0-ABCE-C1B211E627C3` or, as in the registry,
+    /// `{96369F54-8EB6-48F0-ABCE-C1B211E627C3}`, in either case
+    fn from_str(value: &str) -> Result<Self> {
+        let value = value
+            .strip_prefix('{')
+            .and_then(|value| value.strip_suffix('}'))
+            .unwrap_or(value);
+
+        const DASHES: [usize; 4] = [8, 13, 18, 23];
+        let bytes = value.as_bytes();
+
+        if bytes.len() != 36 || DASHES.iter().any(|dash| bytes[*dash] != b'-') {
+            return Err(ErrorCode::INVALID_ARGUMENT.into());
+        }
+
+        let mut guid = 0;
+
+        for (_, digit) in bytes
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !DASHES.contains(index))
+        {
+            match (*digit as char).to_digit(16) {
+                Some(digit) => guid = guid << 4 | digit as u128,
+                None => return Err(ErrorCode::INVALID_ARGUMENT.into()),
+            }
+        }
+
+        Ok(Guid::from_u128(guid))
+    }
+}
+
+/// Parses a GUID that's known to be valid, such as a literal
+///
+/// # Panics
+/// Panics if the string isn't a GUID. Use `str::parse` to parse strings that may not be.
 impl From<&str> for Guid {
     fn from(value: &str) -> Guid {
-        assert!(value.len() == 36, "Invalid GUID string");
-        let mut bytes = value.bytes();
-
-        let a = ((bytes.next_u32() * 16 + bytes.next_u32()) << 24)
-            + ((bytes.next_u32() * 16 + bytes.next_u32()) << 16)
-            + ((bytes.next_u32() * 16 + bytes.next_u32()) << 8)
-            + bytes.next_u32() * 16
-            + bytes.next_u32();
-        assert!(bytes.next().unwrap() == b'-', "Invalid GUID string");
-        let b = ((bytes.next_u16() * 16 + (bytes.next_u16())) << 8)
-            + bytes.next_u16() * 16
-            + bytes.next_u16();
-        assert!(bytes.next().unwrap() == b'-', "Invalid GUID string");
-        let c = ((bytes.next_u16() * 16 + bytes.next_u16()) << 8)
-            + bytes.next_u16() * 16
-            + bytes.next_u16();
-        assert!(bytes.next().unwrap() == b'-', "Invalid GUID string");
-        let d = bytes.next_u8() * 16 + bytes.next_u8();
-        let e = bytes.next_u8() * 16 + bytes.next_u8();
-        assert!(bytes.next().unwrap() == b'-', "Invalid GUID string");
-
-        let f = bytes.next_u8() * 16 + bytes.next_u8();
-        let g = bytes.next_u8() * 16 + bytes.next_u8();
-        let h = bytes.next_u8() * 16 + bytes.next_u8();
-        let i = bytes.next_u8() * 16 + bytes.next_u8();
-        let j = bytes.next_u8() * 16 + bytes.next_u8();
-        let k = bytes.next_u8() * 16 + bytes.next_u8();
-
-        Guid::from_values(a, b, c, [d, e, f, g, h, i, j, k])
+        value.parse().expect("Invalid GUID string")
     }
 }
 
-trait HexReader {
-    fn next_u8(&mut self) -> u8;
-    fn next_u16(&mut self) -> u16;
-    fn next_u32(&mut self) -> u32;
+// GUIDs are serialized in their usual string form, e.g. `96369F54-8EB6-48F0-ABCE-C1B211E627C3`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Guid {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
 }
 
-impl HexReader for std::str::Bytes<'_> {
-    fn next_u8(&mut self) -> u8 {
-        let value = self.next().unwrap();
-        match value {
-            b'0'..=b'9' => value - b'0',
-            b'A'..=b'F' => 10 + value - b'A',
-            b'a'..=b'f' => 10 + value - b'a',
-            _ => panic!("Invalid GUID string"),
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Guid {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Self, D::Error> {
+        let value = <std::borrow::Cow<str>>::deserialize(deserializer)?;
+
+        value.parse().map_err(|_| {
+            serde::de::Error::invalid_value(
+                serde::de::Unexpected::Str(&value),
+                &"a GUID such as 96369F54-8EB6-48F0-ABCE-C1B211E627C3",
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_signature() {
+        // IVector<String> and IAsyncOperation<Boolean>
+        let guid =
+            Guid::from_signature("pinterface({913337e9-11a1-4345-a3a2-4e7f956e222d};string)");
+        assert!(guid == Guid::from("98B9ACC1-4B56-532E-AC73-03D5291CCA90"));
+
+        let guid = Guid::from_signature("pinterface({9fc2b0bb-e446-44e2-aa61-9cab8f636af2};b1)");
+        assert!(guid == Guid::from("CDB5EFB3-5788-509D-9BE1-71CCB8A3362A"));
+    }
+
+    #[test]
+    fn parse() {
+        let guid = Guid::from_values(
+            0x9636_9F54,
+            0x8EB6,
+            0x48F0,
+            [0xAB, 0xCE, 0xC1, 0xB2, 0x11, 0xE6, 0x27, 0xC3],
+        );
+
+        assert!(
+            "96369F54-8EB6-48F0-ABCE-C1B211E627C3"
+                .parse::<Guid>()
+                .unwrap()
+                == guid
+        );
+        assert!(
+            "{96369f54-8eb6-48f0-abce-c1b211e627c3}"
+                .parse::<Guid>()
+                .unwrap()
+                == guid
+        );
+        assert!(guid.to_string() == "96369F54-8EB6-48F0-ABCE-C1B211E627C3");
+        assert!(format!("{:?}", guid) == "96369F54-8EB6-48F0-ABCE-C1B211E627C3");
+        assert!(guid.to_string().parse::<Guid>().unwrap() == guid);
+
+        for invalid in &[
+            "",
+            "96369F54-8EB6-48F0-ABCE-C1B211E627C",
+            "96369F54-8EB6-48F0-ABCE-C1B211E627CG",
+            "96369F548EB6-48F0-ABCE-C1B211E627C3-",
+            "{96369F54-8EB6-48F0-ABCE-C1B211E627C3",
+            "+6369F54-8EB6-48F0-ABCE-C1B211E627C3",
+        ] {
+            assert!(invalid.parse::<Guid>().unwrap_err().code() == ErrorCode::INVALID_ARGUMENT);
         }
     }
 
-    fn next_u16(&mut self) -> u16 {
-        self.next_u8().into()
+    #[test]
+    fn bytes() {
+        let guid = Guid::from("96369F54-8EB6-48F0-ABCE-C1B211E627C3");
+        assert!(guid.to_u128() == 0x9636_9F54_8EB6_48F0_ABCE_C1B2_11E6_27C3);
+        assert!(Guid::from_u128(guid.to_u128()) == guid);
+        assert!((guid.data1(), guid.data2(), guid.data3()) == (0x9636_9F54, 0x8EB6, 0x48F0));
+        assert!(guid.data4() == [0xAB, 0xCE, 0xC1, 0xB2, 0x11, 0xE6, 0x27, 0xC3]);
+
+        // The first three fields are stored little-endian.
+        let bytes = guid.to_bytes();
+        assert!(bytes[..8] == [0x54, 0x9F, 0x36, 0x96, 0xB6, 0x8E, 0xF0, 0x48]);
+        assert!(bytes[8..] == guid.data4());
+        assert!(Guid::from_bytes(bytes) == guid);
+    }
+
+    #[test]
+    fn new() -> Result<()> {
+        let a = Guid::new()?;
+        let b = Guid::new()?;
+
+        assert!(a != b);
+        assert!(a != Guid::default());
+        assert!(a.data3() >> 12 == 4);
+
+        Ok(())
     }
 
-    fn next_u32(&mut self) -> u32 {
-        self.next_u8().into()
+    #[test]
+    fn generic_guid_is_cached() {
+        let first = generic_guid::<(u8, u16)>(|| "b1".to_owned());
+        let second = generic_guid::<(u8, u16)>(|| panic!("signature rebuilt"));
+        assert!(first == second);
+        assert!(first == Guid::from_signature("b1"));
     }
 }
@@ -21,6 +21,22 @@ impl HString {
         }
     }
 
+    /// Create a string from 16-bit wide characters (wchars), which needn't be valid UTF-16
+    pub fn from_wide(value: &[u16]) -> HString {
+        if value.is_empty() {
+            return HString::new();
+        }
+
+        let ptr = Header::alloc(value.len() as u32);
+
+        unsafe {
+            ptr::copy_nonoverlapping(value.as_ptr(), (*ptr).data, value.len());
+            ptr::write((*ptr).data.add(value.len()), 0);
+        }
+
+        Self { ptr }
+    }
+
     /// Check whether the HString is the empty string or not
     pub fn is_empty(&self) -> bool {
         self.ptr.is_null()
@@ -45,6 +61,21 @@ impl HString {
         unsafe { std::slice::from_raw_parts((*header).data, (*header).len as usize) }
     }
 
+    /// Get the characters of the string, decoding surrogate pairs
+    ///
+    /// Halves of surrogate pairs that aren't paired, which strings may hold since they're
+    /// UTF-16 that isn't validated, are returned as the replacement character.
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        std::char::decode_utf16(self.as_wide().iter().copied())
+            .map(|c| c.unwrap_or(std::char::REPLACEMENT_CHARACTER))
+    }
+
+    /// Converts the string to a Rust `String`, replacing halves of surrogate pairs that aren't
+    /// paired with the replacement character
+    pub fn to_string_lossy(&self) -> String {
+        String::from_utf16_lossy(self.as_wide())
+    }
+
     /// Clear the contents of the string and free the memory if the last handle to the string data
     pub fn clear(&mut self) {
         if self.is_empty() {
@@ -75,6 +106,10 @@ unsafe impl RuntimeType for HString {
         self.clear();
         &mut self.ptr
     }
+
+    fn signature() -> String {
+        "string".to_owned()
+    }
 }
 
 impl Default for HString {
@@ -104,8 +139,8 @@ impl Drop for HString {
 impl std::fmt::Display for HString {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use std::fmt::Write;
-        for c in std::char::decode_utf16(self.as_wide().iter().cloned()) {
-            f.write_char(c.map_err(|_| std::fmt::Error)?)?
+        for c in self.chars() {
+            f.write_char(c)?
         }
         Ok(())
     }
@@ -158,38 +193,148 @@ impl PartialEq for HString {
     }
 }
 
-impl PartialEq<String> for HString {
-    fn eq(&self, other: &String) -> bool {
-        self == other.as_str()
+impl Eq for HString {}
+
+// Strings are ordered by their UTF-16 code units, which is how Windows orders them as well.
+impl PartialOrd for HString {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
     }
 }
 
-impl PartialEq<str> for HString {
-    fn eq(&self, other: &str) -> bool {
-        self == other
+impl Ord for HString {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.as_wide().cmp(other.as_wide())
     }
 }
 
-impl PartialEq<&str> for HString {
-    fn eq(&self, other: &&str) -> bool {
-        self.as_wide().iter().copied().eq(other.encode_utf16())
+impl std::hash::Hash for HString {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.as_wide().hash(state)
     }
 }
 
+// Rust strings are compared by encoding them as UTF-16 so that they agree with comparing
+// HStrings.
+macro_rules! compare_str {
+    ($($t:ty),+) => {
+        $(impl PartialEq<$t> for HString {
+            fn eq(&self, other: &$t) -> bool {
+                self.as_wide().iter().copied().eq(other.encode_utf16())
+            }
+        }
+
+        impl PartialEq<HString> for $t {
+            fn eq(&self, other: &HString) -> bool {
+                other == self
+            }
+        }
+
+        impl PartialOrd<$t> for HString {
+            fn partial_cmp(&self, other: &$t) -> Option<std::cmp::Ordering> {
+                Some(self.as_wide().iter().copied().cmp(other.encode_utf16()))
+            }
+        }
+
+        impl PartialOrd<HString> for $t {
+            fn partial_cmp(&self, other: &HString) -> Option<std::cmp::Ordering> {
+                other.partial_cmp(self).map(std::cmp::Ordering::reverse)
+            }
+        })*
+    };
+}
+
+compare_str!(str, &str, String);
+
 impl<'a> From<&'a HString> for String {
     fn from(hstring: &HString) -> Self {
-        String::from_utf16(hstring.as_wide()).unwrap()
+        hstring.to_string_lossy()
     }
 }
 
 impl From<HString> for String {
     fn from(hstring: HString) -> Self {
-        hstring.into()
+        hstring.to_string_lossy()
     }
 }
 
 const REFERENCE_FLAG: u32 = 1;
 
+// Strings shorter than this, leaving room for the null terminator, are passed by reference
+// without being allocated.
+const REFERENCE_CAPACITY: usize = 64;
+
+/// A string that is passed to methods as a "fast-pass" HSTRING, without allocating it
+///
+/// The header and characters of short strings are held by the reference itself, typically on
+/// the stack, rather than being allocated and freed for the call, which is how methods are
+/// passed a `&str`. This is what `WindowsCreateStringReference` creates. A method that keeps
+/// the string makes a copy of its own. Longer strings are allocated like an `HString`.
+pub struct HStringReference {
+    header: Header,
+    buffer: [u16; REFERENCE_CAPACITY],
+    allocated: HString,
+}
+
+impl HStringReference {
+    /// Creates a reference to a copy of the string as UTF-16
+    pub fn new(value: &str) -> Self {
+        let mut reference = Self {
+            header: Header {
+                flags: REFERENCE_FLAG,
+                len: 0,
+                _0: 0,
+                _1: 0,
+                data: ptr::null_mut(),
+                shared: std::mem::MaybeUninit::uninit(),
+            },
+            buffer: [0; REFERENCE_CAPACITY],
+            allocated: HString::new(),
+        };
+
+        // A UTF-8 string has at least as many bytes as it has UTF-16 code units.
+        if value.len() >= REFERENCE_CAPACITY && value.encode_utf16().count() >= REFERENCE_CAPACITY {
+            reference.allocated = value.into();
+            return reference;
+        }
+
+        for (index, wide) in value.encode_utf16().enumerate() {
+            reference.buffer[index] = wide;
+            reference.header.len = index as u32 + 1;
+        }
+
+        reference
+    }
+
+    /// Get the string as 16-bit wide characters (wchars)
+    pub fn as_wide(&self) -> &[u16] {
+        if self.header.len == 0 {
+            self.allocated.as_wide()
+        } else {
+            &self.buffer[..self.header.len as usize]
+        }
+    }
+
+    /// The ABI pointer to the string, which refers to the reference and so is only valid until
+    /// it's moved or dropped
+    pub fn abi(&mut self) -> *mut Header {
+        if self.header.len == 0 {
+            return self.allocated.abi();
+        }
+
+        // The header is pointed at the buffer here since the reference may have moved since
+        // it was created.
+        self.header.data = self.buffer.as_mut_ptr();
+        &mut self.header
+    }
+}
+
+impl std::fmt::Debug for HStringReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", String::from_utf16_lossy(self.as_wide()))
+    }
+}
+
 #[repr(C)]
 pub struct Header {
     flags: u32,
@@ -280,6 +425,74 @@ mod tests {
         assert!(HString::from("Hello").to_string() == String::from("Hello"));
     }
 
+    #[test]
+    fn reference() {
+        let mut reference = HStringReference::new("Hello");
+        assert!(reference.as_wide() == HString::from("Hello").as_wide());
+
+        // Short strings are held by the reference along with a null terminator.
+        let header = reference.abi();
+        unsafe {
+            assert!((*header).flags == REFERENCE_FLAG);
+            assert!((*header).len == 5);
+            assert!(*(*header).data.add(5) == 0);
+            assert!((*header).data == reference.buffer.as_mut_ptr());
+        }
+
+        // Copies of a reference are allocated since the reference doesn't outlive the call.
+        let copy = std::mem::ManuallyDrop::new(HString { ptr: header });
+        let copy = (*copy).clone();
+        assert!(copy == "Hello");
+        unsafe { assert!((*copy.ptr).flags == 0) };
+
+        let long = "a".repeat(REFERENCE_CAPACITY);
+        let mut reference = HStringReference::new(&long);
+        assert!(reference.abi() == reference.allocated.abi());
+        assert!(reference.as_wide().len() == REFERENCE_CAPACITY);
+
+        assert!(HStringReference::new("").abi().is_null());
+    }
+
+    #[test]
+    fn comparisons() {
+        let hello = HString::from("Hello");
+
+        // Both sides of a comparison may be an HString.
+        assert!(hello == *"Hello" && *"Hello" == hello);
+        assert!(hello == String::from("Hello") && String::from("Hello") == hello);
+        assert!("Hello" == hello);
+
+        assert!(hello < HString::from("World"));
+        assert!(hello > "Hell" && "Hell" < hello);
+        assert!(hello < String::from("Hello world"));
+        assert!(HString::new() < hello);
+
+        let mut set = std::collections::BTreeSet::new();
+        set.insert(HString::from("b"));
+        set.insert(HString::from("a"));
+        set.insert(HString::from("b"));
+        assert!(set
+            .iter()
+            .map(HString::to_string_lossy)
+            .eq(["a", "b"].iter().copied()));
+
+        assert!(String::from(hello.clone()) == "Hello");
+        assert!(hello.to_string_lossy() == "Hello");
+    }
+
+    #[test]
+    fn surrogates() {
+        let crab = HString::from("🦀!");
+        assert!(crab.len() == 3);
+        assert!(crab.chars().eq("🦀!".chars()));
+
+        // Unpaired halves of surrogate pairs are replaced rather than failing.
+        let lone = HString::from_wide(&[0xD83E, 0x21]);
+        assert!(lone.as_wide() == [0xD83E, 0x21]);
+        assert!(lone.to_string() == "\u{FFFD}!");
+        assert!(String::from(&lone) == "\u{FFFD}!");
+    }
+
     #[test]
     fn display_format() {
         let value = HString::from("Hello world");
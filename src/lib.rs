@@ -36,12 +36,26 @@
 //! string: http://kennykerr.ca/
 //! ```
 
+mod abi;
 #[doc(hidden)]
 pub mod activation;
+mod agile;
+mod apartment;
+mod api_information;
 mod array;
+mod boolean;
+mod bootstrap;
+mod boxing;
+mod buffer;
+mod cached;
+mod cancellation;
+mod char16;
 mod com_interface;
 mod com_ptr;
+mod compose;
+mod delegate;
 mod error;
+mod event_token;
 mod guid;
 mod hstring;
 mod object;
@@ -50,24 +64,88 @@ mod ref_count;
 mod runtime;
 mod runtime_name;
 mod runtime_type;
+mod sha1;
+mod startup;
 mod try_into;
 mod unknown;
+mod variant;
+mod verify;
+mod vtable;
+mod wait;
+mod weak;
 
+#[doc(hidden)]
+pub use abi::{abi_call, abi_call_return, default_interface, query_with_guid, AbiArgs};
 #[doc(inline)]
-pub use activation::IActivationFactory;
+pub use activation::{register_library, IActivationFactory};
+pub use agile::AgileRef;
+pub use apartment::{init_apartment, Apartment, ApartmentType};
+pub use api_information::{
+    is_api_contract_present, is_event_present, is_method_present, is_property_present,
+    is_type_present,
+};
+#[doc(hidden)]
+pub use api_information::{MemberKind, MethodCheck};
+#[doc(hidden)]
+pub use array::fill_array_abi;
 pub use array::Array;
+pub use boolean::Boolean;
+pub use bootstrap::WindowsAppSdk;
+pub use boxing::{box_value, unbox, Boxable, Unboxable};
+pub use buffer::MemoryBufferBytes;
+#[doc(hidden)]
+pub use buffer::{
+    buffer_as_mut_slice, buffer_as_slice, buffer_copy_from, buffer_to_vec,
+    memory_buffer_as_mut_slice, memory_buffer_as_slice, memory_buffer_copy_from,
+    memory_buffer_to_vec,
+};
+pub use cached::Cached;
+pub use cancellation::{CancellationSource, CancellationToken, Cancelled};
+pub use char16::Char16;
 pub use com_interface::ComInterface;
 pub use com_ptr::ComPtr;
+#[doc(hidden)]
+pub use compose::Composer;
+#[doc(hidden)]
+pub use delegate::Delegate;
 pub use error::*;
+pub use event_token::EventToken;
+#[doc(hidden)]
+pub use guid::generic_guid;
 pub use guid::Guid;
-pub use hstring::HString;
-pub use object::Object;
-pub use param::Param;
+pub use hstring::{HString, HStringReference};
+pub use object::{Object, TrustLevel};
+pub use param::{OptionOut, Param, StringParam};
 pub use runtime_name::RuntimeName;
 pub use runtime_type::RuntimeType;
+pub use startup::{startup_report, StartupEvent, StartupKind, StartupReport};
 pub use try_into::TryInto;
 pub use unknown::IUnknown;
-pub use winrt_macros::import;
+pub use variant::{Variant, VariantElement};
+#[doc(hidden)]
+pub use verify::verify_interface;
+#[doc(hidden)]
+pub use vtable::vtable_method;
+#[doc(hidden)]
+pub use wait::{wait_for, Signal};
+pub use weak::WeakRef;
+pub use winrt_macros::{guid, import, FromValueSet, ToValueSet};
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde;
+
+#[cfg(feature = "futures-io")]
+#[doc(hidden)]
+pub use futures_io;
+
+#[cfg(feature = "futures-core")]
+#[doc(hidden)]
+pub use futures_core;
+
+#[cfg(feature = "chrono")]
+#[doc(hidden)]
+pub use chrono;
 
 /// A convenient alias of a void pointer
 pub type RawPtr = *mut std::ffi::c_void;
@@ -4,12 +4,13 @@ use crate::*;
 ///
 /// Objects implement the [IInspectable interface](https://docs.microsoft.com/en-us/windows/win32/api/inspectable/nn-inspectable-iinspectable)
 #[repr(transparent)]
-#[derive(Default, Clone)]
+#[derive(Clone)]
 pub struct Object {
     ptr: ComPtr<Object>,
 }
 
 impl Object {
+    /// Returns the object's runtime class name, such as `Windows.Foundation.Uri`
     pub fn type_name(&self) -> Result<HString> {
         let this = self.ptr.get();
         if this.is_null() {
@@ -21,8 +22,80 @@ impl Object {
         }
         Ok(string)
     }
+
+    /// Returns the IIDs of the WinRT interfaces the object implements, other than `IUnknown`
+    /// and `IInspectable`. Objects may leave out interfaces they'd rather not advertise, so
+    /// this can be used to log what an API returned but not in place of a query.
+    pub fn iids(&self) -> Result<Vec<Guid>> {
+        let this = self.ptr.get();
+        if this.is_null() {
+            panic!("The `this` pointer was null when calling method");
+        }
+        let mut count = 0;
+        let mut iids = std::ptr::null_mut();
+        unsafe {
+            ((*(*(this))).iids)(this, &mut count, &mut iids).ok()?;
+            let result = if iids.is_null() {
+                Vec::new()
+            } else {
+                std::slice::from_raw_parts(iids, count as usize).to_vec()
+            };
+            runtime::CoTaskMemFree(iids as RawPtr);
+            Ok(result)
+        }
+    }
+
+    /// Returns the object's trust level
+    pub fn trust_level(&self) -> Result<TrustLevel> {
+        let this = self.ptr.get();
+        if this.is_null() {
+            panic!("The `this` pointer was null when calling method");
+        }
+        let mut level = 0;
+        unsafe {
+            ((*(*(this))).trust_level)(this, &mut level).ok()?;
+        }
+        Ok(match level {
+            0 => TrustLevel::Base,
+            1 => TrustLevel::Partial,
+            _ => TrustLevel::Full,
+        })
+    }
 }
 
+/// The trust level of an object, as reported by `Object::trust_level`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TrustLevel {
+    /// The object is a component that can be used by any app
+    Base,
+    /// The object is partially trusted
+    Partial,
+    /// The object is part of the system and may only be activated by trusted code
+    Full,
+}
+
+impl std::fmt::Debug for Object {
+    /// Formats the object as its runtime class name and pointer
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_null() {
+            return write!(f, "null");
+        }
+
+        match self.type_name() {
+            Ok(name) => write!(f, "{}({:?})", name, self.ptr.get()),
+            Err(_) => write!(f, "{:?}", self.ptr.get()),
+        }
+    }
+}
+
+impl PartialEq for Object {
+    fn eq(&self, other: &Self) -> bool {
+        self.query::<IUnknown>() == other.query::<IUnknown>()
+    }
+}
+
+impl Eq for Object {}
+
 unsafe impl ComInterface for Object {
     type VTable = abi_IInspectable;
     const GUID: Guid = Guid::from_values(
@@ -43,13 +116,23 @@ unsafe impl RuntimeType for Object {
     fn set_abi(&mut self) -> *mut Self::Abi {
         self.ptr.set()
     }
+
+    fn signature() -> String {
+        "cinterface(IInspectable)".to_owned()
+    }
 }
 
 #[repr(C)]
 pub struct abi_IInspectable {
-    __base: [usize; 4],
+    __base: [usize; 3],
+    iids: extern "system" fn(
+        *const *const object::abi_IInspectable,
+        *mut u32,
+        *mut *mut Guid,
+    ) -> ErrorCode,
     type_name: extern "system" fn(
         *const *const object::abi_IInspectable,
         *mut <HString as RuntimeType>::Abi,
     ) -> ErrorCode,
+    trust_level: extern "system" fn(*const *const object::abi_IInspectable, *mut i32) -> ErrorCode,
 }
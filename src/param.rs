@@ -7,6 +7,8 @@ pub enum Param<'a, T: RuntimeType> {
 }
 
 impl<'a, T: RuntimeType> Param<'a, T> {
+    /// The ABI of the parameter, which may refer to the parameter itself and so is only valid
+    /// until it's moved or dropped
     pub fn abi(&mut self) -> T::Abi {
         match self {
             Param::Borrowed(value) => value.abi(),
@@ -38,3 +40,81 @@ impl<'a> From<String> for Param<'a, HString> {
         Param::Owned(value.into())
     }
 }
+
+/// A WinRT method parameter of type `HString`
+///
+/// Methods take their string parameters as a `StringParam` rather than a `Param<HString>` so
+/// that a `&str` is passed as an [`HStringReference`] instead of allocating an `HString`.
+pub enum StringParam<'a> {
+    Borrowed(&'a HString),
+    Owned(HString),
+    Reference(HStringReference),
+}
+
+impl<'a> StringParam<'a> {
+    /// The ABI of the parameter, which may refer to the parameter itself and so is only valid
+    /// until it's moved or dropped
+    pub fn abi(&mut self) -> <HString as RuntimeType>::Abi {
+        match self {
+            StringParam::Borrowed(value) => value.abi(),
+            StringParam::Owned(value) => value.abi(),
+            StringParam::Reference(value) => value.abi(),
+        }
+    }
+}
+
+impl<'a> From<HString> for StringParam<'a> {
+    fn from(value: HString) -> StringParam<'a> {
+        StringParam::Owned(value)
+    }
+}
+
+impl<'a> From<&'a HString> for StringParam<'a> {
+    fn from(value: &'a HString) -> StringParam<'a> {
+        StringParam::Borrowed(value)
+    }
+}
+
+impl<'a> From<&'a str> for StringParam<'a> {
+    fn from(value: &'a str) -> StringParam<'a> {
+        StringParam::Reference(HStringReference::new(value))
+    }
+}
+
+impl<'a> From<String> for StringParam<'a> {
+    fn from(value: String) -> StringParam<'a> {
+        StringParam::Owned(value.into())
+    }
+}
+
+/// Receives an object that a method writes to an out parameter into an `Option`
+///
+/// Generated methods take object out parameters as `&mut Option<T>` and pass the method
+/// `abi()`. The object it wrote, or `None` if it wrote null, is stored once this is dropped.
+#[doc(hidden)]
+pub struct OptionOut<'a, T: ComInterface + RuntimeType> {
+    option: &'a mut Option<T>,
+    value: T,
+}
+
+impl<'a, T: ComInterface + RuntimeType> OptionOut<'a, T> {
+    pub fn new(option: &'a mut Option<T>) -> Self {
+        Self {
+            option,
+            value: unsafe { T::none() },
+        }
+    }
+
+    /// The ABI of the out parameter, which refers to this and so is only valid until it's moved
+    /// or dropped
+    pub fn abi(&mut self) -> *mut T::Abi {
+        self.value.set_abi()
+    }
+}
+
+impl<'a, T: ComInterface + RuntimeType> Drop for OptionOut<'a, T> {
+    fn drop(&mut self) {
+        let value = std::mem::replace(&mut self.value, unsafe { T::none() });
+        *self.option = if value.is_null() { None } else { Some(value) };
+    }
+}
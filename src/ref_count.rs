@@ -13,11 +13,23 @@ impl RefCount {
     }
 
     pub fn addref(&self) -> u32 {
-        self.value.fetch_add(1, Ordering::Relaxed) + 1
+        let previous = self.value.fetch_add(1, Ordering::Relaxed);
+
+        if cfg!(feature = "strict") && previous == 0 {
+            panic!("An object was referenced again after its last reference was released");
+        }
+
+        previous + 1
     }
 
     pub fn release(&self) -> u32 {
-        let remaining = self.value.fetch_sub(1, Ordering::Release) - 1;
+        let previous = self.value.fetch_sub(1, Ordering::Release);
+
+        if cfg!(feature = "strict") && previous == 0 {
+            panic!("An object was released more times than it was referenced");
+        }
+
+        let remaining = previous - 1;
 
         if remaining == 0 {
             atomic::fence(Ordering::Acquire);
@@ -26,3 +38,34 @@ impl RefCount {
         remaining
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count() {
+        let count = RefCount::new(1);
+        assert!(count.addref() == 2);
+        assert!(count.release() == 1);
+        assert!(count.release() == 0);
+    }
+
+    #[test]
+    #[cfg(feature = "strict")]
+    #[should_panic(expected = "released more times than it was referenced")]
+    fn strict_release() {
+        let count = RefCount::new(1);
+        count.release();
+        count.release();
+    }
+
+    #[test]
+    #[cfg(feature = "strict")]
+    #[should_panic(expected = "referenced again after its last reference was released")]
+    fn strict_addref() {
+        let count = RefCount::new(1);
+        count.release();
+        count.addref();
+    }
+}
@@ -1,19 +1,64 @@
 use crate::{hstring, ErrorCode, Guid, RawPtr};
 
+/// Searches for a DLL in the app's directory, System32, and the directories added with
+/// `AddDllDirectory`, but not the current directory or `PATH`
+pub const LOAD_LIBRARY_SEARCH_DEFAULT_DIRS: u32 = 0x0000_1000;
+
 #[link(name = "kernel32")]
 extern "system" {
     pub fn GetProcessHeap() -> RawPtr;
     pub fn HeapAlloc(heap: RawPtr, flags: u32, bytes: usize) -> RawPtr;
     pub fn HeapFree(heap: RawPtr, flags: u32, ptr: RawPtr) -> i32;
+    pub fn GetLastError() -> u32;
+    pub fn FormatMessageW(
+        flags: u32,
+        source: *const std::ffi::c_void,
+        message: u32,
+        language: u32,
+        buffer: *mut u16,
+        size: u32,
+        args: *const std::ffi::c_void,
+    ) -> u32;
+    pub fn LoadLibraryExW(name: *const u16, file: RawPtr, flags: u32) -> RawPtr;
+    pub fn GetProcAddress(library: RawPtr, name: *const u8) -> RawPtr;
+    pub fn FreeLibrary(library: RawPtr) -> i32;
 }
 
 #[link(name = "onecore")]
 extern "system" {
     // TODO: get rid of these (not available on Windows 7) - we'll load these dynamically
     pub fn CoIncrementMTAUsage(cookie: *mut RawPtr) -> ErrorCode;
+    pub fn CoGetApartmentType(kind: *mut i32, qualifier: *mut i32) -> ErrorCode;
+    pub fn CoTaskMemFree(ptr: RawPtr);
+    pub fn CoCreateGuid(guid: *mut Guid) -> ErrorCode;
+    pub fn RoInitialize(kind: i32) -> ErrorCode;
+    pub fn RoUninitialize();
+    pub fn GetRestrictedErrorInfo(info: *mut RawPtr) -> ErrorCode;
+    pub fn SetRestrictedErrorInfo(info: RawPtr) -> ErrorCode;
+    pub fn RoOriginateError(code: ErrorCode, message: *mut hstring::Header) -> i32;
+    pub fn RoGetAgileReference(
+        options: u32,
+        interface: &Guid,
+        object: RawPtr,
+        reference: *mut RawPtr,
+    ) -> ErrorCode;
     pub fn RoGetActivationFactory(
         hstring: *mut hstring::Header,
         interface: &Guid,
         result: *mut RawPtr,
     ) -> ErrorCode;
 }
+
+#[link(name = "oleaut32")]
+extern "system" {
+    pub fn SafeArrayCreateVector(vt: u16, lower: i32, count: u32) -> RawPtr;
+    pub fn SafeArrayAccessData(array: RawPtr, data: *mut RawPtr) -> ErrorCode;
+    pub fn SafeArrayUnaccessData(array: RawPtr) -> ErrorCode;
+    pub fn SafeArrayGetDim(array: RawPtr) -> u32;
+    pub fn SafeArrayGetLBound(array: RawPtr, dim: u32, bound: *mut i32) -> ErrorCode;
+    pub fn SafeArrayGetUBound(array: RawPtr, dim: u32, bound: *mut i32) -> ErrorCode;
+    pub fn SysAllocStringLen(value: *const u16, len: u32) -> *mut u16;
+    pub fn SysStringLen(value: *const u16) -> u32;
+    pub fn SysFreeString(value: *mut u16);
+    pub fn VariantClear(variant: RawPtr) -> ErrorCode;
+}
@@ -1,4 +1,35 @@
+use crate::*;
+
 /// A WinRT type that can be identified by a name
 pub trait RuntimeName {
     const NAME: &'static str;
+
+    /// Whether the type is one of the primitive types built into WinRT, whose names aren't
+    /// found in metadata, such as `Int32` or `String`
+    const PRIMITIVE: bool = false;
+}
+
+macro_rules! primitive_runtime_name {
+    ($($t:ty => $name:literal),+) => {
+        $(impl RuntimeName for $t {
+            const NAME: &'static str = $name;
+            const PRIMITIVE: bool = true;
+        })*
+    };
+}
+
+primitive_runtime_name! {
+    bool => "Boolean",
+    u8 => "UInt8",
+    i16 => "Int16",
+    u16 => "UInt16",
+    i32 => "Int32",
+    u32 => "UInt32",
+    i64 => "Int64",
+    u64 => "UInt64",
+    f32 => "Single",
+    f64 => "Double",
+    HString => "String",
+    Guid => "Guid",
+    Object => "Object"
 }
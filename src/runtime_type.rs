@@ -13,10 +13,13 @@ pub unsafe trait RuntimeType {
 
     fn abi(&self) -> Self::Abi;
     fn set_abi(&mut self) -> *mut Self::Abi;
+
+    /// The type's WinRT signature, from which the GUIDs of generic specializations are derived
+    fn signature() -> String;
 }
 
 macro_rules! primitive_runtime_type {
-    ($($t:ty),+) => {
+    ($($t:ty => $signature:literal),+) => {
         $(unsafe impl RuntimeType for $t {
             type Abi = Self;
             fn abi(&self) -> Self::Abi {
@@ -25,8 +28,24 @@ macro_rules! primitive_runtime_type {
             fn set_abi(&mut self) -> *mut Self::Abi {
                 self as *mut Self::Abi
             }
+            fn signature() -> String {
+                $signature.to_owned()
+            }
         })*
     };
 }
 
-primitive_runtime_type! { bool, i8, u8, i16, u16, i32, u32, i64, u64, f32, f64 }
+// Boolean and Char16 are converted to and from their ABI so they're implemented along with
+// their ABI types.
+primitive_runtime_type! {
+    i8 => "i1",
+    u8 => "u1",
+    i16 => "i2",
+    u16 => "u2",
+    i32 => "i4",
+    u32 => "u4",
+    i64 => "i8",
+    u64 => "u8",
+    f32 => "f4",
+    f64 => "f8"
+}
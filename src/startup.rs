@@ -0,0 +1,231 @@
+use std::fmt;
+use std::time::Duration;
+
+/// What a [`StartupEvent`] measured
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum StartupKind {
+    /// Getting the activation factory of a class for the first time
+    Activation,
+    /// Initializing an apartment, or joining the multithreaded apartment implicitly
+    Apartment,
+    /// Creating the string holding a class name for activation
+    String,
+}
+
+/// The first occurrence of an operation that contributes to the cost of starting an app
+#[derive(Clone, Debug)]
+pub struct StartupEvent {
+    pub kind: StartupKind,
+    /// The class name, or the kind of apartment
+    pub name: String,
+    /// When the operation started, relative to the first recorded event
+    pub offset: Duration,
+    /// How long the operation took, including any operations nested within it
+    pub duration: Duration,
+}
+
+/// The startup costs recorded by the runtime, in the order they started
+///
+/// Only the first occurrence of each operation is recorded, which is what a cold start pays
+/// for, so activating the same class again doesn't add an event.
+#[derive(Clone, Debug, Default)]
+pub struct StartupReport {
+    pub events: Vec<StartupEvent>,
+}
+
+impl StartupReport {
+    /// The total time spent on operations of a kind
+    ///
+    /// Nested operations, such as creating a class name while activating the class, are also
+    /// included in the time of the operation that contains them.
+    pub fn total(&self, kind: StartupKind) -> Duration {
+        self.events
+            .iter()
+            .filter(|event| event.kind == kind)
+            .map(|event| event.duration)
+            .sum()
+    }
+}
+
+impl fmt::Display for StartupReport {
+    /// Formats the report as a table of events, slowest first
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut events: Vec<&StartupEvent> = self.events.iter().collect();
+        events.sort_by_key(|event| std::cmp::Reverse(event.duration));
+
+        for event in events {
+            writeln!(
+                f,
+                "{:>10.3?} {:>10.3?}  {:?} {}",
+                event.offset, event.duration, event.kind, event.name
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the startup costs recorded so far
+///
+/// Costs are only recorded when the `startup_profiling` feature is enabled, so that cold
+/// starts can be attributed to the classes activated by the bindings. Otherwise the report
+/// is always empty.
+pub fn startup_report() -> StartupReport {
+    imp::report()
+}
+
+#[cfg(feature = "startup_profiling")]
+mod imp {
+    use super::*;
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+    use std::time::Instant;
+
+    struct Recorded {
+        kind: StartupKind,
+        name: String,
+        start: Instant,
+        duration: Duration,
+    }
+
+    #[derive(Default)]
+    struct State {
+        seen: HashSet<(StartupKind, String)>,
+        events: Vec<Recorded>,
+    }
+
+    static STATE: Mutex<Option<State>> = Mutex::new(None);
+
+    pub fn measure<T, F: FnOnce() -> T>(kind: StartupKind, name: &str, f: F) -> T {
+        let key = (kind, name.to_string());
+
+        if STATE
+            .lock()
+            .unwrap()
+            .get_or_insert_with(Default::default)
+            .seen
+            .contains(&key)
+        {
+            return f();
+        }
+
+        let start = Instant::now();
+        let result = f();
+        let duration = start.elapsed();
+
+        let mut state = STATE.lock().unwrap();
+        let state = state.get_or_insert_with(Default::default);
+
+        // Another thread may have finished the same operation first.
+        if state.seen.insert(key) {
+            state.events.push(Recorded {
+                kind,
+                name: name.to_string(),
+                start,
+                duration,
+            });
+        }
+
+        result
+    }
+
+    pub fn report() -> StartupReport {
+        let state = STATE.lock().unwrap();
+
+        let events = match &*state {
+            Some(state) => &state.events,
+            None => return StartupReport::default(),
+        };
+
+        let origin = events.iter().map(|event| event.start).min();
+        let mut events: Vec<StartupEvent> = events
+            .iter()
+            .map(|event| StartupEvent {
+                kind: event.kind,
+                name: event.name.clone(),
+                offset: event.start - origin.unwrap(),
+                duration: event.duration,
+            })
+            .collect();
+
+        events.sort_by_key(|event| event.offset);
+        StartupReport { events }
+    }
+}
+
+#[cfg(not(feature = "startup_profiling"))]
+mod imp {
+    use super::*;
+
+    #[inline(always)]
+    pub fn measure<T, F: FnOnce() -> T>(_kind: StartupKind, _name: &str, f: F) -> T {
+        f()
+    }
+
+    pub fn report() -> StartupReport {
+        StartupReport::default()
+    }
+}
+
+pub(crate) use imp::measure;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn total() {
+        let event = |kind, duration| StartupEvent {
+            kind,
+            name: String::new(),
+            offset: Duration::default(),
+            duration: Duration::from_millis(duration),
+        };
+
+        let report = StartupReport {
+            events: vec![
+                event(StartupKind::Activation, 5),
+                event(StartupKind::String, 1),
+                event(StartupKind::Activation, 3),
+            ],
+        };
+
+        assert!(report.total(StartupKind::Activation) == Duration::from_millis(8));
+        assert!(report.total(StartupKind::Apartment) == Duration::default());
+    }
+
+    #[cfg(feature = "startup_profiling")]
+    #[test]
+    fn measure() {
+        let events = || -> Vec<StartupEvent> {
+            startup_report()
+                .events
+                .into_iter()
+                .filter(|event| event.name.starts_with("startup::tests::measure"))
+                .collect()
+        };
+
+        let value = super::measure(StartupKind::Activation, "startup::tests::measure", || {
+            super::measure(StartupKind::String, "startup::tests::measure", || {
+                std::thread::sleep(Duration::from_millis(1));
+            });
+            1
+        });
+
+        assert!(value == 1);
+
+        // Only the first occurrence of an operation is recorded.
+        let value = super::measure(StartupKind::Activation, "startup::tests::measure", || 2);
+        assert!(value == 2);
+
+        let events = events();
+        assert!(events.len() == 2);
+
+        // The outer operation starts first and includes the time of the one nested within it.
+        assert!(events[0].kind == StartupKind::Activation);
+        assert!(events[1].kind == StartupKind::String);
+        assert!(events[0].offset <= events[1].offset);
+        assert!(events[0].duration >= events[1].duration);
+        assert!(events[1].duration >= Duration::from_millis(1));
+    }
+}
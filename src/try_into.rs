@@ -1,5 +1,4 @@
-use crate::unknown::abi_IUnknown;
-use crate::{ComInterface, RawPtr, Result};
+use crate::{ComInterface, Result};
 
 /// An equivalent to `std::convert::TryInto` for converting between interfaces
 pub trait TryInto<T: ComInterface> {
@@ -8,24 +7,6 @@ pub trait TryInto<T: ComInterface> {
 
 impl<From: ComInterface + Sized, Into: ComInterface> TryInto<Into> for &From {
     fn try_into(self) -> Result<Into> {
-        unsafe {
-            let mut into = std::ptr::null_mut();
-            let from: RawPtr = std::mem::transmute_copy(self);
-
-            if from.is_null() {
-                return Ok(std::mem::transmute_copy(&into));
-            }
-
-            ((*(*(from as *const *const abi_IUnknown))).query)(
-                from as *const *const abi_IUnknown,
-                &Into::GUID,
-                &mut into,
-            )
-            .ok()?;
-
-            debug_assert!(!into.is_null());
-
-            Ok(std::mem::transmute_copy(&into))
-        }
+        self.try_query()
     }
 }
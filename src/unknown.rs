@@ -2,7 +2,7 @@ use crate::*;
 
 /// The [IUnknown interface](https://docs.microsoft.com/en-us/windows/win32/api/unknwn/nn-unknwn-iunknown)
 #[repr(transparent)]
-#[derive(Default, Clone)]
+#[derive(Clone)]
 pub struct IUnknown {
     ptr: ComPtr<IUnknown>,
 }
@@ -17,6 +17,16 @@ impl IUnknown {
     }
 }
 
+/// Two IUnknown pointers refer to the same object if and only if the pointers are equal,
+/// provided each was obtained by querying for IUnknown.
+impl PartialEq for IUnknown {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr.get() == other.ptr.get()
+    }
+}
+
+impl Eq for IUnknown {}
+
 unsafe impl ComInterface for IUnknown {
     type VTable = abi_IUnknown;
     const GUID: Guid = Guid::from_values(
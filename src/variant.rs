@@ -0,0 +1,219 @@
+use crate::*;
+
+const VT_ARRAY: u16 = 0x2000;
+
+/// A COM [VARIANT](https://docs.microsoft.com/en-us/windows/win32/api/oaidl/ns-oaidl-variant)
+/// holding a one-dimensional SAFEARRAY
+///
+/// This bridges WinRT arrays, such as those boxed by `PropertyValue`, with automation APIs
+/// that expect a VARIANT. Pass `abi` to APIs that take a VARIANT and `set_abi` to those that
+/// return one. The VARIANT is cleared, freeing the SAFEARRAY, when dropped.
+#[repr(C, align(8))]
+#[derive(Default)]
+pub struct Variant {
+    vt: u16,
+    reserved: [u16; 3],
+    // The union holds 64-bit values so it's aligned to 8 bytes even on x86, where it's only
+    // two pointers, and so 8 bytes, long.
+    data: [usize; 2],
+}
+
+impl Variant {
+    /// Creates a VARIANT holding a SAFEARRAY with a copy of the values
+    pub fn from_slice<T: VariantElement>(values: &[T]) -> Result<Self> {
+        unsafe {
+            let array = runtime::SafeArrayCreateVector(T::VT, 0, values.len() as u32);
+
+            if array.is_null() {
+                return Err(ErrorCode::OUT_OF_MEMORY.into());
+            }
+
+            // The VARIANT owns the SAFEARRAY from here on so it is destroyed on failure.
+            let variant = Self {
+                vt: VT_ARRAY | T::VT,
+                data: [array as usize, 0],
+                ..Default::default()
+            };
+
+            let mut data = std::ptr::null_mut();
+            runtime::SafeArrayAccessData(array, &mut data).ok()?;
+            let data = data as *mut T::Element;
+
+            for (index, value) in values.iter().enumerate() {
+                std::ptr::write(data.add(index), value.to_element());
+            }
+
+            runtime::SafeArrayUnaccessData(array).ok()?;
+            Ok(variant)
+        }
+    }
+
+    /// The VARTYPE of the VARIANT
+    pub fn vt(&self) -> u16 {
+        self.vt
+    }
+
+    /// The VARTYPE of the array's elements, if the VARIANT holds an array
+    pub fn array_type(&self) -> Option<u16> {
+        if self.vt & !0xFFF == VT_ARRAY {
+            Some(self.vt & 0xFFF)
+        } else {
+            None
+        }
+    }
+
+    /// Copies the values of a one-dimensional SAFEARRAY of the given element type
+    pub fn to_vec<T: VariantElement>(&self) -> Result<Vec<T>> {
+        if self.vt != VT_ARRAY | T::VT || self.data[0] == 0 {
+            return Err(ErrorCode::INVALID_ARGUMENT.into());
+        }
+
+        unsafe {
+            let array = self.data[0] as RawPtr;
+
+            if runtime::SafeArrayGetDim(array) != 1 {
+                return Err(ErrorCode::INVALID_ARGUMENT.into());
+            }
+
+            let mut lower = 0;
+            let mut upper = 0;
+            runtime::SafeArrayGetLBound(array, 1, &mut lower).ok()?;
+            runtime::SafeArrayGetUBound(array, 1, &mut upper).ok()?;
+            let len = (upper - lower + 1).max(0) as usize;
+
+            let mut data = std::ptr::null_mut();
+            runtime::SafeArrayAccessData(array, &mut data).ok()?;
+            let data = data as *const T::Element;
+
+            let values = (0..len)
+                .map(|index| T::from_element(&*data.add(index)))
+                .collect();
+
+            runtime::SafeArrayUnaccessData(array).ok()?;
+            Ok(values)
+        }
+    }
+
+    /// A pointer to the VARIANT for APIs that take one
+    pub fn abi(&self) -> RawPtr {
+        self as *const Self as RawPtr
+    }
+
+    /// Clears the VARIANT and returns a pointer to it for APIs that return one
+    pub fn set_abi(&mut self) -> RawPtr {
+        self.clear();
+        self as *mut Self as RawPtr
+    }
+
+    /// Clears the VARIANT, freeing its value
+    pub fn clear(&mut self) {
+        if self.vt != 0 {
+            unsafe {
+                runtime::VariantClear(self as *mut Self as RawPtr);
+            }
+        }
+    }
+}
+
+impl Drop for Variant {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl std::fmt::Debug for Variant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Variant({:#06X})", self.vt)
+    }
+}
+
+/// A type that may be stored in a SAFEARRAY
+pub trait VariantElement: Sized {
+    #[doc(hidden)]
+    const VT: u16;
+    #[doc(hidden)]
+    type Element;
+    #[doc(hidden)]
+    fn to_element(&self) -> Self::Element;
+    #[doc(hidden)]
+    unsafe fn from_element(element: &Self::Element) -> Self;
+}
+
+macro_rules! primitive_element {
+    ($ty:ty, $vt:expr) => {
+        impl VariantElement for $ty {
+            const VT: u16 = $vt;
+            type Element = Self;
+
+            fn to_element(&self) -> Self {
+                *self
+            }
+
+            unsafe fn from_element(element: &Self) -> Self {
+                *element
+            }
+        }
+    };
+}
+
+primitive_element!(i8, 16);
+primitive_element!(u8, 17);
+primitive_element!(i16, 2);
+primitive_element!(u16, 18);
+primitive_element!(i32, 3);
+primitive_element!(u32, 19);
+primitive_element!(i64, 20);
+primitive_element!(u64, 21);
+primitive_element!(f32, 4);
+primitive_element!(f64, 5);
+
+// VARIANT_BOOL uses -1 for true.
+impl VariantElement for bool {
+    const VT: u16 = 11;
+    type Element = i16;
+
+    fn to_element(&self) -> i16 {
+        if *self {
+            -1
+        } else {
+            0
+        }
+    }
+
+    unsafe fn from_element(element: &i16) -> Self {
+        *element != 0
+    }
+}
+
+impl VariantElement for Boolean {
+    const VT: u16 = 11;
+    type Element = i16;
+
+    fn to_element(&self) -> i16 {
+        bool::from(*self).to_element()
+    }
+
+    unsafe fn from_element(element: &i16) -> Self {
+        bool::from_element(element).into()
+    }
+}
+
+// Strings are stored as BSTRs, which the SAFEARRAY frees along with itself.
+impl VariantElement for HString {
+    const VT: u16 = 8;
+    type Element = *mut u16;
+
+    fn to_element(&self) -> *mut u16 {
+        let wide = self.as_wide();
+        unsafe { runtime::SysAllocStringLen(wide.as_ptr(), wide.len() as u32) }
+    }
+
+    unsafe fn from_element(element: &*mut u16) -> Self {
+        if element.is_null() {
+            return HString::new();
+        }
+
+        let wide = std::slice::from_raw_parts(*element, runtime::SysStringLen(*element) as usize);
+        String::from_utf16_lossy(wide).into()
+    }
+}
@@ -0,0 +1,49 @@
+use crate::*;
+
+/// Verifies that an object responds to the interface's GUID before calling through its vtable
+///
+/// This is only done when the `verify_interfaces` feature is enabled, where generator or
+/// vtable layout bugs are reported as clean errors rather than calls into the wrong slot.
+/// Each combination of vtable and interface is only checked the first time it is used.
+#[doc(hidden)]
+#[cfg(feature = "verify_interfaces")]
+pub fn verify_interface<T: ComInterface>(this: *const *const T::VTable) -> Result<()> {
+    use std::cell::RefCell;
+    use std::collections::HashSet;
+
+    thread_local! {
+        static VERIFIED: RefCell<HashSet<(usize, u32, u16, u16, [u8; 8])>> = RefCell::new(HashSet::new());
+    }
+
+    let guid = T::iid();
+    let key = unsafe {
+        (
+            *this as usize,
+            guid.data1,
+            guid.data2,
+            guid.data3,
+            guid.data4,
+        )
+    };
+
+    if VERIFIED.with(|verified| verified.borrow().contains(&key)) {
+        return Ok(());
+    }
+
+    let mut unknown = unsafe { IUnknown::none() };
+    let this = this as *const *const <IUnknown as ComInterface>::VTable;
+
+    unsafe {
+        ((*(*(this))).query)(this, &guid, unknown.set()).ok()?;
+    }
+
+    VERIFIED.with(|verified| verified.borrow_mut().insert(key));
+    Ok(())
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "verify_interfaces"))]
+#[inline(always)]
+pub fn verify_interface<T: ComInterface>(_this: *const *const T::VTable) -> Result<()> {
+    Ok(())
+}
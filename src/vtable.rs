@@ -0,0 +1,17 @@
+use crate::*;
+
+/// Reads a method of an interface whose vtable was generated as a table of method slots
+///
+/// The `compact_vtables` generation option generates such tables for interfaces with many
+/// methods, rather than a field per method, and each method is read as the function pointer
+/// type `F` when it is called.
+///
+/// # Safety
+/// `this` must point to an interface with more than `index` methods after those of
+/// IInspectable and `F` must be the function pointer type of the method at `index`.
+#[doc(hidden)]
+#[inline(always)]
+pub unsafe fn vtable_method<F: Copy>(this: RawPtr, index: usize) -> F {
+    let vtable = *(this as *const *const usize);
+    std::mem::transmute_copy(&*vtable.add(6 + index))
+}
@@ -0,0 +1,53 @@
+use crate::*;
+use std::sync::{Arc, Condvar, Mutex};
+
+/// Blocks the calling thread until the work started by `start` signals that it's done
+///
+/// `start` is given a [`Signal`] to set, from any thread, once the work completes, such as from
+/// the completion handler of an async operation. The thread sleeps until then rather than
+/// polling.
+///
+/// Blocking a single-threaded apartment (such as a UI thread) usually deadlocks since the
+/// work being waited on often needs that same thread to complete, so this returns an error
+/// with the `ErrorCode::WRONG_THREAD` code rather than hang, without calling `start`. Generated
+/// `get` methods on the async interfaces use this to wait for completion.
+#[doc(hidden)]
+pub fn wait_for<F: FnOnce(Signal) -> Result<()>>(start: F) -> Result<()> {
+    if is_sta_thread() {
+        return Err(ErrorCode::WRONG_THREAD.into());
+    }
+
+    let signal = Signal::default();
+    start(signal.clone())?;
+    signal.wait();
+    Ok(())
+}
+
+/// Wakes a thread blocked in [`wait_for`]
+#[doc(hidden)]
+#[derive(Clone, Default)]
+pub struct Signal {
+    inner: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl Signal {
+    /// Marks the work as done, waking the waiting thread
+    pub fn set(&self) {
+        let (done, changed) = &*self.inner;
+        *done.lock().unwrap() = true;
+        changed.notify_all();
+    }
+
+    fn wait(&self) {
+        let (done, changed) = &*self.inner;
+        let mut done = done.lock().unwrap();
+
+        while !*done {
+            done = changed.wait(done).unwrap();
+        }
+    }
+}
+
+fn is_sta_thread() -> bool {
+    ApartmentType::current() == Some(ApartmentType::SingleThreaded)
+}
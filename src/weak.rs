@@ -0,0 +1,112 @@
+use crate::*;
+use std::marker::PhantomData;
+
+/// A weak reference to an object, which doesn't keep the object alive
+///
+/// Weak references break the reference cycles that form when an object holds an event handler
+/// that refers back to the object. They're created with `ComInterface::downgrade` and turned
+/// back into the interface with `upgrade` while the object is still alive.
+pub struct WeakRef<T: ComInterface> {
+    reference: IWeakReference,
+    phantom: PhantomData<T>,
+}
+
+impl<T: ComInterface> WeakRef<T> {
+    /// Creates a weak reference to the object
+    ///
+    /// Fails if the object doesn't support weak references.
+    pub fn new(object: &T) -> Result<WeakRef<T>> {
+        let source: IWeakReferenceSource = object.try_query()?;
+        let this = source.ptr.get();
+        let mut reference = unsafe { IWeakReference::none() };
+
+        unsafe {
+            ((*(*(this))).get_weak_reference)(this, reference.ptr.set() as *mut RawPtr).and_then(
+                || WeakRef {
+                    reference,
+                    phantom: PhantomData,
+                },
+            )
+        }
+    }
+
+    /// Returns the interface if the object is still alive
+    pub fn upgrade(&self) -> Option<T> {
+        let this = self.reference.ptr.get();
+        let mut object = std::ptr::null_mut();
+
+        unsafe {
+            let code = ((*(*(this))).resolve)(this, &T::iid(), &mut object);
+
+            if code.is_err() || object.is_null() {
+                None
+            } else {
+                Some(T::from_raw(object))
+            }
+        }
+    }
+}
+
+impl<T: ComInterface> Clone for WeakRef<T> {
+    fn clone(&self) -> Self {
+        WeakRef {
+            reference: self.reference.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: ComInterface> std::fmt::Debug for WeakRef<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "WeakRef({:?})", self.reference.ptr.get())
+    }
+}
+
+/// The [IWeakReferenceSource interface](https://docs.microsoft.com/en-us/windows/win32/api/weakreference/nn-weakreference-iweakreferencesource)
+/// of objects that support weak references
+#[repr(transparent)]
+#[derive(Clone)]
+struct IWeakReferenceSource {
+    ptr: ComPtr<IWeakReferenceSource>,
+}
+
+unsafe impl ComInterface for IWeakReferenceSource {
+    type VTable = abi_IWeakReferenceSource;
+    const GUID: Guid = Guid::from_values(
+        0x0000_0038,
+        0x0000,
+        0x0000,
+        [0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46],
+    );
+}
+
+#[repr(C)]
+struct abi_IWeakReferenceSource {
+    __base: [usize; 3],
+    get_weak_reference:
+        extern "system" fn(*const *const abi_IWeakReferenceSource, *mut RawPtr) -> ErrorCode,
+}
+
+/// The [IWeakReference interface](https://docs.microsoft.com/en-us/windows/win32/api/weakreference/nn-weakreference-iweakreference)
+/// that resolves a weak reference
+#[repr(transparent)]
+#[derive(Clone)]
+struct IWeakReference {
+    ptr: ComPtr<IWeakReference>,
+}
+
+unsafe impl ComInterface for IWeakReference {
+    type VTable = abi_IWeakReference;
+    const GUID: Guid = Guid::from_values(
+        0x0000_0037,
+        0x0000,
+        0x0000,
+        [0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46],
+    );
+}
+
+#[repr(C)]
+struct abi_IWeakReference {
+    __base: [usize; 3],
+    resolve: extern "system" fn(*const *const abi_IWeakReference, &Guid, *mut RawPtr) -> ErrorCode,
+}
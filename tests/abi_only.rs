@@ -0,0 +1,33 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+    options
+        "abi_only"
+);
+
+use windows::foundation::{abi_IStringable, abi_IUriRuntimeClass, AsyncStatus, Point};
+use winrt::Guid;
+
+#[test]
+fn abi_only() {
+    // Vtables start with the methods of IUnknown and IInspectable.
+    assert!(std::mem::size_of::<abi_IStringable>() == 7 * std::mem::size_of::<usize>());
+    assert!(abi_IStringable::IID == Guid::from("96369F54-8EB6-48F0-ABCE-C1B211E627C3"));
+
+    // Objects and strings are raw pointers.
+    fn domain(
+        vtable: &abi_IUriRuntimeClass,
+    ) -> extern "system" fn(winrt::RawPtr, *mut winrt::RawPtr) -> winrt::ErrorCode {
+        vtable.domain
+    }
+    let _ = domain;
+
+    // Enums are integers with a constant for each value.
+    assert!(AsyncStatus::Completed.0 == 1);
+    assert!(AsyncStatus::default() == AsyncStatus::Started);
+
+    let point = Point { x: 1.0, y: 2.0 };
+    assert!(point.x == 1.0);
+}
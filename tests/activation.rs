@@ -0,0 +1,42 @@
+use winrt::{ErrorCode, IActivationFactory, RuntimeName};
+
+struct Unregistered;
+
+impl RuntimeName for Unregistered {
+    const NAME: &'static str = "Contoso.Widgets.Button";
+}
+
+const CLASS_NOT_REGISTERED: ErrorCode = ErrorCode(0x8004_0154_u32 as i32);
+
+#[test]
+fn unregistered_class() {
+    // Neither the registered library nor those named after the namespace exist.
+    winrt::register_library("Contoso.Missing.dll");
+
+    let error = winrt::activation::factory::<Unregistered, IActivationFactory>()
+        .err()
+        .unwrap();
+    assert!(error.code() == CLASS_NOT_REGISTERED);
+}
+
+#[test]
+fn library_without_factories() {
+    // Libraries that load but don't export `DllGetActivationFactory` are skipped.
+    winrt::register_library("kernel32.dll");
+
+    let error = winrt::activation::factory::<Unregistered, IActivationFactory>()
+        .err()
+        .unwrap();
+    assert!(error.code() == CLASS_NOT_REGISTERED);
+}
+
+#[test]
+fn library_without_class() {
+    // Libraries whose `DllGetActivationFactory` doesn't know the class are skipped.
+    winrt::register_library("Windows.Storage.dll");
+
+    let error = winrt::activation::factory::<Unregistered, IActivationFactory>()
+        .err()
+        .unwrap();
+    assert!(error.code() == CLASS_NOT_REGISTERED);
+}
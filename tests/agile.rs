@@ -0,0 +1,26 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation.collections"
+);
+
+use windows::foundation::collections::PropertySet;
+use windows::foundation::PropertyValue;
+use winrt::AgileRef;
+
+#[test]
+fn agile_ref() -> winrt::Result<()> {
+    let set = PropertySet::new()?;
+    set.insert("A", PropertyValue::create_int32(1)?)?;
+    let reference = AgileRef::new(&set)?;
+
+    // The reference is resolved on another thread, which may be in another apartment.
+    std::thread::spawn(move || -> winrt::Result<()> {
+        let set: PropertySet = reference.resolve()?;
+        assert!(set.has_key("A")?);
+        Ok(())
+    })
+    .join()
+    .unwrap()
+}
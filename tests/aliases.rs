@@ -0,0 +1,24 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation.collections"
+    aliases
+        "windows.foundation.collections as collections"
+        "windows.foundation as foundation"
+);
+
+use collections::PropertySet;
+use foundation::IStringable;
+
+#[test]
+fn aliases() -> winrt::Result<()> {
+    let set = PropertySet::new()?;
+    assert!(set.size()? == 0);
+
+    // Aliases refer to the same types as the full module paths.
+    let stringable: Option<IStringable> = None;
+    let _: Option<windows::foundation::IStringable> = stringable;
+
+    Ok(())
+}
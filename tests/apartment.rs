@@ -0,0 +1,114 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation.collections"
+);
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier};
+use windows::foundation::collections::{MapChangedEventHandler, PropertySet};
+use windows::foundation::{PropertyValue, Uri};
+use winrt::{init_apartment, ApartmentType, HString, Object};
+
+// Activates objects, subscribes to events, and releases everything again.
+fn use_runtime() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+    assert!(uri.domain()? == "kennykerr.ca");
+
+    let set = PropertySet::new()?;
+    let changes = Arc::new(AtomicUsize::new(0));
+
+    let handler = {
+        let changes = changes.clone();
+        MapChangedEventHandler::<HString, Object>::new(move |_, _| {
+            changes.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        })
+    };
+
+    let token = set.map_changed(&handler)?;
+    set.insert("A", PropertyValue::create_int32(1)?)?;
+    set.remove_map_changed(token)?;
+    set.insert("B", PropertyValue::create_int32(2)?)?;
+    assert!(changes.load(Ordering::SeqCst) == 1);
+
+    Ok(())
+}
+
+const THREADS: usize = 4;
+
+// The threads initialize, use, and tear down their apartments in lockstep so that each round
+// overlaps the same way every time, rather than depending on how the threads are scheduled.
+fn repeat_on_threads(kind: ApartmentType) {
+    let barrier = Arc::new(Barrier::new(THREADS));
+
+    let threads: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let barrier = barrier.clone();
+
+            std::thread::spawn(move || -> winrt::Result<()> {
+                for _ in 0..10 {
+                    let apartment = init_apartment(kind)?;
+                    assert!(ApartmentType::current() == Some(kind));
+                    barrier.wait();
+
+                    use_runtime()?;
+                    barrier.wait();
+
+                    drop(apartment);
+                    barrier.wait();
+                }
+
+                // Every thread has torn down its apartment by now, so without an apartment of
+                // its own the thread falls back to the multithreaded apartment that the crate
+                // keeps alive.
+                use_runtime()
+            })
+        })
+        .collect();
+
+    for thread in threads {
+        thread.join().unwrap().unwrap();
+    }
+}
+
+#[test]
+fn multithreaded_teardown() {
+    repeat_on_threads(ApartmentType::MultiThreaded);
+}
+
+#[test]
+fn single_threaded_teardown() {
+    repeat_on_threads(ApartmentType::SingleThreaded);
+}
+
+#[test]
+fn agile_object_outlives_apartment() -> winrt::Result<()> {
+    // Uri is agile so it may be used after the apartment that created it is gone.
+    let uri = std::thread::spawn(|| -> winrt::Result<Uri> {
+        let _apartment = init_apartment(ApartmentType::SingleThreaded)?;
+        Uri::create_uri("http://kennykerr.ca")
+    })
+    .join()
+    .unwrap()?;
+
+    assert!(uri.domain()? == "kennykerr.ca");
+    Ok(())
+}
+
+#[test]
+fn changed_mode() -> winrt::Result<()> {
+    std::thread::spawn(|| -> winrt::Result<()> {
+        let _apartment = init_apartment(ApartmentType::MultiThreaded)?;
+
+        // Joining a different kind of apartment fails and needn't be balanced.
+        let error = init_apartment(ApartmentType::SingleThreaded).unwrap_err();
+        assert!(error.code() == winrt::ErrorCode(0x8001_0106_u32 as i32));
+        assert!(ApartmentType::current() == Some(ApartmentType::MultiThreaded));
+
+        Ok(())
+    })
+    .join()
+    .unwrap()
+}
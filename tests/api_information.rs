@@ -0,0 +1,69 @@
+#[test]
+fn api_information() -> winrt::Result<()> {
+    assert!(winrt::is_type_present("Windows.Foundation.Uri")?);
+    assert!(!winrt::is_type_present("Windows.Foundation.Missing")?);
+
+    assert!(winrt::is_method_present(
+        "Windows.Foundation.Uri",
+        "CombineUri"
+    )?);
+    assert!(!winrt::is_method_present(
+        "Windows.Foundation.Uri",
+        "Missing"
+    )?);
+
+    assert!(winrt::is_property_present(
+        "Windows.Foundation.Uri",
+        "Domain"
+    )?);
+    assert!(winrt::is_event_present(
+        "Windows.Foundation.Collections.PropertySet",
+        "MapChanged"
+    )?);
+
+    assert!(winrt::is_api_contract_present(
+        "Windows.Foundation.UniversalApiContract",
+        1,
+        0
+    )?);
+    assert!(!winrt::is_api_contract_present(
+        "Windows.Foundation.UniversalApiContract",
+        1000,
+        0
+    )?);
+
+    Ok(())
+}
+
+#[test]
+fn method_check() -> winrt::Result<()> {
+    use winrt::{ErrorCode, MemberKind, MethodCheck};
+
+    static METHOD: MethodCheck =
+        MethodCheck::new("Windows.Foundation.Uri", "CombineUri", MemberKind::Method);
+    static PROPERTY: MethodCheck =
+        MethodCheck::new("Windows.Foundation.Uri", "Domain", MemberKind::Property);
+    static EVENT: MethodCheck = MethodCheck::new(
+        "Windows.Foundation.Collections.PropertySet",
+        "MapChanged",
+        MemberKind::Event,
+    );
+    static MISSING: MethodCheck =
+        MethodCheck::new("Windows.Foundation.Uri", "Missing", MemberKind::Property);
+
+    METHOD.check()?;
+    PROPERTY.check()?;
+    EVENT.check()?;
+
+    // The answer is remembered, so checking again fails the same way.
+    for _ in 0..2 {
+        let error = MISSING.check().unwrap_err();
+        assert!(error.code() == ErrorCode::OLD_WIN_VERSION);
+        assert!(error
+            .message()
+            .unwrap()
+            .contains("Windows.Foundation.Uri.Missing"));
+    }
+
+    Ok(())
+}
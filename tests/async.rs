@@ -0,0 +1,41 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.storage"
+);
+
+#[test]
+fn get() -> winrt::Result<()> {
+    use windows::storage::StorageFolder;
+
+    let path = std::env::temp_dir();
+    let operation = StorageFolder::get_folder_from_path_async(path.to_str().unwrap())?;
+    let folder = operation.get()?;
+    assert!(!folder.path()?.is_empty());
+
+    // Once the operation has completed its results are returned without waiting again.
+    assert!(operation.get()?.path()? == folder.path()?);
+
+    Ok(())
+}
+
+#[test]
+fn get_vec() -> winrt::Result<()> {
+    use windows::storage::StorageFolder;
+
+    let path = std::env::temp_dir().join("winrt_get_vec");
+    std::fs::create_dir_all(&path).unwrap();
+    std::fs::write(path.join("file.txt"), "").unwrap();
+
+    // IAsyncOperation<IVectorView<StorageFile>> is collected straight into a Vec<StorageFile>.
+    let folder = StorageFolder::get_folder_from_path_async(path.to_str().unwrap())?.get()?;
+    let files = folder
+        .get_files_async_overload_default_options_start_and_count()?
+        .get_vec()?;
+
+    assert!(files.len() == 1);
+    assert!(files[0].name()? == "file.txt");
+
+    Ok(())
+}
@@ -24,3 +24,89 @@ fn boxing() -> winrt::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn receive_array() -> winrt::Result<()> {
+    // Arrays of reference types release their elements along with the allocation.
+    let object = PropertyValue::create_string_array(&["a".into(), "b".into()])?;
+    let pv: IPropertyValue = object.try_into()?;
+    let mut array = winrt::Array::new();
+    pv.get_string_array(&mut array)?;
+    assert_eq!(array.len(), 2);
+    assert!(array[0] == "a");
+    assert!(array[1] == "b");
+
+    // Receiving again frees the previous array first.
+    pv.get_string_array(&mut array)?;
+    assert!(array.iter().map(|s| s.to_string()).collect::<Vec<_>>() == ["a", "b"]);
+
+    Ok(())
+}
+
+#[test]
+fn input_arrays() -> winrt::Result<()> {
+    // Input arrays take slices, which references to arrays and vectors coerce to.
+    let values = vec![1, 2, 3];
+    let objects = [
+        PropertyValue::create_int32_array(&values[..])?,
+        PropertyValue::create_int32_array(&[1, 2, 3])?,
+        PropertyValue::create_int32_array(&values)?,
+    ];
+
+    for object in &objects {
+        let pv: IPropertyValue = object.try_into()?;
+        let mut array = winrt::Array::new();
+        pv.get_int32_array(&mut array)?;
+        assert!(array.as_slice() == [1, 2, 3]);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn boolean() -> winrt::Result<()> {
+    // Booleans are converted from the byte returned by the method.
+    for value in [true, false].iter().copied() {
+        let pv: IPropertyValue = PropertyValue::create_boolean(value)?.try_into()?;
+        assert_eq!(pv.get_boolean()?, value);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn char16() -> winrt::Result<()> {
+    use winrt::Char16;
+
+    let value = Char16::from_char('€').unwrap();
+    let pv: IPropertyValue = PropertyValue::create_char16(value)?.try_into()?;
+    assert_eq!(pv.get_char16()?, value);
+    assert_eq!(pv.get_char16()?.to_char(), Some('€'));
+
+    Ok(())
+}
+
+#[test]
+fn box_value() -> winrt::Result<()> {
+    let object = winrt::box_value(123)?;
+    assert!(winrt::unbox::<i32>(&object)? == 123);
+    assert!(winrt::unbox::<i64>(&object)? == 123);
+    assert!(winrt::unbox::<String>(&object).is_err());
+
+    let object = winrt::box_value("hello")?;
+    let pv: IPropertyValue = object.try_into()?;
+    assert!(pv.get_string()? == "hello");
+    assert!(winrt::unbox::<winrt::HString>(&object)? == "hello");
+    assert!(winrt::unbox::<String>(&object)? == "hello");
+
+    let object = winrt::box_value(true)?;
+    assert!(winrt::unbox::<bool>(&object)?);
+
+    let object = PropertyValue::create_double(1.5)?;
+    assert!(winrt::unbox::<f64>(&object)? == 1.5);
+
+    let guid = winrt::Guid::from_u128(0x0123_4567_89AB_CDEF_0123_4567_89AB_CDEF);
+    assert!(winrt::unbox::<winrt::Guid>(&winrt::box_value(guid)?)? == guid);
+
+    Ok(())
+}
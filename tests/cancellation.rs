@@ -0,0 +1,79 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.system.threading"
+);
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use windows::foundation::{AsyncStatus, IAsyncAction, PropertyValue};
+use windows::system::threading::{ThreadPool, WorkItemHandler};
+use winrt::{CancellationSource, ErrorCode};
+
+struct Flag;
+
+impl Wake for Flag {
+    fn wake(self: Arc<Self>) {}
+}
+
+#[test]
+fn token() {
+    let source = CancellationSource::new();
+    let token = source.token();
+    assert!(!token.is_cancelled());
+    assert!(token.check().is_ok());
+
+    let waker = Waker::from(Arc::new(Flag));
+    let mut context = Context::from_waker(&waker);
+    let mut cancelled = token.cancelled();
+    assert!(Pin::new(&mut cancelled).poll(&mut context) == Poll::Pending);
+
+    source.cancel();
+    assert!(source.is_cancelled());
+    assert!(token.is_cancelled());
+    assert!(token.check().unwrap_err().code() == ErrorCode::CANCELLED);
+    assert!(Pin::new(&mut cancelled).poll(&mut context) == Poll::Ready(()));
+
+    // Cancelling again is harmless.
+    source.cancel();
+}
+
+fn until_cancelled(action: &IAsyncAction) -> winrt::Result<()> {
+    while action.status()? == AsyncStatus::Started {
+        std::thread::sleep(std::time::Duration::from_millis(1));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn operations() -> winrt::Result<()> {
+    let source = CancellationSource::new();
+    let token = source.token();
+
+    // The work item runs until its action is cancelled.
+    let action = ThreadPool::run_async(WorkItemHandler::new(until_cancelled))?;
+
+    token.register(&action)?;
+    assert!(action.status()? == AsyncStatus::Started);
+
+    source.cancel();
+    assert!(action.get().is_err());
+    assert!(action.status()? == AsyncStatus::Canceled);
+
+    // Operations registered after the source is cancelled are cancelled right away.
+    let late = ThreadPool::run_async(WorkItemHandler::new(until_cancelled))?;
+
+    token.register(&late)?;
+    assert!(late.get().is_err());
+    assert!(late.status()? == AsyncStatus::Canceled);
+
+    // Only objects implementing IAsyncInfo can be registered.
+    let value = PropertyValue::create_int32(1)?;
+    assert!(token.register(&value).is_err());
+
+    Ok(())
+}
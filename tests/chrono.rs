@@ -0,0 +1,50 @@
+#![cfg(feature = "chrono")]
+
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+    options
+        "chrono"
+);
+
+use chrono::{TimeZone, Utc};
+use std::convert::TryFrom;
+use windows::foundation::{DateTime, TimeSpan};
+
+#[test]
+fn date_time() {
+    let epoch = Utc.timestamp_opt(0, 0).unwrap();
+    assert!(DateTime::try_from(epoch).unwrap().universal_time == 116_444_736_000_000_000);
+
+    let before = DateTime {
+        universal_time: 116_444_736_000_000_000 - 15,
+    };
+    let time: chrono::DateTime<Utc> = before.into();
+    assert!(time == epoch - chrono::Duration::nanoseconds(1500));
+    assert!(DateTime::try_from(time).unwrap() == before);
+
+    // Times before the epoch round down to the tick before them.
+    let tick = DateTime {
+        universal_time: 116_444_736_000_000_000 - 1,
+    };
+    let time = epoch - chrono::Duration::nanoseconds(50);
+    assert!(DateTime::try_from(time).unwrap() == tick);
+
+    // Chrono's range is far wider than 29,000 years either side of 1601.
+    assert!(DateTime::try_from(chrono::DateTime::<Utc>::MAX_UTC).is_err());
+}
+
+#[test]
+fn time_span() {
+    let span = TimeSpan::try_from(chrono::Duration::milliseconds(-1500)).unwrap();
+    assert!(span.duration == -15_000_000);
+    assert!(chrono::Duration::from(span) == chrono::Duration::milliseconds(-1500));
+
+    let span = TimeSpan::try_from(chrono::Duration::nanoseconds(-50)).unwrap();
+    assert!(span.duration == -1);
+
+    // TimeSpan only reaches about 29,000 years.
+    assert!(TimeSpan::try_from(chrono::Duration::days(365 * 30_000)).is_err());
+}
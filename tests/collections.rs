@@ -5,6 +5,7 @@ winrt::import!(
         "windows.foundation.collections"
 );
 
+use std::convert::TryFrom;
 use std::iter::FromIterator;
 use windows::foundation::collections::{IIterable, IVectorView, PropertySet};
 use windows::foundation::{IPropertyValue, IWwwFormUrlDecoderEntry, PropertyValue, Uri};
@@ -32,7 +33,7 @@ fn uri() -> winrt::Result<()> {
     let mut result = String::new();
 
     for entry in query {
-        result.push_str(&entry.value()?.to_string());
+        result.push_str(&entry?.value()?.to_string());
     }
 
     assert!(result == "123");
@@ -40,12 +41,12 @@ fn uri() -> winrt::Result<()> {
     // This tests the ability to treat the WwwFormUrlDecoder as an IIterable<T> and use
     // the slower IIterator<T> iterator.
 
-    let iterable: IIterable<IWwwFormUrlDecoderEntry> = uri.query_parsed()?.into();
+    let iterable = IIterable::<IWwwFormUrlDecoderEntry>::try_from(uri.query_parsed()?)?;
 
     let mut result = String::new();
 
     for entry in iterable {
-        result.push_str(&entry.name()?.to_string());
+        result.push_str(&entry?.name()?.to_string());
     }
 
     assert!(result == "ABC");
@@ -53,12 +54,12 @@ fn uri() -> winrt::Result<()> {
     // This tests the ability to treat the WwwFormUrlDecoder as an IVectorView<T> and use
     // the fast IVectorView iterator directly.
 
-    let iterable: IVectorView<IWwwFormUrlDecoderEntry> = uri.query_parsed()?.into();
+    let iterable = IVectorView::<IWwwFormUrlDecoderEntry>::try_from(uri.query_parsed()?)?;
 
     let mut result = String::new();
 
     for entry in iterable {
-        result.push_str(&entry.value()?.to_string());
+        result.push_str(&entry?.value()?.to_string());
     }
 
     assert!(result == "123");
@@ -69,7 +70,8 @@ fn uri() -> winrt::Result<()> {
 #[test]
 fn property_set() -> winrt::Result<()> {
     // The PropertySet class implements IIterable<IKeyValuePair<HString, Object>> so the following
-    // for loop will excercise the IIterator<T> iterator implicitly.
+    // for loop will excercise the KeyValueIterator implicitly, yielding (HString, Object) tuples.
+    // Each item is a Result since reading from the map may fail.
 
     let set = PropertySet::new()?;
 
@@ -83,8 +85,9 @@ fn property_set() -> winrt::Result<()> {
     let mut values = 0;
 
     for pair in &set {
-        keys.push(pair.key()?.to_string());
-        let pv: IPropertyValue = pair.value()?.try_into()?;
+        let (key, value) = pair?;
+        keys.push(key.to_string());
+        let pv: IPropertyValue = value.try_into()?;
         values += pv.get_uint32()?;
     }
     assert!(set.size()? == 3);
@@ -93,5 +96,87 @@ fn property_set() -> winrt::Result<()> {
     assert!(String::from_iter(keys) == "ABC");
     assert!(values == 6);
 
+    // Pairs retrieved directly from an iterator may also be converted into tuples.
+    use windows::foundation::collections::IKeyValuePair;
+    use winrt::{HString, Object};
+
+    let iterable: IIterable<IKeyValuePair<HString, Object>> = set.try_into()?;
+    let pair = iterable.first()?.current()?;
+    let (key, _) = <(HString, Object)>::try_from(&pair)?;
+    assert!(["A", "B", "C"].contains(&key.to_string().as_str()));
+
+    Ok(())
+}
+
+#[test]
+fn generic_guids() -> winrt::Result<()> {
+    use windows::foundation::collections::IVector;
+    use winrt::{ComInterface, Guid, HString, Object, RuntimeType};
+
+    assert!(
+        <IVector<HString> as RuntimeType>::signature()
+            == "pinterface({913337e9-11a1-4345-a3a2-4e7f956e222d};string)"
+    );
+    assert!(IVector::<HString>::iid() == Guid::from("98B9ACC1-4B56-532E-AC73-03D5291CCA90"));
+
+    // Specializations get their own GUIDs, which differ from the generic definition's piid.
+    assert!(IVector::<Object>::iid() != IVector::<HString>::iid());
+    assert!(IVector::<HString>::iid() != IVector::<HString>::GUID);
+
+    // Querying uses the specialization's GUID.
+    let uri = Uri::create_uri("http://kennykerr.ca?A=1")?;
+    let query: IVectorView<IWwwFormUrlDecoderEntry> = uri.query_parsed()?.try_query()?;
+    assert!(query.size()? == 1);
+
+    Ok(())
+}
+
+#[test]
+fn fill_array() -> winrt::Result<()> {
+    use winrt::ComInterface;
+
+    let uri = Uri::create_uri("http://kennykerr.ca?A=1&B=2&C=3")?;
+    let query: IVectorView<IWwwFormUrlDecoderEntry> = uri.query_parsed()?.try_query()?;
+
+    // The buffer is allocated by the caller and filled by the callee. Any existing
+    // elements are released before the call.
+    let mut entries = vec![unsafe { IWwwFormUrlDecoderEntry::none() }; 2];
+    assert!(query.get_many(1, &mut entries)? == 2);
+    assert!(entries[0].name()? == "B");
+    assert!(entries[1].name()? == "C");
+
+    assert!(query.get_many(0, &mut entries[..1])? == 1);
+    assert!(entries[0].name()? == "A");
+    assert!(entries[1].name()? == "C");
+
+    Ok(())
+}
+
+#[test]
+fn event_token() -> winrt::Result<()> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use windows::foundation::collections::MapChangedEventHandler;
+    use winrt::{HString, Object};
+
+    let set = PropertySet::new()?;
+    let changes = Arc::new(AtomicUsize::new(0));
+
+    let handler = {
+        let changes = changes.clone();
+        MapChangedEventHandler::<HString, Object>::new(move |_, _| {
+            changes.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        })
+    };
+
+    let token: winrt::EventToken = set.map_changed(&handler)?;
+    set.insert("A", PropertyValue::create_int32(1)?)?;
+    assert!(changes.load(Ordering::SeqCst) == 1);
+
+    set.remove_map_changed(token)?;
+    set.insert("B", PropertyValue::create_int32(2)?)?;
+    assert!(changes.load(Ordering::SeqCst) == 1);
+
     Ok(())
 }
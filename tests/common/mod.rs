@@ -0,0 +1,46 @@
+// Helpers shared by the tests that drive futures without an async runtime. Each test crate uses
+// only some of them.
+#![allow(dead_code)]
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake};
+use std::thread::Thread;
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Polls the future on the current thread, parking it until the future is woken
+pub fn block_on<T>(future: impl Future<Output = T>) -> T {
+    let waker = Arc::new(ThreadWaker(std::thread::current())).into();
+    let mut context = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(value) => return value,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+/// A future that calls the closure each time it's polled
+pub fn poll_fn<T>(f: impl FnMut(&mut Context) -> Poll<T> + Unpin) -> impl Future<Output = T> {
+    struct PollFn<F>(F);
+
+    impl<T, F: FnMut(&mut Context) -> Poll<T> + Unpin> Future for PollFn<F> {
+        type Output = T;
+
+        fn poll(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<T> {
+            (self.0)(context)
+        }
+    }
+
+    PollFn(f)
+}
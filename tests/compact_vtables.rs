@@ -0,0 +1,29 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation.collections"
+    options
+        "compact_vtables:5"
+);
+
+use windows::foundation::collections::PropertySet;
+use windows::foundation::{PropertyValue, Uri};
+
+#[test]
+fn compact_vtables() -> winrt::Result<()> {
+    // IUriRuntimeClass has well over five methods so it's called through its method slots.
+    let uri = Uri::create_uri("http://kennykerr.ca/path?query")?;
+    assert!(uri.domain()? == "kennykerr.ca");
+    assert!(uri.path()? == "/path");
+    assert!(uri.query()? == "?query");
+    assert!(uri.port()? == 80);
+
+    // So is the generic IMap<K, V>, which the set forwards to.
+    let set = PropertySet::new()?;
+    set.insert("A", PropertyValue::create_int32(123)?)?;
+    assert!(set.size()? == 1);
+    assert!(set.has_key("A")?);
+
+    Ok(())
+}
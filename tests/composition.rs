@@ -30,13 +30,13 @@ fn create_dispatcher() -> winrt::IUnknown {
         apartment_type: 0, // DQTAT_COM_NONE
     };
 
-    let mut interop_ptr = winrt::IUnknown::default();
     unsafe {
+        let mut interop_ptr = <winrt::IUnknown as winrt::ComInterface>::none();
         CreateDispatcherQueueController(options, interop_ptr.set())
             .ok()
             .unwrap();
+        interop_ptr
     }
-    interop_ptr
 }
 
 #[test]
@@ -0,0 +1,121 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+);
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use windows::foundation::{EventHandler, IClosable, IStringable, TypedEventHandler, Uri};
+use winrt::{ComInterface, HString, Object};
+
+#[test]
+fn with_sender() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+    let sender: Object = uri.query();
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    // EventHandler<T> passes its sender as an object.
+    let handler = EventHandler::<i32>::with_sender({
+        let calls = calls.clone();
+        move |sender: &Uri, args: &i32| {
+            assert!(sender.domain()? == "kennykerr.ca");
+            assert!(*args == 123);
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    });
+
+    handler.invoke(&sender, 123)?;
+    assert!(calls.load(Ordering::SeqCst) == 1);
+
+    // TypedEventHandler<TSender, TResult> only does so when TSender is Object.
+    let handler = TypedEventHandler::<Object, Object>::with_sender({
+        let calls = calls.clone();
+        move |sender: &IStringable, args: &Object| {
+            assert!(sender.to_string()? == "http://kennykerr.ca/");
+            assert!(args.query::<IStringable>().to_string()? == "http://kennykerr.ca/");
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    });
+
+    handler.invoke(&sender, &sender)?;
+    assert!(calls.load(Ordering::SeqCst) == 2);
+
+    // A sender that doesn't implement the interface fails the delegate without calling it.
+    let handler = EventHandler::<i32>::with_sender({
+        let calls = calls.clone();
+        move |_: &IClosable, _: &i32| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    });
+
+    assert!(handler.invoke(&sender, 123).is_err());
+    assert!(calls.load(Ordering::SeqCst) == 2);
+
+    Ok(())
+}
+
+#[test]
+fn inference() {
+    // The type arguments are inferred from the closure's parameters rather than named.
+    let handler = TypedEventHandler::new(|sender: &Uri, args: &i32| {
+        assert!(sender.domain()? == "kennykerr.ca");
+        assert!(*args == 123);
+        Ok(())
+    });
+
+    let _: &TypedEventHandler<Uri, i32> = &handler;
+    assert!(!handler.is_null());
+
+    let handler = EventHandler::new(|_: &Object, args: &HString| {
+        assert!(*args == "hello");
+        Ok(())
+    });
+
+    let _: &EventHandler<HString> = &handler;
+    assert!(!handler.is_null());
+}
+
+#[test]
+fn into_delegate() -> winrt::Result<()> {
+    use windows::foundation::{IntoEventHandler, IntoTypedEventHandler};
+
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    // The helper traits name the delegate, and its type arguments, after the closure.
+    let handler = {
+        let calls = calls.clone();
+        (move |sender: &Uri, args: &i32| {
+            assert!(sender.domain()? == "kennykerr.ca");
+            assert!(*args == 123);
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        })
+        .into_typed_event_handler()
+    };
+
+    let _: &TypedEventHandler<Uri, i32> = &handler;
+    handler.invoke(&uri, 123)?;
+    assert!(calls.load(Ordering::SeqCst) == 1);
+
+    let handler = {
+        let calls = calls.clone();
+        (move |_: &Object, args: &i32| {
+            assert!(*args == 456);
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        })
+        .into_event_handler()
+    };
+
+    let _: &EventHandler<i32> = &handler;
+    handler.invoke(&uri.query::<Object>(), 456)?;
+    assert!(calls.load(Ordering::SeqCst) == 2);
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.system"
+);
+
+mod common;
+
+use common::block_on;
+use windows::system::DispatcherQueueController;
+use winrt::ErrorCode;
+
+#[test]
+fn run() -> winrt::Result<()> {
+    let controller = DispatcherQueueController::create_on_dedicated_thread()?;
+    let queue = controller.dispatcher_queue()?;
+
+    let here = std::thread::current().id();
+    let there = block_on(queue.run(|| Ok(std::thread::current().id())))?;
+    assert!(here != there);
+
+    block_on(queue.drained())?;
+    assert!(block_on(queue.run(|| Ok(123)))? == 123);
+
+    controller.shutdown_queue_async()?.get()?;
+    let error = block_on(queue.run(|| Ok(()))).unwrap_err();
+    assert!(error.code() == ErrorCode::CLOSED);
+
+    Ok(())
+}
@@ -35,3 +35,46 @@ fn unsigned_enum() {
     // let weekend = AppointmentDaysOfWeek::Sunday | AppointmentDaysOfWeek::Saturday;
     // assert!(weekend as u32 == 0x41);
 }
+
+#[test]
+fn ordered_enum() {
+    use std::collections::{BTreeSet, HashSet};
+    use windows::foundation::AsyncStatus;
+
+    // Values are ordered by their underlying values rather than their order in metadata.
+    assert!(AsyncStatus::Started < AsyncStatus::Completed);
+    assert!(AsyncStatus::Canceled < AsyncStatus::Error);
+
+    let set: BTreeSet<AsyncStatus> = AsyncStatus::iter().collect();
+    let values: Vec<i32> = set.into_iter().map(|value| value as i32).collect();
+    assert_eq!(values, [0, 1, 2, 3]);
+
+    let set: HashSet<AsyncStatus> = AsyncStatus::iter().chain(AsyncStatus::iter()).collect();
+    assert_eq!(set.len(), 4);
+}
+
+#[test]
+fn enum_names() -> winrt::Result<()> {
+    use std::str::FromStr;
+    use windows::foundation::AsyncStatus;
+
+    assert_eq!(AsyncStatus::Completed.to_string(), "Completed");
+    assert_eq!(format!("{}", AsyncStatus::Error), "Error");
+
+    assert!(AsyncStatus::from_str("Started")? == AsyncStatus::Started);
+    assert!("canceled".parse::<AsyncStatus>()? == AsyncStatus::Canceled);
+    assert!(
+        AsyncStatus::from_str("Finished").unwrap_err().code()
+            == winrt::ErrorCode::INVALID_ARGUMENT
+    );
+
+    let values: Vec<AsyncStatus> = AsyncStatus::iter().collect();
+    assert_eq!(values.len(), 4);
+    assert!(values.contains(&AsyncStatus::Started));
+
+    for value in AsyncStatus::iter() {
+        assert!(AsyncStatus::from_str(&value.to_string())? == value);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "futures-io")]
+
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.storage.streams"
+    options
+        "futures_io"
+);
+
+mod common;
+
+use common::{block_on, poll_fn};
+use futures_io::{AsyncRead, AsyncWrite};
+use std::pin::Pin;
+use windows::storage::streams::*;
+
+#[test]
+fn futures_io() -> winrt::Result<()> {
+    let stream = InMemoryRandomAccessStream::new()?;
+
+    let mut output = AsyncOutputStream::new(stream.get_output_stream_at(0)?);
+    let written = block_on(poll_fn(|cx| Pin::new(&mut output).poll_write(cx, b"hello")));
+    assert!(written.unwrap() == 5);
+    block_on(poll_fn(|cx| Pin::new(&mut output).poll_flush(cx))).unwrap();
+    assert!(stream.size()? == 5);
+
+    let mut input = AsyncInputStream::new(stream.get_input_stream_at(0)?);
+    let mut bytes = [0; 5];
+    let read = block_on(poll_fn(|cx| Pin::new(&mut input).poll_read(cx, &mut bytes)));
+    assert!(read.unwrap() == 5);
+    assert!(&bytes == b"hello");
+
+    let read = block_on(poll_fn(|cx| Pin::new(&mut input).poll_read(cx, &mut bytes)));
+    assert!(read.unwrap() == 0);
+
+    Ok(())
+}
+
+#[test]
+fn shorter_buffer() -> winrt::Result<()> {
+    let stream = InMemoryRandomAccessStream::new()?;
+
+    let mut output = AsyncOutputStream::new(stream.get_output_stream_at(0)?);
+    block_on(poll_fn(|cx| Pin::new(&mut output).poll_write(cx, b"hello"))).unwrap();
+    block_on(poll_fn(|cx| Pin::new(&mut output).poll_flush(cx))).unwrap();
+
+    // A read that is still pending after its first poll is polled again with a shorter buffer,
+    // and the bytes that don't fit are returned by the reads after it.
+    let mut input = AsyncInputStream::new(stream.get_input_stream_at(0)?);
+    let mut long = [0; 5];
+    let mut short = [0; 2];
+    let mut polls = 0;
+    let read = block_on(poll_fn(|cx| {
+        polls += 1;
+        if polls == 1 {
+            Pin::new(&mut input).poll_read(cx, &mut long)
+        } else {
+            Pin::new(&mut input).poll_read(cx, &mut short)
+        }
+    }))
+    .unwrap();
+
+    let mut bytes = if polls == 1 {
+        long[..read].to_vec()
+    } else {
+        short[..read].to_vec()
+    };
+
+    loop {
+        let read = block_on(poll_fn(|cx| Pin::new(&mut input).poll_read(cx, &mut short))).unwrap();
+        if read == 0 {
+            break;
+        }
+        bytes.extend_from_slice(&short[..read]);
+    }
+
+    assert!(bytes == b"hello");
+    Ok(())
+}
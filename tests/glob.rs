@@ -0,0 +1,21 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation.*"
+);
+
+use windows::foundation::collections::PropertySet;
+use windows::foundation::Uri;
+
+#[test]
+fn glob() -> winrt::Result<()> {
+    // Windows.Foundation.Collections is matched and its parent namespace is included with it.
+    let set = PropertySet::new()?;
+    assert!(set.size()? == 0);
+
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+    assert!(uri.domain()? == "kennykerr.ca");
+
+    Ok(())
+}
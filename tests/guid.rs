@@ -33,3 +33,11 @@ fn guid_from_string() {
 
     assert!(a == b);
 }
+
+#[test]
+fn guid_macro() {
+    const IID: Guid = winrt::guid!("CFF52E04-CCA6-4614-A17E-754910C84A99");
+
+    assert!(IID == Guid::from("CFF52E04-CCA6-4614-A17E-754910C84A99"));
+    assert!(winrt::guid!("{cff52e04-cca6-4614-a17e-754910c84a99}") == IID);
+}
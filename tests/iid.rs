@@ -0,0 +1,21 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation.collections"
+);
+
+use windows::foundation::collections::{IMap, IPropertySet};
+use windows::foundation::IStringable;
+use winrt::{ComInterface, Guid, HString, Object};
+
+#[test]
+fn iid() {
+    const STRINGABLE: Guid = IStringable::IID;
+    assert!(STRINGABLE == Guid::from("96369F54-8EB6-48F0-ABCE-C1B211E627C3"));
+    assert!(IPropertySet::IID == IPropertySet::iid());
+
+    // Specializations required by generated classes have their computed IIDs as well.
+    assert!(IMap::<HString, HString>::IID == IMap::<HString, HString>::iid());
+    assert!(IMap::<HString, Object>::IID == IMap::<HString, Object>::iid());
+}
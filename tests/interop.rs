@@ -0,0 +1,58 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+);
+
+use windows::foundation::{IStringable, Uri};
+use winrt::{ComInterface, ComPtr};
+
+#[test]
+fn raw_round_trip() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+    let raw = uri.clone().into_raw();
+    assert!(raw == uri.as_raw());
+
+    // Another COM library would take ownership of the pointer here and hand one back.
+    let uri2 = unsafe { Uri::from_raw(raw) };
+    assert!(uri2.domain()? == "kennykerr.ca");
+
+    let borrowed = unsafe { Uri::from_raw_borrowed(&raw) }.unwrap();
+    assert!(borrowed.port()? == 80);
+    assert!(unsafe { Uri::from_raw_borrowed(&std::ptr::null_mut()) }.is_none());
+
+    Ok(())
+}
+
+#[test]
+fn query_raw() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+
+    // Pointers from other COM libraries may be queried for any interface.
+    let stringable = unsafe { IStringable::query_raw(uri.as_raw())? };
+    assert!(stringable.to_string()? == "http://kennykerr.ca/");
+
+    assert!(unsafe { IStringable::query_raw(std::ptr::null_mut()) }.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn com_ptr() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+    let ptr = unsafe { ComPtr::<Uri>::from_raw(uri.clone().into_raw()) };
+    assert!(ptr.as_raw() == uri.as_raw());
+
+    // A reference handed to a C callback, which releases it when it's done.
+    let count = ptr.addref();
+    assert!(unsafe { ptr.release() } == count - 1);
+
+    let uri2 = unsafe { Uri::from_raw(ptr.into_raw()) };
+    assert!(uri2.domain()? == "kennykerr.ca");
+
+    assert!(ComPtr::<Uri>::default().as_raw().is_null());
+    assert!(ComPtr::<Uri>::default().addref() == 0);
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+);
+
+use std::mem::{align_of, size_of};
+use windows::foundation::{DateTime, DeferralCompletedHandler, IStringable, Point, Rect, TimeSpan};
+use winrt::{ComInterface, EventToken, Guid, Variant};
+
+// These layouts are the same on x86 and x64, other than those holding pointers, and are
+// checked on both to catch differences from the C ABI that Windows uses.
+#[test]
+fn layout() {
+    // 64-bit fields are aligned to 8 bytes even on x86.
+    assert_eq!((size_of::<TimeSpan>(), align_of::<TimeSpan>()), (8, 8));
+    assert_eq!((size_of::<DateTime>(), align_of::<DateTime>()), (8, 8));
+    assert_eq!((size_of::<EventToken>(), align_of::<EventToken>()), (8, 8));
+
+    assert_eq!((size_of::<Point>(), align_of::<Point>()), (8, 4));
+
+    // Structs of up to four floats are passed in floating point registers on ARM64 so they
+    // mustn't be padded.
+    assert_eq!((size_of::<Rect>(), align_of::<Rect>()), (16, 4));
+    assert_eq!((size_of::<Guid>(), align_of::<Guid>()), (16, 4));
+
+    // A VARIANT is 16 bytes on x86 and 24 on x64.
+    assert_eq!(size_of::<Variant>(), 8 + 2 * size_of::<usize>());
+    assert_eq!(align_of::<Variant>(), 8);
+}
+
+#[test]
+fn vtables() {
+    // Interfaces start with the methods of IUnknown and IInspectable while delegates only have
+    // those of IUnknown.
+    assert_eq!(
+        size_of::<<IStringable as ComInterface>::VTable>(),
+        7 * size_of::<usize>()
+    );
+    assert_eq!(
+        size_of::<<DeferralCompletedHandler as ComInterface>::VTable>(),
+        4 * size_of::<usize>()
+    );
+}
@@ -0,0 +1,34 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+);
+
+use windows::foundation::MemoryBuffer;
+
+#[test]
+fn memory_buffer() -> winrt::Result<()> {
+    let buffer = MemoryBuffer::new(4)?;
+    let mut reference = buffer.create_reference()?;
+    assert!(reference.capacity()? == 4);
+
+    reference.copy_from(&[1, 2, 3, 4])?;
+    assert!(reference.to_vec()? == [1, 2, 3, 4]);
+    assert!(reference.copy_from(&[0; 5]).is_err());
+
+    // Nothing else uses the bytes or closes the buffer while they're borrowed.
+    unsafe {
+        reference.as_mut_slice()?[3] = 6;
+        assert!(reference.as_slice()? == [1, 2, 3, 6]);
+
+        // The bytes outlive the reference they were read from.
+        let mut bytes = buffer.create_reference()?.bytes()?;
+        bytes[0] = 5;
+        assert!(*bytes == [5, 2, 3, 6]);
+    }
+
+    assert!(reference.to_vec()? == [5, 2, 3, 6]);
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+);
+
+use windows::foundation::{IClosable, IStringable, Uri};
+use winrt::{ComInterface, Object, TrustLevel};
+
+#[test]
+fn introspection() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+    let object = uri.query::<Object>();
+
+    assert!(object.type_name()? == "Windows.Foundation.Uri");
+    assert!(object.trust_level()? == TrustLevel::Base);
+
+    let iids = object.iids()?;
+    assert!(iids.contains(&IStringable::GUID));
+    assert!(!iids.contains(&Object::GUID));
+
+    Ok(())
+}
+
+#[test]
+fn probing() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+    assert!(uri.is::<IStringable>());
+    assert!(!uri.is::<IClosable>());
+
+    assert!(uri.query_optional::<IStringable>().is_some());
+    assert!(uri.query_optional::<IClosable>().is_none());
+    assert!(uri.try_query::<IClosable>().is_err());
+
+    let none: Object = unsafe { ComInterface::none() };
+    assert!(!none.is::<IStringable>());
+    assert!(none.query_optional::<IStringable>().is_none());
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.data.xml.dom"
+        "windows.foundation"
+    options
+        "overrides:tests/overrides.toml"
+);
+
+use windows::data::xml::dom::XmlDocument;
+use windows::foundation::Uri;
+
+#[test]
+fn overrides() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+
+    // Renamed from `domain`, and `port` is skipped entirely.
+    assert!(uri.host()? == "kennykerr.ca");
+
+    let combined: Option<Uri> = uri.combine_uri("about")?;
+    assert!(combined.unwrap().to_string()? == "http://kennykerr.ca/about");
+
+    Ok(())
+}
+
+#[test]
+fn nullable() -> winrt::Result<()> {
+    let doc = XmlDocument::new()?;
+
+    // An empty document has no root element, which is returned by the default interface.
+    assert!(doc.document_element()?.is_none());
+
+    doc.load_xml("<html><body>hello</body></html>")?;
+    let root = doc.document_element()?.unwrap();
+    assert!(root.node_name()? == "html");
+
+    // A query that matches nothing returns null, which is returned by another interface.
+    assert!(doc.select_single_node("/html/head")?.is_none());
+    let body = doc.select_single_node("/html/body")?.unwrap();
+    assert!(body.inner_text()? == "hello");
+
+    Ok(())
+}
@@ -0,0 +1,22 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.system.power"
+);
+
+use windows::system::power::*;
+
+#[test]
+fn status() -> winrt::Result<()> {
+    let status: PowerManagerStatus = PowerManager::status()?;
+    assert!(status.remaining_charge_percent >= 0);
+    assert!(status.remaining_charge_percent <= 100);
+
+    // Registering for changes adds a Rust closure as the handler of each event and
+    // dropping the subscription removes them again.
+    let changes: PowerManagerStatusChanges = PowerManager::changes()?;
+    drop(changes);
+
+    Ok(())
+}
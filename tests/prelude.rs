@@ -0,0 +1,30 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation.collections"
+);
+
+use prelude::*;
+use windows::foundation::collections::StringMap;
+use windows::foundation::Uri;
+use winrt::HString;
+
+fn describe<T: IStringable>(value: &T) -> winrt::Result<HString> {
+    IStringable::to_string(value)
+}
+
+#[test]
+fn prelude() -> winrt::Result<()> {
+    // Trait bounds only need the prelude.
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+    assert!(describe(&uri)? == "http://kennykerr.ca/");
+
+    // As do the methods of an interface's traits and the runtime's traits.
+    let map = StringMap::new()?;
+    map.insert("key", "value")?;
+    assert!(IMap::size(&map)? == 1);
+    assert!(!map.is_null());
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation.collections"
+);
+
+use windows::foundation::collections::PropertySet;
+use windows::foundation::{Point, PropertyBag, PropertyValue, Uri};
+use winrt::ComInterface;
+
+#[test]
+fn typed_values() -> winrt::Result<()> {
+    let set = PropertySet::new()?;
+    let bag = PropertyBag::new(&set)?;
+    assert!(bag.is_empty()?);
+
+    assert!(!bag.insert("count", 123)?);
+    assert!(!bag.insert("name", winrt::HString::from("hello"))?);
+    assert!(!bag.insert("enabled", true)?);
+    assert!(!bag.insert("point", Point { x: 1.0, y: 2.0 })?);
+    assert!(bag.len()? == 4);
+
+    assert!(bag.get::<i32>("count")? == Some(123));
+    assert!(bag.get::<winrt::HString>("name")? == Some("hello".into()));
+    assert!(bag.get::<bool>("enabled")? == Some(true));
+    assert!(bag.get::<Point>("point")? == Some(Point { x: 1.0, y: 2.0 }));
+
+    // Replacing a value returns true.
+    assert!(bag.insert("count", 456)?);
+    assert!(bag.get::<i32>("count")? == Some(456));
+
+    // The values are visible through the original map.
+    assert!(set.size()? == 4);
+
+    bag.remove("count")?;
+    bag.remove("count")?;
+    assert!(!bag.contains_key("count")?);
+    assert!(bag.get::<i32>("count")?.is_none());
+    assert!(bag.get::<i32>("missing")?.is_none());
+
+    // Values boxed by `winrt::box_value` and references are stored the same way.
+    bag.insert("boxed", winrt::box_value(7u8)?)?;
+    assert!(bag.get::<i32>("boxed")? == Some(7));
+    bag.insert(
+        "guid",
+        &winrt::Guid::from("9E365E57-48B2-4160-956F-C7385120BBFC"),
+    )?;
+    assert!(bag.get::<winrt::Guid>("guid")?.is_some());
+
+    Ok(())
+}
+
+#[test]
+fn objects() -> winrt::Result<()> {
+    let bag = PropertyBag::new(&PropertySet::new()?)?;
+
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+    bag.insert("uri", uri.query::<winrt::Object>())?;
+    let object = bag.get::<winrt::Object>("uri")?.unwrap();
+    assert!(object.type_name()? == "Windows.Foundation.Uri");
+
+    // Values that aren't boxed with the requested type fail to unbox.
+    assert!(bag.get::<i32>("uri").is_err());
+
+    // Objects that aren't maps can't be wrapped.
+    assert!(PropertyBag::new(&PropertyValue::create_int32(1)?).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+#![deny(unsafe_code)]
+
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation.collections"
+    options
+        "safe_api"
+);
+
+use windows::foundation::collections::{IIterable, PropertySet};
+use windows::foundation::{IStringable, PropertyValue, Uri};
+
+#[test]
+fn safe_api() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca/path?query")?;
+    assert!(uri.domain()? == "kennykerr.ca");
+    assert!(uri.port()? == 80);
+
+    let stringable: IStringable = uri.into();
+    assert!(stringable.to_string()? == "http://kennykerr.ca/path?query");
+
+    let set = PropertySet::new()?;
+    set.insert("A", PropertyValue::create_int32(123)?)?;
+    assert!(set.size()? == 1);
+
+    let iterable: IIterable<_> = set.into();
+    assert!(iterable.first()?.has_current()?);
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "serde")]
+
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+        "windows.globalization"
+    options
+        "serde"
+);
+
+use serde::de::value::{Error, StrDeserializer};
+use serde::de::{DeserializeOwned, IntoDeserializer};
+use serde::{Deserialize, Serialize};
+use windows::foundation::{Point, Rect};
+use windows::globalization::DayOfWeek;
+use winrt::Guid;
+
+fn serializable<T: Serialize + DeserializeOwned>() {}
+
+#[test]
+fn serde() {
+    serializable::<Point>();
+    serializable::<Rect>();
+    serializable::<DayOfWeek>();
+
+    // Enums are serialized by the names of their values.
+    let name: StrDeserializer<Error> = "Monday".into_deserializer();
+    assert!(DayOfWeek::deserialize(name).unwrap() == DayOfWeek::Monday);
+
+    // GUIDs are serialized as strings.
+    let guid: StrDeserializer<Error> = "96369F54-8EB6-48F0-ABCE-C1B211E627C3".into_deserializer();
+    assert!(Guid::deserialize(guid).unwrap() == Guid::from("96369F54-8EB6-48F0-ABCE-C1B211E627C3"));
+
+    let invalid: StrDeserializer<Error> = "not a guid".into_deserializer();
+    assert!(Guid::deserialize(invalid).is_err());
+}
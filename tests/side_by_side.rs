@@ -0,0 +1,35 @@
+// Each import is generated into its own module so that bindings for different metadata
+// versions (e.g. "sdk:10.0.17763.0" and "sdk:10.0.19041.0") can live side by side.
+
+mod older {
+    winrt::import!(
+        dependencies
+            "sdk:10.0.17763.0"
+        modules
+            "windows.foundation"
+    );
+}
+
+mod newer {
+    winrt::import!(
+        dependencies
+            "sdk:10.0.19041.0"
+        modules
+            "windows.foundation"
+    );
+}
+
+#[test]
+fn side_by_side() -> winrt::Result<()> {
+    let older = older::windows::foundation::Uri::create_uri("http://kennykerr.ca")?;
+    let newer = newer::windows::foundation::Uri::create_uri("http://kennykerr.ca")?;
+
+    assert!(older.domain()? == newer.domain()?);
+    assert!(older.port()? == 80);
+
+    // GuidHelper was added in 10.0.19041.0, so only the newer bindings have it.
+    let guid = newer::windows::foundation::GuidHelper::create_new_guid()?;
+    assert!(guid != newer::windows::foundation::GuidHelper::empty()?);
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.storage.streams"
+);
+
+use std::io::{Read, Seek, SeekFrom, Write};
+use windows::storage::streams::{
+    Buffer, DataReader, DataWriter, IRandomAccessStream, InMemoryRandomAccessStream,
+};
+
+#[test]
+fn fill_array() -> winrt::Result<()> {
+    let writer = DataWriter::new()?;
+    writer.write_bytes(&[1, 2, 3, 4])?;
+    let buffer = writer.detach_buffer()?;
+
+    let reader = DataReader::from_buffer(&buffer)?;
+    let mut bytes = [0; 3];
+    reader.read_bytes(&mut bytes)?;
+    assert!(bytes == [1, 2, 3]);
+
+    let mut bytes = vec![0; 1];
+    reader.read_bytes(&mut bytes)?;
+    assert!(bytes == [4]);
+
+    Ok(())
+}
+
+#[test]
+fn buffer_bytes() -> winrt::Result<()> {
+    let writer = DataWriter::new()?;
+    writer.write_bytes(&[1, 2, 3, 4])?;
+    let mut buffer = writer.detach_buffer()?;
+    assert!(buffer.to_vec()? == [1, 2, 3, 4]);
+
+    // Nothing else refers to the buffer while its bytes are borrowed.
+    unsafe {
+        assert!(buffer.as_slice()? == [1, 2, 3, 4]);
+        buffer.as_mut_slice()?[0] = 5;
+    }
+    let reader = DataReader::from_buffer(&buffer)?;
+    assert!(reader.read_byte()? == 5);
+
+    // Buffers start out empty and are written up to their capacity by setting their length.
+    let mut buffer = Buffer::new(8)?;
+    assert!(buffer.to_vec()?.is_empty());
+    buffer.set_length(2)?;
+    unsafe { buffer.as_mut_slice()?.copy_from_slice(&[6, 7]) };
+    assert!(buffer.to_vec()? == [6, 7]);
+
+    // Copying bytes in sets the length to theirs.
+    buffer.copy_from(&[1, 2, 3])?;
+    assert!(buffer.length()? == 3);
+    assert!(buffer.to_vec()? == [1, 2, 3]);
+    assert!(buffer.copy_from(&[0; 9]).is_err());
+    assert!(buffer.to_vec()? == [1, 2, 3]);
+
+    Ok(())
+}
+
+#[test]
+fn std_io() -> winrt::Result<()> {
+    let mut stream: IRandomAccessStream = InMemoryRandomAccessStream::new()?.into();
+
+    stream.write_all(b"hello world").unwrap();
+    stream.flush().unwrap();
+    assert!(stream.size()? == 11);
+
+    // The stream's own `seek` method takes a position, so `Seek` is called explicitly.
+    assert!(Seek::seek(&mut stream, SeekFrom::Start(6)).unwrap() == 6);
+    let mut text = String::new();
+    stream.read_to_string(&mut text).unwrap();
+    assert!(text == "world");
+
+    assert!(Seek::seek(&mut stream, SeekFrom::End(-11)).unwrap() == 0);
+    assert!(Seek::seek(&mut stream, SeekFrom::Current(-1)).is_err());
+
+    let mut input = stream.get_input_stream_at(0)?;
+    let mut bytes = [0; 5];
+    input.read_exact(&mut bytes).unwrap();
+    assert!(&bytes == b"hello");
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.graphics"
+);
+
+use std::collections::HashMap;
+use windows::graphics::{PointInt32, RectInt32};
+
+#[test]
+fn hashable() {
+    // Structs without floating point fields may be used as keys.
+    let mut map = HashMap::new();
+    map.insert(PointInt32 { x: 1, y: 2 }, "a");
+    map.insert(PointInt32 { x: 2, y: 1 }, "b");
+    map.insert(PointInt32 { x: 1, y: 2 }, "c");
+
+    assert_eq!(map.len(), 2);
+    assert_eq!(map[&PointInt32 { x: 1, y: 2 }], "c");
+
+    let rect = RectInt32 {
+        x: 1,
+        y: 2,
+        width: 3,
+        height: 4,
+    };
+
+    assert!(rect == rect.clone());
+}
+
+#[test]
+fn ordered() {
+    use std::collections::BTreeMap;
+
+    // Structs without floating point fields are ordered field by field.
+    let mut map = BTreeMap::new();
+    map.insert(PointInt32 { x: 2, y: 1 }, "b");
+    map.insert(PointInt32 { x: 1, y: 2 }, "a");
+
+    assert!(PointInt32 { x: 1, y: 2 } < PointInt32 { x: 2, y: 1 });
+    assert_eq!(map.values().copied().collect::<Vec<_>>(), ["a", "b"]);
+}
@@ -0,0 +1,50 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+        "windows.globalization"
+);
+
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use windows::foundation::{DateTime, TimeSpan};
+use windows::globalization::Calendar;
+
+#[test]
+fn date_time() -> winrt::Result<()> {
+    let epoch = DateTime::try_from(UNIX_EPOCH)?;
+    assert!(epoch.universal_time == 116_444_736_000_000_000);
+    assert!(SystemTime::try_from(epoch)? == UNIX_EPOCH);
+
+    let calendar = Calendar::new()?;
+    calendar.change_time_zone("UTC")?;
+    calendar.set_date_time(epoch)?;
+    assert!(calendar.year()? == 1970);
+
+    let now = SystemTime::now();
+    let later = SystemTime::try_from(DateTime::try_from(now)?)?;
+    assert!(now.duration_since(later).unwrap_or_default() < Duration::from_nanos(100));
+
+    // Times before the epoch round down to the tick before them.
+    let before = DateTime::try_from(UNIX_EPOCH - Duration::from_nanos(50))?;
+    assert!(before.universal_time == 116_444_736_000_000_000 - 1);
+
+    // Windows can't represent times before 1601.
+    let ancient = DateTime { universal_time: -1 };
+    assert!(SystemTime::try_from(ancient).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn time_span() {
+    let span = TimeSpan::try_from(Duration::from_millis(1500)).unwrap();
+    assert!(span.duration == 15_000_000);
+    assert!(Duration::try_from(span).unwrap() == Duration::from_millis(1500));
+
+    let negative = TimeSpan { duration: -1 };
+    assert!(Duration::try_from(negative).is_err());
+
+    assert!(TimeSpan::try_from(Duration::from_secs(u64::MAX)).is_err());
+}
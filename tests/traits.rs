@@ -0,0 +1,47 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation.collections"
+);
+
+use windows::foundation::collections::traits::{IIterable, IMap};
+use windows::foundation::collections::{IKeyValuePair, StringMap};
+use windows::foundation::traits::IStringable;
+use windows::foundation::Uri;
+use winrt::HString;
+
+fn describe<T: IStringable>(value: &T) -> winrt::Result<HString> {
+    IStringable::to_string(value)
+}
+
+fn first_key<M: IMap<HString, HString>>(map: &M) -> winrt::Result<HString> {
+    // IMap<K, V> requires IIterable<IKeyValuePair<K, V>> so its methods are available too.
+    assert!(IMap::size(map)? > 0);
+    let pair: IKeyValuePair<HString, HString> = IIterable::first(map)?.current()?;
+    pair.key()
+}
+
+#[test]
+fn classes() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+    assert!(describe(&uri)? == "http://kennykerr.ca/");
+
+    // The interface itself implements its trait as well.
+    let stringable: windows::foundation::IStringable = uri.into();
+    assert!(describe(&stringable)? == "http://kennykerr.ca/");
+
+    Ok(())
+}
+
+#[test]
+fn generic_interfaces() -> winrt::Result<()> {
+    let map = StringMap::new()?;
+    map.insert("key", "value")?;
+    assert!(first_key(&map)? == "key");
+
+    let map: windows::foundation::collections::IMap<HString, HString> = map.into();
+    assert!(first_key(&map)? == "key");
+
+    Ok(())
+}
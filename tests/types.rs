@@ -0,0 +1,20 @@
+winrt::import!(
+    dependencies
+        "os"
+    types
+        "windows.foundation.uri"
+);
+
+use windows::foundation::Uri;
+
+#[test]
+fn types() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca?a=b")?;
+    assert!(uri.domain()? == "kennykerr.ca");
+
+    // Types that Uri refers to, such as the decoder returned by `query_parsed`, are included.
+    let query = uri.query_parsed()?;
+    assert!(query.get_first_value_by_name("a")? == "b");
+
+    Ok(())
+}
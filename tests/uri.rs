@@ -4,6 +4,7 @@ winrt::import!(
     modules
         "windows.foundation.collections"
 );
+use std::convert::TryFrom;
 use windows::foundation::Uri;
 use winrt::ComInterface;
 use winrt::RuntimeName;
@@ -17,10 +18,11 @@ fn uri() -> winrt::Result<()> {
         winrt::Guid::from("9E365E57-48B2-4160-956F-C7385120BBFC") // IUriRuntimeClass
     );
 
-    let _null_uri = Uri::default();
-    // TODO: how to signal/test empty value?
+    // Objects that don't refer to anything are only created explicitly and report themselves as such.
+    assert!(unsafe { Uri::none() }.is_null());
 
     let uri = &Uri::create_uri("http://kennykerr.ca")?;
+    assert!(!uri.is_null());
 
     assert!(uri.domain()? == "kennykerr.ca");
     assert!(uri.port()? == 80);
@@ -31,7 +33,7 @@ fn uri() -> winrt::Result<()> {
     let default: windows::foundation::IUriRuntimeClass = uri.into();
     assert!(default.domain()? == uri.domain()?);
 
-    let stringable: windows::foundation::IStringable = uri.into();
+    let stringable = windows::foundation::IStringable::try_from(uri)?;
     assert!(stringable.to_string()? == uri.to_string()?);
 
     Ok(())
@@ -53,14 +55,19 @@ fn interface_conversion() -> winrt::Result<()> {
 
     // Convert from Uri class to non-default non-generic interface by value.
     let uri: Uri = Uri::create_uri("http://kennykerr.ca")?;
-    let default: IStringable = uri.into();
+    let default = IStringable::try_from(uri)?;
     assert!(default.to_string()? == "http://kennykerr.ca/");
 
     // Convert from Uri class to non-default non-generic interface by reference.
     let uri: &Uri = &Uri::create_uri("http://kennykerr.ca")?;
-    let default: IStringable = uri.into();
+    let default = IStringable::try_from(uri)?;
     assert!(default.to_string()? == uri.to_string()?);
 
+    // Converting a null Uri to a non-default interface yields a null interface.
+    let uri = unsafe { Uri::none() };
+    let default = IStringable::try_from(&uri)?;
+    assert!(default.is_null());
+
     // Convert from ??? class to default generic interface by value.
 
     // Convert from ??? class to default generic interface by reference.
@@ -71,3 +78,95 @@ fn interface_conversion() -> winrt::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn formatting() -> winrt::Result<()> {
+    use windows::foundation::*;
+
+    // Uri implements IStringable so both Display and Debug use ToString.
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+    assert_eq!(format!("{}", uri), "http://kennykerr.ca/");
+    assert_eq!(format!("{:?}", uri), "http://kennykerr.ca/");
+
+    let stringable = IStringable::try_from(&uri)?;
+    assert_eq!(format!("{}", stringable), "http://kennykerr.ca/");
+
+    // Without IStringable, Debug falls back to the runtime class name and pointer.
+    let entries = WwwFormUrlDecoder::create_www_form_url_decoder("?a=b")?;
+    assert!(format!("{:?}", entries).starts_with("Windows.Foundation.WwwFormUrlDecoder(0x"));
+
+    // Null objects are printed as such.
+    assert_eq!(format!("{:?}", unsafe { Uri::none() }), "null");
+
+    Ok(())
+}
+
+#[test]
+fn identity() -> winrt::Result<()> {
+    use windows::foundation::*;
+
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+    let other = Uri::create_uri("http://kennykerr.ca")?;
+
+    // Clones refer to the same object while equivalent objects are still distinct.
+    assert!(uri == uri.clone());
+    assert!(uri != other);
+
+    // Different interfaces on the same object compare equal once converted back.
+    let stringable = IStringable::try_from(&uri)?;
+    let default: IUriRuntimeClass = uri.clone().into();
+    assert!(stringable == IStringable::try_from(default)?);
+    assert!(stringable != IStringable::try_from(&other)?);
+
+    let none = unsafe { Uri::none() };
+    assert!(none == unsafe { Uri::none() });
+    assert!(uri != none);
+
+    Ok(())
+}
+
+#[test]
+fn agile() -> winrt::Result<()> {
+    // Uri is agile so it can be moved to, and shared with, other threads.
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+
+    let domain = std::thread::spawn(move || uri.domain().unwrap())
+        .join()
+        .unwrap();
+
+    assert!(domain == "kennykerr.ca");
+    Ok(())
+}
+
+#[test]
+fn constructors() -> winrt::Result<()> {
+    // Activation factory methods are also available as constructors.
+    let uri = Uri::new("http://kennykerr.ca")?;
+    assert!(uri.domain()? == "kennykerr.ca");
+
+    let uri = Uri::new_with_relative_uri("http://kennykerr.ca", "rust")?;
+    assert!(uri.to_string()? == "http://kennykerr.ca/rust");
+
+    Ok(())
+}
+
+#[test]
+fn cached() -> winrt::Result<()> {
+    use windows::foundation::*;
+
+    let uri = winrt::Cached::new(Uri::create_uri("http://kennykerr.ca")?);
+
+    // Default interface methods are available directly.
+    assert!(uri.domain()? == "kennykerr.ca");
+
+    // Repeated queries return the same interface pointer from the cache.
+    let first = uri.query::<IStringable>()?;
+    let second = uri.query::<IStringable>()?;
+    assert!(first.as_vtable() == second.as_vtable());
+    assert!(second.to_string()? == "http://kennykerr.ca/");
+
+    // Objects that don't implement the interface still fail.
+    assert!(uri.query::<IClosable>().is_err());
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation.collections"
+);
+
+use windows::foundation::collections::{PropertySet, ValueSet};
+use windows::foundation::{FromValueSet, PropertyBag, ToValueSet};
+use winrt::HString;
+
+#[derive(winrt::ToValueSet, winrt::FromValueSet, Debug, PartialEq)]
+struct Request {
+    command: HString,
+    #[value_set(rename = "Id")]
+    id: u32,
+    scale: Option<f64>,
+}
+
+#[test]
+fn round_trip() -> winrt::Result<()> {
+    let request = Request {
+        command: "open".into(),
+        id: 42,
+        scale: Some(1.5),
+    };
+
+    let bag = PropertyBag::new(&ValueSet::new()?)?;
+    request.to_value_set(&bag)?;
+
+    assert!(bag.len()? == 3);
+    assert!(bag.get::<u32>("Id")? == Some(42));
+    assert!(bag.get::<HString>("command")? == Some("open".into()));
+
+    assert_eq!(Request::from_value_set(&bag)?, request);
+
+    Ok(())
+}
+
+#[test]
+fn optional_fields() -> winrt::Result<()> {
+    let bag = PropertyBag::new(&PropertySet::new()?)?;
+    bag.insert("scale", 2.0)?;
+
+    // None removes any existing value.
+    let request = Request {
+        command: "close".into(),
+        id: 1,
+        scale: None,
+    };
+
+    request.to_value_set(&bag)?;
+    assert!(!bag.contains_key("scale")?);
+    assert!(Request::from_value_set(&bag)?.scale.is_none());
+
+    // Required fields must be present.
+    bag.remove("Id")?;
+    assert!(Request::from_value_set(&bag).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+);
+
+use windows::foundation::{IPropertyValue, PropertyType, PropertyValue};
+use winrt::{TryInto, Variant};
+
+#[test]
+fn round_trip() -> winrt::Result<()> {
+    let object = PropertyValue::create_int32_array(&[1, 2, 3])?;
+    let pv: IPropertyValue = object.try_into()?;
+
+    let variant = pv.to_variant()?;
+    assert_eq!(variant.array_type(), Some(3));
+    assert_eq!(variant.to_vec::<i32>()?, [1, 2, 3]);
+
+    let object = PropertyValue::from_variant(&variant)?;
+    let pv: IPropertyValue = object.try_into()?;
+    assert!(pv.r#type()? == PropertyType::Int32Array);
+
+    Ok(())
+}
+
+#[test]
+fn strings() -> winrt::Result<()> {
+    let variant = Variant::from_slice(&[winrt::HString::from("a"), "bc".into()])?;
+    assert_eq!(variant.array_type(), Some(8));
+
+    let object = PropertyValue::from_variant(&variant)?;
+    let pv: IPropertyValue = object.try_into()?;
+    let mut array = winrt::Array::new();
+    pv.get_string_array(&mut array)?;
+    assert!(array[0] == "a");
+    assert!(array[1] == "bc");
+
+    // Element types must match exactly.
+    assert!(variant.to_vec::<i32>().is_err());
+    assert!(Variant::default().to_vec::<i32>().is_err());
+
+    Ok(())
+}
@@ -0,0 +1,19 @@
+#![deny(unreachable_pub)]
+
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+    options
+        "visibility:pub(crate)"
+);
+
+use windows::foundation::Uri;
+
+#[test]
+fn visibility() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+    assert!(uri.domain()? == "kennykerr.ca");
+    Ok(())
+}
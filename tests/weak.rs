@@ -0,0 +1,24 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+);
+
+use windows::foundation::Uri;
+use winrt::ComInterface;
+
+#[test]
+fn weak_ref() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+    let weak = uri.downgrade()?;
+
+    // The weak reference doesn't keep the object alive.
+    assert!(weak.upgrade().unwrap().domain()? == "kennykerr.ca");
+    let clone = weak.clone();
+    drop(uri);
+    assert!(weak.upgrade().is_none());
+    assert!(clone.upgrade().is_none());
+
+    Ok(())
+}
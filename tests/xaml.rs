@@ -9,3 +9,26 @@ winrt::import!(
 fn xaml() -> winrt::Result<()> {
     Ok(())
 }
+
+#[test]
+fn type_name() {
+    use windows::foundation::Uri;
+    use windows::ui::xaml::interop::{TypeKind, TypeName};
+    use windows::ui::xaml::Visibility;
+
+    let name = TypeName::of::<Uri>();
+    assert!(name.name == "Windows.Foundation.Uri");
+    assert!(name.kind == TypeKind::Metadata);
+
+    let name = TypeName::of::<Visibility>();
+    assert!(name.name == "Windows.UI.Xaml.Visibility");
+    assert!(name.kind == TypeKind::Metadata);
+
+    let name = TypeName::of::<i32>();
+    assert!(name.name == "Int32");
+    assert!(name.kind == TypeKind::Primitive);
+
+    let name = TypeName::of::<winrt::HString>();
+    assert!(name.name == "String");
+    assert!(name.kind == TypeKind::Primitive);
+}